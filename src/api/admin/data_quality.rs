@@ -0,0 +1,37 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use jiff::civil::Date;
+use serde_json::json;
+
+use crate::db::{data_quality::run_checks, prod_db::ProdDb};
+use crate::interval::term::Term;
+
+/// Names of the archives that can be checked
+#[get("/admin/data_quality/names")]
+async fn api_get_names() -> impl Responder {
+    let names: Vec<String> = ProdDb::quality_specs()
+        .into_iter()
+        .map(|spec| spec.name)
+        .collect();
+    HttpResponse::Ok().json(json!(names))
+}
+
+/// http://127.0.0.1:8111/admin/data_quality/isone_dalmp/start/2025-11-01/end/2025-11-30
+#[get("/admin/data_quality/{name}/start/{start}/end/{end}")]
+async fn api_report(path: web::Path<(String, Date, Date)>) -> impl Responder {
+    let (name, start, end) = path.into_inner();
+    let spec = match ProdDb::quality_specs()
+        .into_iter()
+        .find(|spec| spec.name == name)
+    {
+        Some(spec) => spec,
+        None => return HttpResponse::NotFound().body(format!("Unknown archive: {}", name)),
+    };
+    let term = match Term::new(start, end) {
+        Some(term) => term,
+        None => return HttpResponse::BadRequest().body("End date is before the start date"),
+    };
+    match run_checks(&spec, &term) {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error checking data: {}", e)),
+    }
+}
@@ -1 +1,2 @@
+pub mod data_quality;
 pub mod jobs;
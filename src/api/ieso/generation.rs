@@ -0,0 +1,131 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::{AccessMode, Connection};
+use jiff::Zoned;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::db::ieso::generation_output_by_fuel::*;
+use crate::time::bucket::Bucket;
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// Hourly generation by fuel type, e.g.
+/// /ieso/generation/fuel?hour_beginning_gte=2025-07-01T00:00:00-05:00&fuel_type_in=WIND,SOLAR
+#[get("/ieso/generation/fuel")]
+pub async fn api_data(
+    query: web::Query<ApiQuery>,
+    data: web::Data<IesoGenOutputByFuelArchive>,
+) -> impl Responder {
+    let conn = match open(&data.duckdb_path) {
+        Ok(conn) => conn,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+
+    let query_filter = query.to_query_filter();
+    match get_data(&conn, &query_filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// Generation by fuel type (or the total with `total=true`) aggregated by
+/// `hour`, `day` or `bucket`, e.g.
+/// /ieso/generation/fuel/aggregate/day?hour_beginning_gte=2025-07-01T00:00:00-05:00&hour_beginning_lt=2025-08-01T00:00:00-05:00
+/// /ieso/generation/fuel/aggregate/bucket?hour_beginning_gte=...&hour_beginning_lt=...&buckets=5x16,2x16H,7x8
+#[get("/ieso/generation/fuel/aggregate/{resolution}")]
+pub async fn api_aggregate(
+    path: web::Path<String>,
+    query: web::Query<AggregateQuery>,
+    data: web::Data<IesoGenOutputByFuelArchive>,
+) -> impl Responder {
+    let (Some(gte), Some(lt)) = (&query.hour_beginning_gte, &query.hour_beginning_lt) else {
+        return HttpResponse::BadRequest().body("Both hour_beginning_gte and hour_beginning_lt are required");
+    };
+    let conn = match open(&data.duckdb_path) {
+        Ok(conn) => conn,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    let mut builder = QueryFilterBuilder::new()
+        .hour_beginning_gte(gte.clone())
+        .hour_beginning_lt(lt.clone());
+    if let Some(fuels) = &query.fuel_type_in {
+        builder = builder.fuel_type_in(fuels.split(',').map(|e| e.trim().to_uppercase()));
+    }
+    let records = match get_data(&conn, &builder.build(), None) {
+        Ok(records) => records,
+        Err(e) => return HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    };
+    let total = query.total.unwrap_or(false);
+    match path.as_str() {
+        "hour" => HttpResponse::Ok().json(aggregate(&records, Resolution::Hour, total)),
+        "day" => HttpResponse::Ok().json(aggregate(&records, Resolution::Day, total)),
+        "bucket" => match query.buckets() {
+            Ok(buckets) => HttpResponse::Ok().json(aggregate_by_bucket(&records, &buckets, total)),
+            Err(e) => HttpResponse::BadRequest().body(e),
+        },
+        _ => HttpResponse::BadRequest().body(format!(
+            "Invalid resolution {}, use one of hour, day, bucket",
+            path
+        )),
+    }
+}
+
+fn open(duckdb_path: &str) -> Result<Connection, String> {
+    open_with_retry(duckdb_path, 8, Duration::from_millis(25), AccessMode::ReadOnly)
+        .map_err(|e| format!("Error opening DuckDB database at {}: {}", duckdb_path, e))
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiQuery {
+    pub hour_beginning: Option<Zoned>,
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    pub fuel_type: Option<String>,
+    /// One or more fuel types, separated by commas
+    pub fuel_type_in: Option<String>,
+    pub output_quality: Option<i8>,
+    pub _limit: Option<usize>,
+}
+
+impl ApiQuery {
+    pub fn to_query_filter(&self) -> QueryFilter {
+        QueryFilter {
+            hour_beginning: self.hour_beginning.clone(),
+            hour_beginning_gte: self.hour_beginning_gte.clone(),
+            hour_beginning_lt: self.hour_beginning_lt.clone(),
+            fuel_type: self.fuel_type.as_ref().map(|e| e.to_uppercase()),
+            fuel_type_in: self
+                .fuel_type_in
+                .as_ref()
+                .map(|s| s.split(',').map(|e| e.trim().to_uppercase()).collect()),
+            output_quality: self.output_quality,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AggregateQuery {
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    /// One or more fuel types, separated by commas
+    pub fuel_type_in: Option<String>,
+    /// Add up all the fuel types, default false
+    pub total: Option<bool>,
+    /// One or more bucket names, separated by commas.  Default: atc
+    pub buckets: Option<String>,
+}
+
+impl AggregateQuery {
+    fn buckets(&self) -> Result<Vec<Bucket>, String> {
+        match &self.buckets {
+            Some(names) => names.split(',').map(|e| e.trim().parse::<Bucket>()).collect(),
+            None => Ok(vec![Bucket::Atc]),
+        }
+    }
+}
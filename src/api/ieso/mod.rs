@@ -1,2 +1,4 @@
 pub mod dalmp;
+pub mod generation;
 pub mod node_table;
+pub mod vg_forecast;
@@ -0,0 +1,201 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::{AccessMode, Connection};
+use jiff::{civil::Date, Zoned};
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::db::ieso::generation_output_by_fuel::IesoGenOutputByFuelArchive;
+use crate::db::ieso::vgforecast_summary::*;
+use crate::elec::forecast_accuracy::{self, Grouping};
+use crate::time::bucket::Bucket;
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// Zonal forecasts of wind and solar generation, e.g.
+/// /ieso/vg_forecast?forecast_timestamp_gte=2025-09-08T00:00:00-05:00&organization=MARKET PARTICIPANT&fuel_type=Wind
+#[get("/ieso/vg_forecast")]
+pub async fn api_data(
+    query: web::Query<ApiQuery>,
+    data: web::Data<IesoVGForecastSummaryArchive>,
+) -> impl Responder {
+    let conn = match open(&data.duckdb_path) {
+        Ok(conn) => conn,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+
+    let query_filter = query.to_query_filter();
+    match get_data(&conn, &query_filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// Forecasts added up over the zones, by `hour` or averaged by `bucket` and
+/// delivery day, for each vintage, e.g.
+/// /ieso/vg_forecast/aggregate/bucket?forecast_timestamp_gte=2025-09-08T00:00:00-05:00&forecast_timestamp_lt=2025-09-09T00:00:00-05:00&buckets=5x16,7x8
+#[get("/ieso/vg_forecast/aggregate/{resolution}")]
+pub async fn api_aggregate(
+    path: web::Path<String>,
+    query: web::Query<AggregateQuery>,
+    data: web::Data<IesoVGForecastSummaryArchive>,
+) -> impl Responder {
+    let (Some(gte), Some(lt)) = (&query.forecast_timestamp_gte, &query.forecast_timestamp_lt) else {
+        return HttpResponse::BadRequest()
+            .body("Both forecast_timestamp_gte and forecast_timestamp_lt are required");
+    };
+    let conn = match open(&data.duckdb_path) {
+        Ok(conn) => conn,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    let mut builder = QueryFilterBuilder::new()
+        .forecast_timestamp_gte(gte.clone())
+        .forecast_timestamp_lt(lt.clone())
+        .organization(query.organization.as_deref().unwrap_or("MARKET PARTICIPANT"));
+    if let Some(fuel_type) = &query.fuel_type {
+        builder = builder.fuel_type(fuel_type.clone());
+    }
+    if let Some(zones) = &query.zone_in {
+        builder = builder.zone_in(zones.split(',').map(|e| e.trim().to_string()));
+    }
+    let records = match get_data(&conn, &builder.build(), None) {
+        Ok(records) => records,
+        Err(e) => return HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    };
+    let hourly = aggregate_zones(&records);
+    match path.as_str() {
+        "hour" => HttpResponse::Ok().json(hourly),
+        "bucket" => match query.buckets() {
+            Ok(buckets) => HttpResponse::Ok().json(aggregate_by_bucket(&hourly, &buckets)),
+            Err(e) => HttpResponse::BadRequest().body(e),
+        },
+        _ => HttpResponse::BadRequest().body(format!(
+            "Invalid resolution {}, use one of hour, bucket",
+            path
+        )),
+    }
+}
+
+/// Accuracy of the market participant `wind` or `solar` forecasts issued
+/// between two dates against the actual output, by horizon (day 1 is the
+/// issue day) and by `month`, `hour` or `month_hour`, e.g.
+/// /ieso/vg_forecast/accuracy/wind/start/2025-09-01/end/2025-09-30?group_by=hour
+#[get("/ieso/vg_forecast/accuracy/{fuel_type}/start/{start}/end/{end}")]
+pub async fn api_accuracy(
+    path: web::Path<(String, Date, Date)>,
+    query: web::Query<AccuracyQuery>,
+    db: web::Data<(IesoVGForecastSummaryArchive, IesoGenOutputByFuelArchive)>,
+) -> impl Responder {
+    let (fuel_type, start, end) = path.into_inner();
+    if !["wind", "solar"].contains(&fuel_type.to_lowercase().as_str()) {
+        return HttpResponse::BadRequest().body(format!("Invalid fuel type {}, use wind or solar", fuel_type));
+    }
+    if end < start {
+        return HttpResponse::BadRequest().body("The end date needs to be after the start date");
+    }
+    let conn = match attach(&db.0.duckdb_path, &db.1.duckdb_path) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    let (forecasts, actuals) =
+        match forecast_accuracy::load_ieso_vg(&conn, &fuel_type, query.zone.as_deref(), start, end) {
+            Ok(v) => v,
+            Err(e) => return HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+        };
+    let matched = forecast_accuracy::match_actuals(&forecasts, &actuals, query.max_horizon.unwrap_or(7));
+    HttpResponse::Ok().json(forecast_accuracy::accuracy(
+        &matched,
+        query.group_by.unwrap_or(Grouping::Month),
+        query.min_actual_mw.unwrap_or(1.0),
+    ))
+}
+
+fn open(duckdb_path: &str) -> Result<Connection, String> {
+    open_with_retry(duckdb_path, 8, Duration::from_millis(25), AccessMode::ReadOnly)
+        .map_err(|e| format!("Error opening DuckDB database at {}: {}", duckdb_path, e))
+}
+
+fn attach(forecast_path: &str, gen_path: &str) -> Result<Connection, String> {
+    let conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+    conn.execute_batch(&format!(
+        "ATTACH '{}' AS forecast_db (READ_ONLY);\nATTACH '{}' AS gen_db (READ_ONLY);",
+        forecast_path, gen_path
+    ))
+    .map_err(|e| format!("Error attaching the DuckDB databases: {}", e))?;
+    Ok(conn)
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiQuery {
+    pub forecast_timestamp: Option<Zoned>,
+    pub forecast_timestamp_gte: Option<Zoned>,
+    pub forecast_timestamp_lt: Option<Zoned>,
+    /// One of `MARKET PARTICIPANT` or `EMBEDDED`
+    pub organization: Option<String>,
+    /// One of `Wind` or `Solar`
+    pub fuel_type: Option<String>,
+    pub zone: Option<String>,
+    /// One or more zones, separated by commas
+    pub zone_in: Option<String>,
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    pub _limit: Option<usize>,
+}
+
+impl ApiQuery {
+    pub fn to_query_filter(&self) -> QueryFilter {
+        QueryFilter {
+            forecast_timestamp: self.forecast_timestamp.clone(),
+            forecast_timestamp_gte: self.forecast_timestamp_gte.clone(),
+            forecast_timestamp_lt: self.forecast_timestamp_lt.clone(),
+            organization: self.organization.clone(),
+            fuel_type: self.fuel_type.clone(),
+            zone: self.zone.clone(),
+            zone_in: self
+                .zone_in
+                .as_ref()
+                .map(|s| s.split(',').map(|e| e.trim().to_string()).collect()),
+            hour_beginning_gte: self.hour_beginning_gte.clone(),
+            hour_beginning_lt: self.hour_beginning_lt.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AggregateQuery {
+    pub forecast_timestamp_gte: Option<Zoned>,
+    pub forecast_timestamp_lt: Option<Zoned>,
+    /// Default: MARKET PARTICIPANT
+    pub organization: Option<String>,
+    pub fuel_type: Option<String>,
+    /// One or more zones, separated by commas
+    pub zone_in: Option<String>,
+    /// One or more bucket names, separated by commas.  Default: atc
+    pub buckets: Option<String>,
+}
+
+impl AggregateQuery {
+    fn buckets(&self) -> Result<Vec<Bucket>, String> {
+        match &self.buckets {
+            Some(names) => names.split(',').map(|e| e.trim().parse::<Bucket>()).collect(),
+            None => Ok(vec![Bucket::Atc]),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuracyQuery {
+    /// Only one zone, default is the sum over all the zones
+    pub zone: Option<String>,
+    /// One of `month`, `hour`, `month_hour`.  Default: month
+    pub group_by: Option<Grouping>,
+    /// Hours with a lower actual are left out of the MAPE.  Default: 1.0
+    pub min_actual_mw: Option<f64>,
+    /// Default: 7
+    pub max_horizon: Option<u8>,
+}
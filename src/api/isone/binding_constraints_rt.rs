@@ -0,0 +1,125 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::{AccessMode, Connection};
+use jiff::Zoned;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::db::isone::binding_constraints_da::IsoneDaBindingConstraintsArchive;
+use crate::db::isone::binding_constraints_rt::*;
+use crate::interval::term::Term;
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// The ISONE RT binding constraints, `report` is one of `5min` or `hourly`, e.g.
+/// /isone/binding_constraints/rt/5min?constraint_name=SHFHGE&interval_beginning_gte=2025-07-01T00:00:00-04:00[America/New_York]
+#[get("/isone/binding_constraints/rt/{report}")]
+pub async fn get_data_api(
+    path: web::Path<String>,
+    query: web::Query<ApiQuery>,
+    data: web::Data<IsoneRtBindingConstraintsArchive>,
+) -> impl Responder {
+    let report: RtConstraintReport = match path.parse() {
+        Ok(r) => r,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn = match open(&data.duckdb_path) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    match get_data(&conn, report, &query.to_query_filter(), query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// DA vs. RT binding frequency and cumulative shadow price for each constraint
+/// over a term.  The RT statistics use the 5-minute report unless
+/// `report=hourly`, e.g.
+/// /isone/binding_constraints/da_rt/stats?term=Q1,25&constraint_name_like=NNE%25
+#[get("/isone/binding_constraints/da_rt/stats")]
+pub async fn get_binding_stats_api(
+    query: web::Query<StatsQuery>,
+    data: web::Data<(IsoneDaBindingConstraintsArchive, IsoneRtBindingConstraintsArchive)>,
+) -> impl Responder {
+    let term: Term = match query.term.parse() {
+        Ok(t) => t,
+        Err(e) => return HttpResponse::BadRequest().body(format!("Invalid term: {}", e)),
+    };
+    let report: RtConstraintReport = match &query.report {
+        Some(r) => match r.parse() {
+            Ok(r) => r,
+            Err(e) => return HttpResponse::BadRequest().body(e),
+        },
+        None => RtConstraintReport::FiveMinute,
+    };
+    let conn = match open(&data.1.duckdb_path) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    if let Err(e) = attach_da_binding_constraints(&conn, &data.0.duckdb_path) {
+        return HttpResponse::InternalServerError()
+            .body(format!("Error attaching DuckDB database: {}", e));
+    }
+    let filter = QueryFilter {
+        interval_beginning_gte: None,
+        interval_beginning_lt: None,
+        constraint_name: query.constraint_name.clone(),
+        constraint_name_like: query.constraint_name_like.clone(),
+        constraint_name_in: query
+            .constraint_name_in
+            .as_ref()
+            .map(|s| s.split(',').map(|v| v.trim().to_string()).collect()),
+    };
+    match get_binding_stats(&conn, &term, report, &filter) {
+        Ok(records) => HttpResponse::Ok().json(records),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+fn open(duckdb_path: &str) -> Result<Connection, String> {
+    open_with_retry(duckdb_path, 8, Duration::from_millis(25), AccessMode::ReadOnly)
+        .map_err(|e| format!("Error opening DuckDB database at {}: {}", duckdb_path, e))
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiQuery {
+    pub interval_beginning_gte: Option<Zoned>,
+    pub interval_beginning_lt: Option<Zoned>,
+    pub constraint_name: Option<String>,
+    pub constraint_name_like: Option<String>,
+    pub constraint_name_in: Option<String>,
+    pub _limit: Option<usize>,
+}
+
+impl ApiQuery {
+    pub fn to_query_filter(&self) -> QueryFilter {
+        QueryFilter {
+            interval_beginning_gte: self.interval_beginning_gte.clone(),
+            interval_beginning_lt: self.interval_beginning_lt.clone(),
+            constraint_name: self.constraint_name.clone(),
+            constraint_name_like: self.constraint_name_like.clone(),
+            constraint_name_in: self
+                .constraint_name_in
+                .as_ref()
+                .map(|s| s.split(',').map(|v| v.trim().to_string()).collect()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StatsQuery {
+    /// A term like `Cal25`, `Q1,25` or `Jan25-Mar25`
+    pub term: String,
+    /// `5min` (default) or `hourly`
+    pub report: Option<String>,
+    pub constraint_name: Option<String>,
+    pub constraint_name_like: Option<String>,
+    /// One or more constraint names, separated by commas
+    pub constraint_name_in: Option<String>,
+}
@@ -0,0 +1,99 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::{AccessMode, Connection};
+use jiff::civil::Date;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::db::isone::capacity_auction_results_archive::{
+    self, IsoneCapacityAuctionResultsArchive,
+};
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// Capacity auction clearing results by capacity zone, e.g.
+/// /isone/capacity/results/zone?auction=MRA&period_start_gte=2025-06-01
+/// /isone/capacity/results/zone?auction=MRA&capability_period=2024-25&name=Rest-of-Pool
+#[get("/isone/capacity/results/zone")]
+pub async fn api_zone_results(
+    query: web::Query<ApiQuery>,
+    data: web::Data<IsoneCapacityAuctionResultsArchive>,
+) -> impl Responder {
+    let conn = match open(&data) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    match capacity_auction_results_archive::get_zone_results(
+        &conn,
+        &query.to_query_filter(),
+        query._limit,
+    ) {
+        Ok(records) => HttpResponse::Ok().json(records),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// Capacity auction clearing results by external interface, e.g.
+/// /isone/capacity/results/interface?auction=MRA&capability_period=2025-26
+#[get("/isone/capacity/results/interface")]
+pub async fn api_interface_results(
+    query: web::Query<ApiQuery>,
+    data: web::Data<IsoneCapacityAuctionResultsArchive>,
+) -> impl Responder {
+    let conn = match open(&data) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    match capacity_auction_results_archive::get_interface_results(
+        &conn,
+        &query.to_query_filter(),
+        query._limit,
+    ) {
+        Ok(records) => HttpResponse::Ok().json(records),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+fn open(data: &IsoneCapacityAuctionResultsArchive) -> Result<Connection, String> {
+    open_with_retry(
+        &data.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    )
+    .map_err(|e| {
+        format!(
+            "Error opening DuckDB database at {}: {}",
+            data.duckdb_path, e
+        )
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiQuery {
+    /// One of FCA, ARA1, ARA2, ARA3, MRA
+    pub auction: Option<String>,
+    /// One or more auctions, separated by commas
+    pub auction_in: Option<String>,
+    /// A capability period like 2025-26
+    pub capability_period: Option<String>,
+    pub period_start_gte: Option<Date>,
+    pub period_start_lte: Option<Date>,
+    /// The capacity zone or the external interface name
+    pub name: Option<String>,
+    pub _limit: Option<usize>,
+}
+
+impl ApiQuery {
+    pub fn to_query_filter(&self) -> capacity_auction_results_archive::QueryFilter {
+        capacity_auction_results_archive::QueryFilter {
+            auction: self.auction.clone(),
+            auction_in: self
+                .auction_in
+                .as_ref()
+                .map(|s| s.split(',').map(|v| v.trim().to_string()).collect()),
+            capability_period: self.capability_period.clone(),
+            period_start_gte: self.period_start_gte,
+            period_start_lte: self.period_start_lte,
+            name: self.name.clone(),
+        }
+    }
+}
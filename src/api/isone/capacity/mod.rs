@@ -1,3 +1,4 @@
 pub mod annual_reconfiguration_bidsoffers;
+pub mod auction_results;
 pub mod monthly_capacity_bidsoffers;
 pub mod monthly_capacity_results;
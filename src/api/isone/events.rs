@@ -0,0 +1,102 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::AccessMode;
+use serde::Deserialize;
+use std::time::Duration;
+
+use jiff::{Timestamp, Zoned};
+
+use crate::db::isone::calendar_events::{self, IsoneEventsCalendarArchive};
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// Events from the ISONE calendar, by default only the upcoming ones, e.g.
+/// /isone/events?category_in=Auction,Market Trial,Stakeholder Meeting
+/// /isone/events?title_like=%25FCA%25&start_time_gte=2026-01-01T00:00:00-05:00[America/New_York]
+#[get("/isone/events")]
+pub async fn api_events(
+    query: web::Query<EventsQuery>,
+    data: web::Data<IsoneEventsCalendarArchive>,
+) -> impl Responder {
+    match get_events(&query, &data) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+/// Same as `/isone/events` but exported as an iCalendar file that can be
+/// imported in Outlook or Google Calendar, e.g.
+/// /isone/events/ical?category=Auction
+#[get("/isone/events/ical")]
+pub async fn api_events_ical(
+    query: web::Query<EventsQuery>,
+    data: web::Data<IsoneEventsCalendarArchive>,
+) -> impl Responder {
+    match get_events(&query, &data) {
+        Ok(records) => HttpResponse::Ok()
+            .content_type("text/calendar; charset=utf-8")
+            .insert_header((
+                "Content-Disposition",
+                "attachment; filename=\"isone_events.ics\"",
+            ))
+            .body(calendar_events::to_icalendar(&records, &Timestamp::now())),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+fn get_events(
+    query: &EventsQuery,
+    data: &IsoneEventsCalendarArchive,
+) -> Result<Vec<calendar_events::Record>, String> {
+    let conn = open_with_retry(
+        &data.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    )
+    .map_err(|e| {
+        format!(
+            "Error opening DuckDB database at {}: {}",
+            data.duckdb_path, e
+        )
+    })?;
+    calendar_events::get_data(&conn, &query.to_query_filter(), query._limit)
+        .map_err(|e| format!("Error querying data: {}", e))
+}
+
+#[derive(Debug, Deserialize)]
+struct EventsQuery {
+    /// If not specified, only return the events that start from now on.
+    pub start_time_gte: Option<Zoned>,
+    pub start_time_lt: Option<Zoned>,
+    pub category: Option<String>,
+    /// One or more categories, separated by commas
+    pub category_in: Option<String>,
+    /// A case insensitive SQL LIKE pattern, e.g. `%FCA%`
+    pub title_like: Option<String>,
+    pub _limit: Option<usize>,
+}
+
+impl EventsQuery {
+    pub fn to_query_filter(&self) -> calendar_events::QueryFilter {
+        calendar_events::QueryFilter {
+            start_time_gte: Some(
+                self.start_time_gte
+                    .clone()
+                    .unwrap_or_else(|| Zoned::now().in_tz("America/New_York").unwrap()),
+            ),
+            start_time_lt: self.start_time_lt.clone(),
+            category: self.category.clone(),
+            category_in: self
+                .category_in
+                .as_ref()
+                .map(|s| s.split(',').map(|v| v.trim().to_string()).collect()),
+            title_like: self.title_like.clone(),
+        }
+    }
+}
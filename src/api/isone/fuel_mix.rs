@@ -0,0 +1,182 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::{AccessMode, Connection};
+use jiff::Zoned;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::db::isone::fuelmix_archive::*;
+use crate::time::bucket::Bucket;
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// Generation by fuel category at the native 5-minute resolution
+#[get("/isone/fuel_mix")]
+pub async fn get_data_api(
+    query: web::Query<ApiQuery>,
+    data: web::Data<IsoneFuelMixArchive>,
+) -> impl Responder {
+    let conn = match open(&data.duckdb_path) {
+        Ok(conn) => conn,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+
+    let query_filter = query.to_query_filter();
+    match get_data(&conn, &query_filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// Generation by fuel category (or rollup with `rollup=true`) aggregated by
+/// `hour`, `day` or `bucket`, e.g.
+/// /isone/fuel_mix/aggregate/hour?timestamp_gte=2025-07-01T00:00:00-04:00&timestamp_lt=2025-07-02T00:00:00-04:00&rollup=true
+/// /isone/fuel_mix/aggregate/bucket?timestamp_gte=...&timestamp_lt=...&buckets=5x16,2x16H,7x8
+#[get("/isone/fuel_mix/aggregate/{resolution}")]
+pub async fn get_aggregate_api(
+    path: web::Path<String>,
+    query: web::Query<AggregateQuery>,
+    data: web::Data<IsoneFuelMixArchive>,
+) -> impl Responder {
+    let records = match query_records(&query, &data.duckdb_path) {
+        Ok(records) => records,
+        Err(e) => return *e,
+    };
+    let rollup = query.rollup.unwrap_or(false);
+    match path.as_str() {
+        "hour" => HttpResponse::Ok().json(aggregate(&records, Resolution::Hour, rollup)),
+        "day" => HttpResponse::Ok().json(aggregate(&records, Resolution::Day, rollup)),
+        "bucket" => match query.buckets() {
+            Ok(buckets) => HttpResponse::Ok().json(aggregate_by_bucket(&records, &buckets, rollup)),
+            Err(e) => HttpResponse::BadRequest().body(e),
+        },
+        _ => HttpResponse::BadRequest().body(format!(
+            "Invalid resolution {}, use one of hour, day, bucket",
+            path
+        )),
+    }
+}
+
+/// Frequency with which each fuel category was marginal, by `hour` or by
+/// `bucket`.  The hourly series has the same hour_beginning as the LMPs, e.g.
+/// /isone/fuel_mix/marginal/hour?timestamp_gte=2025-07-01T00:00:00-04:00&timestamp_lt=2025-07-02T00:00:00-04:00
+#[get("/isone/fuel_mix/marginal/{resolution}")]
+pub async fn get_marginal_api(
+    path: web::Path<String>,
+    query: web::Query<AggregateQuery>,
+    data: web::Data<IsoneFuelMixArchive>,
+) -> impl Responder {
+    let records = match query_records(&query, &data.duckdb_path) {
+        Ok(records) => records,
+        Err(e) => return *e,
+    };
+    match path.as_str() {
+        "hour" => HttpResponse::Ok().json(marginal_frequency(&records)),
+        "bucket" => match query.buckets() {
+            Ok(buckets) => HttpResponse::Ok().json(marginal_frequency_by_bucket(&records, &buckets)),
+            Err(e) => HttpResponse::BadRequest().body(e),
+        },
+        _ => HttpResponse::BadRequest().body(format!(
+            "Invalid resolution {}, use one of hour, bucket",
+            path
+        )),
+    }
+}
+
+fn open(duckdb_path: &str) -> Result<Connection, String> {
+    open_with_retry(duckdb_path, 8, Duration::from_millis(25), AccessMode::ReadOnly)
+        .map_err(|e| format!("Error opening DuckDB database at {}: {}", duckdb_path, e))
+}
+
+fn query_records(query: &AggregateQuery, duckdb_path: &str) -> Result<Vec<Record>, Box<HttpResponse>> {
+    let (Some(gte), Some(lt)) = (&query.timestamp_gte, &query.timestamp_lt) else {
+        return Err(Box::new(
+            HttpResponse::BadRequest().body("Both timestamp_gte and timestamp_lt are required"),
+        ));
+    };
+    let conn = open(duckdb_path).map_err(|e| Box::new(HttpResponse::InternalServerError().body(e)))?;
+    let mut builder = QueryFilterBuilder::new()
+        .timestamp_gte(gte.clone())
+        .timestamp_lt(lt.clone());
+    if let Some(fuels) = &query.fuel_category_in {
+        builder = builder.fuel_category_in(fuels.split(',').map(|e| e.trim().to_string()));
+    }
+    get_data(&conn, &builder.build(), None)
+        .map_err(|e| Box::new(HttpResponse::InternalServerError().body(format!("Error querying data: {}", e))))
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiQuery {
+    pub timestamp: Option<Zoned>,
+    pub timestamp_gte: Option<Zoned>,
+    pub timestamp_lt: Option<Zoned>,
+    pub fuel_category: Option<String>,
+    /// One or more fuel categories, separated by commas
+    pub fuel_category_in: Option<String>,
+    pub fuel_category_rollup: Option<String>,
+    pub marginal_flag: Option<bool>,
+    pub _limit: Option<usize>,
+}
+
+impl ApiQuery {
+    pub fn to_query_filter(&self) -> QueryFilter {
+        QueryFilter {
+            timestamp: self.timestamp.clone(),
+            timestamp_gte: self.timestamp_gte.clone(),
+            timestamp_lt: self.timestamp_lt.clone(),
+            fuel_category: self.fuel_category.clone(),
+            fuel_category_in: self
+                .fuel_category_in
+                .as_ref()
+                .map(|s| s.split(',').map(|e| e.trim().to_string()).collect()),
+            fuel_category_rollup: self.fuel_category_rollup.clone(),
+            marginal_flag: self.marginal_flag,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AggregateQuery {
+    pub timestamp_gte: Option<Zoned>,
+    pub timestamp_lt: Option<Zoned>,
+    /// One or more fuel categories, separated by commas
+    pub fuel_category_in: Option<String>,
+    /// Aggregate by fuel category rollup instead of fuel category, default false
+    pub rollup: Option<bool>,
+    /// One or more bucket names, separated by commas.  Default: atc
+    pub buckets: Option<String>,
+}
+
+impl AggregateQuery {
+    fn buckets(&self) -> Result<Vec<Bucket>, String> {
+        match &self.buckets {
+            Some(names) => names.split(',').map(|e| e.trim().parse::<Bucket>()).collect(),
+            None => Ok(vec![Bucket::Atc]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod api_tests {
+    use super::*;
+    use crate::db::prod_db::ProdDb;
+    use actix_web::{test, web, App};
+
+    #[actix_web::test]
+    async fn test_get_data_api() {
+        let data = web::Data::new(ProdDb::isone_fuel_mix());
+        let app = test::init_service(App::new().app_data(data.clone()).service(get_data_api)).await;
+        let params = QueryFilterBuilder::new().build().to_query_url();
+        let uri = format!("/isone/fuel_mix?{}&_limit=5", params);
+        let req = test::TestRequest::get().uri(&uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        let rs: Vec<Record> = test::read_body_json(resp).await;
+        assert_eq!(rs.len(), 5);
+    }
+}
@@ -0,0 +1,329 @@
+use std::time::Duration;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::{AccessMode, Connection};
+use itertools::Itertools;
+use jiff::{tz::TimeZone, Timestamp, Zoned};
+use serde::{Deserialize, Serialize};
+
+use crate::db::isone::{
+    actual_interchange_archive::IsoneActualInterchangeArchive,
+    single_source_contingency_archive::SingleSourceContingencyArchive,
+    total_transfer_capability_archive::TotalTransferCapabilityArchive,
+};
+use crate::utils::lib_duckdb::open_with_retry;
+use crate::utils::serde_helpers::*;
+
+/// An ISONE external interface, with the names used by the different reports.
+pub struct ExternalInterface {
+    /// The name used in the TTC table, e.g. `hq_phase2`
+    pub name: &'static str,
+    /// The ptid of the external node, used in the actual interchange report
+    pub ptid: u32,
+    /// The interface name in the SSC report, if the interface has an SSC limit
+    pub ssc_name: Option<&'static str>,
+}
+
+pub const EXTERNAL_INTERFACES: [ExternalInterface; 6] = [
+    ExternalInterface {
+        name: "ny_north",
+        ptid: 4010,
+        ssc_name: None,
+    },
+    ExternalInterface {
+        name: "hq_phase2",
+        ptid: 4011,
+        ssc_name: Some("Phase II"),
+    },
+    ExternalInterface {
+        name: "hq_highgate",
+        ptid: 4012,
+        ssc_name: None,
+    },
+    ExternalInterface {
+        name: "nb",
+        ptid: 4013,
+        ssc_name: Some("New Brunswick"),
+    },
+    ExternalInterface {
+        name: "ny_csc",
+        ptid: 4014,
+        ssc_name: None,
+    },
+    ExternalInterface {
+        name: "ny_northport",
+        ptid: 4017,
+        ssc_name: None,
+    },
+];
+
+/// Hourly transfer limits and flows for one external interface.  Flows are
+/// positive for imports into New England.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeadroomRecord {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    pub interface: String,
+    pub ptid: u32,
+    pub ttc_import_mw: f64,
+    pub ttc_export_mw: f64,
+    /// The lowest SSC limit in the hour, if the interface has one
+    pub ssc_limit_mw: Option<f64>,
+    pub actual_flow_mw: Option<f64>,
+    /// The import limit (TTC or SSC, whichever is lower) minus the flow
+    pub import_headroom_mw: Option<f64>,
+    /// The export limit plus the flow
+    pub export_headroom_mw: Option<f64>,
+}
+
+/// Hourly SSC limit, TTC and actual flows for each external interface, e.g.
+/// /isone/interface_headroom?interface_in=hq_phase2,nb&hour_beginning_gte=2025-05-04T00:00:00-04:00[America/New_York]
+#[get("/isone/interface_headroom")]
+pub async fn api_interface_headroom(
+    query: web::Query<HeadroomQuery>,
+    data: web::Data<(
+        SingleSourceContingencyArchive,
+        TotalTransferCapabilityArchive,
+        IsoneActualInterchangeArchive,
+    )>,
+) -> impl Responder {
+    let conn = match open_with_retry(
+        &data.1.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    ) {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError().body(format!(
+                "Error opening DuckDB database at {}: {}",
+                data.1.duckdb_path, e
+            ))
+        }
+    };
+    let sql = format!(
+        "ATTACH '{}' AS ttc_db (READ_ONLY);\nATTACH '{}' AS ssc_db (READ_ONLY);\nATTACH '{}' AS flows_db (READ_ONLY);",
+        data.1.duckdb_path, data.0.duckdb_path, data.2.duckdb_path
+    );
+    if let Err(e) = conn.execute_batch(&sql) {
+        return HttpResponse::InternalServerError()
+            .body(format!("Error attaching DuckDB databases: {}", e));
+    }
+    let interfaces: Option<Vec<String>> = query
+        .interface_in
+        .as_ref()
+        .map(|s| s.split(',').map(|v| v.trim().to_string()).collect());
+    match get_headroom(
+        &conn,
+        query.hour_beginning_gte.as_ref(),
+        query.hour_beginning_lt.as_ref(),
+        interfaces,
+    ) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct HeadroomQuery {
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    /// One or more interface names, separated by commas, e.g. `hq_phase2,nb`.
+    /// If not specified, return all interfaces.
+    pub interface_in: Option<String>,
+}
+
+/// Join the TTC limits with the hourly SSC limits and the actual flows.
+/// The three databases need to be attached as `ttc_db`, `ssc_db` and `flows_db`.
+pub fn get_headroom(
+    conn: &Connection,
+    hour_beginning_gte: Option<&Zoned>,
+    hour_beginning_lt: Option<&Zoned>,
+    interfaces: Option<Vec<String>>,
+) -> Result<Vec<HeadroomRecord>, Box<dyn std::error::Error>> {
+    let selected: Vec<&ExternalInterface> = EXTERNAL_INTERFACES
+        .iter()
+        .filter(|e| match &interfaces {
+            Some(xs) => xs.iter().any(|x| x == e.name),
+            None => true,
+        })
+        .collect();
+    if selected.is_empty() {
+        return Ok(vec![]);
+    }
+    let mapping = selected
+        .iter()
+        .map(|e| {
+            format!(
+                "('{}', {}, {})",
+                e.name,
+                e.ptid,
+                e.ssc_name
+                    .map(|s| format!("'{}'", s))
+                    .unwrap_or("NULL".to_string())
+            )
+        })
+        .join(", ");
+    let ttc = selected
+        .iter()
+        .map(|e| {
+            format!(
+                "SELECT hour_beginning, '{0}' AS interface, {0}_import AS ttc_import, {0}_export AS ttc_export FROM ttc_db.ttc_limits",
+                e.name
+            )
+        })
+        .join("\n    UNION ALL ");
+    let mut filter = String::new();
+    if let Some(gte) = hour_beginning_gte {
+        filter.push_str(&format!(
+            "\n    AND t.hour_beginning >= '{}'",
+            gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(lt) = hour_beginning_lt {
+        filter.push_str(&format!(
+            "\n    AND t.hour_beginning < '{}'",
+            lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+
+    let query = format!(
+        r#"
+WITH mapping(interface, ptid, ssc_name) AS (
+    VALUES {mapping}
+),
+ttc AS (
+    {ttc}
+),
+ssc AS (
+    SELECT
+        to_timestamp(epoch_us(begin_date) // 3600000000 * 3600) AS hour_beginning,
+        interface_name,
+        min(single_source_contingency_limit_mw) AS ssc_limit
+    FROM ssc_db.ssc
+    GROUP BY ALL
+)
+SELECT
+    t.hour_beginning,
+    t.interface,
+    m.ptid,
+    t.ttc_import::DOUBLE,
+    t.ttc_export::DOUBLE,
+    s.ssc_limit,
+    f.Net::DOUBLE
+FROM ttc t
+JOIN mapping m ON m.interface = t.interface
+LEFT JOIN ssc s
+    ON s.hour_beginning = t.hour_beginning AND s.interface_name = m.ssc_name
+LEFT JOIN flows_db.flows f
+    ON f.hour_beginning = t.hour_beginning AND f.ptid = m.ptid
+WHERE 1=1{filter}
+ORDER BY t.hour_beginning, t.interface;
+"#
+    );
+
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        let ttc_import_mw = row.get::<usize, f64>(3)?;
+        let ttc_export_mw = row.get::<usize, f64>(4)?;
+        let ssc_limit_mw = row.get::<usize, Option<f64>>(5)?;
+        let actual_flow_mw = row.get::<usize, Option<f64>>(6)?;
+        let import_limit = match ssc_limit_mw {
+            Some(ssc) => ssc.min(ttc_import_mw),
+            None => ttc_import_mw,
+        };
+        Ok(HeadroomRecord {
+            hour_beginning: Zoned::new(Timestamp::from_microsecond(_micros0).unwrap(), tz.clone()),
+            interface: row.get::<usize, String>(1)?,
+            ptid: row.get::<usize, u32>(2)?,
+            ttc_import_mw,
+            ttc_export_mw,
+            ssc_limit_mw,
+            actual_flow_mw,
+            import_headroom_mw: actual_flow_mw.map(|flow| import_limit - flow),
+            export_headroom_mw: actual_flow_mw.map(|flow| ttc_export_mw + flow),
+        })
+    })?;
+    let results: Vec<HeadroomRecord> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_headroom() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        let columns = TotalTransferCapabilityArchive::all_columns()
+            .iter()
+            .map(|e| format!("{} int64 NOT NULL", e))
+            .join(", ");
+        conn.execute_batch(&format!(
+            r"
+ATTACH ':memory:' AS ttc_db;
+ATTACH ':memory:' AS ssc_db;
+ATTACH ':memory:' AS flows_db;
+CREATE TABLE ttc_db.ttc_limits (hour_beginning TIMESTAMPTZ NOT NULL, {});
+INSERT INTO ttc_db.ttc_limits VALUES
+    ('2025-05-04 10:00:00-04:00', 1400, 1200, 200, 200, 346, 330, 1000, 550, 217, 100, 2000, 1200);
+CREATE TABLE ssc_db.ssc (
+    begin_date TIMESTAMPTZ NOT NULL,
+    rt_flow_mw DOUBLE NOT NULL,
+    lowest_limit_mw DOUBLE NOT NULL,
+    distribution_factor DOUBLE NOT NULL,
+    interface_name VARCHAR NOT NULL,
+    actual_margin_mw DOUBLE NOT NULL,
+    authorized_margin_mw DOUBLE NOT NULL,
+    base_limit_mw DOUBLE NOT NULL,
+    single_source_contingency_limit_mw DOUBLE NOT NULL
+);
+INSERT INTO ssc_db.ssc VALUES
+    ('2025-05-04 10:00:00-04:00', 1450, 1400, 1.0, 'Phase II', 250, 200, 2000, 1650),
+    ('2025-05-04 10:05:00-04:00', 1460, 1400, 1.0, 'Phase II', 240, 200, 2000, 1640);
+CREATE TABLE flows_db.flows (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    ptid UINTEGER NOT NULL,
+    Net DECIMAL(9,2) NOT NULL,
+    Purchase DECIMAL(9,2) NOT NULL,
+    Sale DECIMAL(9,2) NOT NULL,
+);
+INSERT INTO flows_db.flows VALUES
+    ('2025-05-04 10:00:00-04:00', 4011, 1455, 1455, 0),
+    ('2025-05-04 10:00:00-04:00', 4012, 210, 210, 0),
+    ('2025-05-04 10:00:00-04:00', 4013, -120.5, 0, 120.5);
+",
+            columns
+        ))?;
+        let xs = get_headroom(
+            &conn,
+            None,
+            None,
+            Some(vec!["hq_phase2".to_string(), "nb".to_string()]),
+        )?;
+        assert_eq!(xs.len(), 2);
+        let phase2 = &xs[0];
+        assert_eq!(phase2.interface, "hq_phase2");
+        assert_eq!(phase2.ssc_limit_mw, Some(1640.0));
+        assert_eq!(phase2.import_headroom_mw, Some(185.0));
+        assert_eq!(phase2.export_headroom_mw, Some(2655.0));
+        let nb = &xs[1];
+        assert_eq!(nb.ssc_limit_mw, None);
+        assert_eq!(nb.import_headroom_mw, Some(1120.5));
+        assert_eq!(nb.export_headroom_mw, Some(429.5));
+        Ok(())
+    }
+}
@@ -6,7 +6,11 @@ use crate::{
     api::isone::_api_isone_core::Market,
     db::{
         calendar::buckets::BucketsArchive,
-        isone::{dalmp_archive::IsoneDaLmpArchive, rtlmp_archive::IsoneRtLmpArchive},
+        isone::{
+            dalmp_archive::{get_revisions, IsoneDaLmpArchive, QueryFilterBuilder},
+            rtlmp_archive::IsoneRtLmpArchive,
+        },
+        revisions::RevisionSpec,
     },
     interval::{
         month::{month, Month},
@@ -49,27 +53,51 @@ async fn api_hourly_prices(
             AccessMode::ReadOnly,
         ),
     };
-    if conn.is_err() {
-        return HttpResponse::InternalServerError().body(format!(
-            "Error opening DuckDB database: {}",
-            conn.err().unwrap()
-        ));
-    }
+    let conn = match conn {
+        Ok(conn) => conn,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("Error opening DuckDB database: {}", e))
+        }
+    };
 
-    let ptids: Option<Vec<u32>> = query
+    let ptids: Option<Vec<u32>> = match query
         .ptids
         .as_ref()
-        .map(|ids| ids.split(',').map(|e| e.parse::<u32>().unwrap()).collect());
+        .map(|ids| ids.split(',').map(|e| e.trim().parse::<u32>()).collect())
+        .transpose()
+    {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::BadRequest().body(format!(
+                "Unable to parse {} to a list of ptids",
+                query.ptids.as_ref().unwrap()
+            ))
+        }
+    };
 
-    let components: Option<Vec<LmpComponent>> = query.components.as_ref().map(|ids| {
-        ids.split(',')
-            .map(|e| e.parse::<LmpComponent>().unwrap())
-            .collect()
-    });
+    let components: Option<Vec<LmpComponent>> = match query
+        .components
+        .as_ref()
+        .map(|ids| ids.split(',').map(|e| e.trim().parse::<LmpComponent>()).collect())
+        .transpose()
+    {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::BadRequest().body(format!(
+                "Unable to parse {} to a list of LMP components",
+                query.components.as_ref().unwrap()
+            ))
+        }
+    };
 
     let format = query.format.clone().unwrap_or("default".into());
     match format.as_str() {
         "compact" => {
+            if query.as_of.is_some() {
+                return HttpResponse::BadRequest()
+                    .body("Compact format doesn't support the as_of parameter");
+            }
             let component = match &components {
                 Some(cs) if cs.len() == 1 => cs[0],
                 _ => {
@@ -78,25 +106,86 @@ async fn api_hourly_prices(
                 }
             };
             let prices =
-                get_hourly_prices_compact(&conn.unwrap(), start_date, end_date, ptids, component)
-                    .unwrap();
+                match get_hourly_prices_compact(&conn, start_date, end_date, ptids, component) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        return HttpResponse::InternalServerError()
+                            .body(format!("Error querying DuckDB: {}", e))
+                    }
+                };
             use actix_web::http::header::HeaderName;
             HttpResponse::Ok()
                 .insert_header((HeaderName::from_static("content-type"), "application/json"))
                 .body(prices)
         }
-        _ => {
-            let offers = get_hourly_prices(
-                &conn.unwrap(),
-                start_date,
-                end_date,
-                market,
-                ptids,
-                components,
-            )
-            .unwrap();
-            HttpResponse::Ok().json(offers)
+        _ => match get_hourly_prices(
+            &conn,
+            start_date,
+            end_date,
+            &match market {
+                Market::DA => db.0.revision_spec(),
+                Market::RT => db.1.revision_spec(),
+            },
+            ptids,
+            components,
+            query.as_of.as_ref(),
+        ) {
+            Ok(offers) => HttpResponse::Ok().json(offers),
+            Err(e) => {
+                HttpResponse::InternalServerError().body(format!("Error querying DuckDB: {}", e))
+            }
+        },
+    }
+}
+
+/// Return all the versions of the DA hourly prices, ordered by insertion time.
+/// Requires revision tracking to be enabled for the archive.
+/// http://127.0.0.1:8111/isone/prices/da/revisions/start/2025-07-01/end/2025-07-01?ptids=4000
+#[get("/isone/prices/da/revisions/start/{start}/end/{end}")]
+async fn api_da_revisions(
+    path: web::Path<(Date, Date)>,
+    query: web::Query<RevisionsQuery>,
+    db: web::Data<(IsoneDaLmpArchive, IsoneRtLmpArchive, BucketsArchive)>,
+) -> impl Responder {
+    let (start_date, end_date) = path.into_inner();
+    let conn = match open_with_retry(
+        &db.0.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    ) {
+        Ok(conn) => conn,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("Error opening DuckDB database: {}", e))
         }
+    };
+
+    let mut builder = QueryFilterBuilder::new()
+        .hour_beginning_gte(start_date.in_tz("America/New_York").unwrap())
+        .hour_beginning_lt(
+            end_date
+                .tomorrow()
+                .unwrap()
+                .in_tz("America/New_York")
+                .unwrap(),
+        );
+    if let Some(ids) = &query.ptids {
+        let ptids: Vec<u32> = match ids.split(',').map(|e| e.trim().parse()).collect() {
+            Ok(v) => v,
+            Err(_) => {
+                return HttpResponse::BadRequest()
+                    .body(format!("Unable to parse {} to a list of ptids", ids))
+            }
+        };
+        builder = builder.ptid_in(ptids);
+    }
+    if let Some(as_of) = &query.as_of {
+        builder = builder.as_of(as_of.clone());
+    }
+    match get_revisions(&conn, &builder.build()) {
+        Ok(rows) => HttpResponse::Ok().json(rows),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying DuckDB: {}", e)),
     }
 }
 
@@ -318,6 +407,20 @@ struct LmpQuery {
     /// The compact format returns data with following shape:
     /// {'2025-01-01': {4000: <num>[...], 4001: <num>[...]}, '2025-01-02': {...}, ...}
     format: Option<String>,
+
+    /// Return the prices as known at this time, e.g. 2025-07-02T10:00:00-04:00[America/New_York].
+    /// Only supported for the default format.
+    as_of: Option<Zoned>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RevisionsQuery {
+    /// One or more ptids, separated by commas.
+    /// If not specified, return all ptids.
+    ptids: Option<String>,
+
+    /// Only return the versions inserted at or before this time.
+    as_of: Option<Zoned>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -373,20 +476,25 @@ pub struct Row {
     pub price: Decimal,
 }
 
-/// Get hourly prices between a [start, end] date for a list of ptids
+/// Get hourly prices between a [start, end] date for a list of ptids from `spec.table`.
+/// If `as_of` is set, return the prices as known at that time from the revisions table.
 ///
 pub fn get_hourly_prices(
     conn: &Connection,
     start: Date,
     end: Date,
-    market: Market,
+    spec: &RevisionSpec,
     ptids: Option<Vec<u32>>,
     components: Option<Vec<LmpComponent>>,
+    as_of: Option<&Zoned>,
 ) -> Result<Vec<Row>> {
     let query = format!(
         r#"
-WITH unpivot_alias AS (
-    UNPIVOT {}_lmp
+WITH prices AS (
+    SELECT * FROM {}
+),
+unpivot_alias AS (
+    UNPIVOT prices
     ON {}
     INTO
         NAME component
@@ -402,7 +510,10 @@ WHERE hour_beginning >= '{}'
 AND hour_beginning < '{}'{}
 ORDER BY component, ptid, hour_beginning; 
     "#,
-        market.to_string().to_lowercase(),
+        match as_of {
+            Some(t) => spec.latest_sql(Some(t)),
+            None => spec.table.clone(),
+        },
         match components {
             Some(cs) => cs.iter().join(", ").to_string(),
             None => "lmp, mcc, mcl".to_string(),
@@ -423,7 +534,7 @@ ORDER BY component, ptid, hour_beginning;
         },
     );
     // println!("{}", query);
-    let mut stmt = conn.prepare(&query).unwrap();
+    let mut stmt = conn.prepare(&query)?;
     let offers_iter = stmt.query_map([], |row| {
         let micro: i64 = row.get(0).unwrap();
         Ok(Row {
@@ -439,7 +550,7 @@ ORDER BY component, ptid, hour_beginning;
             },
         })
     })?;
-    let offers: Vec<Row> = offers_iter.map(|e| e.unwrap()).collect();
+    let offers: Vec<Row> = offers_iter.collect::<Result<_>>()?;
 
     Ok(offers)
 }
@@ -855,9 +966,10 @@ mod tests {
             &conn,
             date(2025, 7, 1),
             date(2025, 7, 14),
-            Market::DA,
+            &ProdDb::isone_dalmp().revision_spec(),
             Some(vec![4000]),
             Some(vec![LmpComponent::Lmp]),
+            None,
         )
         .unwrap();
         assert_eq!(data.len(), 24 * 14);
@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::AccessMode;
+use jiff::civil::Date;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::{
+    api::isone::lmp::get_hourly_prices as get_published_prices,
+    db::{
+        isone::{
+            rtlmp5_archive::{
+                get_hourly_prices, get_prices, validate_hourly, IsoneRtLmp5Archive, Rt5Version,
+            },
+            rtlmp_archive::IsoneRtLmpArchive,
+        },
+        nyiso::dalmp::LmpComponent,
+    },
+    utils::lib_duckdb::open_with_retry,
+};
+
+/// 5-minute RT prices, `version` is one of `prelim` or `final`.
+/// http://127.0.0.1:8111/isone/prices/rt5/final/start/2025-07-01/end/2025-07-01?ptids=4000&components=lmp
+#[get("/isone/prices/rt5/{version}/start/{start}/end/{end}")]
+async fn api_prices(
+    path: web::Path<(Rt5Version, Date, Date)>,
+    query: web::Query<Lmp5Query>,
+    db: web::Data<(IsoneRtLmp5Archive, IsoneRtLmpArchive)>,
+) -> impl Responder {
+    let (version, start, end) = path.into_inner();
+    let (ptids, components) = match (query.ptids(), query.components()) {
+        (Ok(p), Ok(c)) => (p, c),
+        (Err(e), _) | (_, Err(e)) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn = match open_with_retry(
+        &db.0.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    ) {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("Error opening DuckDB database: {}", e))
+        }
+    };
+    match get_prices(&conn, version, start, end, ptids, components) {
+        Ok(rows) => {
+            if rows.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", rows.len()))
+            } else {
+                HttpResponse::Ok().json(rows)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// 5-minute RT prices aggregated to hourly.
+/// http://127.0.0.1:8111/isone/prices/rt5/final/hourly/start/2025-07-01/end/2025-07-01?ptids=4000
+#[get("/isone/prices/rt5/{version}/hourly/start/{start}/end/{end}")]
+async fn api_hourly_prices(
+    path: web::Path<(Rt5Version, Date, Date)>,
+    query: web::Query<Lmp5Query>,
+    db: web::Data<(IsoneRtLmp5Archive, IsoneRtLmpArchive)>,
+) -> impl Responder {
+    let (version, start, end) = path.into_inner();
+    let (ptids, components) = match (query.ptids(), query.components()) {
+        (Ok(p), Ok(c)) => (p, c),
+        (Err(e), _) | (_, Err(e)) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn = match open_with_retry(
+        &db.0.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    ) {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("Error opening DuckDB database: {}", e))
+        }
+    };
+    match get_hourly_prices(&conn, version, start, end, ptids, components) {
+        Ok(rows) => {
+            if rows.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", rows.len()))
+            } else {
+                HttpResponse::Ok().json(rows)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// Compare the final 5-minute prices aggregated to hourly with the published
+/// hourly RT prices.  Return the hours that don't match.
+/// http://127.0.0.1:8111/isone/prices/rt5/validate/start/2025-07-01/end/2025-07-31?ptids=4000&tolerance=0.01
+#[get("/isone/prices/rt5/validate/start/{start}/end/{end}")]
+async fn api_validate_hourly(
+    path: web::Path<(Date, Date)>,
+    query: web::Query<Lmp5Query>,
+    db: web::Data<(IsoneRtLmp5Archive, IsoneRtLmpArchive)>,
+) -> impl Responder {
+    let (start, end) = path.into_inner();
+    let (ptids, components) = match (query.ptids(), query.components()) {
+        (Ok(p), Ok(c)) => (p, c),
+        (Err(e), _) | (_, Err(e)) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn5 = open_with_retry(
+        &db.0.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    );
+    let conn = open_with_retry(
+        &db.1.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    );
+    let (conn5, conn) = match (conn5, conn) {
+        (Ok(c5), Ok(c)) => (c5, c),
+        (Err(e), _) | (_, Err(e)) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("Error opening DuckDB database: {}", e))
+        }
+    };
+    let aggregated = match get_hourly_prices(
+        &conn5,
+        Rt5Version::Final,
+        start,
+        end,
+        ptids.clone(),
+        components.clone(),
+    ) {
+        Ok(rows) => rows,
+        Err(e) => {
+            return HttpResponse::InternalServerError().body(format!("Error querying data: {}", e))
+        }
+    };
+    let published = match get_published_prices(
+        &conn,
+        start,
+        end,
+        &db.1.revision_spec(),
+        ptids,
+        components,
+        None,
+    ) {
+        Ok(rows) => rows,
+        Err(e) => {
+            return HttpResponse::InternalServerError().body(format!("Error querying data: {}", e))
+        }
+    };
+    let tolerance = query.tolerance.unwrap_or(Decimal::new(1, 2));
+    HttpResponse::Ok().json(validate_hourly(&aggregated, &published, tolerance))
+}
+
+#[derive(Debug, Deserialize)]
+struct Lmp5Query {
+    /// One or more ptids, separated by commas.
+    /// If not specified, return all ptids.  Use carefully
+    /// because it's a lot of data...
+    ptids: Option<String>,
+
+    /// One or more components, separated by commas: lmp, mcc, mcl.
+    /// If not specified, return all of them.
+    components: Option<String>,
+
+    /// Only for validation, the largest difference allowed.  Default: 0.01
+    tolerance: Option<Decimal>,
+}
+
+impl Lmp5Query {
+    fn ptids(&self) -> Result<Option<Vec<u32>>, String> {
+        self.ptids
+            .as_ref()
+            .map(|ids| {
+                ids.split(',')
+                    .map(|e| e.trim().parse::<u32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| format!("Unable to parse {} to a list of ptids", ids))
+            })
+            .transpose()
+    }
+
+    fn components(&self) -> Result<Option<Vec<LmpComponent>>, String> {
+        self.components
+            .as_ref()
+            .map(|ids| {
+                ids.split(',')
+                    .map(|e| e.trim().parse::<LmpComponent>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| format!("Unable to parse {} to a list of LMP components", ids))
+            })
+            .transpose()
+    }
+}
@@ -0,0 +1,124 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::AccessMode;
+use serde::Deserialize;
+use std::time::Duration;
+
+use jiff::Zoned;
+
+use crate::db::isone::load_forecast_archive::{self, IsoneLoadForecastArchive};
+use crate::db::isone::system_load_archive::{self, IsoneSystemLoadArchive, SystemLoadReport};
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// System load, `report` is one of `5min` or `hourly`, e.g.
+/// /isone/load/system/hourly?interval_beginning_gte=2025-05-01T00:00:00-04:00[America/New_York]
+#[get("/isone/load/system/{report}")]
+pub async fn api_system_load(
+    path: web::Path<String>,
+    query: web::Query<SystemLoadQuery>,
+    data: web::Data<IsoneSystemLoadArchive>,
+) -> impl Responder {
+    let report: SystemLoadReport = match path.into_inner().parse() {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn = open_with_retry(
+        &data.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    );
+    if conn.is_err() {
+        return HttpResponse::InternalServerError().body(format!(
+            "Error opening DuckDB database at {}: {}",
+            data.duckdb_path,
+            conn.err().unwrap(),
+        ));
+    }
+    let conn = conn.unwrap();
+
+    let query_filter = system_load_archive::QueryFilter {
+        interval_beginning_gte: query.interval_beginning_gte.clone(),
+        interval_beginning_lt: query.interval_beginning_lt.clone(),
+    };
+    match system_load_archive::get_data(&conn, report, &query_filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// Three-day load forecast by zone, e.g.
+/// /isone/load/forecast?zone=.Z.MAINE&hour_beginning_gte=2025-05-02T00:00:00-04:00[America/New_York]
+#[get("/isone/load/forecast")]
+pub async fn api_load_forecast(
+    query: web::Query<LoadForecastQuery>,
+    data: web::Data<IsoneLoadForecastArchive>,
+) -> impl Responder {
+    let conn = open_with_retry(
+        &data.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    );
+    if conn.is_err() {
+        return HttpResponse::InternalServerError().body(format!(
+            "Error opening DuckDB database at {}: {}",
+            data.duckdb_path,
+            conn.err().unwrap(),
+        ));
+    }
+    let conn = conn.unwrap();
+
+    let query_filter = query.to_query_filter();
+    match load_forecast_archive::get_data(&conn, &query_filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SystemLoadQuery {
+    pub interval_beginning_gte: Option<Zoned>,
+    pub interval_beginning_lt: Option<Zoned>,
+    pub _limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LoadForecastQuery {
+    pub creation_date_gte: Option<Zoned>,
+    pub creation_date_lt: Option<Zoned>,
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    pub zone: Option<String>,
+    pub zone_in: Option<String>,
+    pub _limit: Option<usize>,
+}
+
+impl LoadForecastQuery {
+    pub fn to_query_filter(&self) -> load_forecast_archive::QueryFilter {
+        load_forecast_archive::QueryFilter {
+            creation_date_gte: self.creation_date_gte.clone(),
+            creation_date_lt: self.creation_date_lt.clone(),
+            hour_beginning_gte: self.hour_beginning_gte.clone(),
+            hour_beginning_lt: self.hour_beginning_lt.clone(),
+            zone: self.zone.clone(),
+            zone_in: self
+                .zone_in
+                .as_ref()
+                .map(|s| s.split(',').map(|v| v.trim().to_string()).collect()),
+        }
+    }
+}
@@ -0,0 +1,377 @@
+//! Hourly analytics of the masked DA demand bids and virtuals at one location.
+//!
+//! The masked data doesn't say which bids cleared, so clearing is inferred
+//! from the DA LMP at the location: fixed demand always clears, price
+//! sensitive demand and DECs clear if their price is at or above the LMP,
+//! INCs clear if their price is at or below the LMP.  Masked location ids are
+//! not published with their ptid, the caller has to supply the mapping.
+
+use std::collections::BTreeMap;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::Connection;
+use jiff::{civil::Date, tz::TimeZone, Timestamp, ToSpan, Zoned};
+use serde::Serialize;
+
+use crate::db::isone::{
+    dalmp_archive::IsoneDaLmpArchive, masked_data::demand_bids_archive::DemandBidsArchive,
+    rtlmp_archive::IsoneRtLmpArchive,
+};
+use crate::utils::serde_helpers::serialize_zoned_as_offset;
+
+/// Hourly demand and virtual analytics for a masked location, e.g.
+/// /isone/masked/demand_bids/analytics/masked_location_id/28934/ptid/4008/start/2025-06-01/end/2025-06-30
+#[get("/isone/masked/demand_bids/analytics/masked_location_id/{masked_location_id}/ptid/{ptid}/start/{start}/end/{end}")]
+pub async fn api_demand_analytics(
+    path: web::Path<(u32, u32, Date, Date)>,
+    db: web::Data<(DemandBidsArchive, IsoneDaLmpArchive, IsoneRtLmpArchive)>,
+) -> impl Responder {
+    let (masked_location_id, ptid, start, end) = path.into_inner();
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let (Ok(start), Ok(end)) = (
+        start.to_zoned(tz.clone()),
+        end.checked_add(1.day()).and_then(|e| e.to_zoned(tz)),
+    ) else {
+        return HttpResponse::BadRequest().body("Invalid start or end date");
+    };
+
+    let conn = match Connection::open_in_memory() {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("Error opening DuckDB connection: {}", e))
+        }
+    };
+    let sql = format!(
+        "ATTACH '{}' AS bids_db (READ_ONLY);\nATTACH '{}' AS da_db (READ_ONLY);\nATTACH '{}' AS rt_db (READ_ONLY);",
+        db.0.duckdb_path, db.1.duckdb_path, db.2.duckdb_path
+    );
+    if let Err(e) = conn.execute_batch(&sql) {
+        return HttpResponse::InternalServerError()
+            .body(format!("Error attaching DuckDB databases: {}", e));
+    }
+
+    match get_demand_analytics(&conn, masked_location_id, ptid, &start, &end) {
+        Ok(hours) => HttpResponse::Ok().json(DemandAnalytics {
+            masked_location_id,
+            ptid,
+            summary: summarize(&hours),
+            hours,
+        }),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DemandAnalytics {
+    masked_location_id: u32,
+    ptid: u32,
+    summary: Summary,
+    hours: Vec<HourlyDemand>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HourlyDemand {
+    #[serde(serialize_with = "serialize_zoned_as_offset")]
+    pub hour_beginning: Zoned,
+    pub fixed_mw: f64,
+    pub price_sensitive_bid_mw: f64,
+    pub price_sensitive_cleared_mw: f64,
+    /// Arc elasticity of the demand bid curve for a ±10% price move around
+    /// the DA LMP.  `None` if the LMP is not positive or there is no demand.
+    pub elasticity: Option<f64>,
+    pub inc_offered_mw: f64,
+    pub dec_bid_mw: f64,
+    pub inc_cleared_mw: f64,
+    pub dec_cleared_mw: f64,
+    /// Cleared DECs minus cleared INCs.  `None` if there are no virtual bids
+    /// at this location.
+    pub net_virtual_mw: Option<f64>,
+    pub da_lmp: f64,
+    pub rt_lmp: Option<f64>,
+    /// DA LMP minus RT LMP
+    pub da_rt_spread: Option<f64>,
+    /// Settlement of the net virtual position, net DEC MW times the RT minus
+    /// DA price
+    pub virtual_pnl: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary {
+    pub hours: usize,
+    pub average_fixed_mw: Option<f64>,
+    pub average_price_sensitive_cleared_mw: Option<f64>,
+    /// Cleared price sensitive MWh as a fraction of total cleared demand MWh
+    pub price_sensitive_share: Option<f64>,
+    pub average_elasticity: Option<f64>,
+    pub average_net_virtual_mw: Option<f64>,
+    pub average_da_rt_spread: Option<f64>,
+    /// Average DA-RT spread in the hours with a net DEC position
+    pub average_spread_net_dec: Option<f64>,
+    /// Average DA-RT spread in the hours with a net INC position
+    pub average_spread_net_inc: Option<f64>,
+    /// Correlation between the net virtual MW and the DA-RT spread
+    pub correlation_net_virtual_spread: Option<f64>,
+    pub total_virtual_pnl: f64,
+}
+
+/// One bid segment, price in $/MWh
+#[derive(Debug, Clone, PartialEq)]
+pub struct BidSegment {
+    /// One of INC, DEC, FIXED, PRICE
+    pub bid_type: String,
+    pub price: f64,
+    pub mw: f64,
+}
+
+/// Calculate the hourly analytics for a masked location between
+/// [start, end).  The connection needs the demand bids attached as
+/// `bids_db`, the DA LMP as `da_db` and the RT LMP as `rt_db`.  Only the
+/// hours with a DA LMP are returned.
+pub fn get_demand_analytics(
+    conn: &Connection,
+    masked_location_id: u32,
+    ptid: u32,
+    start: &Zoned,
+    end: &Zoned,
+) -> Result<Vec<HourlyDemand>, Box<dyn std::error::Error>> {
+    let start = start.strftime("%Y-%m-%d %H:%M:%S.000%:z");
+    let end = end.strftime("%Y-%m-%d %H:%M:%S.000%:z");
+
+    let query = format!(
+        r#"
+SELECT HourBeginning, BidType::VARCHAR, Price::DOUBLE, MW::DOUBLE
+FROM bids_db.da_bids
+WHERE MaskedLocationId = {masked_location_id}
+AND HourBeginning >= '{start}'
+AND HourBeginning < '{end}'
+ORDER BY HourBeginning, BidType, Segment;
+"#
+    );
+    let mut bids: BTreeMap<i64, Vec<BidSegment>> = BTreeMap::new();
+    let mut stmt = conn.prepare(&query)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        bids.entry(row.get::<usize, i64>(0)?)
+            .or_default()
+            .push(BidSegment {
+                bid_type: row.get(1)?,
+                price: row.get(2)?,
+                mw: row.get(3)?,
+            });
+    }
+
+    let query = format!(
+        r#"
+SELECT d.hour_beginning, d.lmp::DOUBLE, r.lmp::DOUBLE
+FROM da_db.da_lmp d
+LEFT JOIN rt_db.rt_lmp r
+    ON r.hour_beginning = d.hour_beginning AND r.ptid = d.ptid
+WHERE d.ptid = {ptid}
+AND d.hour_beginning >= '{start}'
+AND d.hour_beginning < '{end}'
+ORDER BY d.hour_beginning;
+"#
+    );
+    let tz = TimeZone::get("America/New_York")?;
+    let mut out: Vec<HourlyDemand> = Vec::new();
+    let mut stmt = conn.prepare(&query)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let micros: i64 = row.get(0)?;
+        let hour_beginning = Zoned::new(Timestamp::from_microsecond(micros)?, tz.clone());
+        let segments = bids.get(&micros).map(|e| e.as_slice()).unwrap_or(&[]);
+        out.push(analyze_hour(hour_beginning, segments, row.get(1)?, row.get(2)?));
+    }
+    Ok(out)
+}
+
+/// MW of demand willing to pay at least `price`
+fn demand_at(segments: &[BidSegment], price: f64) -> f64 {
+    segments
+        .iter()
+        .filter(|s| s.bid_type == "FIXED" || (s.bid_type == "PRICE" && s.price >= price))
+        .map(|s| s.mw)
+        .sum()
+}
+
+pub fn analyze_hour(
+    hour_beginning: Zoned,
+    segments: &[BidSegment],
+    da_lmp: f64,
+    rt_lmp: Option<f64>,
+) -> HourlyDemand {
+    let sum = |f: &dyn Fn(&BidSegment) -> bool| -> f64 {
+        segments.iter().filter(|s| f(s)).map(|s| s.mw).sum()
+    };
+    let fixed_mw = sum(&|s| s.bid_type == "FIXED");
+    let price_sensitive_bid_mw = sum(&|s| s.bid_type == "PRICE");
+    let price_sensitive_cleared_mw = sum(&|s| s.bid_type == "PRICE" && s.price >= da_lmp);
+    let inc_offered_mw = sum(&|s| s.bid_type == "INC");
+    let dec_bid_mw = sum(&|s| s.bid_type == "DEC");
+    let inc_cleared_mw = sum(&|s| s.bid_type == "INC" && s.price <= da_lmp);
+    let dec_cleared_mw = sum(&|s| s.bid_type == "DEC" && s.price >= da_lmp);
+
+    let elasticity = if da_lmp > 0.0 {
+        let (lo, hi) = (0.9 * da_lmp, 1.1 * da_lmp);
+        let (q_lo, q_hi) = (demand_at(segments, lo), demand_at(segments, hi));
+        let q_mid = (q_lo + q_hi) / 2.0;
+        if q_mid > 0.0 {
+            Some(((q_hi - q_lo) / q_mid) / ((hi - lo) / ((hi + lo) / 2.0)))
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let has_virtuals = segments
+        .iter()
+        .any(|s| s.bid_type == "INC" || s.bid_type == "DEC");
+    let net_virtual_mw = has_virtuals.then_some(dec_cleared_mw - inc_cleared_mw);
+    let da_rt_spread = rt_lmp.map(|rt| da_lmp - rt);
+
+    HourlyDemand {
+        hour_beginning,
+        fixed_mw,
+        price_sensitive_bid_mw,
+        price_sensitive_cleared_mw,
+        elasticity,
+        inc_offered_mw,
+        dec_bid_mw,
+        inc_cleared_mw,
+        dec_cleared_mw,
+        net_virtual_mw,
+        da_lmp,
+        rt_lmp,
+        da_rt_spread,
+        virtual_pnl: net_virtual_mw.zip(da_rt_spread).map(|(mw, spread)| -mw * spread),
+    }
+}
+
+fn mean(xs: impl Iterator<Item = f64>) -> Option<f64> {
+    let (n, total) = xs.fold((0usize, 0.0), |(n, t), x| (n + 1, t + x));
+    (n > 0).then(|| total / n as f64)
+}
+
+fn correlation(xy: &[(f64, f64)]) -> Option<f64> {
+    let mx = mean(xy.iter().map(|e| e.0))?;
+    let my = mean(xy.iter().map(|e| e.1))?;
+    let sxy: f64 = xy.iter().map(|(x, y)| (x - mx) * (y - my)).sum();
+    let sxx: f64 = xy.iter().map(|(x, _)| (x - mx).powi(2)).sum();
+    let syy: f64 = xy.iter().map(|(_, y)| (y - my).powi(2)).sum();
+    (sxx > 0.0 && syy > 0.0).then(|| sxy / (sxx * syy).sqrt())
+}
+
+pub fn summarize(hours: &[HourlyDemand]) -> Summary {
+    let cleared: f64 = hours
+        .iter()
+        .map(|h| h.fixed_mw + h.price_sensitive_cleared_mw)
+        .sum();
+    let ps_cleared: f64 = hours.iter().map(|h| h.price_sensitive_cleared_mw).sum();
+    let position_spread: Vec<(f64, f64)> = hours
+        .iter()
+        .filter_map(|h| h.net_virtual_mw.zip(h.da_rt_spread))
+        .collect();
+    Summary {
+        hours: hours.len(),
+        average_fixed_mw: mean(hours.iter().map(|h| h.fixed_mw)),
+        average_price_sensitive_cleared_mw: mean(hours.iter().map(|h| h.price_sensitive_cleared_mw)),
+        price_sensitive_share: (cleared > 0.0).then(|| ps_cleared / cleared),
+        average_elasticity: mean(hours.iter().filter_map(|h| h.elasticity)),
+        average_net_virtual_mw: mean(hours.iter().filter_map(|h| h.net_virtual_mw)),
+        average_da_rt_spread: mean(hours.iter().filter_map(|h| h.da_rt_spread)),
+        average_spread_net_dec: mean(
+            position_spread
+                .iter()
+                .filter(|(mw, _)| *mw > 0.0)
+                .map(|e| e.1),
+        ),
+        average_spread_net_inc: mean(
+            position_spread
+                .iter()
+                .filter(|(mw, _)| *mw < 0.0)
+                .map(|e| e.1),
+        ),
+        correlation_net_virtual_spread: correlation(&position_spread),
+        total_virtual_pnl: hours.iter().filter_map(|h| h.virtual_pnl).sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_demand_analytics() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+ATTACH ':memory:' AS bids_db;
+ATTACH ':memory:' AS da_db;
+ATTACH ':memory:' AS rt_db;
+CREATE TABLE bids_db.da_bids (
+    HourBeginning TIMESTAMPTZ NOT NULL,
+    MaskedParticipantId UINTEGER NOT NULL,
+    MaskedLocationId UINTEGER NOT NULL,
+    LocationType ENUM('HUB', 'LOAD ZONE', 'NETWORK NODE') NOT NULL,
+    BidType ENUM('INC', 'DEC', 'FIXED', 'PRICE') NOT NULL,
+    Segment UTINYINT NOT NULL,
+    Price DECIMAL(9,2) NOT NULL,
+    MW DECIMAL(9,2) NOT NULL,
+);
+INSERT INTO bids_db.da_bids VALUES
+    ('2025-06-01 00:00:00-04:00', 1, 28934, 'LOAD ZONE', 'FIXED', 0, 0, 500),
+    ('2025-06-01 00:00:00-04:00', 1, 28934, 'LOAD ZONE', 'PRICE', 0, 1000, 100),
+    ('2025-06-01 00:00:00-04:00', 1, 28934, 'LOAD ZONE', 'PRICE', 1, 42, 100),
+    ('2025-06-01 00:00:00-04:00', 1, 28934, 'LOAD ZONE', 'PRICE', 2, 30, 50),
+    ('2025-06-01 00:00:00-04:00', 2, 28934, 'LOAD ZONE', 'DEC', 0, 45, 20),
+    ('2025-06-01 00:00:00-04:00', 3, 28934, 'LOAD ZONE', 'INC', 0, 35, 50),
+    ('2025-06-01 01:00:00-04:00', 1, 28934, 'LOAD ZONE', 'FIXED', 0, 0, 400),
+    ('2025-06-01 00:00:00-04:00', 1, 11111, 'LOAD ZONE', 'FIXED', 0, 0, 900);
+CREATE TABLE da_db.da_lmp (hour_beginning TIMESTAMPTZ NOT NULL, ptid UINTEGER NOT NULL, lmp DECIMAL(9,4) NOT NULL);
+INSERT INTO da_db.da_lmp VALUES
+    ('2025-06-01 00:00:00-04:00', 4008, 40),
+    ('2025-06-01 01:00:00-04:00', 4008, 30),
+    ('2025-06-01 00:00:00-04:00', 4000, 20);
+CREATE TABLE rt_db.rt_lmp (hour_beginning TIMESTAMPTZ NOT NULL, ptid UINTEGER NOT NULL, lmp DECIMAL(9,4) NOT NULL);
+INSERT INTO rt_db.rt_lmp VALUES
+    ('2025-06-01 00:00:00-04:00', 4008, 50);
+",
+        )?;
+        let start: Zoned = "2025-06-01 00:00:00-04:00[America/New_York]".parse()?;
+        let end: Zoned = "2025-06-02 00:00:00-04:00[America/New_York]".parse()?;
+        let xs = get_demand_analytics(&conn, 28934, 4008, &start, &end)?;
+        assert_eq!(xs.len(), 2);
+        let x0 = &xs[0];
+        assert_eq!(x0.fixed_mw, 500.0);
+        assert_eq!(x0.price_sensitive_bid_mw, 250.0);
+        assert_eq!(x0.price_sensitive_cleared_mw, 200.0);
+        assert_eq!(x0.dec_cleared_mw, 20.0);
+        assert_eq!(x0.inc_cleared_mw, 50.0);
+        assert_eq!(x0.net_virtual_mw, Some(-30.0));
+        assert_eq!(x0.da_rt_spread, Some(-10.0));
+        // net INC of 30 MW sold at $40 and bought back at $50
+        assert_eq!(x0.virtual_pnl, Some(-300.0));
+        // demand is 700 MW at $36 and 600 MW at $44
+        let e = x0.elasticity.unwrap();
+        assert!((e - (-100.0 / 650.0) / 0.2).abs() < 1e-9);
+
+        let x1 = &xs[1];
+        assert_eq!(x1.fixed_mw, 400.0);
+        assert_eq!(x1.net_virtual_mw, None);
+        assert_eq!(x1.rt_lmp, None);
+        assert_eq!(x1.elasticity, Some(0.0));
+
+        let summary = summarize(&xs);
+        assert_eq!(summary.hours, 2);
+        assert_eq!(summary.average_fixed_mw, Some(450.0));
+        assert_eq!(summary.price_sensitive_share, Some(200.0 / 1100.0));
+        assert_eq!(summary.average_spread_net_inc, Some(-10.0));
+        assert_eq!(summary.average_spread_net_dec, None);
+        assert_eq!(summary.correlation_net_virtual_spread, None);
+        assert_eq!(summary.total_virtual_pnl, -300.0);
+        Ok(())
+    }
+}
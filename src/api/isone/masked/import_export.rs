@@ -0,0 +1,198 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::{AccessMode, Connection};
+use jiff::Zoned;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::api::isone::_api_isone_core::Market;
+use crate::db::isone::actual_interchange_archive::IsoneActualInterchangeArchive;
+use crate::db::isone::masked_data::import_export_archive::{self, ImportExportArchive};
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// The masked import/export transactions, e.g.
+/// /isone/masked/import_export?market_type=DA&direction=IMPORT&hour_beginning_gte=2025-07-01T00:00:00-04:00[America/New_York]&hour_beginning_lt=2025-07-02T00:00:00-04:00[America/New_York]
+#[get("/isone/masked/import_export")]
+pub async fn api_data(
+    query: web::Query<ImportExportQuery>,
+    data: web::Data<(ImportExportArchive, IsoneActualInterchangeArchive)>,
+) -> impl Responder {
+    let filter = match query.to_query_filter() {
+        Ok(f) => f,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn = match open(&data.0) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    match import_export_archive::get_data(&conn, &filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// Hourly MW offered by interface, direction and transaction type, e.g.
+/// /isone/masked/import_export/hourly_summary?market_type=RT&masked_interface_id_in=12345,67890
+#[get("/isone/masked/import_export/hourly_summary")]
+pub async fn api_hourly_summary(
+    query: web::Query<ImportExportQuery>,
+    data: web::Data<(ImportExportArchive, IsoneActualInterchangeArchive)>,
+) -> impl Responder {
+    let filter = match query.to_query_filter() {
+        Ok(f) => f,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn = match open(&data.0) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    match import_export_archive::get_hourly_summary(&conn, &filter, query._limit) {
+        Ok(records) => HttpResponse::Ok().json(records),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// The hourly import supply curve at each interface, e.g.
+/// /isone/masked/import_export/supply_curve?market_type=DA&masked_interface_id=12345&hour_beginning_gte=2025-07-01T00:00:00-04:00[America/New_York]
+#[get("/isone/masked/import_export/supply_curve")]
+pub async fn api_supply_curve(
+    query: web::Query<ImportExportQuery>,
+    data: web::Data<(ImportExportArchive, IsoneActualInterchangeArchive)>,
+) -> impl Responder {
+    let filter = match query.to_query_filter() {
+        Ok(f) => f,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn = match open(&data.0) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    match import_export_archive::get_import_supply_curves(&conn, &filter) {
+        Ok(curves) => HttpResponse::Ok().json(curves),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// The hourly import supply curve at one masked interface compared with the
+/// actual flows at the interface node, e.g.
+/// /isone/masked/import_export/supply_curve/masked_interface_id/12345/ptid/4012?market_type=RT
+#[get("/isone/masked/import_export/supply_curve/masked_interface_id/{masked_interface_id}/ptid/{ptid}")]
+pub async fn api_supply_curve_vs_flows(
+    path: web::Path<(u32, u32)>,
+    query: web::Query<ImportExportQuery>,
+    data: web::Data<(ImportExportArchive, IsoneActualInterchangeArchive)>,
+) -> impl Responder {
+    let filter = match query.to_query_filter() {
+        Ok(f) => f,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn = match open(&data.0) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    if let Err(e) = import_export_archive::attach_actual_interchange(&conn, &data.1.duckdb_path) {
+        return HttpResponse::InternalServerError()
+            .body(format!("Error attaching DuckDB database: {}", e));
+    }
+    let (masked_interface_id, ptid) = path.into_inner();
+    match import_export_archive::compare_with_flows(&conn, &filter, masked_interface_id, ptid) {
+        Ok(records) => HttpResponse::Ok().json(records),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+fn open(data: &ImportExportArchive) -> Result<Connection, String> {
+    open_with_retry(
+        &data.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    )
+    .map_err(|e| {
+        format!(
+            "Error opening DuckDB database at {}: {}",
+            data.duckdb_path, e
+        )
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportExportQuery {
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    /// DA or RT
+    pub market_type: Option<Market>,
+    pub masked_customer_id: Option<u32>,
+    /// The masked source id for imports, the masked sink id for exports
+    pub masked_interface_id: Option<u32>,
+    /// One or more masked interface ids, separated by commas
+    pub masked_interface_id_in: Option<String>,
+    /// One of IMPORT, EXPORT, THROUGH
+    pub direction: Option<String>,
+    /// One of FIXED, DISPATCHABLE, UP-TO CONGESTION
+    pub transaction_type: Option<String>,
+    pub emergency_flag: Option<bool>,
+    pub _limit: Option<usize>,
+}
+
+impl ImportExportQuery {
+    /// Return an error listing the values of `masked_interface_id_in` that
+    /// are not valid ids.
+    pub fn to_query_filter(&self) -> Result<import_export_archive::QueryFilter, String> {
+        let masked_interface_id_in = match &self.masked_interface_id_in {
+            Some(s) => {
+                let (ids, bad): (Vec<_>, Vec<_>) = s
+                    .split(',')
+                    .map(|v| (v.trim(), v.trim().parse::<u32>()))
+                    .partition(|(_, id)| id.is_ok());
+                if !bad.is_empty() {
+                    return Err(format!(
+                        "Unable to parse {} to a list of masked interface ids",
+                        bad.iter().map(|(v, _)| *v).collect::<Vec<_>>().join(", ")
+                    ));
+                }
+                Some(ids.into_iter().map(|(_, id)| id.unwrap()).collect())
+            }
+            None => None,
+        };
+        Ok(import_export_archive::QueryFilter {
+            hour_beginning_gte: self.hour_beginning_gte.clone(),
+            hour_beginning_lt: self.hour_beginning_lt.clone(),
+            market_type: self.market_type,
+            masked_customer_id: self.masked_customer_id,
+            masked_interface_id: self.masked_interface_id,
+            masked_interface_id_in,
+            direction: self.direction.clone(),
+            transaction_type: self.transaction_type.clone(),
+            emergency_flag: self.emergency_flag,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_masked_interface_id_in() {
+        let query =
+            web::Query::<ImportExportQuery>::from_query("masked_interface_id_in=12345,%2067890")
+                .unwrap();
+        let filter = query.to_query_filter().unwrap();
+        assert_eq!(filter.masked_interface_id_in, Some(vec![12345, 67890]));
+
+        let query =
+            web::Query::<ImportExportQuery>::from_query("masked_interface_id_in=12345,abc,-1")
+                .unwrap();
+        assert_eq!(
+            query.to_query_filter().unwrap_err(),
+            "Unable to parse abc, -1 to a list of masked interface ids"
+        );
+    }
+}
@@ -246,8 +246,8 @@ SELECT
     UnitStatus,
     HourBeginning,
     Segment,
-    Quantity,
-    Price,
+    Quantity::DOUBLE AS Quantity,
+    Price::DOUBLE AS Price,
 FROM {:?}_offers
 WHERE UnitStatus <> 'UNAVAILABLE'
 {}
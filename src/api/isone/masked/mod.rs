@@ -1,3 +1,6 @@
+pub mod demand_bid_analytics;
+pub mod import_export;
 pub mod masked_daas_offers;
 pub mod masked_energy_offers;
-pub mod masked_demand_bids;
\ No newline at end of file
+pub mod masked_demand_bids;
+pub mod offer_clearing;
@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::{AccessMode, Connection};
+use jiff::{civil::Date, Timestamp, ToSpan};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::api::isone::_api_isone_core::Market;
+use crate::api::isone::masked::masked_energy_offers::get_stack;
+use crate::db::isone::dalmp_archive::{self, IsoneDaLmpArchive};
+use crate::db::isone::masked_data::da_energy_offers_archive::IsoneDaEnergyOffersArchive;
+use crate::db::isone::system_load_archive::{self, IsoneSystemLoadArchive, SystemLoadReport};
+use crate::elec::supply_curve::{self, Adjustment, ClearingResult, Replay};
+use crate::utils::lib_duckdb::open_with_retry;
+
+#[derive(Debug, Serialize)]
+struct HourlyClearing {
+    timestamp: Timestamp,
+    #[serde(flatten)]
+    result: ClearingResult,
+}
+
+/// Clear the DA or RT stack for a list of timestamps (seconds from epoch)
+/// against a load level, e.g.
+/// /isone/energy_offers/da/clearing/timestamps/1751392800?load_mw=18000&imports_mw=2000
+#[get("/isone/energy_offers/{market}/clearing/timestamps/{timestamps}")]
+pub async fn api_clearing(
+    path: web::Path<(String, String)>,
+    query: web::Query<ClearingQuery>,
+    db: web::Data<IsoneDaEnergyOffersArchive>,
+) -> impl Responder {
+    let market: Market = match path.0.parse() {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let timestamps = match parse_timestamps(&path.1) {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn = match open(&db.duckdb_path) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    let offers = match get_stack(&conn, market, timestamps) {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    };
+    let adjustment = Adjustment {
+        imports_mw: query.imports_mw.unwrap_or(0.0),
+        must_run_mw: query.must_run_mw.unwrap_or(0.0),
+    };
+    let out: Vec<HourlyClearing> = supply_curve::from_isone_offers(&offers)
+        .into_iter()
+        .map(|(timestamp, curve)| HourlyClearing {
+            timestamp,
+            result: curve.clear(query.load_mw, &adjustment),
+        })
+        .collect();
+    HttpResponse::Ok().json(out)
+}
+
+#[derive(Debug, Serialize)]
+struct ReplayResponse {
+    /// Median of the implied adjustments, in MW
+    calibrated_adjustment_mw: Option<f64>,
+    hours: Vec<Replay>,
+}
+
+/// Replay the hours between two dates: clear the DA stack against the actual
+/// hourly system load and compare the simulated price with the DA LMP at
+/// `ptid` (the Hub by default), e.g.
+/// /isone/energy_offers/da/replay/start/2025-07-01/end/2025-07-07?imports_mw=1500
+#[get("/isone/energy_offers/{market}/replay/start/{start}/end/{end}")]
+pub async fn api_replay(
+    path: web::Path<(String, Date, Date)>,
+    query: web::Query<ReplayQuery>,
+    db: web::Data<(IsoneDaEnergyOffersArchive, IsoneDaLmpArchive, IsoneSystemLoadArchive)>,
+) -> impl Responder {
+    let market: Market = match path.0.parse() {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let (start, end) = match (path.1.in_tz("America/New_York"), end_of(path.2)) {
+        (Ok(s), Ok(e)) => (s, e),
+        _ => return HttpResponse::BadRequest().body("Invalid start or end date"),
+    };
+    let adjustment = Adjustment {
+        imports_mw: query.imports_mw.unwrap_or(0.0),
+        must_run_mw: query.must_run_mw.unwrap_or(0.0),
+    };
+
+    let (offers_conn, lmp_conn, load_conn) =
+        match (open(&db.0.duckdb_path), open(&db.1.duckdb_path), open(&db.2.duckdb_path)) {
+            (Ok(a), Ok(b), Ok(c)) => (a, b, c),
+            _ => return HttpResponse::InternalServerError().body("Error opening the DuckDB databases"),
+        };
+
+    let filter = dalmp_archive::QueryFilterBuilder::new()
+        .ptid(query.ptid.unwrap_or(4000))
+        .hour_beginning_gte(start.clone())
+        .hour_beginning_lt(end.clone())
+        .build();
+    let lmps = match dalmp_archive::get_data(&lmp_conn, &filter) {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::InternalServerError().body(format!("Error querying the LMPs: {}", e)),
+    };
+    let filter = system_load_archive::QueryFilterBuilder::new()
+        .interval_beginning_gte(start.clone())
+        .interval_beginning_lt(end.clone())
+        .build();
+    let loads = match system_load_archive::get_data(&load_conn, SystemLoadReport::Hourly, &filter, None) {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::InternalServerError().body(format!("Error querying the load: {}", e)),
+    };
+
+    let timestamps: Vec<Timestamp> = lmps.iter().map(|e| e.hour_beginning.timestamp()).collect();
+    if timestamps.is_empty() {
+        return HttpResponse::Ok().json(ReplayResponse {
+            calibrated_adjustment_mw: None,
+            hours: vec![],
+        });
+    }
+    let offers = match get_stack(&offers_conn, market, timestamps) {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::InternalServerError().body(format!("Error querying the offers: {}", e)),
+    };
+    let curves = supply_curve::from_isone_offers(&offers);
+
+    let hours: Vec<Replay> = lmps
+        .iter()
+        .filter_map(|lmp| {
+            let ts = lmp.hour_beginning.timestamp();
+            let curve = curves.get(&ts)?;
+            let load = loads.iter().find(|l| l.interval_beginning.timestamp() == ts)?;
+            Some(supply_curve::replay(
+                ts,
+                curve,
+                load.load.to_f64()?,
+                lmp.lmp.to_f64()?,
+                &adjustment,
+            ))
+        })
+        .collect();
+    HttpResponse::Ok().json(ReplayResponse {
+        calibrated_adjustment_mw: supply_curve::calibrate(&hours),
+        hours,
+    })
+}
+
+fn end_of(date: Date) -> Result<jiff::Zoned, jiff::Error> {
+    date.checked_add(1.day())?.in_tz("America/New_York")
+}
+
+fn parse_timestamps(s: &str) -> Result<Vec<Timestamp>, String> {
+    s.split(',')
+        .map(|n| {
+            n.trim()
+                .parse::<i64>()
+                .map_err(|_| format!("Failed to parse {} to an integer", n))
+                .and_then(|e| Timestamp::from_second(e).map_err(|e| e.to_string()))
+        })
+        .collect()
+}
+
+fn open(duckdb_path: &str) -> Result<Connection, String> {
+    open_with_retry(duckdb_path, 8, Duration::from_millis(25), AccessMode::ReadOnly)
+        .map_err(|e| format!("Error opening DuckDB database at {}: {}", duckdb_path, e))
+}
+
+#[derive(Debug, Deserialize)]
+struct ClearingQuery {
+    pub load_mw: f64,
+    pub imports_mw: Option<f64>,
+    pub must_run_mw: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReplayQuery {
+    /// The location of the DA LMP, default 4000 (the Hub)
+    pub ptid: Option<u32>,
+    pub imports_mw: Option<f64>,
+    pub must_run_mw: Option<f64>,
+}
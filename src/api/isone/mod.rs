@@ -1,10 +1,20 @@
 pub mod _api_isone_core;
 pub mod actual_interchange;
 pub mod binding_constraints_da;
+pub mod binding_constraints_rt;
 pub mod capacity;
+pub mod events;
 pub mod ftr;
+pub mod fuel_mix;
+pub mod interface_headroom;
 pub mod lmp;
+pub mod lmp5;
+pub mod load;
 pub mod masked;
 pub mod mis;
 pub mod participant_list;
+pub mod reserve_prices;
+pub mod sevenday_capacity_forecast;
+pub mod solar_forecast;
+pub mod ssc;
 pub mod ttc;
@@ -0,0 +1,119 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::AccessMode;
+use serde::Deserialize;
+use std::time::Duration;
+
+use jiff::Zoned;
+
+use crate::db::isone::daas_reserve_data_archive::DaasReserveDataArchive;
+use crate::db::isone::rt_reserve_prices_archive::{
+    self, IsoneRtReservePricesArchive, RtReserveReport,
+};
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// RT reserve clearing prices, `report` is one of `5min` or `hourly`, e.g.
+/// /isone/reserve_prices/rt/hourly?reserve_zone=SWCT&interval_beginning_gte=2025-07-01T00:00:00-04:00[America/New_York]
+#[get("/isone/reserve_prices/rt/{report}")]
+pub async fn api_rt_prices(
+    path: web::Path<String>,
+    query: web::Query<ApiQuery>,
+    data: web::Data<(IsoneRtReservePricesArchive, DaasReserveDataArchive)>,
+) -> impl Responder {
+    let report: RtReserveReport = match path.into_inner().parse() {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    let conn = open_with_retry(
+        &data.0.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    );
+    if conn.is_err() {
+        return HttpResponse::InternalServerError().body(format!(
+            "Error opening DuckDB database at {}: {}",
+            data.0.duckdb_path,
+            conn.err().unwrap(),
+        ));
+    }
+    let conn = conn.unwrap();
+
+    let query_filter = query.to_query_filter();
+    match rt_reserve_prices_archive::get_data(&conn, report, &query_filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// Hourly DA and RT reserve clearing prices side by side, e.g.
+/// /isone/reserve_prices/da_rt?reserve_zone=ROS&interval_beginning_gte=2025-07-01T00:00:00-04:00[America/New_York]
+#[get("/isone/reserve_prices/da_rt")]
+pub async fn api_da_rt_prices(
+    query: web::Query<ApiQuery>,
+    data: web::Data<(IsoneRtReservePricesArchive, DaasReserveDataArchive)>,
+) -> impl Responder {
+    let conn = open_with_retry(
+        &data.0.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    );
+    if conn.is_err() {
+        return HttpResponse::InternalServerError().body(format!(
+            "Error opening DuckDB database at {}: {}",
+            data.0.duckdb_path,
+            conn.err().unwrap(),
+        ));
+    }
+    let conn = conn.unwrap();
+    if let Err(e) = rt_reserve_prices_archive::attach_da_reserve_data(&conn, &data.1.duckdb_path) {
+        return HttpResponse::InternalServerError().body(format!(
+            "Error attaching DuckDB database at {}: {}",
+            data.1.duckdb_path, e,
+        ));
+    }
+
+    let query_filter = query.to_query_filter();
+    match rt_reserve_prices_archive::get_da_rt_prices(&conn, &query_filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiQuery {
+    pub interval_beginning_gte: Option<Zoned>,
+    pub interval_beginning_lt: Option<Zoned>,
+    pub reserve_zone: Option<String>,
+    /// One or more reserve zones, separated by commas
+    pub reserve_zone_in: Option<String>,
+    pub _limit: Option<usize>,
+}
+
+impl ApiQuery {
+    pub fn to_query_filter(&self) -> rt_reserve_prices_archive::QueryFilter {
+        rt_reserve_prices_archive::QueryFilter {
+            interval_beginning_gte: self.interval_beginning_gte.clone(),
+            interval_beginning_lt: self.interval_beginning_lt.clone(),
+            reserve_zone: self.reserve_zone.clone(),
+            reserve_zone_in: self
+                .reserve_zone_in
+                .as_ref()
+                .map(|s| s.split(',').map(|v| v.trim().to_string()).collect()),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::{AccessMode, Connection};
+use jiff::civil::Date;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::db::isone::sevenday_capacity_forecast_archive::*;
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// All the vintages of the peak load and surplus/deficiency forecast for the
+/// target days between `start` and `end`, with the change from the previous
+/// vintage, e.g.
+/// /isone/sevenday_capacity_forecast/vintages/start/2025-12-08/end/2025-12-08?changes_only=true
+#[get("/isone/sevenday_capacity_forecast/vintages/start/{start}/end/{end}")]
+pub async fn api_vintages(
+    path: web::Path<(Date, Date)>,
+    query: web::Query<VintagesQuery>,
+    data: web::Data<SevendayCapacityForecastArchive>,
+) -> impl Responder {
+    let (start, end) = path.into_inner();
+    let conn = match open(&data.duckdb_path) {
+        Ok(conn) => conn,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    match get_vintages(&conn, start, end) {
+        Ok(mut xs) => {
+            if query.changes_only.unwrap_or(false) {
+                xs.retain(|e| e.peak_load_change_mw != Some(0));
+            }
+            HttpResponse::Ok().json(xs)
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// The surplus/deficiency for each target day between `start` and `end` by
+/// number of days ahead, with the first power watch and warning, e.g.
+/// /isone/sevenday_capacity_forecast/surplus_deficiency/start/2025-12-01/end/2025-12-31
+#[get("/isone/sevenday_capacity_forecast/surplus_deficiency/start/{start}/end/{end}")]
+pub async fn api_surplus_deficiency(
+    path: web::Path<(Date, Date)>,
+    data: web::Data<SevendayCapacityForecastArchive>,
+) -> impl Responder {
+    let (start, end) = path.into_inner();
+    let conn = match open(&data.duckdb_path) {
+        Ok(conn) => conn,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    match get_vintages(&conn, start, end) {
+        Ok(xs) => HttpResponse::Ok().json(surplus_trajectories(&xs)),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+fn open(duckdb_path: &str) -> Result<Connection, String> {
+    open_with_retry(duckdb_path, 8, Duration::from_millis(25), AccessMode::ReadOnly)
+        .map_err(|e| format!("Error opening DuckDB database at {}: {}", duckdb_path, e))
+}
+
+#[derive(Debug, Deserialize)]
+struct VintagesQuery {
+    /// Skip the vintages where the peak load didn't change.  Default: false
+    pub changes_only: Option<bool>,
+}
@@ -0,0 +1,58 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::Connection;
+use jiff::civil::Date;
+use serde::Deserialize;
+
+use crate::db::isone::fuelmix_archive::IsoneFuelMixArchive;
+use crate::db::isone::sevenday_solar_forecast_archive::SevendaySolarForecastArchive;
+use crate::elec::forecast_accuracy::{self, Grouping};
+
+/// Accuracy of the 7-day solar forecast against the solar generation in the
+/// fuel mix, for the forecasts issued between two dates, by horizon (day 1 is
+/// the issue day) and by `month`, `hour` or `month_hour`, e.g.
+/// /isone/solar_forecast/accuracy/start/2025-06-01/end/2025-08-31?group_by=month_hour&min_actual_mw=50
+#[get("/isone/solar_forecast/accuracy/start/{start}/end/{end}")]
+pub async fn api_accuracy(
+    path: web::Path<(Date, Date)>,
+    query: web::Query<AccuracyQuery>,
+    db: web::Data<(SevendaySolarForecastArchive, IsoneFuelMixArchive)>,
+) -> impl Responder {
+    let (start, end) = path.into_inner();
+    if end < start {
+        return HttpResponse::BadRequest().body("The end date needs to be after the start date");
+    }
+    let conn = match open(&db.0.duckdb_path, &db.1.duckdb_path) {
+        Ok(c) => c,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    let (forecasts, actuals) = match forecast_accuracy::load_isone_solar(&conn, start, end) {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    };
+    let matched = forecast_accuracy::match_actuals(&forecasts, &actuals, query.max_horizon.unwrap_or(7));
+    HttpResponse::Ok().json(forecast_accuracy::accuracy(
+        &matched,
+        query.group_by.unwrap_or(Grouping::Month),
+        query.min_actual_mw.unwrap_or(1.0),
+    ))
+}
+
+fn open(forecast_path: &str, fuel_mix_path: &str) -> Result<Connection, String> {
+    let conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+    conn.execute_batch(&format!(
+        "ATTACH '{}' AS forecast_db (READ_ONLY);\nATTACH '{}' AS fuel_mix_db (READ_ONLY);",
+        forecast_path, fuel_mix_path
+    ))
+    .map_err(|e| format!("Error attaching the DuckDB databases: {}", e))?;
+    Ok(conn)
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuracyQuery {
+    /// One of `month`, `hour`, `month_hour`.  Default: month
+    pub group_by: Option<Grouping>,
+    /// Hours with a lower actual are left out of the MAPE.  Default: 1.0
+    pub min_actual_mw: Option<f64>,
+    /// Default: 7
+    pub max_horizon: Option<u8>,
+}
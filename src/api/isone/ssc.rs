@@ -0,0 +1,69 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::AccessMode;
+use serde::Deserialize;
+use std::time::Duration;
+
+use jiff::Zoned;
+
+use crate::db::isone::single_source_contingency_archive::{self, SingleSourceContingencyArchive};
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// Single source contingency limits, e.g.
+/// /isone/ssc?interface_name=Phase II&begin_date_gte=2025-05-04T00:00:00-04:00[America/New_York]
+#[get("/isone/ssc")]
+pub async fn api_ssc(
+    query: web::Query<SscQuery>,
+    data: web::Data<SingleSourceContingencyArchive>,
+) -> impl Responder {
+    let conn = open_with_retry(
+        &data.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    );
+    if conn.is_err() {
+        return HttpResponse::InternalServerError().body(format!(
+            "Error opening DuckDB database at {}: {}",
+            data.duckdb_path,
+            conn.err().unwrap(),
+        ));
+    }
+    let conn = conn.unwrap();
+
+    let query_filter = query.to_query_filter();
+    match single_source_contingency_archive::get_data(&conn, &query_filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SscQuery {
+    pub begin_date_gte: Option<Zoned>,
+    pub begin_date_lt: Option<Zoned>,
+    pub interface_name: Option<String>,
+    /// One or more interface names, separated by commas
+    pub interface_name_in: Option<String>,
+    pub _limit: Option<usize>,
+}
+
+impl SscQuery {
+    pub fn to_query_filter(&self) -> single_source_contingency_archive::QueryFilter {
+        single_source_contingency_archive::QueryFilter {
+            begin_date_gte: self.begin_date_gte.clone(),
+            begin_date_lt: self.begin_date_lt.clone(),
+            interface_name: self.interface_name.clone(),
+            interface_name_in: self
+                .interface_name_in
+                .as_ref()
+                .map(|s| s.split(',').map(|v| v.trim().to_string()).collect()),
+        }
+    }
+}
@@ -1,14 +1,76 @@
 use std::error::Error;
+use std::time::Duration;
 
 use csv::Writer;
 use duckdb::{AccessMode, Config, Connection, Result};
 use itertools::Itertools;
-use jiff::{civil::Date, Timestamp};
+use jiff::{civil::Date, Timestamp, Zoned};
 
-use crate::db::isone::total_transfer_capability_archive::TotalTransferCapabilityArchive;
+use crate::db::isone::total_transfer_capability_archive::{self, TotalTransferCapabilityArchive};
+use crate::utils::lib_duckdb::open_with_retry;
 use actix_web::{get, web, HttpResponse, Responder};
 use serde::Deserialize;
 
+/// TTC limits in long format, one row for each hour and interface, e.g.
+/// http://127.0.0.1:8111/isone/ttc?interface_in=hq_phase2,nb&hour_beginning_gte=2025-05-04T00:00:00-04:00[America/New_York]
+#[get("/isone/ttc")]
+async fn api_ttc(
+    query: web::Query<TtcQuery>,
+    db: web::Data<TotalTransferCapabilityArchive>,
+) -> impl Responder {
+    let conn = match open_with_retry(
+        &db.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    ) {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError().body(format!(
+                "Error opening DuckDB database at {}: {}",
+                db.duckdb_path, e
+            ))
+        }
+    };
+    match total_transfer_capability_archive::get_data(&conn, &query.to_query_filter(), query._limit)
+    {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TtcQuery {
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    /// An interface name, e.g. 'hq_phase2'
+    pub interface: Option<String>,
+    /// One or more interface names, separated by commas
+    pub interface_in: Option<String>,
+    pub _limit: Option<usize>,
+}
+
+impl TtcQuery {
+    pub fn to_query_filter(&self) -> total_transfer_capability_archive::QueryFilter {
+        total_transfer_capability_archive::QueryFilter {
+            hour_beginning_gte: self.hour_beginning_gte.clone(),
+            hour_beginning_lt: self.hour_beginning_lt.clone(),
+            interface: self.interface.clone(),
+            interface_in: self
+                .interface_in
+                .as_ref()
+                .map(|s| s.split(',').map(|v| v.trim().to_string()).collect()),
+        }
+    }
+}
+
 /// http://127.0.0.1:8111/ttc/start/2024-01-01/end/2024-01-04?columns=hq_phase2_import,ny_north_import&format=csv
 #[get("/ttc/start/{start}/end/{end}")]
 async fn api_ttc_data(
@@ -1 +1,2 @@
-pub mod contracts;
\ No newline at end of file
+pub mod contracts;
+pub mod shift_factors;
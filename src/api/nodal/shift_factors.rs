@@ -0,0 +1,93 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::Connection;
+use serde::Deserialize;
+
+use crate::db::isone::binding_constraints_da::IsoneDaBindingConstraintsArchive;
+use crate::db::isone::dalmp_archive::IsoneDaLmpArchive;
+use crate::db::nyiso::binding_constraints::NyisoBindingConstraintsDaArchive;
+use crate::db::nyiso::dalmp::NyisoDalmpArchive;
+use crate::elec::shift_factors::{self, CongestionData};
+use crate::interval::term::Term;
+
+/// Estimated shift factors of ISONE nodes to the frequently binding DA
+/// constraints, with the attribution of the average MCC to each constraint, e.g.
+/// /isone/congestion/shift_factors?term=Cal24&ptids=4000,4001&min_binding_hours=100&max_constraints=15
+#[get("/isone/congestion/shift_factors")]
+pub async fn api_isone_shift_factors(
+    query: web::Query<ShiftFactorsQuery>,
+    data: web::Data<(IsoneDaLmpArchive, IsoneDaBindingConstraintsArchive)>,
+) -> impl Responder {
+    estimate(&query, &data.0.duckdb_path, &data.1.duckdb_path, shift_factors::load_isone)
+}
+
+/// Estimated shift factors of NYISO nodes to the frequently binding DA
+/// constraints, e.g.
+/// /nyiso/congestion/shift_factors?term=Cal24&ptids=61752,61757
+#[get("/nyiso/congestion/shift_factors")]
+pub async fn api_nyiso_shift_factors(
+    query: web::Query<ShiftFactorsQuery>,
+    data: web::Data<(NyisoDalmpArchive, NyisoBindingConstraintsDaArchive)>,
+) -> impl Responder {
+    estimate(&query, &data.0.duckdb_path, &data.1.duckdb_path, shift_factors::load_nyiso)
+}
+
+type Loader = fn(&Connection, &Term, &[u32]) -> Result<CongestionData, Box<dyn std::error::Error>>;
+
+fn estimate(
+    query: &ShiftFactorsQuery,
+    lmp_path: &str,
+    constraints_path: &str,
+    load: Loader,
+) -> HttpResponse {
+    let term: Term = match query.term.parse() {
+        Ok(t) => t,
+        Err(e) => return HttpResponse::BadRequest().body(format!("Invalid term: {}", e)),
+    };
+    let ptids: Vec<u32> = match query
+        .ptids
+        .split(',')
+        .map(|s| s.trim().parse::<u32>())
+        .collect()
+    {
+        Ok(xs) => xs,
+        Err(e) => return HttpResponse::BadRequest().body(format!("Invalid ptids: {}", e)),
+    };
+
+    let conn = match Connection::open_in_memory() {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("Error opening DuckDB connection: {}", e))
+        }
+    };
+    let sql = format!(
+        "ATTACH '{}' AS lmp_db (READ_ONLY);\nATTACH '{}' AS constraints_db (READ_ONLY);",
+        lmp_path, constraints_path
+    );
+    if let Err(e) = conn.execute_batch(&sql) {
+        return HttpResponse::InternalServerError()
+            .body(format!("Error attaching DuckDB databases: {}", e));
+    }
+
+    match load(&conn, &term, &ptids) {
+        Ok(data) => HttpResponse::Ok().json(shift_factors::estimate(
+            &data,
+            &ptids,
+            query.min_binding_hours.unwrap_or(50),
+            query.max_constraints.unwrap_or(20),
+        )),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ShiftFactorsQuery {
+    /// A term like `Cal24` or `Jan25-Mar25`
+    pub term: String,
+    /// One or more ptids, separated by commas
+    pub ptids: String,
+    /// Only use the constraints binding in at least this many hours, default 50
+    pub min_binding_hours: Option<usize>,
+    /// Use at most this many constraints, the most frequently binding ones, default 20
+    pub max_constraints: Option<usize>,
+}
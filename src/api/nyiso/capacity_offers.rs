@@ -0,0 +1,216 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::AccessMode;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::db::nyiso::capacity_offers::*;
+use crate::db::nyiso::capacity_prices_monthly::{
+    self, NyisoCapacityPricesMonthlyArchive, QueryFilterBuilder as PricesFilterBuilder,
+};
+use crate::interval::month::Month;
+use crate::utils::lib_duckdb::open_with_retry;
+
+#[get("/nyiso/capacity_offers")]
+pub async fn get_data_api(
+    query: web::Query<ApiQuery>,
+    data: web::Data<(
+        NyisoCapacityOffersArchive,
+        NyisoCapacityPricesMonthlyArchive,
+    )>,
+) -> impl Responder {
+    let conn = open_with_retry(
+        &data.0.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    );
+    if conn.is_err() {
+        return HttpResponse::InternalServerError().body(format!(
+            "Error opening DuckDB database at {}: {}",
+            data.0.duckdb_path,
+            conn.err().unwrap(),
+        ));
+    }
+    let conn = conn.unwrap();
+
+    let query_filter = match query.to_query_filter() {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+    match get_data(&conn, &query_filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupplyCurveResponse {
+    pub supply_curve: Vec<SupplyPoint>,
+    pub clearing_prices: Vec<capacity_prices_monthly::Record>,
+}
+
+/// Return the aggregate supply curve of an auction for a locality (including
+/// the nested localities), next to the clearing prices of that auction, e.g.
+/// `/nyiso/capacity_offers/supply_curve/monthly/2025-06/2025-07/G-J`
+#[get(
+    "/nyiso/capacity_offers/supply_curve/{auction_type}/{auction_month}/{forward_month}/{location}"
+)]
+pub async fn api_supply_curve(
+    path: web::Path<(AuctionType, Month, Month, String)>,
+    data: web::Data<(
+        NyisoCapacityOffersArchive,
+        NyisoCapacityPricesMonthlyArchive,
+    )>,
+) -> impl Responder {
+    let (auction_type, auction_month, forward_month, location) = path.into_inner();
+    let conn = match open_with_retry(
+        &data.0.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    ) {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("Error opening DuckDB database: {}", e))
+        }
+    };
+    let supply_curve = match get_supply_curve(
+        &conn,
+        auction_type,
+        &auction_month,
+        &forward_month,
+        &location,
+    ) {
+        Ok(v) => v,
+        Err(e) => return HttpResponse::BadRequest().body(format!("Error querying data: {}", e)),
+    };
+
+    let conn = match open_with_retry(
+        &data.1.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    ) {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("Error opening DuckDB database: {}", e))
+        }
+    };
+    let filter = PricesFilterBuilder::new().location(location).build();
+    let clearing_prices = match capacity_prices_monthly::get_data(&conn, &filter, None) {
+        Ok(records) => {
+            auction_clearing_prices(records, auction_type, &auction_month, &forward_month)
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().body(format!("Error querying data: {}", e))
+        }
+    };
+
+    HttpResponse::Ok().json(SupplyCurveResponse {
+        supply_curve,
+        clearing_prices,
+    })
+}
+
+/// Only the results of the Monthly auctions are archived, in the
+/// `capacity_prices_monthly` table.  A forward month is cleared in several
+/// Monthly auctions, so keep the prices of the one auction held in
+/// `auction_month`.  There are no clearing prices for the Strip and Spot
+/// auctions.
+fn auction_clearing_prices(
+    records: Vec<capacity_prices_monthly::Record>,
+    auction_type: AuctionType,
+    auction_month: &Month,
+    forward_month: &Month,
+) -> Vec<capacity_prices_monthly::Record> {
+    match auction_type {
+        AuctionType::Monthly => records
+            .into_iter()
+            .filter(|r| {
+                r.auction_month == auction_month.to_string()
+                    && r.forward_month == forward_month.to_string()
+            })
+            .collect(),
+        AuctionType::Strip | AuctionType::Spot => vec![],
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiQuery {
+    pub auction_type: Option<String>,
+    pub auction_month: Option<String>,
+    pub forward_month: Option<String>,
+    pub location: Option<String>,
+    pub location_in: Option<String>,
+    pub _limit: Option<usize>,
+}
+
+impl ApiQuery {
+    pub fn to_query_filter(&self) -> Result<QueryFilter, String> {
+        Ok(QueryFilter {
+            auction_type: self
+                .auction_type
+                .as_ref()
+                .map(|s| s.parse::<AuctionType>())
+                .transpose()?,
+            auction_month: self.auction_month.clone(),
+            forward_month: self.forward_month.clone(),
+            location: self.location.clone(),
+            location_in: self
+                .location_in
+                .as_ref()
+                .map(|s| s.split(',').map(|v| v.trim().to_string()).collect()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::interval::month::month;
+
+    #[test]
+    fn test_auction_clearing_prices() {
+        let record = |auction_month: &str, forward_month: &str| capacity_prices_monthly::Record {
+            capability_period: "Summer 2025".to_string(),
+            auction_month: auction_month.to_string(),
+            forward_month: forward_month.to_string(),
+            location: "NYC".to_string(),
+            clearing_price: dec!(10.5),
+            awarded_mw: dec!(100),
+        };
+        let records = vec![
+            record("2025-05", "2025-07"),
+            record("2025-06", "2025-07"),
+            record("2025-06", "2025-08"),
+        ];
+        let prices = auction_clearing_prices(
+            records.clone(),
+            AuctionType::Monthly,
+            &month(2025, 6),
+            &month(2025, 7),
+        );
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices[0].auction_month, "2025-06");
+        assert_eq!(prices[0].forward_month, "2025-07");
+        // no prices for the other auctions
+        assert!(auction_clearing_prices(
+            records,
+            AuctionType::Spot,
+            &month(2025, 6),
+            &month(2025, 7),
+        )
+        .is_empty());
+    }
+}
@@ -220,7 +220,7 @@ WITH unpivot_alias AS (
 SELECT "Masked Gen ID", 
     "Date Time", 
     CAST("Segment" as UTINYINT) AS Segment, 
-    "MW", "Price", 
+    "MW"::DOUBLE AS "MW", "Price"::DOUBLE AS "Price",
 FROM unpivot_alias
 ORDER BY "Masked Gen ID", "Date Time", "Price";    
     "#,
@@ -315,7 +315,7 @@ pub fn get_stack(
         SELECT "Masked Gen ID", 
             "Date Time", 
             CAST("Segment" as UTINYINT) AS Segment, 
-            "MW", "Price", 
+            "MW"::DOUBLE AS "MW", "Price"::DOUBLE AS "Price",
         FROM unpivot_alias
         WHERE MW > 0
         ORDER BY "Masked Gen ID", "Date Time", "Price";    
@@ -377,6 +377,39 @@ mod tests {
         assert_eq!("dam".parse::<Market>(), Ok(Market::Dam));
     }
 
+    #[test]
+    fn test_fractional_prices() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(&crate::db::nyiso::energy_offers::setup_sql())?;
+        conn.execute_batch(
+            r#"
+INSERT INTO offers ("Masked Gen ID", "Date Time", "Market", "Upper Oper Limit",
+    "Dispatch $/MW1", "Dispatch MW1", "Dispatch $/MW2", "Dispatch MW2", "Bid Status")
+VALUES (35537750, '2024-03-01 00:00:00-05:00', 'DAM', 200, 15.37, 150.5, 25.61, 180.2, 'ACCEPTED');
+"#,
+        )?;
+        let xs = get_energy_offers(&conn, Market::Dam, date(2024, 3, 1), date(2024, 3, 1), None)?;
+        assert_eq!(
+            xs[0],
+            EnergyOffer {
+                masked_asset_id: 35537750,
+                timestamp_s: 1709269200,
+                segment: 0,
+                price: 15.37,
+                quantity: 150.5
+            }
+        );
+        let xs = get_stack(
+            &conn,
+            Market::Dam,
+            vec!["2024-03-01 00:00:00-05".parse().unwrap()],
+        )?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[1].price, 25.61);
+        assert_eq!(xs[1].quantity, 29.7);
+        Ok(())
+    }
+
     #[test]
     fn test_get_masked_unit_ids() -> Result<()> {
         let config = Config::default().access_mode(AccessMode::ReadOnly)?;
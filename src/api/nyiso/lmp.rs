@@ -74,14 +74,14 @@ async fn api_hourly_prices(
                 }
             };
             let prices =
-                get_hourly_prices_compact(&conn, start_date, end_date, ptids, component).unwrap();
+                get_hourly_prices_compact(&conn, start_date, end_date, market, ptids, component).unwrap();
             use actix_web::http::header::HeaderName;
             HttpResponse::Ok()
                 .insert_header((HeaderName::from_static("content-type"), "application/json"))
                 .body(prices)
         }
         _ => {
-            let offers = get_hourly_prices(&conn, start_date, end_date, ptids, components).unwrap();
+            let offers = get_hourly_prices(&conn, start_date, end_date, market, ptids, components).unwrap();
             HttpResponse::Ok().json(offers)
         }
     }
@@ -142,7 +142,7 @@ async fn api_daily_prices(
     let statistic = query.statistic.clone().unwrap_or("mean".into());
 
     let prices = get_daily_prices(
-        &conn, start_date, end_date, ptids, component, buckets, statistic,
+        &conn, start_date, end_date, market, ptids, component, buckets, statistic,
     )
     .unwrap();
     HttpResponse::Ok().json(prices)
@@ -188,6 +188,7 @@ async fn api_monthly_prices(
         &conn,
         start_month,
         end_month,
+        market,
         ptids,
         component,
         buckets,
@@ -210,19 +211,26 @@ pub struct Row {
     price: Decimal,
 }
 
+/// Name of the DuckDB table for the market, `dalmp` or `rtlmp`.  Both tables
+/// have the same schema.
+fn table(market: Market) -> String {
+    format!("{}lmp", market.to_string().to_lowercase())
+}
+
 /// Get hourly prices between a [start, end] date for a list of ptids
 ///
 pub fn get_hourly_prices(
     conn: &Connection,
     start: Date,
     end: Date,
+    market: Market,
     ptids: Option<Vec<u32>>,
     components: Option<Vec<LmpComponent>>,
 ) -> Result<Vec<Row>> {
     let query = format!(
         r#"
 WITH unpivot_alias AS (
-    UNPIVOT dalmp
+    UNPIVOT {}
     ON {}
     INTO
         NAME component
@@ -238,6 +246,7 @@ WHERE hour_beginning >= '{}'
 AND hour_beginning < '{}'{}
 ORDER BY component, ptid, hour_beginning; 
     "#,
+        table(market),
         match components {
             Some(cs) => cs.iter().join(", ").to_string().replace("mcl", "mlc"),
             None => "lmp, mcc, mlc".to_string(),
@@ -286,6 +295,7 @@ pub fn get_hourly_prices_compact(
     conn: &Connection,
     start: Date,
     end: Date,
+    market: Market,
     ptids: Option<Vec<u32>>,
     component: LmpComponent,
 ) -> Result<String> {
@@ -303,7 +313,7 @@ FROM (
         strftime(hour_beginning, '%Y-%m-%d') AS date,
         ptid,
         list({} ORDER BY hour_beginning)::DECIMAL(9,4)[] AS prices
-    FROM dalmp
+    FROM {}
     WHERE hour_beginning >= '{}'
         AND hour_beginning <  '{}'{}
     GROUP BY date, ptid
@@ -317,6 +327,7 @@ FROM (
 );
     "#,
         c,
+        table(market),
         start
             .in_tz("America/New_York")
             .unwrap()
@@ -339,10 +350,12 @@ FROM (
     Ok(out.unwrap())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn get_daily_prices(
     conn: &Connection,
     start: Date,
     end: Date,
+    market: Market,
     ptids: Option<Vec<i32>>,
     component: LmpComponent,
     buckets: Vec<Bucket>,
@@ -363,6 +376,7 @@ pub fn get_daily_prices(
             conn,
             start,
             end,
+            market,
             ptids.clone(),
             bucket,
             component,
@@ -374,10 +388,12 @@ pub fn get_daily_prices(
     Ok(prices)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn get_daily_prices_bucket(
     conn: &Connection,
     start: Date,
     end: Date,
+    market: Market,
     ptids: Option<Vec<i32>>,
     bucket: Bucket,
     component: LmpComponent,
@@ -393,7 +409,7 @@ SELECT
     ptid,
     hour_beginning::DATE AS day,
     {}({})::DECIMAL(9,4) AS price,
-FROM dalmp
+FROM {}
 JOIN buckets.buckets 
     USING (hour_beginning)
 WHERE hour_beginning >= '{}'
@@ -404,6 +420,7 @@ ORDER BY ptid, day;
         "#,
         statistic,
         c,
+        table(market),
         start
             .in_tz("America/New_York")
             .unwrap()
@@ -450,10 +467,12 @@ pub struct RowD {
     value: Decimal,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn get_monthly_prices(
     conn: &Connection,
     start: Month,
     end: Month,
+    market: Market,
     ptids: Option<Vec<i32>>,
     component: LmpComponent,
     buckets: Vec<Bucket>,
@@ -474,6 +493,7 @@ pub fn get_monthly_prices(
             conn,
             start,
             end,
+            market,
             ptids.clone(),
             component,
             bucket,
@@ -485,10 +505,12 @@ pub fn get_monthly_prices(
     Ok(prices)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn get_monthly_prices_bucket(
     conn: &Connection,
     start: Month,
     end: Month,
+    market: Market,
     ptids: Option<Vec<i32>>,
     component: LmpComponent,
     bucket: Bucket,
@@ -504,7 +526,7 @@ SELECT
     ptid,
     date_trunc('month', hour_beginning) AS month_beginning,
     {}({})::DECIMAL(9,4) AS price,
-FROM dalmp
+FROM {}
 JOIN buckets.buckets 
     USING (hour_beginning)
 WHERE hour_beginning >= '{}'
@@ -515,6 +537,7 @@ ORDER BY ptid, month_beginning;
         "#,
         statistic,
         c,
+        table(market),
         start
             .start()
             .in_tz("America/New_York")
@@ -575,6 +598,46 @@ mod tests {
 
     use crate::{api::nyiso::lmp::*, db::prod_db::ProdDb, interval::month::month};
 
+    #[test]
+    fn test_hourly_data_rt() -> Result<(), Box<dyn Error>> {
+        // DA and RT tables have the same schema
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE dalmp (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    ptid INTEGER NOT NULL,
+    lmp DECIMAL(9,2) NOT NULL,
+    mlc DECIMAL(9,2) NOT NULL,
+    mcc DECIMAL(9,2) NOT NULL,
+);
+CREATE TABLE rtlmp AS SELECT * FROM dalmp;
+INSERT INTO dalmp VALUES ('2025-10-15 00:00:00-04:00', 61758, 37.07, 1.10, -0.50);
+INSERT INTO rtlmp VALUES ('2025-10-15 00:00:00-04:00', 61758, 41.25, 1.20, 0.00);
+",
+        )?;
+        let da = get_hourly_prices(
+            &conn,
+            date(2025, 10, 15),
+            date(2025, 10, 15),
+            Market::DA,
+            Some(vec![61758]),
+            Some(vec![LmpComponent::Lmp]),
+        )?;
+        let rt = get_hourly_prices(
+            &conn,
+            date(2025, 10, 15),
+            date(2025, 10, 15),
+            Market::RT,
+            Some(vec![61758]),
+            Some(vec![LmpComponent::Lmp]),
+        )?;
+        assert_eq!(rt.len(), 1);
+        assert_eq!(rt[0].hour_beginning, da[0].hour_beginning);
+        assert_eq!(da[0].price - rt[0].price, dec!(-4.18));
+        Ok(())
+    }
+
     #[test]
     fn test_hourly_data() -> Result<(), Box<dyn Error>> {
         let config = Config::default().access_mode(AccessMode::ReadOnly)?;
@@ -583,6 +646,7 @@ mod tests {
             &conn,
             date(2025, 10, 15),
             date(2025, 10, 16),
+            Market::DA,
             Some(vec![61758]),
             Some(vec![LmpComponent::Lmp]),
         )
@@ -609,6 +673,7 @@ mod tests {
             &conn,
             date(2025, 7, 1),
             date(2025, 7, 14),
+            Market::DA,
             Some(vec![61758, 31759]),
             LmpComponent::Mcc,
         )
@@ -625,6 +690,7 @@ mod tests {
             &conn,
             date(2025, 10, 15),
             date(2025, 10, 16),
+            Market::DA,
             Some(vec![61758]),
             LmpComponent::Lmp,
             vec![
@@ -659,6 +725,7 @@ mod tests {
             &conn,
             date(2025, 7, 1),
             date(2025, 7, 14),
+            Market::DA,
             Some(vec![61758]),
             LmpComponent::Lmp,
             vec![Bucket::B5x16],
@@ -685,6 +752,7 @@ mod tests {
             &conn,
             date(2025, 7, 1),
             date(2025, 7, 14),
+            Market::DA,
             Some(vec![61758]),
             LmpComponent::Lmp,
             vec![Bucket::B2x16H],
@@ -712,6 +780,7 @@ mod tests {
             &conn,
             month(2025, 1),
             month(2025, 7),
+            Market::DA,
             Some(vec![61758]),
             LmpComponent::Lmp,
             vec![
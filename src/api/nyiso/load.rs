@@ -0,0 +1,142 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use duckdb::AccessMode;
+use serde::Deserialize;
+use std::time::Duration;
+
+use jiff::civil::Date;
+use jiff::Zoned;
+
+use crate::db::nyiso::load_forecast::{self, NyisoLoadForecastArchive};
+use crate::db::nyiso::rt_load::{self, NyisoRtLoadArchive};
+use crate::utils::lib_duckdb::open_with_retry;
+
+/// Integrated RT load by zone, e.g.
+/// /nyiso/load/rt?name=N.Y.C.&hour_beginning_gte=2025-05-01T00:00:00-04:00[America/New_York]
+#[get("/nyiso/load/rt")]
+pub async fn api_rt_load(
+    query: web::Query<RtLoadQuery>,
+    data: web::Data<NyisoRtLoadArchive>,
+) -> impl Responder {
+    let conn = open_with_retry(
+        &data.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    );
+    if conn.is_err() {
+        return HttpResponse::InternalServerError().body(format!(
+            "Error opening DuckDB database at {}: {}",
+            data.duckdb_path,
+            conn.err().unwrap(),
+        ));
+    }
+    let conn = conn.unwrap();
+
+    let query_filter = query.to_query_filter();
+    match rt_load::get_data(&conn, &query_filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+/// ISO load forecast vintages by zone, e.g.
+/// /nyiso/load/forecast?zone=NYISO&as_of_date=2025-05-01
+#[get("/nyiso/load/forecast")]
+pub async fn api_load_forecast(
+    query: web::Query<LoadForecastQuery>,
+    data: web::Data<NyisoLoadForecastArchive>,
+) -> impl Responder {
+    let conn = open_with_retry(
+        &data.duckdb_path,
+        8,
+        Duration::from_millis(25),
+        AccessMode::ReadOnly,
+    );
+    if conn.is_err() {
+        return HttpResponse::InternalServerError().body(format!(
+            "Error opening DuckDB database at {}: {}",
+            data.duckdb_path,
+            conn.err().unwrap(),
+        ));
+    }
+    let conn = conn.unwrap();
+
+    let query_filter = query.to_query_filter();
+    match load_forecast::get_data(&conn, &query_filter, query._limit) {
+        Ok(records) => {
+            if records.len() > 100_000 {
+                HttpResponse::BadRequest()
+                    .body(format!("Query returned {} records, only a max of 100,000 are allowed.  Please narrow your query.", records.len()))
+            } else {
+                HttpResponse::Ok().json(records)
+            }
+        }
+        Err(e) => HttpResponse::InternalServerError().body(format!("Error querying data: {}", e)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RtLoadQuery {
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    pub ptid: Option<i32>,
+    pub ptid_in: Option<String>,
+    pub name: Option<String>,
+    pub name_in: Option<String>,
+    pub _limit: Option<usize>,
+}
+
+impl RtLoadQuery {
+    pub fn to_query_filter(&self) -> rt_load::QueryFilter {
+        rt_load::QueryFilter {
+            hour_beginning_gte: self.hour_beginning_gte.clone(),
+            hour_beginning_lt: self.hour_beginning_lt.clone(),
+            ptid: self.ptid,
+            ptid_in: self
+                .ptid_in
+                .as_ref()
+                .map(|s| s.split(',').map(|v| v.trim().parse().unwrap()).collect()),
+            name: self.name.clone(),
+            name_in: self
+                .name_in
+                .as_ref()
+                .map(|s| s.split(',').map(|v| v.trim().to_string()).collect()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LoadForecastQuery {
+    pub as_of_date: Option<Date>,
+    pub as_of_date_gte: Option<Date>,
+    pub as_of_date_lte: Option<Date>,
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    pub zone: Option<String>,
+    pub zone_in: Option<String>,
+    pub _limit: Option<usize>,
+}
+
+impl LoadForecastQuery {
+    pub fn to_query_filter(&self) -> load_forecast::QueryFilter {
+        load_forecast::QueryFilter {
+            as_of_date: self.as_of_date,
+            as_of_date_gte: self.as_of_date_gte,
+            as_of_date_lte: self.as_of_date_lte,
+            hour_beginning_gte: self.hour_beginning_gte.clone(),
+            hour_beginning_lt: self.hour_beginning_lt.clone(),
+            zone: self.zone.clone(),
+            zone_in: self
+                .zone_in
+                .as_ref()
+                .map(|s| s.split(',').map(|v| v.trim().to_string()).collect()),
+        }
+    }
+}
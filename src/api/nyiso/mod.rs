@@ -1,9 +1,10 @@
 pub mod binding_constraints;
-// pub mod capacity_offers;
+pub mod capacity_offers;
 pub mod capacity_prices_monthly;
 pub mod capacity_seasons;
 pub mod energy_offers;
 pub mod lmp;
+pub mod load;
 pub mod ptid_table;
 pub mod scheduled_outages;
 pub mod transmission_outages;
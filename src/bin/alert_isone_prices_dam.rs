@@ -1,10 +1,7 @@
 use std::{env, error::Error, path::Path};
 
 use bust::{
-    api::isone::{
-        _api_isone_core::Market,
-        lmp::{get_hourly_prices, Row},
-    },
+    api::isone::lmp::{get_hourly_prices, Row},
     db::{nyiso::dalmp::LmpComponent, prod_db::ProdDb},
     utils::{lib_duckdb::open_with_retry, send_email::*},
 };
@@ -120,9 +117,10 @@ fn get_data(as_of: Date) -> Result<Vec<Row>, Box<dyn Error>> {
         &conn,
         as_of,
         as_of,
-        Market::DA,
+        &ProdDb::isone_dalmp().revision_spec(),
         Some(vec![4000, 4001, 4002, 4004, 4005, 4006, 4007, 4008]),
         Some(vec![LmpComponent::Lmp]),
+        None,
     )
     .unwrap();
     Ok(data)
@@ -158,4 +156,3 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
-
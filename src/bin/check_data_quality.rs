@@ -0,0 +1,71 @@
+use std::{env, error::Error, path::Path};
+
+use bust::{
+    db::{data_quality::run_checks, prod_db::ProdDb},
+    interval::term::Term,
+    utils::send_email::send_email_blocking,
+};
+use clap::Parser;
+use jiff::{ToSpan, Zoned};
+use log::{error, info};
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Environment name, e.g., test, prod
+    #[arg(short, long, default_value = "prod")]
+    env: String,
+
+    /// Number of days to look back, ending yesterday
+    #[arg(short, long, default_value_t = 7)]
+    days: i32,
+}
+
+/// Run this job every day at 7:00AM.  Email a report only if there are issues.
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+
+    dotenvy::from_path(Path::new(format!(".env/{}.env", args.env).as_str())).unwrap();
+
+    let end = Zoned::now().date().yesterday()?;
+    let start = end.checked_sub((args.days - 1).days())?;
+    let term = Term::new(start, end).unwrap();
+
+    let mut sections: Vec<String> = Vec::new();
+    for spec in ProdDb::quality_specs() {
+        match run_checks(&spec, &term) {
+            Ok(report) if report.is_ok() => info!("{}: no issues", spec.name),
+            Ok(report) => {
+                info!("{}: {} issues", spec.name, report.issues.len());
+                sections.push(report.to_html());
+            }
+            Err(e) => {
+                error!("{}: {}", spec.name, e);
+                sections.push(format!("<h3>{}</h3><p>Checks failed: {}</p>", spec.name, e));
+            }
+        }
+    }
+    if sections.is_empty() {
+        return Ok(());
+    }
+
+    let html = format!("<html><body>{}</body></html>", sections.join("\n"));
+    let response = send_email_blocking(
+        env::var("EMAIL_FROM").unwrap(),
+        vec![env::var("EMAIL_MAIN").unwrap()],
+        format!("Data quality issues for {}", term),
+        "".to_string(),
+        Some(html),
+    )?;
+    if response.status().is_success() {
+        info!("Email sent successfully!");
+    } else {
+        error!("Failed to send email. Status: {:?}", response.status());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use std::error::Error;
+
+use bust::db::prod_db::ProdDb;
+use clap::Parser;
+
+/// Export a DuckDB table to hive-partitioned Parquet files, or recreate
+/// the table from a previous export, e.g.
+///   parquet_archive --duckdb-path .../isone/dalmp.duckdb --table da_lmp --time-column hour_beginning
+///   parquet_archive --duckdb-path .../isone/dalmp.duckdb --table da_lmp --import
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Path to the DuckDB file
+    #[arg(long)]
+    duckdb_path: String,
+
+    /// Name of the table
+    #[arg(long)]
+    table: String,
+
+    /// Column used to partition the data by year and month.  If missing,
+    /// the table is exported to one file.
+    #[arg(long)]
+    time_column: Option<String>,
+
+    /// Timezone of the archive, used for the year/month partitions, e.g.
+    /// Etc/GMT+5 for IESO
+    #[arg(long, default_value = "America/New_York")]
+    tz: String,
+
+    /// Import the table from Parquet instead of exporting it
+    #[arg(long, default_value_t = false)]
+    import: bool,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+
+    let archive = ProdDb::parquet_archive();
+    if args.import {
+        archive.import_table(&args.duckdb_path, &args.table)?;
+    } else {
+        archive.export_table(
+            &args.duckdb_path,
+            &args.table,
+            args.time_column.as_deref(),
+            &args.tz,
+        )?;
+    }
+    Ok(())
+}
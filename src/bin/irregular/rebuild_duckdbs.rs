@@ -5,22 +5,13 @@ use std::{
 };
 
 use bust::db::prod_db::ProdDb;
-use duckdb::{params, Connection};
 use regex::Regex;
 
 fn rebuild_isone_sevenday_solar_forecast() -> Result<(), Box<dyn Error>> {
     info!("rebuilding isone_seven_day_forecast archive ...");
     let archive = ProdDb::isone_sevenday_solar_forecast();
     fs::remove_file(&archive.duckdb_path)?;
-    let conn = Connection::open(archive.duckdb_path)?;
-    conn.execute_batch(
-        r"
-CREATE TABLE IF NOT EXISTS forecast (
-    report_date DATE,
-    forecast_hour_beginning TIMESTAMPTZ,
-    forecast_generation USMALLINT,
-);",
-    )?;
+    archive.setup()?;
 
     // list all the monthly files and add them to the db, in order
     let mut paths: Vec<_> = fs::read_dir(archive.base_dir.clone() + "/month")
@@ -33,19 +24,8 @@ CREATE TABLE IF NOT EXISTS forecast (
     for path in paths {
         let filename = path.file_name();
         let month = re.find(filename.to_str().unwrap()).unwrap().as_str();
-        info!("month {month} ...");
-        let sql = format!(
-            r"
-INSERT INTO forecast
-FROM read_csv(
-    '{}/month/solar_forecast_{}.csv.gz', 
-    header = true, 
-    timestampformat = '%Y-%m-%dT%H:%M:%S.000%z');",
-            archive.base_dir, month
-        );
-        match conn.execute(&sql, params![]) {
-            Ok(n) => info!("  inserted {} rows", n),
-            Err(e) => error!("{:?}", e),
+        if let Err(e) = archive.update_duckdb(&month.parse()?) {
+            error!("{:?}", e);
         }
     }
     info!("done\n");
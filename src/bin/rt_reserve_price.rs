@@ -1,53 +1,47 @@
-use std::error::Error;
+use std::{error::Error, path::Path};
 
-use duckdb::Connection;
+use bust::{
+    db::{isone::rt_reserve_prices_archive::RtReserveReport, prod_db::ProdDb},
+    interval::month::{month, Month},
+};
+use clap::Parser;
+use jiff::Zoned;
+use log::info;
 
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Environment name, e.g., test, prod
+    #[arg(short, long, default_value = "prod")]
+    env: String,
+}
+
+/// Run this job every day in the morning.  Downloads the 5-minute and hourly
+/// ISONE RT reserve prices for the days that are missing.
 fn main() -> Result<(), Box<dyn Error>> {
-    let conn = Connection::open(
-        "/home/adrian/Downloads/Archive/IsoExpress/PricingReports/RtReservePrice/foo.duckdb",
-    )?;
+    let args = Args::parse();
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+
+    dotenvy::from_path(Path::new(format!(".env/{}.env", args.env).as_str())).unwrap();
+
+    let asof = Zoned::now().date().yesterday()?;
+    let current_month = month(asof.year(), asof.month());
+    let mut months: Vec<Month> = Vec::new();
+    if asof.day() < 4 {
+        months.push(current_month.previous());
+    }
+    months.push(current_month);
+    info!("Updating ISONE RT reserve prices for months: {:?}", months);
 
-    conn.execute_batch(
-        r"
-CREATE TABLE IF NOT EXISTS foo (
-    IntervalBeginning5Min TIMESTAMPTZ,
-    Ros10MinSpinRequirement FLOAT,
-    RosTotal10MinRequirement FLOAT,
-    RosTotal30MinRequirement FLOAT,
-    RosTmsrDesignatedMw FLOAT,
-    RosTmnsrDesignatedMw FLOAT,
-    RosTmorDesignatedMw FLOAT,
-    RosTmsrClearingPrice FLOAT,
-    RosTmnsrClearingPrice FLOAT,
-    RosTmorClearingPrice FLOAT,
-    SwctTotal30MinRequirement FLOAT,
-    SwctTmsrDesignatedMw FLOAT,
-    SwctTmnsrDesignatedMw FLOAT,
-    SwctTmorDesignatedMw FLOAT,
-    SwctTmsrClearingPrice FLOAT,
-    SwctTmnsrClearingPrice FLOAT,
-    SwctTmorClearingPrice FLOAT,
-    CtTotal30MinRequirement FLOAT,
-    CtTmsrDesignatedMw FLOAT,
-    CtTmnsrDesignatedMw FLOAT,
-    CtTmorDesignatedMw FLOAT,
-    CtTmsrClearingPrice FLOAT,
-    CtTmnsrClearingPrice FLOAT,
-    CtTmorClearingPrice FLOAT,
-    NemabstnTotal30MinRequirement FLOAT,
-    NemabstnTmsrDesignatedMw FLOAT,
-    NemabstnTmnsrDesignatedMw FLOAT,
-    NemabstnTmorDesignatedMw FLOAT,
-    NemabstnTmsrClearingPrice FLOAT,
-    NemabstnTmnsrClearingPrice FLOAT,
-    NemabstnTmorClearingPrice FLOAT
-);
-INSERT INTO foo
-FROM read_csv(
-    '/home/adrian/Downloads/Archive/IsoExpress/PricingReports/RtReservePrice/month/rt_reserve_price_2021-01.csv.gz', 
-    header = true, 
-    timestampformat = '%Y-%m-%dT%H:%M:%S.000%z');
-        ")?;
+    let archive = ProdDb::isone_rt_reserve_prices();
+    for month in months {
+        for report in [RtReserveReport::Hourly, RtReserveReport::FiveMinute] {
+            archive.download_missing_days(month, report)?;
+            archive.update_duckdb(&month, report)?;
+        }
+    }
 
     Ok(())
 }
@@ -64,6 +64,12 @@ async fn main() -> std::io::Result<()> {
             .app_data(Data::new(ProdDb::caiso_dalmp()))
             .app_data(Data::new(ProdDb::caiso_public_bids()))
             .app_data(Data::new(ProdDb::ieso_dalmp_nodes()))
+            .app_data(Data::new(ProdDb::ieso_generation_output_by_fuel()))
+            .app_data(Data::new(ProdDb::ieso_vgforecast_summary()))
+            .app_data(Data::new((
+                ProdDb::ieso_vgforecast_summary(),
+                ProdDb::ieso_generation_output_by_fuel(),
+            )))
             .app_data(Data::new((
                 ProdDb::caiso_dalmp(),
                 ProdDb::caiso_rtlmp(),
@@ -79,24 +85,79 @@ async fn main() -> std::io::Result<()> {
                 ProdDb::buckets(),
                 ProdDb::isone_ftr_cleared_prices(),
             )))
+            .app_data(Data::new((ProdDb::isone_rtlmp5(), ProdDb::isone_rtlmp())))
+            .app_data(Data::new((
+                ProdDb::isone_rt_reserve_prices(),
+                ProdDb::isone_daas_reserve_data(),
+            )))
+            .app_data(Data::new(ProdDb::isone_capacity_auction_results()))
             .app_data(Data::new(ProdDb::isone_da_binding_constraints()))
+            .app_data(Data::new(ProdDb::isone_rt_binding_constraints()))
+            .app_data(Data::new((
+                ProdDb::isone_da_binding_constraints(),
+                ProdDb::isone_rt_binding_constraints(),
+            )))
+            .app_data(Data::new(ProdDb::isone_single_source_contingency()))
+            .app_data(Data::new(ProdDb::isone_ttc()))
+            .app_data(Data::new((
+                ProdDb::isone_single_source_contingency(),
+                ProdDb::isone_ttc(),
+                ProdDb::isone_actual_interchange(),
+            )))
             .app_data(Data::new(ProdDb::isone_mra_bids_offers()))
             .app_data(Data::new(ProdDb::isone_masked_ara_bids_offers()))
             .app_data(Data::new(ProdDb::isone_masked_daas_offers()))
             .app_data(Data::new(ProdDb::isone_masked_demand_bids()))
+            .app_data(Data::new((
+                ProdDb::isone_masked_demand_bids(),
+                ProdDb::isone_dalmp(),
+                ProdDb::isone_rtlmp(),
+            )))
+            .app_data(Data::new((
+                ProdDb::isone_masked_import_export(),
+                ProdDb::isone_actual_interchange(),
+            )))
+            .app_data(Data::new(ProdDb::isone_fuel_mix()))
+            .app_data(Data::new((
+                ProdDb::isone_sevenday_solar_forecast(),
+                ProdDb::isone_fuel_mix(),
+            )))
             .app_data(Data::new(ProdDb::isone_masked_da_energy_offers()))
+            .app_data(Data::new((
+                ProdDb::isone_masked_da_energy_offers(),
+                ProdDb::isone_dalmp(),
+                ProdDb::isone_system_load(),
+            )))
             .app_data(Data::new(ProdDb::isone_participants_archive()))
+            .app_data(Data::new(ProdDb::isone_events_calendar()))
+            .app_data(Data::new(ProdDb::isone_load_forecast()))
+            .app_data(Data::new(ProdDb::isone_sevenday_capacity_forecast()))
+            .app_data(Data::new(ProdDb::isone_system_load()))
             .app_data(Data::new(ProdDb::nrc_generator_status()))
             .app_data(Data::new(ProdDb::sd_daasdt()))
             .app_data(Data::new(ProdDb::sd_rtload()))
             .app_data(Data::new(ProdDb::sr_rsvcharge2()))
             .app_data(Data::new(ProdDb::sr_rsvstl2()))
             .app_data(Data::new(ProdDb::nodal_contracts()))
+            .app_data(Data::new((
+                ProdDb::isone_dalmp(),
+                ProdDb::isone_da_binding_constraints(),
+            )))
+            .app_data(Data::new((
+                ProdDb::nyiso_dalmp(),
+                ProdDb::nyiso_binding_constraints_da(),
+            )))
             .app_data(Data::new(ProdDb::nyiso_binding_constraints_da()))
+            .app_data(Data::new((
+                ProdDb::nyiso_capacity_offers(),
+                ProdDb::nyiso_capacity_prices_monthly(),
+            )))
             .app_data(Data::new(ProdDb::nyiso_capacity_prices_monthly()))
             .app_data(Data::new(ProdDb::nyiso_capacity_seasons()))
             .app_data(Data::new((ProdDb::nyiso_dalmp(), ProdDb::nyiso_rtlmp())))
             .app_data(Data::new(ProdDb::nyiso_energy_offers()))
+            .app_data(Data::new(ProdDb::nyiso_load_forecast()))
+            .app_data(Data::new(ProdDb::nyiso_rt_load()))
             .app_data(Data::new(ProdDb::nyiso_ptid_table()))
             .app_data(Data::new(ProdDb::nyiso_scheduled_outages()))
             .app_data(Data::new(ProdDb::nyiso_transmission_outages_da()))
@@ -105,6 +166,8 @@ async fn main() -> std::io::Result<()> {
             .app_data(Data::from(Arc::new(ProdDb {}) as Arc<dyn EpaEmissionsDbProvider>))
             .service(hello)
             // Admin
+            .service(admin::data_quality::api_get_names)
+            .service(admin::data_quality::api_report)
             .service(admin::jobs::api_get_job_names)
             .service(admin::jobs::api_get_log)
             .service(admin::jobs::api_run_job)
@@ -130,24 +193,57 @@ async fn main() -> std::io::Result<()> {
             .service(ieso::node_table::api_get_all)
             .service(ieso::dalmp::api_hourly_prices)
             .service(ieso::dalmp::api_daily_prices)
+            .service(ieso::generation::api_data)
+            .service(ieso::generation::api_aggregate)
+            .service(ieso::vg_forecast::api_data)
+            .service(ieso::vg_forecast::api_aggregate)
+            .service(ieso::vg_forecast::api_accuracy)
             // ISONE
             .service(isone::actual_interchange::api_actual_flows)
             .service(isone::binding_constraints_da::get_data_api)
+            .service(isone::binding_constraints_rt::get_data_api)
+            .service(isone::binding_constraints_rt::get_binding_stats_api)
+            .service(isone::capacity::auction_results::api_interface_results)
+            .service(isone::capacity::auction_results::api_zone_results)
             .service(isone::capacity::monthly_capacity_results::participant_ids)
             .service(isone::capacity::monthly_capacity_results::results_interface)
             .service(isone::capacity::monthly_capacity_results::results_zone)
             .service(isone::capacity::monthly_capacity_bidsoffers::bids_offers)
             .service(isone::ftr::api_monthly_settle_prices)
+            .service(isone::lmp::api_da_revisions)
             .service(isone::lmp::api_daily_prices)
             .service(isone::lmp::api_hourly_prices)
             .service(isone::lmp::api_monthly_prices)
             .service(isone::lmp::api_term_prices)
+            // register before api_prices, "validate" is not a version
+            .service(isone::events::api_events_ical)
+            .service(isone::events::api_events)
+            .service(isone::lmp5::api_validate_hourly)
+            .service(isone::lmp5::api_hourly_prices)
+            .service(isone::lmp5::api_prices)
+            .service(isone::load::api_load_forecast)
+            .service(isone::load::api_system_load)
+            .service(isone::reserve_prices::api_da_rt_prices)
+            .service(isone::reserve_prices::api_rt_prices)
+            .service(isone::fuel_mix::get_data_api)
+            .service(isone::fuel_mix::get_aggregate_api)
+            .service(isone::fuel_mix::get_marginal_api)
+            .service(isone::sevenday_capacity_forecast::api_vintages)
+            .service(isone::sevenday_capacity_forecast::api_surplus_deficiency)
+            .service(isone::solar_forecast::api_accuracy)
             .service(isone::masked::masked_daas_offers::api_offers)
             .service(isone::masked::masked_demand_bids::api_bids)
             .service(isone::masked::masked_demand_bids::api_bids_daily_agg)
             .service(isone::masked::masked_demand_bids::api_bids_daily_zonal)
+            .service(isone::masked::demand_bid_analytics::api_demand_analytics)
             .service(isone::masked::masked_energy_offers::api_offers)
             .service(isone::masked::masked_energy_offers::api_stack)
+            .service(isone::masked::offer_clearing::api_clearing)
+            .service(isone::masked::offer_clearing::api_replay)
+            .service(isone::masked::import_export::api_data)
+            .service(isone::masked::import_export::api_hourly_summary)
+            .service(isone::masked::import_export::api_supply_curve)
+            .service(isone::masked::import_export::api_supply_curve_vs_flows)
             .service(isone::mis::sd_daasdt::api_daily_charges)
             .service(isone::mis::sd_daasdt::api_daily_credits)
             .service(isone::mis::sd_daasdt::api_tab_data)
@@ -156,14 +252,21 @@ async fn main() -> std::io::Result<()> {
             .service(isone::mis::sr_rsvstl2::api_daily_credits)
             .service(isone::mis::sr_rsvstl2::api_tab_data)
             .service(isone::participant_list::get_data_api)
+            .service(isone::interface_headroom::api_interface_headroom)
+            .service(isone::ssc::api_ssc)
+            .service(isone::ttc::api_ttc)
             .service(isone::ttc::api_ttc_data)
             // Nodal
             .service(nodal::contracts::get_data_api)
+            .service(nodal::shift_factors::api_isone_shift_factors)
+            .service(nodal::shift_factors::api_nyiso_shift_factors)
             // NRC
             .service(nrc::generator_status::api_get_names)
             .service(nrc::generator_status::api_status)
             // NYISO
             .service(nyiso::binding_constraints::get_data_api)
+            .service(nyiso::capacity_offers::api_supply_curve)
+            .service(nyiso::capacity_offers::get_data_api)
             .service(nyiso::capacity_prices_monthly::get_data_api)
             .service(nyiso::capacity_seasons::get_data_api)
             .service(nyiso::energy_offers::api_offers)
@@ -171,6 +274,8 @@ async fn main() -> std::io::Result<()> {
             .service(nyiso::lmp::api_daily_prices)
             .service(nyiso::lmp::api_hourly_prices)
             .service(nyiso::lmp::api_monthly_prices)
+            .service(nyiso::load::api_load_forecast)
+            .service(nyiso::load::api_rt_load)
             .service(nyiso::ptid_table::get_data_api)
             .service(nyiso::scheduled_outages::api_scheduled_outages)
             .service(nyiso::transmission_outages::api_transmission_outages_da)
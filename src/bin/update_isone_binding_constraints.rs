@@ -3,7 +3,10 @@ use std::{env, error::Error, path::Path, time::Duration};
 use build_html::{Html, HtmlContainer, HtmlPage};
 use bust::{
     db::{
-        isone::{binding_constraints_da::get_new_constraints, lib_dam::is_dalmp_published},
+        isone::{
+            binding_constraints_da::get_new_constraints,
+            binding_constraints_rt::RtConstraintReport, lib_dam::is_dalmp_published,
+        },
         prod_db::ProdDb,
     },
     interval::month::month,
@@ -57,6 +60,15 @@ fn main() -> Result<(), Box<dyn Error>> {
         archive.update_duckdb(&prev_month)?;
     }
 
+    // RT constraints are complete for yesterday
+    let rt_archive = ProdDb::isone_rt_binding_constraints();
+    let yesterday = Zoned::now().date().yesterday().unwrap();
+    let rt_month = month(yesterday.year(), yesterday.month());
+    for report in [RtConstraintReport::FiveMinute, RtConstraintReport::Hourly] {
+        rt_archive.download_missing_days(rt_month, report)?;
+        rt_archive.update_duckdb(&rt_month, report)?;
+    }
+
     // Check if there are new constraints and email them
     let conn = open_with_retry(
         &archive.duckdb_path,
@@ -0,0 +1,33 @@
+use std::{error::Error, path::Path};
+
+use bust::{db::prod_db::ProdDb, interval::month::month};
+use clap::Parser;
+use jiff::Zoned;
+
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about,
+    long_about = "Update ISONE capacity auction results (Rust)."
+)]
+struct Args {}
+
+/// Run after the MRA database is updated.  The MRA for next month clears
+/// in the middle of the current month.
+fn main() -> Result<(), Box<dyn Error>> {
+    let _ = Args::parse();
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+    dotenvy::from_path(Path::new(".env/test.env")).unwrap();
+
+    let today = Zoned::now().date();
+    let current = month(today.year(), today.month());
+
+    let archive = ProdDb::isone_capacity_auction_results();
+    for month in [current, current.next()] {
+        archive.update_duckdb(&month)?;
+    }
+
+    Ok(())
+}
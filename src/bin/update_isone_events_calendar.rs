@@ -0,0 +1,48 @@
+use std::{error::Error, path::Path};
+
+use bust::{
+    db::prod_db::ProdDb,
+    interval::month::{month, Month},
+};
+use clap::Parser;
+use jiff::Zoned;
+use log::info;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Environment name, e.g., test, prod
+    #[arg(short, long, default_value = "prod")]
+    env: String,
+
+    /// How many days ahead to refresh the calendar
+    #[arg(short, long, default_value_t = 90)]
+    days: i32,
+}
+
+/// Run this job every day.  Downloads the events for the days that are
+/// missing and refreshes the upcoming events.
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+
+    dotenvy::from_path(Path::new(format!(".env/{}.env", args.env).as_str())).unwrap();
+
+    let today = Zoned::now().date();
+    let current_month = month(today.year(), today.month());
+    let archive = ProdDb::isone_events_calendar();
+    archive.download_missing_days(current_month)?;
+
+    let mut months: Vec<Month> = archive.download_upcoming_days(args.days)?;
+    if !months.contains(&current_month) {
+        months.insert(0, current_month);
+    }
+    info!("Updating ISONE events calendar for months: {:?}", months);
+    for month in months {
+        archive.update_duckdb(&month)?;
+    }
+
+    Ok(())
+}
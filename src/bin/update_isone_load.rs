@@ -0,0 +1,50 @@
+use std::{error::Error, path::Path};
+
+use bust::{
+    db::{isone::system_load_archive::SystemLoadReport, prod_db::ProdDb},
+    interval::month::{month, Month},
+};
+use clap::Parser;
+use jiff::Zoned;
+use log::info;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Environment name, e.g., test, prod
+    #[arg(short, long, default_value = "prod")]
+    env: String,
+}
+
+/// Run this job every day in the morning.  Downloads the system load for
+/// the days that are missing and the load forecasts issued yesterday.
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+
+    dotenvy::from_path(Path::new(format!(".env/{}.env", args.env).as_str())).unwrap();
+
+    let asof = Zoned::now().date().yesterday()?;
+    let current_month = month(asof.year(), asof.month());
+    let mut months: Vec<Month> = Vec::new();
+    if asof.day() < 4 {
+        months.push(current_month.previous());
+    }
+    months.push(current_month);
+    info!("Updating ISONE load for months: {:?}", months);
+
+    let system_load = ProdDb::isone_system_load();
+    let load_forecast = ProdDb::isone_load_forecast();
+    for month in months {
+        for report in [SystemLoadReport::Hourly, SystemLoadReport::FiveMinute] {
+            system_load.download_missing_days(month, report)?;
+            system_load.update_duckdb(&month, report)?;
+        }
+        load_forecast.download_missing_days(month)?;
+        load_forecast.update_duckdb(&month)?;
+    }
+
+    Ok(())
+}
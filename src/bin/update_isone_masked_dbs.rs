@@ -1,6 +1,6 @@
 use std::{error::Error, path::Path};
 
-use bust::{db::prod_db::ProdDb, interval::month::month};
+use bust::{api::isone::_api_isone_core::Market, db::prod_db::ProdDb, interval::month::month};
 use clap::Parser;
 use jiff::Zoned;
 use log::info;
@@ -33,5 +33,14 @@ fn main() -> Result<(), Box<dyn Error>> {
     }
     archive.update_duckdb(&month)?;
 
+    let archive = ProdDb::isone_masked_da_energy_offers();
+    for market in [Market::DA, Market::RT] {
+        for day in &days {
+            println!("Processing {} energy offers for {}", market, day);
+            archive.download_file(day, &market)?;
+        }
+        archive.update_duckdb(&month, &market)?;
+    }
+
     Ok(())
 }
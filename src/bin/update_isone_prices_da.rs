@@ -14,6 +14,10 @@ struct Args {
     /// Environment name, e.g., test, prod
     #[arg(short, long, default_value = "prod")]
     env: String,
+
+    /// Keep the previous versions of republished prices in the revisions table
+    #[arg(long, default_value_t = false)]
+    revisions: bool,
 }
 
 /// Run this job every day at 12:30PM
@@ -43,13 +47,21 @@ fn main() -> Result<(), Box<dyn Error>> {
         Err(e) => error!("{:?}", e),
     }
     let current_month = month(tomorrow.year(), tomorrow.month());
-    archive.update_duckdb(&current_month)?;
+    if args.revisions {
+        archive.update_duckdb_with_revisions(&current_month, &Zoned::now())?;
+    } else {
+        archive.update_duckdb(&current_month)?;
+    }
 
     // repair the previous month's missing files if tomorrow is the first of the month
     if tomorrow.day() == 1 {
         let prev_month = current_month.previous();
         archive.download_missing_days(prev_month)?;
-        archive.update_duckdb(&prev_month)?;
+        if args.revisions {
+            archive.update_duckdb_with_revisions(&prev_month, &Zoned::now())?;
+        } else {
+            archive.update_duckdb(&prev_month)?;
+        }
     }
 
     Ok(())
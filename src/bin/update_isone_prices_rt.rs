@@ -1,8 +1,11 @@
 use std::{error::Error, path::Path};
 
 use bust::{
-    db::{isone::lib_dam::is_rtlmp_published, prod_db::ProdDb},
-    interval::month::month,
+    db::{
+        isone::{lib_dam::is_rtlmp_published, rtlmp5_archive::Rt5Version},
+        prod_db::ProdDb,
+    },
+    interval::month::{month, Month},
 };
 use clap::Parser;
 use jiff::{ToSpan, Zoned};
@@ -14,6 +17,14 @@ struct Args {
     /// Environment name, e.g., test, prod
     #[arg(short, long, default_value = "prod")]
     env: String,
+
+    /// Keep the previous versions of republished prices in the revisions table
+    #[arg(long, default_value_t = false)]
+    revisions: bool,
+
+    /// Also update the 5-min prices, preliminary and final
+    #[arg(long, default_value_t = false)]
+    five_minute: bool,
 }
 
 /// Run this job every day at 5:30PM
@@ -48,9 +59,32 @@ fn main() -> Result<(), Box<dyn Error>> {
     if today.day() < 5 {
         let prev_month = current_month.previous();
         archive.download_missing_days(prev_month)?;
-        archive.update_duckdb(&prev_month)?;
+        if args.revisions {
+            archive.update_duckdb_with_revisions(&prev_month, &Zoned::now())?;
+        } else {
+            archive.update_duckdb(&prev_month)?;
+        }
+    }
+    if args.revisions {
+        archive.update_duckdb_with_revisions(&current_month, &Zoned::now())?;
+    } else {
+        archive.update_duckdb(&current_month)?;
+    }
+
+    if args.five_minute {
+        let archive5 = ProdDb::isone_rtlmp5();
+        let mut months: Vec<Month> = Vec::new();
+        if today.day() < 10 {
+            months.push(current_month.previous());
+        }
+        months.push(current_month);
+        for month in months {
+            for version in [Rt5Version::Final, Rt5Version::Prelim] {
+                archive5.download_missing_days(month, version)?;
+                archive5.update_duckdb(&month, version)?;
+            }
+        }
     }
-    archive.update_duckdb(&current_month)?;
 
     Ok(())
 }
@@ -3,11 +3,12 @@ use std::error::Error;
 use bust::{db::prod_db::ProdDb, interval::month::Month};
 use duckdb::{params, Connection};
 use jiff::{civil::Date, ToSpan, Zoned};
-use log::{error, info};
+use log::info;
 
 /// Insert today's report into the DB
 fn add_day(date: Date) -> Result<(), Box<dyn Error>> {
     let archive = ProdDb::isone_sevenday_solar_forecast();
+    archive.setup()?;
     let conn = Connection::open(&archive.duckdb_path)?;
 
     // check if the data is already there not add it again
@@ -65,35 +66,8 @@ fn main() -> Result<(), Box<dyn Error>> {
             // make the gzfile for month (need all days!)
             archive.make_gzfile_for_month(&month)?;
 
-            let conn = Connection::open(archive.duckdb_path)?;
-            // remove what you have in the DB
-            let stmt = format!(
-                r"
-        DELETE FROM forecast
-        WHERE forecast_hour_beginning >= {}
-        AND forecast_hour_beginning < {}
-        ",
-                today.saturating_sub(1.month()).strftime("%Y-%m-%d"),
-                today.strftime("%Y-%m-%d"),
-            );
-            conn.execute_batch(&stmt)?;
-
-            // upload this month's data to the DB
-            let stmt = format!(
-                r"
-        INSERT INTO forecast
-        FROM read_csv(
-            '{}/month/rt_reserve_price_{}.csv.gz', 
-            header = true, 
-            timestampformat = '%Y-%m-%dT%H:%M:%S.000%z');
-                ",
-                archive.base_dir,
-                today.strftime("%Y-%m")
-            );
-            match conn.execute(&stmt, params![]) {
-                Ok(n) => info!("  inserted {} rows", n),
-                Err(e) => error!("{:?}", e),
-            }
+            // replace the month's data in the DB
+            archive.update_duckdb(&month)?;
         }
     }
 
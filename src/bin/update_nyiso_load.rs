@@ -0,0 +1,48 @@
+use std::{error::Error, path::Path};
+
+use bust::{
+    db::prod_db::ProdDb,
+    interval::month::{month, Month},
+};
+use clap::Parser;
+use jiff::Zoned;
+use log::info;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Environment name, e.g., test, prod
+    #[arg(short, long, default_value = "prod")]
+    env: String,
+}
+
+/// Run this job every day in the morning, after the integrated RT load for
+/// the previous day and the ISO load forecast are published.
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+
+    dotenvy::from_path(Path::new(format!(".env/{}.env", args.env).as_str())).unwrap();
+
+    let asof = Zoned::now().date().yesterday()?;
+    let current_month = month(asof.year(), asof.month());
+    let mut months: Vec<Month> = Vec::new();
+    if asof.day() < 4 {
+        months.push(current_month.previous());
+    }
+    months.push(current_month);
+    info!("Updating NYISO load for months: {:?}", months);
+
+    let rt_load = ProdDb::nyiso_rt_load();
+    let load_forecast = ProdDb::nyiso_load_forecast();
+    for month in months {
+        rt_load.download_file(&month)?;
+        rt_load.update_duckdb(&month)?;
+        load_forecast.download_file(&month)?;
+        load_forecast.update_duckdb(&month)?;
+    }
+
+    Ok(())
+}
@@ -1,29 +1,49 @@
 use std::{error::Error, path::Path};
 
-use bust::{db::prod_db::ProdDb, interval::month::month};
+use bust::{
+    db::prod_db::ProdDb,
+    interval::month::{month, Month},
+};
 use clap::Parser;
 use jiff::Zoned;
 use log::info;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = "Download NYISO masked bid/offer data.  See https://mis.nyiso.com/public/P-27list.htm")]
-struct Args {}
+struct Args {
+    /// First month to backfill, e.g. 2024-01.  If missing, process only the
+    /// latest published month.
+    #[arg(long)]
+    start: Option<Month>,
+
+    /// Last month to backfill, defaults to the latest published month
+    #[arg(long)]
+    end: Option<Month>,
+}
 
 /// Run every month on the 1st of the month
 fn main() -> Result<(), Box<dyn Error>> {
-    let _ = Args::parse();
+    let args = Args::parse();
     env_logger::builder()
         .filter_level(log::LevelFilter::Info)
         .init();
     dotenvy::from_path(Path::new(".env/test.env")).unwrap();
 
     let today = Zoned::now().date();
-    let month = month(today.year(), today.month()).add(-4)?;
-    info!("Processing month {}", month);
+    let last = month(today.year(), today.month()).add(-4)?;
+    let end = args.end.unwrap_or(last);
+    let months = args.start.unwrap_or(end).up_to(end)?;
+
+    let capacity_offers = ProdDb::nyiso_capacity_offers();
+    let energy_offers = ProdDb::nyiso_energy_offers();
+    for month in months {
+        info!("Processing month {}", month);
+        capacity_offers.download_file(&month)?;
+        capacity_offers.update_duckdb(&month)?;
 
-    let archive = ProdDb::nyiso_capacity_offers();
-    archive.download_file(&month)?;
-    // archive.update_duckdb(&month)?;
+        energy_offers.download_file(&month)?;
+        energy_offers.update_duckdb(&month)?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,60 @@
+use std::{error::Error, path::Path};
+
+use bust::{
+    db::{
+        nyiso::{dalmp::NodeType, rtlmp::RtReport},
+        prod_db::ProdDb,
+    },
+    interval::month::{month, Month},
+};
+use clap::Parser;
+use jiff::Zoned;
+use log::info;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Environment name, e.g., test, prod
+    #[arg(short, long, default_value = "prod")]
+    env: String,
+
+    /// Also update the 5-min RTD prices
+    #[arg(long, default_value_t = false)]
+    five_minute: bool,
+}
+
+/// Run this job every day in the morning, after the hourly integrated
+/// RT prices for the previous day are published.
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+
+    dotenvy::from_path(Path::new(format!(".env/{}.env", args.env).as_str())).unwrap();
+
+    let asof = Zoned::now().date().yesterday()?;
+    let current_month = month(asof.year(), asof.month());
+    let mut months: Vec<Month> = Vec::new();
+    if asof.day() < 4 {
+        months.push(current_month.previous());
+    }
+    months.push(current_month);
+    info!("Updating NYISO RTLMP for months: {:?}", months);
+
+    let mut reports = vec![RtReport::Hourly];
+    if args.five_minute {
+        reports.push(RtReport::FiveMinute);
+    }
+
+    let archive = ProdDb::nyiso_rtlmp();
+    for month in months {
+        for report in &reports {
+            archive.download_file(month, *report, NodeType::Gen)?;
+            archive.download_file(month, *report, NodeType::Zone)?;
+            archive.update_duckdb(month, *report)?;
+        }
+    }
+
+    Ok(())
+}
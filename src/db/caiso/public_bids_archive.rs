@@ -3,27 +3,24 @@
 
 use convert_case::{Case, Casing};
 use duckdb::Connection;
-use futures::StreamExt;
 use jiff::civil::Date;
 use jiff::Timestamp;
 use jiff::{tz::TimeZone, ToSpan, Zoned};
 use log::{error, info};
-use reqwest::get;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::error::Error;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::str::FromStr;
-use tokio::fs::{self, File};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio_util::io::StreamReader;
 use url::form_urlencoded;
 
 use crate::{
     utils::serde_helpers::{deserialize_zoned_assume_la, serialize_zoned_as_offset},
     interval::month::Month,
+    utils::compression::unzip_to_gz,
+    utils::downloader::{download_file_async, AuthProfile},
 };
 
 #[derive(Clone)]
@@ -155,65 +152,20 @@ INSERT INTO public_bids_da
         let url = format!("https://oasis.caiso.com/oasisapi/GroupZip?resultformat=6&version=3&groupid=PUB_DAM_GRP&startdatetime={}", start_z);
         // info!("Downloading from URL: {}", url);
 
-        let resp = get(&url).await?;
-        let stream = resp.bytes_stream();
-        let mut reader = StreamReader::new(stream.map(|r| r.map_err(std::io::Error::other)));
 
         let zip_path = self.base_dir.to_owned()
             + "/Raw/"
             + &date.year().to_string()
             + format!("/{}_{}_PUB_BID_DAM_GRP_N_N_v3_csv.zip", yyyymmdd, yyyymmdd).as_str();
-        let dir = Path::new(&zip_path).parent().unwrap();
-        fs::create_dir_all(dir).await?;
+        download_file_async(url, AuthProfile::CaisoOasis, PathBuf::from(&zip_path)).await?;
+        info!("downloaded file {}", zip_path);
 
-        let mut out = File::create(&zip_path).await?;
-        let out = tokio::io::copy(&mut reader, &mut out).await?;
-        info!("downloaded {} bytes", out);
-
-        // Unzip the file
+        // Unzip the file and gzip each csv file
         info!("Unzipping file {}", zip_path);
-        let mut zip_file = File::open(&zip_path).await?;
-        let mut zip_data = Vec::new();
-        zip_file.read_to_end(&mut zip_data).await?;
-        let reader = std::io::Cursor::new(zip_data);
-        let mut zip = zip::ZipArchive::new(reader)?;
-        use std::fs::File as StdFile;
-        use std::io::copy as std_copy;
-
-        for i in 0..zip.len() {
-            let mut file = zip.by_index(i)?;
-            let out_path = match file.enclosed_name() {
-                Some(path) => path.to_owned(),
-                None => continue,
-            };
-            let out_path = self.base_dir.to_owned()
-                + "/Raw/"
-                + &date.year().to_string()
-                + "/"
-                + out_path.file_name().unwrap().to_str().unwrap();
-
-            // Use blocking std::fs::File and std::io::copy for extraction
-            let mut outfile = StdFile::create(&out_path)?;
-            std_copy(&mut file, &mut outfile)?;
-            info!("extracted file to {}", out_path);
-
-            // Gzip the csv file
-            let mut csv_file = File::open(&out_path).await?;
-            let mut csv_data = Vec::new();
-            csv_file.read_to_end(&mut csv_data).await?;
-            let gz_path = format!("{}.gz", out_path);
-            let mut gz_file = File::create(&gz_path).await?;
-            let mut encoder =
-                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
-            use std::io::Write;
-            encoder.write_all(&csv_data)?;
-            let compressed_data = encoder.finish()?;
-            gz_file.write_all(&compressed_data).await?;
-            info!("gzipped file to {}", gz_path);
-
-            // Remove the original csv file
-            tokio::fs::remove_file(&out_path).await?;
-        }
+        let out_dir = format!("{}/Raw/{}", self.base_dir, date.year());
+        unzip_to_gz(Path::new(&zip_path), |name| {
+            Ok(Some(format!("{}/{}", out_dir, name)))
+        })?;
 
         // Remove the original zip file
         tokio::fs::remove_file(&zip_path).await?;
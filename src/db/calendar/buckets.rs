@@ -1,11 +1,11 @@
-use std::{path::Path, process::Command};
+use std::path::Path;
 
 use duckdb::Connection;
 use jiff::Zoned;
 
 use crate::{
     time::bucket::{Bucket, BucketLike},
-    db::prod_db::ProdDb,
+    utils::compression::gzip_file,
     elec::iso::ISONE,
     interval::{interval_base::IntervalTzLike, term::Term},
 };
@@ -91,14 +91,7 @@ pub fn generate_csv(term: Term, file_path: &str) -> Result<(), Box<dyn std::erro
     wtr.flush().unwrap();
 
     // gzip the file
-    let dir = Path::new(file_path).parent().unwrap();
-    Command::new("gzip")
-        .args(["-f", file_path])
-        .current_dir(dir)
-        .spawn()
-        .unwrap()
-        .wait()
-        .expect("gzip failed");
+    gzip_file(Path::new(file_path))?;
 
     Ok(())
 }
@@ -152,13 +145,7 @@ pub fn count_hour_by_month(term: Term, file_path: &str) -> Result<(), Box<dyn st
     }
     wtr.flush().unwrap();
 
-    Command::new("gzip")
-        .args(["-f", file_path])
-        .current_dir(ProdDb::buckets().base_dir.as_str())
-        .spawn()
-        .unwrap()
-        .wait()
-        .expect("gzip failed");
+    gzip_file(Path::new(file_path))?;
 
     Ok(())
 }
@@ -0,0 +1,663 @@
+// Data completeness and quality checks for the archives.
+//
+// Each archive declares its expected grid with a `quality_spec()` method, e.g.
+// 23/24/25 hours per day for each ptid, or one raw file per day.  The checks
+// below compare what is in DuckDB (or on disk) with that grid and return a
+// `QualityReport` that a job can email or the server can expose.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use build_html::{Html, Table};
+use duckdb::{AccessMode, Connection};
+use jiff::civil::Date;
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, Zoned};
+use log::info;
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::interval::month::Month;
+use crate::interval::term::Term;
+use crate::utils::lib_duckdb::open_with_retry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Frequency {
+    FiveMinute,
+    FifteenMinute,
+    Hourly,
+    Daily,
+}
+
+impl Frequency {
+    pub fn minutes(&self) -> i64 {
+        match self {
+            Frequency::FiveMinute => 5,
+            Frequency::FifteenMinute => 15,
+            Frequency::Hourly => 60,
+            Frequency::Daily => 1440,
+        }
+    }
+
+    /// Number of intervals expected in a given local day.  Is DST aware, e.g.
+    /// for an hourly frequency it returns 23, 24 or 25.
+    pub fn intervals_in_day(&self, day: Date, tz: &TimeZone) -> usize {
+        if *self == Frequency::Daily {
+            return 1;
+        }
+        let minutes = minutes_in_day(day, tz);
+        (minutes / self.minutes()) as usize
+    }
+}
+
+/// Length of the local day in minutes, 1380, 1440 or 1500.
+pub fn minutes_in_day(day: Date, tz: &TimeZone) -> i64 {
+    let start = day.to_zoned(tz.clone()).unwrap();
+    let end = day.tomorrow().unwrap().to_zoned(tz.clone()).unwrap();
+    (end.timestamp().as_second() - start.timestamp().as_second()) / 60
+}
+
+/// Is this day a DST transition day in the given timezone?
+pub fn is_dst_day(day: Date, tz: &TimeZone) -> bool {
+    minutes_in_day(day, tz) != 1440
+}
+
+/// The allowed range for a value column.  Use `None` for an open bound.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueRange {
+    pub column: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl ValueRange {
+    pub fn new(column: &str, min: Option<f64>, max: Option<f64>) -> Self {
+        ValueRange {
+            column: column.to_string(),
+            min,
+            max,
+        }
+    }
+}
+
+/// A timeseries stored in a DuckDB table, one row per key per interval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableGrid {
+    pub table: String,
+    /// A TIMESTAMPTZ column with the start of the interval, e.g. `hour_beginning`
+    pub time_column: String,
+    /// Columns that together with the time column identify a row, e.g. `ptid`
+    pub key_columns: Vec<String>,
+    /// Keys expected every day, e.g. the hub and load zone ptids, so a key
+    /// missing for the whole term is reported.  Multiple key columns are
+    /// joined with `|`.  Other keys found in the table are checked too.
+    pub expected_keys: Vec<String>,
+    pub frequency: Frequency,
+    /// Timezone name used to define the local day, e.g. "America/New_York"
+    pub tz: String,
+    pub value_ranges: Vec<ValueRange>,
+    /// An extra SQL condition if the table is shared with other archives,
+    /// e.g. `location_type = 'AREA'`
+    pub condition: Option<String>,
+}
+
+/// Raw files the archive expects to find on disk.
+#[derive(Clone)]
+pub enum FileGrid {
+    /// One file per day, the function returns the file path for the day
+    Daily(Arc<dyn Fn(&Date) -> String + Send + Sync>),
+    /// One file (report) per month
+    Monthly(Arc<dyn Fn(&Month) -> String + Send + Sync>),
+}
+
+#[derive(Clone)]
+pub struct QualitySpec {
+    /// Short name used in reports and urls, e.g. "isone_dalmp"
+    pub name: String,
+    pub duckdb_path: String,
+    pub table: Option<TableGrid>,
+    pub files: Option<FileGrid>,
+    /// Days known to be missing at the source, they are not reported
+    pub skip_days: Vec<Date>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum IssueKind {
+    MissingPeriods,
+    ExtraPeriods,
+    DuplicateKeys,
+    NullValues,
+    OutOfRange,
+    DstAnomaly,
+    OffGrid,
+    MissingFile,
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use IssueKind::*;
+        match self {
+            MissingPeriods => write!(f, "Missing periods"),
+            ExtraPeriods => write!(f, "Extra periods"),
+            DuplicateKeys => write!(f, "Duplicate keys"),
+            NullValues => write!(f, "Null values"),
+            OutOfRange => write!(f, "Out of range"),
+            DstAnomaly => write!(f, "DST anomaly"),
+            OffGrid => write!(f, "Off grid"),
+            MissingFile => write!(f, "Missing file"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Issue {
+    pub kind: IssueKind,
+    /// The day, month or column the issue refers to
+    pub period: String,
+    /// The key with the issue.  `None` if it applies to all keys.
+    pub key: Option<String>,
+    pub count: usize,
+    pub details: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QualityReport {
+    pub name: String,
+    pub start: Date,
+    pub end: Date,
+    pub issues: Vec<Issue>,
+}
+
+impl QualityReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// An HTML table with the issues, suitable for an email.
+    pub fn to_html(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .issues
+            .iter()
+            .map(|e| {
+                vec![
+                    e.kind.to_string(),
+                    e.period.clone(),
+                    e.key.clone().unwrap_or("all".to_string()),
+                    e.count.to_string(),
+                    e.details.clone(),
+                ]
+            })
+            .collect();
+        let table = Table::from(rows).with_header_row(["Issue", "Period", "Key", "Count", "Details"]);
+        format!(
+            "<h3>{}, {} to {}: {} issues</h3>\n{}",
+            self.name,
+            self.start,
+            self.end,
+            self.issues.len(),
+            table.to_html_string()
+        )
+    }
+}
+
+/// Run all the checks declared in the spec for the days in the term.
+pub fn run_checks(spec: &QualitySpec, term: &Term) -> Result<QualityReport, Box<dyn Error>> {
+    info!("running quality checks for {} in {}", spec.name, term);
+    let mut issues: Vec<Issue> = Vec::new();
+    if let Some(files) = &spec.files {
+        issues.extend(check_files(files, term, &spec.skip_days));
+    }
+    if let Some(grid) = &spec.table {
+        let conn = open_with_retry(
+            &spec.duckdb_path,
+            8,
+            Duration::from_millis(25),
+            AccessMode::ReadOnly,
+        )?;
+        issues.extend(check_table(&conn, grid, term, &spec.skip_days)?);
+    }
+    Ok(QualityReport {
+        name: spec.name.clone(),
+        start: term.start,
+        end: term.end,
+        issues,
+    })
+}
+
+/// Check that the raw files for the term exist on disk.  Today and future days
+/// (or months) are not checked.
+pub fn check_files(files: &FileGrid, term: &Term, skip_days: &[Date]) -> Vec<Issue> {
+    let today = Zoned::now().date();
+    let mut issues: Vec<Issue> = Vec::new();
+    match files {
+        FileGrid::Daily(filename) => {
+            for day in term.days() {
+                if day >= today || skip_days.contains(&day) {
+                    continue;
+                }
+                let path = filename(&day);
+                if !Path::new(&path).exists() && !Path::new(&format!("{}.gz", path)).exists() {
+                    issues.push(Issue {
+                        kind: IssueKind::MissingFile,
+                        period: day.to_string(),
+                        key: None,
+                        count: 1,
+                        details: path,
+                    });
+                }
+            }
+        }
+        FileGrid::Monthly(filename) => {
+            for month in term.months() {
+                if month.end_date() >= today {
+                    continue;
+                }
+                let path = filename(&month);
+                if !Path::new(&path).exists() {
+                    issues.push(Issue {
+                        kind: IssueKind::MissingFile,
+                        period: month.to_string(),
+                        key: None,
+                        count: 1,
+                        details: path,
+                    });
+                }
+            }
+        }
+    }
+    issues
+}
+
+/// Check a DuckDB table against its expected grid: missing and extra periods,
+/// DST anomalies, duplicate keys, timestamps off the grid, null and out of range
+/// values.  Today and future days are not checked for missing periods.
+pub fn check_table(
+    conn: &Connection,
+    grid: &TableGrid,
+    term: &Term,
+    skip_days: &[Date],
+) -> Result<Vec<Issue>, Box<dyn Error>> {
+    let tz = TimeZone::get(&grid.tz)?;
+    let start = term.start.to_zoned(tz.clone())?;
+    let end = term.end.tomorrow()?.to_zoned(tz.clone())?;
+    let mut filter = format!(
+        "{} >= to_timestamp({}) AND {} < to_timestamp({})",
+        grid.time_column,
+        start.timestamp().as_second(),
+        grid.time_column,
+        end.timestamp().as_second()
+    );
+    if let Some(condition) = &grid.condition {
+        filter.push_str(&format!(" AND ({})", condition));
+    }
+    let key = if grid.key_columns.is_empty() {
+        "''".to_string()
+    } else {
+        format!("concat_ws('|', {})", grid.key_columns.join(", "))
+    };
+
+    // one row for each (key, timestamp) with the number of times it appears
+    let query = format!(
+        r#"
+SELECT {key} AS key, {time} AS ts, count(*) AS n
+FROM {table}
+WHERE {filter}
+GROUP BY ALL;
+"#,
+        key = key,
+        time = grid.time_column,
+        table = grid.table,
+        filter = filter,
+    );
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<usize, String>(0)?,
+            row.get::<usize, i64>(1)?,
+            row.get::<usize, i64>(2)?,
+        ))
+    })?;
+
+    let mut issues: Vec<Issue> = Vec::new();
+    // for each key, count the distinct intervals in each local day
+    let mut counts: BTreeMap<Date, HashMap<String, usize>> = BTreeMap::new();
+    let mut keys: BTreeSet<String> = grid.expected_keys.iter().cloned().collect();
+    let mut duplicates: BTreeMap<Date, (usize, BTreeSet<String>)> = BTreeMap::new();
+    let mut off_grid: BTreeMap<Date, usize> = BTreeMap::new();
+    let step = grid.frequency.minutes() * 60 * 1_000_000;
+    for row in rows {
+        let (k, micros, n) = row?;
+        let zoned = Timestamp::from_microsecond(micros)?.to_zoned(tz.clone());
+        let day = zoned.date();
+        if grid.frequency != Frequency::Daily && micros % step != 0 {
+            *off_grid.entry(day).or_default() += 1;
+        }
+        if n > 1 {
+            let e = duplicates.entry(day).or_default();
+            e.0 += 1;
+            e.1.insert(k.clone());
+        }
+        *counts.entry(day).or_default().entry(k.clone()).or_default() += 1;
+        keys.insert(k);
+    }
+
+    if keys.is_empty() {
+        // nothing in the table and no configured keys, check the days as a whole
+        keys.insert(String::new());
+    }
+
+    let today = Zoned::now().date();
+    for day in term.days() {
+        if day >= today || skip_days.contains(&day) {
+            continue;
+        }
+        let expected = grid.frequency.intervals_in_day(day, &tz);
+        let empty = HashMap::new();
+        let day_counts = counts.get(&day).unwrap_or(&empty);
+        issues.extend(check_day_counts(
+            day,
+            expected,
+            is_dst_day(day, &tz),
+            &keys,
+            day_counts,
+        ));
+    }
+
+    for (day, (n, ks)) in duplicates {
+        issues.push(Issue {
+            kind: IssueKind::DuplicateKeys,
+            period: day.to_string(),
+            key: if ks.len() == 1 { ks.first().cloned() } else { None },
+            count: n,
+            details: format!("{} keys with duplicated timestamps", ks.len()),
+        });
+    }
+    for (day, n) in off_grid {
+        issues.push(Issue {
+            kind: IssueKind::OffGrid,
+            period: day.to_string(),
+            key: None,
+            count: n,
+            details: format!("timestamps not aligned to a {:?} grid", grid.frequency),
+        });
+    }
+    issues.extend(check_values(conn, grid, &filter)?);
+
+    Ok(issues)
+}
+
+/// Compare the number of intervals for each key in a day with the expected number.
+/// When all keys are short in the same way, report one issue for the day.
+pub fn check_day_counts(
+    day: Date,
+    expected: usize,
+    is_dst_day: bool,
+    keys: &BTreeSet<String>,
+    counts: &HashMap<String, usize>,
+) -> Vec<Issue> {
+    let mut bad: BTreeMap<(IssueKind, usize), Vec<String>> = BTreeMap::new();
+    for k in keys {
+        let actual = *counts.get(k).unwrap_or(&0);
+        if actual == expected {
+            continue;
+        }
+        let kind = if actual == 0 {
+            IssueKind::MissingPeriods
+        } else if is_dst_day {
+            IssueKind::DstAnomaly
+        } else if actual < expected {
+            IssueKind::MissingPeriods
+        } else {
+            IssueKind::ExtraPeriods
+        };
+        bad.entry((kind, actual)).or_default().push(k.clone());
+    }
+
+    let mut issues: Vec<Issue> = Vec::new();
+    for ((kind, actual), ks) in bad {
+        let count = expected.abs_diff(actual);
+        if ks.len() == keys.len() && keys.len() > 1 {
+            issues.push(Issue {
+                kind,
+                period: day.to_string(),
+                key: None,
+                count: count * ks.len(),
+                details: format!("expected {} intervals, found {}", expected, actual),
+            });
+        } else {
+            for k in ks {
+                issues.push(Issue {
+                    kind,
+                    period: day.to_string(),
+                    key: if k.is_empty() { None } else { Some(k) },
+                    count,
+                    details: format!("expected {} intervals, found {}", expected, actual),
+                });
+            }
+        }
+    }
+    issues
+}
+
+/// Count the null and out of range values for each value column.
+fn check_values(
+    conn: &Connection,
+    grid: &TableGrid,
+    filter: &str,
+) -> Result<Vec<Issue>, Box<dyn Error>> {
+    let mut issues: Vec<Issue> = Vec::new();
+    for range in &grid.value_ranges {
+        let mut conditions: Vec<String> = Vec::new();
+        if let Some(min) = range.min {
+            conditions.push(format!("{} < {}", range.column, min));
+        }
+        if let Some(max) = range.max {
+            conditions.push(format!("{} > {}", range.column, max));
+        }
+        let out_of_range = if conditions.is_empty() {
+            "FALSE".to_string()
+        } else {
+            conditions.join(" OR ")
+        };
+        let query = format!(
+            r#"
+SELECT
+    count(*) FILTER (WHERE {col} IS NULL) AS n_null,
+    count(*) FILTER (WHERE {out}) AS n_out,
+    min({col})::DOUBLE AS min_value,
+    max({col})::DOUBLE AS max_value
+FROM {table}
+WHERE {filter};
+"#,
+            col = range.column,
+            out = out_of_range,
+            table = grid.table,
+            filter = filter,
+        );
+        let (n_null, n_out, min_value, max_value) = conn.query_row(&query, [], |row| {
+            Ok((
+                row.get::<usize, i64>(0)?,
+                row.get::<usize, i64>(1)?,
+                row.get::<usize, Option<f64>>(2)?,
+                row.get::<usize, Option<f64>>(3)?,
+            ))
+        })?;
+        if n_null > 0 {
+            issues.push(Issue {
+                kind: IssueKind::NullValues,
+                period: range.column.clone(),
+                key: None,
+                count: n_null as usize,
+                details: format!("{} null values in column {}", n_null, range.column),
+            });
+        }
+        if n_out > 0 {
+            issues.push(Issue {
+                kind: IssueKind::OutOfRange,
+                period: range.column.clone(),
+                key: None,
+                count: n_out as usize,
+                details: format!(
+                    "allowed range [{}, {}], observed [{}, {}]",
+                    range.min.map_or("-inf".to_string(), |v| v.to_string()),
+                    range.max.map_or("inf".to_string(), |v| v.to_string()),
+                    min_value.unwrap_or(f64::NAN),
+                    max_value.unwrap_or(f64::NAN),
+                ),
+            });
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use duckdb::Connection;
+    use jiff::civil::date;
+    use std::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn test_intervals_in_day() {
+        let tz = TimeZone::get("America/New_York").unwrap();
+        assert_eq!(Frequency::Hourly.intervals_in_day(date(2024, 3, 10), &tz), 23);
+        assert_eq!(Frequency::Hourly.intervals_in_day(date(2024, 7, 1), &tz), 24);
+        assert_eq!(Frequency::Hourly.intervals_in_day(date(2024, 11, 3), &tz), 25);
+        assert_eq!(Frequency::FiveMinute.intervals_in_day(date(2024, 11, 3), &tz), 300);
+        assert_eq!(Frequency::Daily.intervals_in_day(date(2024, 11, 3), &tz), 1);
+        let est = TimeZone::get("Etc/GMT+5").unwrap();
+        assert_eq!(Frequency::Hourly.intervals_in_day(date(2024, 11, 3), &est), 24);
+    }
+
+    #[test]
+    fn test_check_table() -> Result<(), Box<dyn Error>> {
+        let tz = TimeZone::get("America/New_York")?;
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            "CREATE TABLE da_lmp (hour_beginning TIMESTAMPTZ, ptid UINTEGER, lmp DECIMAL(9,4));",
+        )?;
+        // two ptids, the fall DST day plus the next day
+        let mut values: Vec<String> = Vec::new();
+        for ptid in [4000, 4001] {
+            let mut hb = date(2024, 11, 3).to_zoned(tz.clone())?;
+            let end = date(2024, 11, 5).to_zoned(tz.clone())?;
+            while hb < end {
+                let hour = hb.hour();
+                // ptid 4001 is missing hour 10 on Nov-4, ptid 4000 has a null price at 12
+                let skip = ptid == 4001 && hb.date() == date(2024, 11, 4) && hour == 10;
+                if !skip {
+                    let price = if ptid == 4000 && hour == 12 {
+                        "NULL".to_string()
+                    } else if hour == 18 {
+                        "2500".to_string()
+                    } else {
+                        "30".to_string()
+                    };
+                    values.push(format!(
+                        "(to_timestamp({}), {}, {})",
+                        hb.timestamp().as_second(),
+                        ptid,
+                        price
+                    ));
+                }
+                hb = hb.checked_add(jiff::Span::new().hours(1))?;
+            }
+        }
+        // a duplicate row
+        values.push(format!(
+            "(to_timestamp({}), 4000, 30)",
+            date(2024, 11, 4).to_zoned(tz.clone())?.timestamp().as_second()
+        ));
+        conn.execute_batch(&format!("INSERT INTO da_lmp VALUES {};", values.join(", ")))?;
+
+        let grid = TableGrid {
+            table: "da_lmp".to_string(),
+            time_column: "hour_beginning".to_string(),
+            key_columns: vec!["ptid".to_string()],
+            expected_keys: vec![],
+            frequency: Frequency::Hourly,
+            tz: "America/New_York".to_string(),
+            value_ranges: vec![ValueRange::new("lmp", Some(-500.0), Some(2000.0))],
+            condition: None,
+        };
+        let term = Term::new(date(2024, 11, 3), date(2024, 11, 5)).unwrap();
+        let issues = check_table(&conn, &grid, &term, &[])?;
+        let kinds: Vec<(IssueKind, String, Option<String>)> = issues
+            .iter()
+            .map(|e| (e.kind, e.period.clone(), e.key.clone()))
+            .collect();
+        assert!(kinds.contains(&(
+            IssueKind::MissingPeriods,
+            "2024-11-04".to_string(),
+            Some("4001".to_string())
+        )));
+        // Nov-5 is missing for all ptids
+        assert!(kinds.contains(&(IssueKind::MissingPeriods, "2024-11-05".to_string(), None)));
+        assert!(kinds.contains(&(
+            IssueKind::DuplicateKeys,
+            "2024-11-04".to_string(),
+            Some("4000".to_string())
+        )));
+        let null = issues.iter().find(|e| e.kind == IssueKind::NullValues).unwrap();
+        assert_eq!(null.count, 2);
+        let out = issues.iter().find(|e| e.kind == IssueKind::OutOfRange).unwrap();
+        assert_eq!(out.count, 4);
+        // the DST day is complete
+        assert!(!issues.iter().any(|e| e.period == "2024-11-03"));
+
+        // a configured ptid that is not in the table is missing every day
+        let grid = TableGrid {
+            expected_keys: vec!["4000".to_string(), "4002".to_string()],
+            ..grid
+        };
+        let issues = check_table(&conn, &grid, &term, &[])?;
+        let missing: Vec<&Issue> = issues
+            .iter()
+            .filter(|e| e.key.as_deref() == Some("4002"))
+            .collect();
+        assert_eq!(missing.len(), 2);
+        assert!(missing.iter().all(|e| e.kind == IssueKind::MissingPeriods));
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_empty_table() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch("CREATE TABLE rt_lmp (hour_beginning TIMESTAMPTZ, ptid UINTEGER);")?;
+        let mut grid = TableGrid {
+            table: "rt_lmp".to_string(),
+            time_column: "hour_beginning".to_string(),
+            key_columns: vec!["ptid".to_string()],
+            expected_keys: vec![],
+            frequency: Frequency::Hourly,
+            tz: "America/New_York".to_string(),
+            value_ranges: vec![],
+            condition: None,
+        };
+        let term = Term::new(date(2024, 11, 3), date(2024, 11, 4)).unwrap();
+
+        // without configured keys, each day is reported as a whole
+        let issues = check_table(&conn, &grid, &term, &[])?;
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].kind, IssueKind::MissingPeriods);
+        assert_eq!(issues[0].key, None);
+        assert_eq!(issues[0].count, 25);
+
+        // with configured keys, each key is reported
+        grid.expected_keys = vec!["4000".to_string(), "4001".to_string()];
+        let issues = check_table(&conn, &grid, &term, &[])?;
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|e| e.kind == IssueKind::MissingPeriods && e.key.is_none()));
+        assert_eq!(issues[1].count, 48);
+
+        // today and future days are not reported
+        let today = Zoned::now().date();
+        let term = Term::new(today, today.tomorrow()?).unwrap();
+        assert!(check_table(&conn, &grid, &term, &[])?.is_empty());
+        Ok(())
+    }
+}
@@ -15,15 +15,13 @@ use rust_decimal::Decimal;
 use log::error;
 use log::info;
 use std::error::Error;
-use std::fs;
-use std::fs::File;
-use std::io;
 use std::path::Path;
 use std::process::Command;
 
 use crate::{
     utils::serde_helpers::{deserialize_zoned_assume_ny, serialize_zoned_as_offset},
     interval::month::Month,
+    utils::downloader::{downloader, AuthProfile},
 };
 
 // 15-minute data for total electricity demand in Quebec from https://electricite-quebec.info/en#.
@@ -129,22 +127,8 @@ INSERT INTO total_demand
             month.end_date()
         );
         println!("downloading from url: {}", url);
-        let resp = reqwest::blocking::get(url).expect("request failed");
-        let body = resp.text().expect("body invalid");
         let path = &self.filename(month);
-        let dir = Path::new(path).parent().unwrap();
-        let _ = fs::create_dir_all(dir);
-        let mut out = File::create(path).expect("failed to create file");
-        io::copy(&mut body.as_bytes(), &mut out).expect("failed to copy content");
-
-        // gzip it
-        Command::new("gzip")
-            .args(["-f", path])
-            .current_dir(dir)
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
+        downloader().download_file(&url, AuthProfile::Anonymous, None, Path::new(path), true)?;
 
         Ok(())
     }
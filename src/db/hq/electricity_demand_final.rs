@@ -1,6 +1,7 @@
 // Hourly data for total electricity demand in Quebec.
 // https://donnees.hydroquebec.com/explore/dataset/historique-demande-electricite-quebec/information/
 
+use crate::utils::downloader::{downloader, AuthProfile};
 use jiff::civil::*;
 use jiff::Zoned;
 use log::error;
@@ -8,9 +9,6 @@ use log::info;
 use rust_decimal::Decimal;
 use serde::Serialize;
 use std::error::Error;
-use std::fs;
-use std::fs::File;
-use std::io;
 use std::path::Path;
 use std::process::Command;
 
@@ -106,22 +104,8 @@ INSERT INTO total_demand_final
     /// New data is published annually! On 15-Sep-2025 only data up to end of 2023 is available.
     pub fn download_file(&self, day: &Date) -> Result<(), Box<dyn Error>> {
         let url = format!("https://donnees.hydroquebec.com/api/explore/v2.1/catalog/datasets/historique-demande-electricite-quebec/records?where=date%20%3E%3D%20date%27{}%27%20and%20date%20%3C%20date%27{}%27&limit=40", day, day.tomorrow().unwrap());
-        let resp = reqwest::blocking::get(url).expect("request failed");
-        let body = resp.text().expect("body invalid");
         let path = &self.filename(day);
-        let dir = Path::new(path).parent().unwrap();
-        let _ = fs::create_dir_all(dir);
-        let mut out = File::create(path).expect("failed to create file");
-        io::copy(&mut body.as_bytes(), &mut out).expect("failed to copy content");
-
-        // gzip it
-        Command::new("gzip")
-            .args(["-f", path])
-            .current_dir(dir)
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
+        downloader().download_file(&url, AuthProfile::Anonymous, None, Path::new(path), true)?;
 
         Ok(())
     }
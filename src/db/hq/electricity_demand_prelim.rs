@@ -2,6 +2,7 @@
 // https://donnees.hydroquebec.com/explore/dataset/demande-electricite-quebec/information/
 // Note the alternative dataset with hourly data:  electricity_demand_other.rs
 
+use crate::utils::downloader::{downloader, AuthProfile};
 use jiff::civil::*;
 use jiff::Zoned;
 use log::error;
@@ -9,9 +10,6 @@ use log::info;
 use rust_decimal::Decimal;
 use serde::Serialize;
 use std::error::Error;
-use std::fs;
-use std::fs::File;
-use std::io;
 use std::path::Path;
 use std::process::Command;
 
@@ -107,23 +105,9 @@ INSERT INTO total_demand_prelim
     /// Data is updated on the website every 15 min
     pub fn download_file(&self) -> Result<(), Box<dyn Error>> {
         let url = "https://donnees.hydroquebec.com/api/explore/v2.1/catalog/datasets/demande-electricite-quebec/records?limit=100";
-        let resp = reqwest::blocking::get(url).expect("request failed");
-        let body = resp.text().expect("body invalid");
         let today: Date = Zoned::now().date();
         let path = &self.filename(&today);
-        let dir = Path::new(path).parent().unwrap();
-        let _ = fs::create_dir_all(dir);
-        let mut out = File::create(path).expect("failed to create file");
-        io::copy(&mut body.as_bytes(), &mut out).expect("failed to copy content");
-
-        // gzip it
-        Command::new("gzip")
-            .args(["-f", path])
-            .current_dir(dir)
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
+        downloader().download_file(url, AuthProfile::Anonymous, None, Path::new(path), true)?;
 
         Ok(())
     }
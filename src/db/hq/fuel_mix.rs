@@ -6,9 +6,6 @@ use log::error;
 use log::info;
 use reqwest::header;
 use std::error::Error;
-use std::fs;
-use std::fs::File;
-use std::io;
 use std::path::Path;
 use std::process::Command;
 
@@ -18,6 +15,7 @@ use duckdb::Connection;
 use serde::{Deserialize, Serialize};
 use url::form_urlencoded;
 
+use crate::utils::compression::write_gz;
 use crate::utils::serde_helpers::*;
 use jiff::Timestamp;
 use jiff::{tz::TimeZone, Zoned};
@@ -141,24 +139,12 @@ INSERT INTO fuel_mix
             .header("sec-ch-ua-mobile", "?0")
             .header("sec-ch-ua-platform", r#""Windows""#)
             .send()
-            .expect("request failed");
+            ?;
 
-        // let resp = reqwest::blocking::get(url).expect("request failed");
-        let body = resp.text().expect("body invalid");
+        // let resp = reqwest::blocking::get(url)?;
+        let body = resp.text()?;
         let path = &self.filename(&date);
-        let dir = Path::new(path).parent().unwrap();
-        let _ = fs::create_dir_all(dir);
-        let mut out = File::create(path).expect("failed to create file");
-        io::copy(&mut body.as_bytes(), &mut out).expect("failed to copy content");
-
-        // gzip it
-        Command::new("gzip")
-            .args(["-f", path])
-            .current_dir(dir)
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
+        write_gz(&mut body.as_bytes(), Path::new(&format!("{}.gz", path)))?;
 
         Ok(())
     }
@@ -181,10 +167,10 @@ INSERT INTO fuel_mix
         let resp = client
             .get(url)
             .send()
-            .expect("request failed");
+            ?;
 
-        // let resp = reqwest::blocking::get(url).expect("request failed");
-        let body = resp.text().expect("body invalid");
+        // let resp = reqwest::blocking::get(url)?;
+        let body = resp.text()?;
         let day = Zoned::now().date();
         let path =         self.base_dir.to_owned()
             + "/Raw2/"
@@ -193,19 +179,7 @@ INSERT INTO fuel_mix
             + &day.to_string()
             + ".json"
 ;
-        let dir = Path::new(&path).parent().unwrap();
-        let _ = fs::create_dir_all(dir);
-        let mut out = File::create(&path).expect("failed to create file");
-        io::copy(&mut body.as_bytes(), &mut out).expect("failed to copy content");
-
-        // gzip it
-        Command::new("gzip")
-            .args(["-f", &path])
-            .current_dir(dir)
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
+        write_gz(&mut body.as_bytes(), Path::new(&format!("{}.gz", path)))?;
 
         Ok(())
     }
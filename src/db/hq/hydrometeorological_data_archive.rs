@@ -1,3 +1,4 @@
+use crate::utils::compression::write_gz;
 use duckdb::Connection;
 use flate2::read::GzDecoder;
 use jiff::civil::*;
@@ -8,12 +9,9 @@ use log::info;
 use serde::Serialize;
 use serde_json::Value;
 use std::error::Error;
-use std::fs;
 use std::fs::File;
-use std::io;
 use std::io::Read;
 use std::path::Path;
-use std::process::Command;
 use std::str::FromStr;
 
 /// See https://www.hydroquebec.com/documents-data/open-data/hydrometeorological-data/
@@ -327,19 +325,7 @@ impl HqHydroDataArchive {
         let body = resp.text()?;
         let today: Date = Zoned::now().date();
         let path = &self.filename(&today);
-        let dir = Path::new(path).parent().unwrap();
-        let _ = fs::create_dir_all(dir);
-        let mut out = File::create(path).expect("failed to create file");
-        io::copy(&mut body.as_bytes(), &mut out).expect("failed to copy content");
-
-        // gzip it
-        Command::new("gzip")
-            .args(["-f", path])
-            .current_dir(dir)
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
+        write_gz(&mut body.as_bytes(), Path::new(&format!("{}.gz", path)))?;
 
         Ok(())
     }
@@ -10,9 +10,12 @@ use std::io::Read;
 use std::path::Path;
 use std::process::Command;
 use std::str::FromStr;
+use std::sync::Arc;
 
+use crate::db::data_quality::{FileGrid, Frequency, QualitySpec, TableGrid, ValueRange};
 use crate::db::isone::lib_isoexpress::download_file;
 use crate::interval::month::Month;
+use crate::utils::compression::create_gz;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone)]
@@ -60,11 +63,11 @@ impl IesoDaLmpAreaArchive {
     ///
     pub fn make_gzfile_for_month(&self, month: &Month) -> Result<(), Box<dyn Error>> {
         let file_out = format!(
-            "{}/month/area_da_prices_{}.csv",
+            "{}/month/area_da_prices_{}.csv.gz",
             self.base_dir.to_owned(),
             month
         );
-        let mut wtr = csv::Writer::from_path(&file_out)?;
+        let mut wtr = csv::Writer::from_writer(create_gz(Path::new(&file_out))?);
         wtr.write_record(["hour_beginning", "lmp", "mcc", "mcl"])?;
 
         let mut last = Zoned::now().date();
@@ -80,26 +83,18 @@ impl IesoDaLmpAreaArchive {
             }
             let rows = self.read_file(&day)?;
             for row in rows {
-                let _ = wtr.write_record(&[
+                wtr.write_record(&[
                     row.begin_hour
                         .strftime("%Y-%m-%dT%H:%M:%S.000%:z")
                         .to_string(),
                     row.lmp.to_string(),
                     row.mcc.to_string(),
                     row.mcl.to_string(),
-                ]);
+                ])?;
             }
         }
         wtr.flush()?;
-
-        // gzip it
-        Command::new("gzip")
-            .args(["-f", &file_out])
-            .current_dir(format!("{}/month", self.base_dir))
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
+        wtr.into_inner().map_err(|e| e.into_error())?.finish()?;
         Ok(())
     }
 
@@ -197,6 +192,28 @@ ORDER BY hour_beginning, location_name;
 
         Ok(())
     }
+
+    /// The expected grid: one row per hour for the Ontario area.  IESO reports
+    /// in EST all year, so every day has 24 hours.
+    pub fn quality_spec(&self) -> QualitySpec {
+        let archive = self.clone();
+        QualitySpec {
+            name: "ieso_dalmp_area".to_string(),
+            duckdb_path: self.duckdb_path.clone(),
+            table: Some(TableGrid {
+                table: "da_lmp".to_string(),
+                time_column: "hour_beginning".to_string(),
+                key_columns: vec!["location_name".to_string()],
+                expected_keys: vec!["ONTARIO".to_string()],
+                frequency: Frequency::Hourly,
+                tz: "Etc/GMT+5".to_string(),
+                value_ranges: vec![ValueRange::new("lmp", Some(-2000.0), Some(2000.0))],
+                condition: Some("location_type = 'AREA'".to_string()),
+            }),
+            files: Some(FileGrid::Daily(Arc::new(move |day| archive.filename(day)))),
+            skip_days: IesoDaLmpAreaArchive::get_missing_days(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
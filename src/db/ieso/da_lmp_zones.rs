@@ -15,6 +15,7 @@ use std::str::FromStr;
 
 use crate::db::isone::lib_isoexpress::download_file;
 use crate::interval::month::Month;
+use crate::utils::compression::create_gz;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone)]
@@ -58,11 +59,11 @@ impl IesoDaLmpZonalArchive {
     ///
     pub fn make_gzfile_for_month(&self, month: &Month) -> Result<(), Box<dyn Error>> {
         let file_out = format!(
-            "{}/month/zonal_da_prices_{}.csv",
+            "{}/month/zonal_da_prices_{}.csv.gz",
             self.base_dir.to_owned(),
             month
         );
-        let mut wtr = csv::Writer::from_path(&file_out)?;
+        let mut wtr = csv::Writer::from_writer(create_gz(Path::new(&file_out))?);
         wtr.write_record([
             "location_type",
             "location_name",
@@ -82,7 +83,7 @@ impl IesoDaLmpZonalArchive {
             }
             let rows = self.read_file(&day)?;
             for row in rows {
-                let _ = wtr.write_record(&[
+                wtr.write_record(&[
                     "HUB".to_owned(),
                     row.location_name.to_owned().replace(":HUB", ""),
                     row.begin_hour
@@ -91,19 +92,11 @@ impl IesoDaLmpZonalArchive {
                     row.lmp.to_string(),
                     row.mcc.to_string(),
                     row.mcl.to_string(),
-                ]);
+                ])?;
             }
         }
         wtr.flush()?;
-
-        // gzip it
-        Command::new("gzip")
-            .args(["-f", &file_out])
-            .current_dir(format!("{}/month", self.base_dir))
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
+        wtr.into_inner().map_err(|e| e.into_error())?.finish()?;
         Ok(())
     }
 
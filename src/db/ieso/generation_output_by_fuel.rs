@@ -1,8 +1,10 @@
+use duckdb::Connection;
 use flate2::read::GzDecoder;
 use jiff::tz::{self, TimeZone};
-use jiff::{civil::*, Zoned};
+use jiff::{civil::*, SignedDuration, Timestamp, Zoned};
 use log::{error, info};
 use quick_xml::de::from_str;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs::File;
 use std::io::Read;
@@ -10,7 +12,12 @@ use std::path::Path;
 use std::process::Command;
 
 use crate::db::isone::lib_isoexpress::download_file;
+use crate::elec::aggregation::{self, Observation};
+use crate::time::bucket::Bucket;
+use crate::utils::compression::create_gz;
+use crate::utils::serde_helpers::serialize_zoned_as_offset;
 use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
 
 #[derive(Clone)]
 pub struct IesoGenOutputByFuelArchive {
@@ -75,16 +82,16 @@ impl IesoGenOutputByFuelArchive {
     /// Don't use a time offset anymore because America/Cancun timezone doesn't work properly before 2016.
     pub fn make_gzfile_for_year(&self, year: i16) -> Result<(), Box<dyn Error>> {
         let file_out = format!(
-            "{}/year/PUB_GenOutputbyFuelHourly_{}.csv",
+            "{}/year/PUB_GenOutputbyFuelHourly_{}.csv.gz",
             self.base_dir.to_owned(),
             year
         );
-        let mut wtr = csv::Writer::from_path(&file_out)?;
+        let mut wtr = csv::Writer::from_writer(create_gz(Path::new(&file_out))?);
         wtr.write_record(["hour_beginning", "fuel_type", "output_quality", "mw"])?;
 
         let rows = self.read_file(year)?;
         for row in rows {
-            let _ = wtr.write_record(&[
+            wtr.write_record(&[
                 row.begin_hour.strftime("%Y-%m-%dT%H:%M:%S.000").to_string(),
                 row.fuel_type.to_string(),
                 row.output_quality.to_string(),
@@ -92,17 +99,10 @@ impl IesoGenOutputByFuelArchive {
                     Some(mw) => mw.to_string(),
                     None => "".into(),
                 },
-            ]);
+            ])?;
         }
         wtr.flush()?;
-
-        Command::new("gzip")
-            .args(["-f", &file_out])
-            .current_dir(format!("{}/year", self.base_dir))
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
+        wtr.into_inner().map_err(|e| e.into_error())?.finish()?;
         Ok(())
     }
 
@@ -245,6 +245,277 @@ pub struct Row {
     pub mw: Option<usize>,
 }
 
+/// Hourly output of a fuel type.  The hour beginning is in EST all year.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Record {
+    #[serde(serialize_with = "serialize_zoned_as_offset")]
+    pub hour_beginning: Zoned,
+    pub fuel_type: String,
+    pub output_quality: i8,
+    pub mw: Option<u16>,
+}
+
+/// Format a zoned datetime as the naive EST timestamp used in the table
+fn to_est(value: &Zoned) -> String {
+    value
+        .with_time_zone(TimeZone::fixed(tz::offset(-5)))
+        .strftime("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+pub fn get_data(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    epoch_us(hour_beginning),
+    fuel_type::VARCHAR,
+    output_quality,
+    mw
+FROM gen_by_fuel WHERE 1=1"#,
+    );
+    if let Some(hour_beginning) = &query_filter.hour_beginning {
+        query.push_str(&format!(
+            "
+    AND hour_beginning = '{}'",
+            to_est(hour_beginning)
+        ));
+    }
+    if let Some(hour_beginning_gte) = &query_filter.hour_beginning_gte {
+        query.push_str(&format!(
+            "
+    AND hour_beginning >= '{}'",
+            to_est(hour_beginning_gte)
+        ));
+    }
+    if let Some(hour_beginning_lt) = &query_filter.hour_beginning_lt {
+        query.push_str(&format!(
+            "
+    AND hour_beginning < '{}'",
+            to_est(hour_beginning_lt)
+        ));
+    }
+    if let Some(fuel_type) = &query_filter.fuel_type {
+        query.push_str(&format!(
+            "
+    AND fuel_type = '{}'",
+            fuel_type
+        ));
+    }
+    if let Some(fuel_type_in) = &query_filter.fuel_type_in {
+        query.push_str(&format!(
+            "
+    AND fuel_type IN ('{}')",
+            fuel_type_in.join("','")
+        ));
+    }
+    if let Some(output_quality) = &query_filter.output_quality {
+        query.push_str(&format!(
+            "
+    AND output_quality = {}",
+            output_quality
+        ));
+    }
+    query.push_str("\nORDER BY hour_beginning, fuel_type");
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let tz = TimeZone::fixed(tz::offset(-5));
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        // naive EST timestamps
+        let _micros0: i64 = row.get::<usize, i64>(0)? + 5 * 3_600_000_000;
+        Ok(Record {
+            hour_beginning: Zoned::new(Timestamp::from_microsecond(_micros0).unwrap(), tz.clone()),
+            fuel_type: row.get::<usize, String>(1)?,
+            output_quality: row.get::<usize, i8>(2)?,
+            mw: row.get::<usize, Option<u16>>(3)?,
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+pub use crate::elec::aggregation::Resolution;
+
+/// Output of a fuel type over an hour or a day.  The `interval_beginning` is
+/// in EST.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Generation {
+    #[serde(serialize_with = "serialize_zoned_as_offset")]
+    pub interval_beginning: Zoned,
+    pub fuel_type: String,
+    pub average_mw: f64,
+    pub mwh: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BucketGeneration {
+    pub bucket: Bucket,
+    pub fuel_type: String,
+    pub average_mw: f64,
+    pub mwh: f64,
+}
+
+fn observations(records: &[Record], total: bool) -> Vec<Observation> {
+    records
+        .iter()
+        .filter_map(|r| {
+            Some(Observation {
+                interval_beginning: r.hour_beginning.clone(),
+                series: if total {
+                    "TOTAL".to_string()
+                } else {
+                    r.fuel_type.clone()
+                },
+                mw: r.mw? as f64,
+            })
+        })
+        .collect()
+}
+
+/// Aggregate the hourly records by hour or day.  If `total` is true, add up
+/// all the fuel types into a `TOTAL` fuel type.  Hours without an output are
+/// skipped.
+pub fn aggregate(records: &[Record], resolution: Resolution, total: bool) -> Vec<Generation> {
+    aggregation::aggregate(
+        &observations(records, total),
+        resolution,
+        SignedDuration::from_hours(1),
+    )
+    .into_iter()
+    .map(|e| Generation {
+        interval_beginning: e.period,
+        fuel_type: e.series,
+        average_mw: e.average_mw,
+        mwh: e.mwh,
+    })
+    .collect()
+}
+
+/// Aggregate the hourly records by bucket.  The buckets are evaluated in
+/// Eastern prevailing time, same as the NERC calendar.
+pub fn aggregate_by_bucket(
+    records: &[Record],
+    buckets: &[Bucket],
+    total: bool,
+) -> Vec<BucketGeneration> {
+    aggregation::aggregate_by_bucket(
+        &observations(records, total),
+        buckets,
+        SignedDuration::from_hours(1),
+    )
+    .into_iter()
+    .map(|e| BucketGeneration {
+        bucket: e.period,
+        fuel_type: e.series,
+        average_mw: e.average_mw,
+        mwh: e.mwh,
+    })
+    .collect()
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub hour_beginning: Option<Zoned>,
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    pub fuel_type: Option<String>,
+    pub fuel_type_in: Option<Vec<String>>,
+    pub output_quality: Option<i8>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.hour_beginning {
+            params.insert("hour_beginning", value.to_string());
+        }
+        if let Some(value) = &self.hour_beginning_gte {
+            params.insert("hour_beginning_gte", value.to_string());
+        }
+        if let Some(value) = &self.hour_beginning_lt {
+            params.insert("hour_beginning_lt", value.to_string());
+        }
+        if let Some(value) = &self.fuel_type {
+            params.insert("fuel_type", value.to_string());
+        }
+        if let Some(value) = &self.fuel_type_in {
+            params.insert("fuel_type_in", value.join(","));
+        }
+        if let Some(value) = &self.output_quality {
+            params.insert("output_quality", value.to_string());
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn hour_beginning(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning = Some(value);
+        self
+    }
+
+    pub fn hour_beginning_gte(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_gte = Some(value);
+        self
+    }
+
+    pub fn hour_beginning_lt(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_lt = Some(value);
+        self
+    }
+
+    pub fn fuel_type<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.fuel_type = Some(value.into());
+        self
+    }
+
+    pub fn fuel_type_in<I, S>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.inner.fuel_type_in = Some(values.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn output_quality(mut self, value: i8) -> Self {
+        self.inner.output_quality = Some(value);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{error::Error, path::Path};
@@ -253,6 +524,69 @@ mod tests {
 
     use super::*;
 
+    fn test_conn() -> Result<Connection, Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE gen_by_fuel (
+    hour_beginning TIMESTAMP NOT NULL,
+    fuel_type ENUM('NUCLEAR', 'GAS', 'HYDRO', 'WIND', 'SOLAR', 'BIOFUEL', 'OTHER') NOT NULL,
+    output_quality INT1 NOT NULL,
+    mw UINT16,
+);
+INSERT INTO gen_by_fuel VALUES
+    ('2025-07-01 06:00:00', 'NUCLEAR', 0, 10000),
+    ('2025-07-01 06:00:00', 'WIND', 0, 400),
+    ('2025-07-01 07:00:00', 'NUCLEAR', 0, 10100),
+    ('2025-07-01 07:00:00', 'WIND', 0, NULL),
+    ('2025-07-02 07:00:00', 'NUCLEAR', 0, 10200),
+    ('2025-07-02 07:00:00', 'WIND', 0, 600);
+",
+        )?;
+        Ok(conn)
+    }
+
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = test_conn()?;
+        let filter = QueryFilterBuilder::new()
+            .hour_beginning_gte("2025-07-01 07:00[America/Toronto]".parse()?)
+            .fuel_type("WIND")
+            .build();
+        let xs = get_data(&conn, &filter, None)?;
+        // 07:00 EDT is 06:00 EST
+        assert_eq!(xs.len(), 3);
+        assert_eq!(xs[0].hour_beginning, "2025-07-01 06:00-05:00[-05:00]".parse()?);
+        assert_eq!(xs[0].mw, Some(400));
+        assert_eq!(xs[1].mw, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregate() -> Result<(), Box<dyn Error>> {
+        let conn = test_conn()?;
+        let xs = get_data(&conn, &QueryFilter::default(), None)?;
+
+        let daily = aggregate(&xs, Resolution::Day, false);
+        assert_eq!(daily.len(), 4);
+        assert_eq!(daily[0].fuel_type, "NUCLEAR");
+        assert_eq!(daily[0].mwh, 20100.0);
+        assert_eq!(daily[0].average_mw, 10050.0);
+        assert_eq!(daily[1].mwh, 400.0);
+
+        let hourly = aggregate(&xs, Resolution::Hour, true);
+        assert_eq!(hourly.len(), 3);
+        assert_eq!(hourly[0].average_mw, 10400.0);
+
+        // 2025-07-01 is a Tuesday, 06:00 EST is 07:00 EDT, in the peak
+        let buckets = aggregate_by_bucket(&xs, &[Bucket::B5x16, Bucket::B7x8], false);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].bucket, Bucket::B5x16);
+        assert_eq!(buckets[0].average_mw, 10100.0);
+        assert_eq!(buckets[1].mwh, 1000.0);
+        Ok(())
+    }
+
     #[ignore]
     #[test]
     fn update_db() -> Result<(), Box<dyn Error>> {
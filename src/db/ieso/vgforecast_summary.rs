@@ -1,9 +1,11 @@
+use duckdb::Connection;
 use flate2::read::GzDecoder;
 use jiff::tz::{self, TimeZone};
-use jiff::{civil::*, Zoned};
+use jiff::{civil::*, Timestamp, Zoned};
 use log::{error, info};
 use quick_xml::de::from_str;
 use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::fs::File;
 use std::io::Read;
@@ -13,7 +15,11 @@ use std::str::FromStr;
 
 use crate::db::isone::lib_isoexpress::download_file;
 use crate::interval::month::Month;
+use crate::time::bucket::{Bucket, BucketLike};
+use crate::utils::compression::create_gz;
+use crate::utils::serde_helpers::serialize_zoned_as_offset;
 use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
 
 #[derive(Clone)]
 pub struct IesoVGForecastSummaryArchive {
@@ -96,11 +102,11 @@ impl IesoVGForecastSummaryArchive {
     ///
     pub fn make_gzfile_for_month(&self, month: &Month) -> Result<(), Box<dyn Error>> {
         let file_out = format!(
-            "{}/month/PUB_VGForecastSummary_{}.csv",
+            "{}/month/PUB_VGForecastSummary_{}.csv.gz",
             self.base_dir.to_owned(),
             month
         );
-        let mut wtr = csv::Writer::from_path(&file_out)?;
+        let mut wtr = csv::Writer::from_writer(create_gz(Path::new(&file_out))?);
         wtr.write_record([
             "forecast_timestamp",
             "organization",
@@ -120,7 +126,7 @@ impl IesoVGForecastSummaryArchive {
             }
             let rows = self.read_file(&day)?;
             for row in rows {
-                let _ = wtr.write_record(&[
+                wtr.write_record(&[
                     row.forecast_timestamp
                         .strftime("%Y-%m-%dT%H:%M:%S.000%:z")
                         .to_string(),
@@ -131,19 +137,11 @@ impl IesoVGForecastSummaryArchive {
                         .strftime("%Y-%m-%dT%H:%M:%S.000%:z")
                         .to_string(),
                     row.mw.to_string(),
-                ]);
+                ])?;
             }
         }
         wtr.flush()?;
-
-        // gzip it
-        Command::new("gzip")
-            .args(["-f", &file_out])
-            .current_dir(format!("{}/month", self.base_dir))
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
+        wtr.into_inner().map_err(|e| e.into_error())?.finish()?;
         Ok(())
     }
 
@@ -305,6 +303,345 @@ pub struct Row {
     pub mw: Decimal,
 }
 
+/// One hourly forecast for a zone.  Timestamps are in EST all year.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Record {
+    #[serde(serialize_with = "serialize_zoned_as_offset")]
+    pub forecast_timestamp: Zoned,
+    pub organization: String,
+    pub fuel_type: String,
+    pub zone: String,
+    #[serde(serialize_with = "serialize_zoned_as_offset")]
+    pub hour_beginning: Zoned,
+    pub mw: f64,
+}
+
+pub fn get_data(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    forecast_timestamp,
+    organization::VARCHAR,
+    fuel_type::VARCHAR,
+    zone,
+    hour_beginning,
+    mw::DOUBLE
+FROM forecast_summary WHERE 1=1"#,
+    );
+    if let Some(forecast_timestamp) = &query_filter.forecast_timestamp {
+        query.push_str(&format!(
+            "
+    AND forecast_timestamp = '{}'",
+            forecast_timestamp.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(forecast_timestamp_gte) = &query_filter.forecast_timestamp_gte {
+        query.push_str(&format!(
+            "
+    AND forecast_timestamp >= '{}'",
+            forecast_timestamp_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(forecast_timestamp_lt) = &query_filter.forecast_timestamp_lt {
+        query.push_str(&format!(
+            "
+    AND forecast_timestamp < '{}'",
+            forecast_timestamp_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(organization) = &query_filter.organization {
+        query.push_str(&format!(
+            "
+    AND organization = '{}'",
+            organization
+        ));
+    }
+    if let Some(fuel_type) = &query_filter.fuel_type {
+        query.push_str(&format!(
+            "
+    AND fuel_type = '{}'",
+            fuel_type
+        ));
+    }
+    if let Some(zone) = &query_filter.zone {
+        query.push_str(&format!(
+            "
+    AND zone = '{}'",
+            zone
+        ));
+    }
+    if let Some(zone_in) = &query_filter.zone_in {
+        query.push_str(&format!(
+            "
+    AND zone IN ('{}')",
+            zone_in.join("','")
+        ));
+    }
+    if let Some(hour_beginning_gte) = &query_filter.hour_beginning_gte {
+        query.push_str(&format!(
+            "
+    AND hour_beginning >= '{}'",
+            hour_beginning_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(hour_beginning_lt) = &query_filter.hour_beginning_lt {
+        query.push_str(&format!(
+            "
+    AND hour_beginning < '{}'",
+            hour_beginning_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    query.push_str("\nORDER BY forecast_timestamp, organization, fuel_type, zone, hour_beginning");
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let tz = TimeZone::fixed(tz::offset(-5));
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        let _micros4: i64 = row.get::<usize, i64>(4)?;
+        Ok(Record {
+            forecast_timestamp: Zoned::new(Timestamp::from_microsecond(_micros0).unwrap(), tz.clone()),
+            organization: row.get::<usize, String>(1)?,
+            fuel_type: row.get::<usize, String>(2)?,
+            zone: row.get::<usize, String>(3)?,
+            hour_beginning: Zoned::new(Timestamp::from_microsecond(_micros4).unwrap(), tz.clone()),
+            mw: row.get::<usize, f64>(5)?,
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+/// The forecast of one vintage, added up over the zones
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct HourlyForecast {
+    #[serde(serialize_with = "serialize_zoned_as_offset")]
+    pub forecast_timestamp: Zoned,
+    pub organization: String,
+    pub fuel_type: String,
+    #[serde(serialize_with = "serialize_zoned_as_offset")]
+    pub hour_beginning: Zoned,
+    pub mw: f64,
+}
+
+/// The average forecast of one vintage over the hours of a bucket in a day
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BucketForecast {
+    #[serde(serialize_with = "serialize_zoned_as_offset")]
+    pub forecast_timestamp: Zoned,
+    pub organization: String,
+    pub fuel_type: String,
+    pub date: Date,
+    pub bucket: Bucket,
+    pub average_mw: f64,
+}
+
+/// Zones with a total in the name (e.g. `OntarioTotal`) hold the sum of the
+/// other zones, so they get skipped when adding up the zones.
+pub fn is_total(zone: &str) -> bool {
+    zone.to_lowercase().contains("total")
+}
+
+/// SQL condition on the `zone` column that skips the total zones, the same
+/// rule as [`is_total`].
+pub fn not_total_sql() -> &'static str {
+    "lower(zone) NOT LIKE '%total%'"
+}
+
+/// Add up the zonal forecasts of each vintage by hour.  Zones with a total
+/// in the name are skipped so they don't get double counted.
+pub fn aggregate_zones(records: &[Record]) -> Vec<HourlyForecast> {
+    type Key = (Timestamp, String, String, Timestamp);
+    let mut groups: BTreeMap<Key, HourlyForecast> = BTreeMap::new();
+    for r in records.iter().filter(|r| !is_total(&r.zone)) {
+        let key = (
+            r.forecast_timestamp.timestamp(),
+            r.organization.clone(),
+            r.fuel_type.clone(),
+            r.hour_beginning.timestamp(),
+        );
+        groups
+            .entry(key)
+            .or_insert(HourlyForecast {
+                forecast_timestamp: r.forecast_timestamp.clone(),
+                organization: r.organization.clone(),
+                fuel_type: r.fuel_type.clone(),
+                hour_beginning: r.hour_beginning.clone(),
+                mw: 0.0,
+            })
+            .mw += r.mw;
+    }
+    groups.into_values().collect()
+}
+
+/// Average the hourly forecasts of each vintage by bucket and delivery day.
+/// The buckets are evaluated in Eastern prevailing time.
+pub fn aggregate_by_bucket(forecasts: &[HourlyForecast], buckets: &[Bucket]) -> Vec<BucketForecast> {
+    let mut out = Vec::new();
+    for bucket in buckets {
+        let tz = bucket.timezone();
+        type Key = (Timestamp, String, String, Date);
+        let mut groups: BTreeMap<Key, (Zoned, f64, usize)> = BTreeMap::new();
+        for f in forecasts {
+            let hb = f.hour_beginning.with_time_zone(tz.clone());
+            if !bucket.contains(&hb) {
+                continue;
+            }
+            let e = groups
+                .entry((
+                    f.forecast_timestamp.timestamp(),
+                    f.organization.clone(),
+                    f.fuel_type.clone(),
+                    hb.date(),
+                ))
+                .or_insert((f.forecast_timestamp.clone(), 0.0, 0));
+            e.1 += f.mw;
+            e.2 += 1;
+        }
+        out.extend(groups.into_iter().map(
+            |((_, organization, fuel_type, date), (forecast_timestamp, mw, n))| BucketForecast {
+                forecast_timestamp,
+                organization,
+                fuel_type,
+                date,
+                bucket: *bucket,
+                average_mw: mw / n as f64,
+            },
+        ));
+    }
+    out
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub forecast_timestamp: Option<Zoned>,
+    pub forecast_timestamp_gte: Option<Zoned>,
+    pub forecast_timestamp_lt: Option<Zoned>,
+    pub organization: Option<String>,
+    pub fuel_type: Option<String>,
+    pub zone: Option<String>,
+    pub zone_in: Option<Vec<String>>,
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.forecast_timestamp {
+            params.insert("forecast_timestamp", value.to_string());
+        }
+        if let Some(value) = &self.forecast_timestamp_gte {
+            params.insert("forecast_timestamp_gte", value.to_string());
+        }
+        if let Some(value) = &self.forecast_timestamp_lt {
+            params.insert("forecast_timestamp_lt", value.to_string());
+        }
+        if let Some(value) = &self.organization {
+            params.insert("organization", value.to_string());
+        }
+        if let Some(value) = &self.fuel_type {
+            params.insert("fuel_type", value.to_string());
+        }
+        if let Some(value) = &self.zone {
+            params.insert("zone", value.to_string());
+        }
+        if let Some(value) = &self.zone_in {
+            params.insert("zone_in", value.join(","));
+        }
+        if let Some(value) = &self.hour_beginning_gte {
+            params.insert("hour_beginning_gte", value.to_string());
+        }
+        if let Some(value) = &self.hour_beginning_lt {
+            params.insert("hour_beginning_lt", value.to_string());
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn forecast_timestamp(mut self, value: Zoned) -> Self {
+        self.inner.forecast_timestamp = Some(value);
+        self
+    }
+
+    pub fn forecast_timestamp_gte(mut self, value: Zoned) -> Self {
+        self.inner.forecast_timestamp_gte = Some(value);
+        self
+    }
+
+    pub fn forecast_timestamp_lt(mut self, value: Zoned) -> Self {
+        self.inner.forecast_timestamp_lt = Some(value);
+        self
+    }
+
+    pub fn organization<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.organization = Some(value.into());
+        self
+    }
+
+    pub fn fuel_type<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.fuel_type = Some(value.into());
+        self
+    }
+
+    pub fn zone<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.zone = Some(value.into());
+        self
+    }
+
+    pub fn zone_in<I, S>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.inner.zone_in = Some(values.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn hour_beginning_gte(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_gte = Some(value);
+        self
+    }
+
+    pub fn hour_beginning_lt(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_lt = Some(value);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{error::Error, path::Path};
@@ -315,6 +652,50 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_aggregate() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE forecast_summary (
+    forecast_timestamp TIMESTAMPTZ NOT NULL,
+    organization ENUM('MARKET PARTICIPANT', 'EMBEDDED') NOT NULL,
+    fuel_type ENUM('Wind', 'Solar') NOT NULL,
+    zone VARCHAR NOT NULL,
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    mw DECIMAL(9,4) NOT NULL,
+);
+INSERT INTO forecast_summary VALUES
+    ('2025-09-08 05:33:09-05:00', 'MARKET PARTICIPANT', 'Wind', 'NORTHEAST', '2025-09-09 06:00:00-05:00', 300.5),
+    ('2025-09-08 05:33:09-05:00', 'MARKET PARTICIPANT', 'Wind', 'WEST', '2025-09-09 06:00:00-05:00', 700),
+    ('2025-09-08 05:33:09-05:00', 'MARKET PARTICIPANT', 'Wind', 'OntarioTotal', '2025-09-09 06:00:00-05:00', 1000.5),
+    ('2025-09-08 05:33:09-05:00', 'MARKET PARTICIPANT', 'Wind', 'WEST', '2025-09-09 07:00:00-05:00', 800),
+    ('2025-09-08 05:33:09-05:00', 'MARKET PARTICIPANT', 'Wind', 'WEST', '2025-09-09 23:00:00-05:00', 500);
+",
+        )?;
+        let filter = QueryFilterBuilder::new()
+            .organization("MARKET PARTICIPANT")
+            .fuel_type("Wind")
+            .build();
+        let xs = get_data(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 5);
+        assert_eq!(xs[0].hour_beginning, "2025-09-09 06:00-05:00[-05:00]".parse()?);
+
+        let hourly = aggregate_zones(&xs);
+        assert_eq!(hourly.len(), 3);
+        assert_eq!(hourly[0].mw, 1000.5);
+
+        // 06:00 EST is 07:00 EDT, in the peak
+        let buckets = aggregate_by_bucket(&hourly, &[Bucket::B5x16, Bucket::B7x8]);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].date, date(2025, 9, 9));
+        assert_eq!(buckets[0].average_mw, 900.25);
+        // 23:00 EST is midnight EDT of the next day
+        assert_eq!(buckets[1].date, date(2025, 9, 10));
+        assert_eq!(buckets[1].average_mw, 500.0);
+        Ok(())
+    }
+
     #[ignore]
     #[test]
     fn update_db() -> Result<(), Box<dyn Error>> {
@@ -0,0 +1,596 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+
+use duckdb::{types::ValueRef, Connection};
+use jiff::civil::Date;
+use jiff::{tz::TimeZone, Timestamp, Zoned};
+use log::{error, info};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::interval::interval_base::IntervalTzLike;
+use crate::interval::month::Month;
+use crate::interval::term::Term;
+use crate::utils::serde_helpers::*;
+
+/// The two ISONE real-time binding constraints reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtConstraintReport {
+    FiveMinute,
+    Hourly,
+}
+
+impl RtConstraintReport {
+    /// Name of the webservices endpoint
+    fn endpoint(&self) -> &'static str {
+        match self {
+            RtConstraintReport::FiveMinute => "fiveminuteconstraints",
+            RtConstraintReport::Hourly => "realtimeconstraints",
+        }
+    }
+
+    /// Path to the array of observations in the json file
+    fn json_path(&self) -> &'static str {
+        match self {
+            RtConstraintReport::FiveMinute => "FiveMinuteConstraints.FiveMinuteConstraint",
+            RtConstraintReport::Hourly => "RealTimeConstraints.RealTimeConstraint",
+        }
+    }
+
+    /// Length of the interval in minutes
+    pub fn duration_minutes(&self) -> u8 {
+        match self {
+            RtConstraintReport::FiveMinute => 5,
+            RtConstraintReport::Hourly => 60,
+        }
+    }
+}
+
+impl std::str::FromStr for RtConstraintReport {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "5min" | "fiveminute" => Ok(RtConstraintReport::FiveMinute),
+            "hourly" => Ok(RtConstraintReport::Hourly),
+            _ => Err(format!("Invalid RT binding constraints report: {}", s)),
+        }
+    }
+}
+
+/// ISONE real-time binding constraints.  Both the 5-minute and the hourly
+/// reports go in the same `constraints` table, distinguished by the
+/// `duration_minutes` column.
+#[derive(Clone)]
+pub struct IsoneRtBindingConstraintsArchive {
+    pub base_dir: String,
+    pub duckdb_path: String,
+}
+
+impl IsoneRtBindingConstraintsArchive {
+    /// Return the json filename for the day.  Does not check if the file exists.
+    pub fn filename(&self, date: &Date, report: RtConstraintReport) -> String {
+        self.base_dir.to_owned()
+            + "/Raw/"
+            + &date.year().to_string()
+            + "/"
+            + report.endpoint()
+            + "_"
+            + &date.strftime("%Y%m%d").to_string()
+            + ".json"
+    }
+
+    /// See https://webservices.iso-ne.com/api/v1.1/fiveminuteconstraints/day/20250501
+    /// and https://webservices.iso-ne.com/api/v1.1/realtimeconstraints/day/20250501
+    pub fn download_file(&self, date: Date, report: RtConstraintReport) -> Result<(), Box<dyn Error>> {
+        super::lib_isoexpress::download_file(
+            format!(
+                "https://webservices.iso-ne.com/api/v1.1/{}/day/{}",
+                report.endpoint(),
+                date.strftime("%Y%m%d")
+            ),
+            true,
+            Some("application/json".to_string()),
+            Path::new(&self.filename(&date, report)),
+            true,
+        )
+    }
+
+    /// Look for missing days.  Does not download current day.
+    pub fn download_missing_days(
+        &self,
+        month: Month,
+        report: RtConstraintReport,
+    ) -> Result<(), Box<dyn Error>> {
+        let last = Zoned::now().date();
+        for day in month.days() {
+            if day >= last {
+                continue;
+            }
+            let fname = format!("{}.gz", self.filename(&day, report));
+            if !Path::new(&fname).exists() {
+                info!("Working on {}", day);
+                self.download_file(day, report)?;
+                info!("  downloaded file for {}", day);
+            }
+        }
+        Ok(())
+    }
+
+    /// Upload one month to DuckDB.  Rows already in the table are skipped.
+    pub fn update_duckdb(
+        &self,
+        month: &Month,
+        report: RtConstraintReport,
+    ) -> Result<(), Box<dyn Error>> {
+        info!(
+            "inserting ISONE RT binding constraints {} files for month {} ...",
+            report.endpoint(),
+            month
+        );
+        let sql = format!(
+            r#"
+CREATE TABLE IF NOT EXISTS constraints (
+    interval_beginning TIMESTAMPTZ NOT NULL,
+    duration_minutes UTINYINT NOT NULL,
+    constraint_name VARCHAR NOT NULL,
+    contingency_name VARCHAR NOT NULL,
+    marginal_value DECIMAL(9,2) NOT NULL
+);
+
+CREATE TEMPORARY TABLE tmp AS
+    SELECT
+        make_timestamptz(epoch_us(BeginDate)) AS interval_beginning,
+        {duration}::UTINYINT AS duration_minutes,
+        ConstraintName::VARCHAR AS constraint_name,
+        ContingencyName::VARCHAR AS contingency_name,
+        MarginalValue::DECIMAL(9,2) AS marginal_value
+    FROM (
+        SELECT unnest({path}, recursive := true)
+        FROM read_json('{dir}/Raw/{year}/{endpoint}_{yyyymm}*.json.gz')
+    )
+ORDER BY interval_beginning, constraint_name;
+
+INSERT INTO constraints
+(SELECT * FROM tmp
+WHERE NOT EXISTS (
+    SELECT * FROM constraints d
+    WHERE d.interval_beginning = tmp.interval_beginning
+    AND d.duration_minutes = tmp.duration_minutes
+    AND d.constraint_name = tmp.constraint_name
+    AND d.contingency_name = tmp.contingency_name
+    )
+)
+ORDER BY interval_beginning, constraint_name;
+"#,
+            duration = report.duration_minutes(),
+            path = report.json_path(),
+            dir = self.base_dir,
+            year = month.year(),
+            endpoint = report.endpoint(),
+            yyyymm = month.strftime("%Y%m"),
+        );
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(&sql)
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for month {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub interval_beginning: Zoned,
+    pub duration_minutes: u8,
+    pub constraint_name: String,
+    pub contingency_name: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub marginal_value: Decimal,
+}
+
+fn decimal(v: ValueRef) -> Decimal {
+    match v {
+        ValueRef::Decimal(v) => v,
+        _ => Decimal::MIN,
+    }
+}
+
+pub fn get_data(
+    conn: &Connection,
+    report: RtConstraintReport,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = format!(
+        r#"
+SELECT
+    interval_beginning,
+    duration_minutes,
+    constraint_name,
+    contingency_name,
+    marginal_value
+FROM constraints
+WHERE duration_minutes = {}"#,
+        report.duration_minutes()
+    );
+    query.push_str(&where_clause(query_filter, "interval_beginning"));
+    query.push_str(
+        "
+ORDER BY interval_beginning, constraint_name, contingency_name",
+    );
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        Ok(Record {
+            interval_beginning: Zoned::new(
+                Timestamp::from_microsecond(_micros0).unwrap(),
+                tz.clone(),
+            ),
+            duration_minutes: row.get::<usize, u8>(1)?,
+            constraint_name: row.get::<usize, String>(2)?,
+            contingency_name: row.get::<usize, String>(3)?,
+            marginal_value: decimal(row.get_ref_unwrap(4)),
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+/// The filter as a SQL `AND ...` clause.  The time filters are applied to
+/// `time_column` so the same filter works on the DA table too.
+fn where_clause(query_filter: &QueryFilter, time_column: &str) -> String {
+    let mut out = String::new();
+    if let Some(interval_beginning_gte) = &query_filter.interval_beginning_gte {
+        out.push_str(&format!(
+            "
+    AND {} >= '{}'",
+            time_column,
+            interval_beginning_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(interval_beginning_lt) = &query_filter.interval_beginning_lt {
+        out.push_str(&format!(
+            "
+    AND {} < '{}'",
+            time_column,
+            interval_beginning_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(constraint_name) = &query_filter.constraint_name {
+        out.push_str(&format!(
+            "
+    AND constraint_name = '{}'",
+            constraint_name
+        ));
+    }
+    if let Some(constraint_name_like) = &query_filter.constraint_name_like {
+        out.push_str(&format!(
+            "
+    AND constraint_name LIKE '{}'",
+            constraint_name_like
+        ));
+    }
+    if let Some(constraint_name_in) = &query_filter.constraint_name_in {
+        out.push_str(&format!(
+            "
+    AND constraint_name IN ('{}')",
+            constraint_name_in.join("','")
+        ));
+    }
+    out
+}
+
+/// How often a constraint binds in the DA and RT markets over a term.
+///
+/// The binding hours count the hours (or the 5-minute intervals, expressed in
+/// hours) when the constraint binds for at least one contingency.  The
+/// cumulative shadow price is the sum of the marginal values over all the
+/// intervals and contingencies, weighted by the interval duration, in $/MW.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BindingStats {
+    pub constraint_name: String,
+    pub hours_in_term: u32,
+    pub da_binding_hours: f64,
+    pub rt_binding_hours: f64,
+    /// DA binding hours divided by the hours in the term
+    pub da_frequency: f64,
+    pub rt_frequency: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub da_cumulative_shadow_price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub rt_cumulative_shadow_price: Decimal,
+}
+
+/// Attach the DA binding constraints database (see
+/// `IsoneDaBindingConstraintsArchive`) as `da` so [`get_binding_stats`] can
+/// read its `constraints` table.
+pub fn attach_da_binding_constraints(
+    conn: &Connection,
+    da_duckdb_path: &str,
+) -> Result<(), Box<dyn Error>> {
+    conn.execute_batch(&format!("ATTACH '{}' AS da (READ_ONLY);", da_duckdb_path))?;
+    Ok(())
+}
+
+/// Compare the DA and RT binding frequency and cumulative shadow price of each
+/// constraint over a term.  The RT statistics are calculated from the
+/// `report` data.  The time filters of `query_filter` are ignored, the term
+/// defines the period.  Requires the DA database to be attached as `da`, see
+/// [`attach_da_binding_constraints`].
+pub fn get_binding_stats(
+    conn: &Connection,
+    term: &Term,
+    report: RtConstraintReport,
+    query_filter: &QueryFilter,
+) -> Result<Vec<BindingStats>, Box<dyn Error>> {
+    let term_tz = term.with_tz(&TimeZone::get("America/New_York").unwrap());
+    let hours_in_term = term_tz.hours().len() as u32;
+    let filter = QueryFilter {
+        interval_beginning_gte: Some(term_tz.start()),
+        interval_beginning_lt: Some(term_tz.end()),
+        constraint_name: query_filter.constraint_name.clone(),
+        constraint_name_like: query_filter.constraint_name_like.clone(),
+        constraint_name_in: query_filter.constraint_name_in.clone(),
+    };
+    let query = format!(
+        r#"
+WITH da_stats AS (
+    SELECT
+        constraint_name,
+        COUNT(DISTINCT hour_beginning)::DOUBLE AS binding_hours,
+        SUM(marginal_value) AS cumulative_shadow_price
+    FROM da.constraints
+    WHERE 1=1{}
+    GROUP BY constraint_name
+),
+rt_stats AS (
+    SELECT
+        constraint_name,
+        COUNT(DISTINCT interval_beginning) * {} / 60.0 AS binding_hours,
+        (SUM(marginal_value) * {} / 60)::DECIMAL(18,2) AS cumulative_shadow_price
+    FROM constraints
+    WHERE duration_minutes = {}{}
+    GROUP BY constraint_name
+)
+SELECT
+    COALESCE(d.constraint_name, r.constraint_name) AS constraint_name,
+    COALESCE(d.binding_hours, 0) AS da_binding_hours,
+    COALESCE(r.binding_hours, 0) AS rt_binding_hours,
+    COALESCE(d.cumulative_shadow_price, 0)::DECIMAL(18,2) AS da_cumulative_shadow_price,
+    COALESCE(r.cumulative_shadow_price, 0)::DECIMAL(18,2) AS rt_cumulative_shadow_price
+FROM da_stats d
+FULL OUTER JOIN rt_stats r
+    ON d.constraint_name = r.constraint_name
+ORDER BY constraint_name;
+"#,
+        where_clause(&filter, "hour_beginning"),
+        report.duration_minutes(),
+        report.duration_minutes(),
+        report.duration_minutes(),
+        where_clause(&filter, "interval_beginning"),
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let da_binding_hours = row.get::<usize, f64>(1)?;
+        let rt_binding_hours = row.get::<usize, f64>(2)?;
+        Ok(BindingStats {
+            constraint_name: row.get::<usize, String>(0)?,
+            hours_in_term,
+            da_binding_hours,
+            rt_binding_hours,
+            da_frequency: da_binding_hours / hours_in_term as f64,
+            rt_frequency: rt_binding_hours / hours_in_term as f64,
+            da_cumulative_shadow_price: decimal(row.get_ref_unwrap(3)),
+            rt_cumulative_shadow_price: decimal(row.get_ref_unwrap(4)),
+        })
+    })?;
+    let results: Vec<BindingStats> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub interval_beginning_gte: Option<Zoned>,
+    pub interval_beginning_lt: Option<Zoned>,
+    pub constraint_name: Option<String>,
+    pub constraint_name_like: Option<String>,
+    pub constraint_name_in: Option<Vec<String>>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.interval_beginning_gte {
+            params.insert("interval_beginning_gte", value.to_string());
+        }
+        if let Some(value) = &self.interval_beginning_lt {
+            params.insert("interval_beginning_lt", value.to_string());
+        }
+        if let Some(value) = &self.constraint_name {
+            params.insert("constraint_name", value.to_string());
+        }
+        if let Some(value) = &self.constraint_name_like {
+            params.insert("constraint_name_like", value.to_string());
+        }
+        if let Some(value) = &self.constraint_name_in {
+            params.insert("constraint_name_in", value.join(","));
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn interval_beginning_gte(mut self, value: Zoned) -> Self {
+        self.inner.interval_beginning_gte = Some(value);
+        self
+    }
+
+    pub fn interval_beginning_lt(mut self, value: Zoned) -> Self {
+        self.inner.interval_beginning_lt = Some(value);
+        self
+    }
+
+    pub fn constraint_name<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.constraint_name = Some(value.into());
+        self
+    }
+
+    pub fn constraint_name_like(mut self, value_like: String) -> Self {
+        self.inner.constraint_name_like = Some(value_like);
+        self
+    }
+
+    pub fn constraint_name_in(mut self, values_in: Vec<String>) -> Self {
+        self.inner.constraint_name_in = Some(values_in);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::prod_db::ProdDb, interval::month::month};
+    use jiff::civil::date;
+    use rust_decimal_macros::dec;
+    use std::error::Error;
+
+    fn setup(conn: &Connection) -> Result<(), Box<dyn Error>> {
+        conn.execute_batch(
+            r"
+CREATE TABLE constraints (
+    interval_beginning TIMESTAMPTZ NOT NULL,
+    duration_minutes UTINYINT NOT NULL,
+    constraint_name VARCHAR NOT NULL,
+    contingency_name VARCHAR NOT NULL,
+    marginal_value DECIMAL(9,2) NOT NULL
+);
+INSERT INTO constraints VALUES
+    ('2025-07-01 17:00:00-04:00', 5, 'SHFHGE', 'Actl', -12.00),
+    ('2025-07-01 17:05:00-04:00', 5, 'SHFHGE', 'Actl', -24.00),
+    ('2025-07-01 17:05:00-04:00', 5, 'SHFHGE', 'L-3001', -6.00),
+    ('2025-07-01 17:10:00-04:00', 5, 'NNE-SCOBIE', 'Actl', -60.00),
+    ('2025-07-01 17:00:00-04:00', 60, 'SHFHGE', 'Actl', -3.50);
+ATTACH ':memory:' AS da;
+CREATE TABLE da.constraints (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    constraint_name VARCHAR NOT NULL,
+    contingency_name VARCHAR NOT NULL,
+    marginal_value DECIMAL(9,2) NOT NULL
+);
+INSERT INTO da.constraints VALUES
+    ('2025-07-01 16:00:00-04:00', 'SHFHGE', 'Actl', -5.00),
+    ('2025-07-01 17:00:00-04:00', 'SHFHGE', 'Actl', -7.00),
+    ('2025-07-01 17:00:00-04:00', 'KR-EXP', 'Actl', -1.25);
+",
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        setup(&conn)?;
+        let filter = QueryFilterBuilder::new().constraint_name("SHFHGE").build();
+        let xs = get_data(&conn, RtConstraintReport::FiveMinute, &filter, None)?;
+        assert_eq!(xs.len(), 3);
+        let xs = get_data(&conn, RtConstraintReport::Hourly, &filter, None)?;
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].duration_minutes, 60);
+        assert_eq!(xs[0].marginal_value, dec!(-3.50));
+        Ok(())
+    }
+
+    #[test]
+    fn test_binding_stats() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        setup(&conn)?;
+        let term = Term::new(date(2025, 7, 1), date(2025, 7, 1)).unwrap();
+        let xs = get_binding_stats(
+            &conn,
+            &term,
+            RtConstraintReport::FiveMinute,
+            &QueryFilter::default(),
+        )?;
+        assert_eq!(xs.len(), 3);
+        assert_eq!(xs[0].constraint_name, "KR-EXP");
+        assert_eq!(xs[0].rt_binding_hours, 0.0);
+        let x = &xs[2];
+        assert_eq!(x.constraint_name, "SHFHGE");
+        assert_eq!(x.hours_in_term, 24);
+        assert_eq!(x.da_binding_hours, 2.0);
+        assert_eq!(x.da_frequency, 2.0 / 24.0);
+        assert_eq!(x.rt_binding_hours, 10.0 / 60.0);
+        assert_eq!(x.da_cumulative_shadow_price, dec!(-12.00));
+        assert_eq!(x.rt_cumulative_shadow_price, dec!(-3.50));
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn update_db() -> Result<(), Box<dyn Error>> {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+        dotenvy::from_path(std::path::Path::new(".env/test.env")).unwrap();
+        let archive = ProdDb::isone_rt_binding_constraints();
+        let months = month(2025, 1).up_to(month(2025, 7)).unwrap();
+        for month in months {
+            for report in [RtConstraintReport::FiveMinute, RtConstraintReport::Hourly] {
+                archive.download_missing_days(month, report)?;
+                archive.update_duckdb(&month, report)?;
+            }
+        }
+        Ok(())
+    }
+}
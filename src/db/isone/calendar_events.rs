@@ -1,12 +1,22 @@
-use jiff::civil::Date;
-use jiff::Zoned;
-use log::{error, info};
+use std::collections::HashMap;
 use std::error::Error;
 use std::path::Path;
 use std::process::Command;
 
+use duckdb::Connection;
+use jiff::civil::Date;
+use jiff::{tz::TimeZone, Timestamp, ToSpan, Zoned};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
 use crate::interval::month::Month;
+use crate::utils::serde_helpers::*;
 
+/// The ISONE events calendar: auctions, market trials, stakeholder meetings,
+/// training sessions, etc.  One file per day with the events starting on
+/// that day.  Events can be rescheduled or cancelled, so the files for the
+/// upcoming days are downloaded again every day.
 #[derive(Clone)]
 pub struct IsoneEventsCalendarArchive {
     pub base_dir: String,
@@ -14,7 +24,7 @@ pub struct IsoneEventsCalendarArchive {
 }
 
 impl IsoneEventsCalendarArchive {
-    /// Return the json filename for the day.  Does not check if the file exists.  
+    /// Return the json filename for the day.  Does not check if the file exists.
     pub fn filename(&self, date: &Date) -> String {
         self.base_dir.to_owned()
             + "/Raw/"
@@ -24,53 +34,57 @@ impl IsoneEventsCalendarArchive {
             + ".json"
     }
 
-    /// Upload one month to DuckDB.
-    /// Assumes all json.gz file exists for DA and RT.  Skips the day if it doesn't exist.
-    ///  
+    /// Upload one month to DuckDB.  All the events starting in the month are
+    /// replaced with the ones in the files, so events that have been
+    /// cancelled are removed and rescheduled events are moved.
+    ///
+    /// The file is an array of events with fields `id`, `event_title`,
+    /// `event_category`, `event_start_date_gmt`, `event_end_date_gmt`,
+    /// `event_location` and `event_url`.  The dates are in GMT.  Events
+    /// without an end date are stored with the end equal to the start.
     pub fn update_duckdb(&self, month: &Month) -> Result<(), Box<dyn Error>> {
-        info!(
-            "inserting actual interface flow files for month {} ...",
-            month
-        );
+        info!("inserting ISONE events files for month {} ...", month);
+        let start = month.start().in_tz("America/New_York")?;
+        let end = month.end().in_tz("America/New_York")?;
 
         let sql = format!(
             r#"
-CREATE TABLE IF NOT EXISTS flows (
-    hour_beginning TIMESTAMPTZ NOT NULL,
-    ptid UINTEGER NOT NULL,
-    Net DECIMAL(9,2) NOT NULL,
-    Purchase DECIMAL(9,2) NOT NULL,
-    Sale DECIMAL(9,2) NOT NULL,
+CREATE TABLE IF NOT EXISTS events (
+    event_id VARCHAR NOT NULL,
+    title VARCHAR NOT NULL,
+    category VARCHAR NOT NULL,
+    start_time TIMESTAMPTZ NOT NULL,
+    end_time TIMESTAMPTZ NOT NULL,
+    location VARCHAR,
+    url VARCHAR,
 );
 
 CREATE TEMPORARY TABLE tmp AS
-    SELECT 
-        BeginDate::TIMESTAMPTZ AS hour_beginning,
-        "@LocId"::UINTEGER AS ptid,
-        ActInterchange::DECIMAL(9,2) AS Net,
-        Purchase::DECIMAL(9,2) AS Purchase,
-        Sale::DECIMAL(9,2) AS Sale
-    FROM (
-        SELECT unnest(ActualInterchanges.ActualInterchange, recursive := true)
-        FROM read_json('{}/Raw/{}/act_interchange_{}*.json.gz')
-    )
-ORDER BY hour_beginning, ptid;
-
-INSERT INTO flows
-(SELECT * FROM tmp 
-WHERE NOT EXISTS (
-    SELECT * FROM flows d
-    WHERE d.hour_beginning = tmp.hour_beginning
-    AND d.ptid = tmp.ptid
-    )
-)
-ORDER BY hour_beginning, ptid;
+    SELECT DISTINCT ON (event_id)
+        id::VARCHAR AS event_id,
+        trim(event_title)::VARCHAR AS title,
+        coalesce(event_category, 'Other')::VARCHAR AS category,
+        make_timestamptz(epoch_us(event_start_date_gmt::TIMESTAMP)) AS start_time,
+        make_timestamptz(epoch_us(coalesce(event_end_date_gmt, event_start_date_gmt)::TIMESTAMP)) AS end_time,
+        nullif(trim(event_location), '')::VARCHAR AS location,
+        event_url::VARCHAR AS url
+    FROM read_json('{}/Raw/{}/events_{}*.json.gz', format = 'array')
+ORDER BY event_id;
+
+DELETE FROM events
+WHERE event_id IN (SELECT event_id FROM tmp)
+OR (start_time >= '{}' AND start_time < '{}');
+
+INSERT INTO events
+SELECT * FROM tmp
+ORDER BY start_time, event_id;
 "#,
             self.base_dir,
-            month.start_date().year(),
-            month.start_date().strftime("%Y%m"),
+            month.year(),
+            month.strftime("%Y%m"),
+            start.strftime("%Y-%m-%d %H:%M:%S.000%:z"),
+            end.strftime("%Y-%m-%d %H:%M:%S.000%:z"),
         );
-        // println!("{}", sql);
 
         let output = Command::new("duckdb")
             .arg("-c")
@@ -123,16 +137,358 @@ ORDER BY hour_beginning, ptid;
         }
         Ok(())
     }
+
+    /// Download again the files for today and the next `days` days, to pick
+    /// up the newly scheduled events and the changes to existing ones.
+    /// Return the months that need to be updated in DuckDB.
+    pub fn download_upcoming_days(&self, days: i32) -> Result<Vec<Month>, Box<dyn Error>> {
+        let today = Zoned::now().date();
+        let mut months: Vec<Month> = Vec::new();
+        for i in 0..=days {
+            let day = today.checked_add(i.days())?;
+            self.download_file(day)?;
+            let m = Month::containing(day.at(0, 0, 0, 0));
+            if !months.contains(&m) {
+                months.push(m);
+            }
+        }
+        info!("downloaded events for the next {} days", days);
+        Ok(months)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub event_id: String,
+    pub title: String,
+    pub category: String,
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub start_time: Zoned,
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub end_time: Zoned,
+    pub location: Option<String>,
+    pub url: Option<String>,
+}
+
+pub fn get_data(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    event_id,
+    title,
+    category,
+    start_time,
+    end_time,
+    location,
+    url
+FROM events WHERE 1=1"#,
+    );
+    if let Some(start_time_gte) = &query_filter.start_time_gte {
+        query.push_str(&format!(
+            "
+    AND start_time >= '{}'",
+            start_time_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(start_time_lt) = &query_filter.start_time_lt {
+        query.push_str(&format!(
+            "
+    AND start_time < '{}'",
+            start_time_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(category) = &query_filter.category {
+        query.push_str(&format!(
+            "
+    AND category = '{}'",
+            category
+        ));
+    }
+    if let Some(category_in) = &query_filter.category_in {
+        query.push_str(&format!(
+            "
+    AND category IN ('{}')",
+            category_in.join("','")
+        ));
+    }
+    if let Some(title_like) = &query_filter.title_like {
+        query.push_str(&format!(
+            "
+    AND title ILIKE '{}'",
+            title_like
+        ));
+    }
+    query.push_str(
+        "
+ORDER BY start_time, event_id",
+    );
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros3: i64 = row.get::<usize, i64>(3)?;
+        let _micros4: i64 = row.get::<usize, i64>(4)?;
+        Ok(Record {
+            event_id: row.get::<usize, String>(0)?,
+            title: row.get::<usize, String>(1)?,
+            category: row.get::<usize, String>(2)?,
+            start_time: Zoned::new(Timestamp::from_microsecond(_micros3).unwrap(), tz.clone()),
+            end_time: Zoned::new(Timestamp::from_microsecond(_micros4).unwrap(), tz.clone()),
+            location: row.get::<usize, Option<String>>(5)?,
+            url: row.get::<usize, Option<String>>(6)?,
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+/// Export the events as an iCalendar (RFC 5545) document, so they can be
+/// imported in a calendar application.  Use `now` for the DTSTAMP property.
+pub fn to_icalendar(events: &[Record], now: &Timestamp) -> String {
+    let stamp = now.strftime("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines: Vec<String> = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//bust//ISONE events calendar//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for e in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@iso-ne.com", e.event_id));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!(
+            "DTSTART:{}",
+            e.start_time.timestamp().strftime("%Y%m%dT%H%M%SZ")
+        ));
+        lines.push(format!(
+            "DTEND:{}",
+            e.end_time.timestamp().strftime("%Y%m%dT%H%M%SZ")
+        ));
+        lines.push(format!("SUMMARY:{}", escape_text(&e.title)));
+        lines.push(format!("CATEGORIES:{}", escape_text(&e.category)));
+        if let Some(location) = &e.location {
+            lines.push(format!("LOCATION:{}", escape_text(location)));
+        }
+        if let Some(url) = &e.url {
+            lines.push(format!("URL:{}", url));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold_line(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Escape the characters that have a special meaning in iCalendar TEXT values.
+fn escape_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Lines longer than 75 octets are split, continuation lines start with a space.
+fn fold_line(line: &str) -> String {
+    let mut out = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        let n = c.len_utf8();
+        if len + n > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += n;
+    }
+    out
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub start_time_gte: Option<Zoned>,
+    pub start_time_lt: Option<Zoned>,
+    pub category: Option<String>,
+    pub category_in: Option<Vec<String>>,
+    /// A SQL ILIKE pattern, e.g. `%auction%`
+    pub title_like: Option<String>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.start_time_gte {
+            params.insert("start_time_gte", value.to_string());
+        }
+        if let Some(value) = &self.start_time_lt {
+            params.insert("start_time_lt", value.to_string());
+        }
+        if let Some(value) = &self.category {
+            params.insert("category", value.to_string());
+        }
+        if let Some(value) = &self.category_in {
+            params.insert("category_in", value.join(","));
+        }
+        if let Some(value) = &self.title_like {
+            params.insert("title_like", value.to_string());
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn start_time_gte(mut self, value: Zoned) -> Self {
+        self.inner.start_time_gte = Some(value);
+        self
+    }
+
+    pub fn start_time_lt(mut self, value: Zoned) -> Self {
+        self.inner.start_time_lt = Some(value);
+        self
+    }
+
+    pub fn category<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.category = Some(value.into());
+        self
+    }
+
+    pub fn category_in(mut self, values_in: Vec<String>) -> Self {
+        self.inner.category_in = Some(values_in);
+        self
+    }
+
+    pub fn title_like<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.title_like = Some(value.into());
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
-
+    use super::*;
+    use duckdb::Connection;
     use jiff::civil::date;
     use std::{error::Error, path::Path};
 
     use crate::{db::prod_db::ProdDb, interval::month::month};
 
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE events (
+    event_id VARCHAR NOT NULL,
+    title VARCHAR NOT NULL,
+    category VARCHAR NOT NULL,
+    start_time TIMESTAMPTZ NOT NULL,
+    end_time TIMESTAMPTZ NOT NULL,
+    location VARCHAR,
+    url VARCHAR,
+);
+INSERT INTO events VALUES
+    ('101', 'Markets Committee', 'Stakeholder Meeting', '2026-02-10 09:30:00-05:00', '2026-02-10 16:00:00-05:00', 'Westborough, MA', NULL),
+    ('102', 'FCA 19 Qualification Deadline', 'Auction', '2026-02-11 00:00:00-05:00', '2026-02-11 00:00:00-05:00', NULL, NULL),
+    ('103', 'Market Trial: Day-Ahead Ancillary Services', 'Market Trial', '2026-02-12 10:00:00-05:00', '2026-02-12 12:00:00-05:00', 'WebEx', NULL);
+",
+        )?;
+        let filter = QueryFilterBuilder::new()
+            .category_in(vec!["Auction".to_string(), "Market Trial".to_string()])
+            .build();
+        let xs = get_data(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].event_id, "102");
+        assert_eq!(xs[0].end_time, xs[0].start_time);
+
+        let filter = QueryFilterBuilder::new()
+            .start_time_gte("2026-02-10 12:00[America/New_York]".parse()?)
+            .title_like("%trial%")
+            .build();
+        let xs = get_data(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].location, Some("WebEx".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_icalendar() -> Result<(), Box<dyn Error>> {
+        let events = vec![Record {
+            event_id: "101".to_string(),
+            title: "Markets Committee; Summer Meeting, Day 1".to_string(),
+            category: "Stakeholder Meeting".to_string(),
+            start_time: "2026-07-10 09:30[America/New_York]".parse()?,
+            end_time: "2026-07-10 16:00[America/New_York]".parse()?,
+            location: Some("Westborough, MA".to_string()),
+            url: None,
+        }];
+        let now: Timestamp = "2026-07-01T12:00:00Z".parse()?;
+        let ics = to_icalendar(&events, &now);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20260710T133000Z\r\n"));
+        assert!(ics.contains("DTEND:20260710T200000Z\r\n"));
+        assert!(ics.contains("DTSTAMP:20260701T120000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Markets Committee\\; Summer Meeting\\, Day 1\r\n"));
+        assert!(ics.contains("LOCATION:Westborough\\, MA\r\n"));
+        assert!(!ics.contains("URL:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = "X".repeat(160);
+        let folded = fold_line(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].len(), 75);
+        assert_eq!(parts[1].len(), 75);
+        assert_eq!(parts[2], format!(" {}", "X".repeat(11)));
+    }
+
     #[ignore]
     #[test]
     fn update_db() -> Result<(), Box<dyn Error>> {
@@ -145,7 +501,7 @@ mod tests {
 
         let months = month(2026, 1).up_to(month(2026, 1)).unwrap();
         for month in months {
-            // archive.download_missing_days(month)?;
+            archive.download_missing_days(month)?;
             archive.update_duckdb(&month)?;
         }
         Ok(())
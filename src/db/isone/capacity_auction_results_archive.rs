@@ -0,0 +1,608 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::process::Command;
+
+use duckdb::{types::ValueRef, Connection};
+use jiff::civil::Date;
+use jiff::ToSpan;
+use log::{error, info};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::db::isone::masked_data::ara_archive::CapabilityYear;
+use crate::interval::month::Month;
+
+/// Clearing results of the ISONE Forward Capacity Market auctions by
+/// capacity zone and external interface.  The `auction` column is one of
+/// FCA, ARA1, ARA2, ARA3, MRA.  Only the MRA results are loaded for now,
+/// from the `results_zone` and `results_interface` tables of the MRA
+/// database.
+#[derive(Clone)]
+pub struct IsoneCapacityAuctionResultsArchive {
+    /// The MRA database, see `ProdDb::isone_mra_bids_offers`
+    pub mra_duckdb_path: String,
+    pub duckdb_path: String,
+}
+
+impl IsoneCapacityAuctionResultsArchive {
+    /// Insert the MRA results for the month.  Rows already in the tables
+    /// are skipped.
+    pub fn update_duckdb(&self, month: &Month) -> Result<(), Box<dyn Error>> {
+        info!("inserting ISONE MRA results for {} ...", month);
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(self.update_sql(month))
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+
+    /// SQL to insert the MRA results of one month.
+    fn update_sql(&self, month: &Month) -> String {
+        let start = month.start_date();
+        format!(
+            r#"
+ATTACH '{mra}' AS mra (READ_ONLY);
+
+CREATE TABLE IF NOT EXISTS results_zone (
+    auction VARCHAR NOT NULL,
+    capability_period VARCHAR NOT NULL,
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    capacity_zone_id UINTEGER NOT NULL,
+    capacity_zone_name VARCHAR NOT NULL,
+    capacity_zone_type VARCHAR NOT NULL,
+    supply_offers_submitted DECIMAL(12,3) NOT NULL,
+    demand_bids_submitted DECIMAL(12,3) NOT NULL,
+    supply_offers_cleared DECIMAL(12,3) NOT NULL,
+    demand_bids_cleared DECIMAL(12,3) NOT NULL,
+    net_capacity_cleared DECIMAL(12,3) NOT NULL,
+    clearing_price DECIMAL(9,3) NOT NULL,
+);
+CREATE TABLE IF NOT EXISTS results_interface (
+    auction VARCHAR NOT NULL,
+    capability_period VARCHAR NOT NULL,
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    external_interface_id UINTEGER NOT NULL,
+    external_interface_name VARCHAR NOT NULL,
+    supply_offers_submitted DECIMAL(12,3) NOT NULL,
+    demand_bids_submitted DECIMAL(12,3) NOT NULL,
+    supply_offers_cleared DECIMAL(12,3) NOT NULL,
+    demand_bids_cleared DECIMAL(12,3) NOT NULL,
+    net_capacity_cleared DECIMAL(12,3) NOT NULL,
+    clearing_price DECIMAL(9,3) NOT NULL,
+);
+
+CREATE TEMPORARY TABLE tmp_zone AS
+    SELECT
+        'MRA' AS auction,
+        '{cp}' AS capability_period,
+        '{start}'::DATE AS period_start,
+        '{end}'::DATE AS period_end,
+        capacityZoneId::UINTEGER AS capacity_zone_id,
+        capacityZoneName::VARCHAR AS capacity_zone_name,
+        capacityZoneType::VARCHAR AS capacity_zone_type,
+        supplyOffersSubmitted::DECIMAL(12,3) AS supply_offers_submitted,
+        demandBidsSubmitted::DECIMAL(12,3) AS demand_bids_submitted,
+        supplyOffersCleared::DECIMAL(12,3) AS supply_offers_cleared,
+        demandBidsCleared::DECIMAL(12,3) AS demand_bids_cleared,
+        netCapacityCleared::DECIMAL(12,3) AS net_capacity_cleared,
+        clearingPrice::DECIMAL(9,3) AS clearing_price
+    FROM mra.results_zone
+    WHERE month = {yyyymm};
+
+CREATE TEMPORARY TABLE tmp_interface AS
+    SELECT
+        'MRA' AS auction,
+        '{cp}' AS capability_period,
+        '{start}'::DATE AS period_start,
+        '{end}'::DATE AS period_end,
+        externalInterfaceId::UINTEGER AS external_interface_id,
+        externalInterfaceName::VARCHAR AS external_interface_name,
+        supplyOffersSubmitted::DECIMAL(12,3) AS supply_offers_submitted,
+        demandBidsSubmitted::DECIMAL(12,3) AS demand_bids_submitted,
+        supplyOffersCleared::DECIMAL(12,3) AS supply_offers_cleared,
+        demandBidsCleared::DECIMAL(12,3) AS demand_bids_cleared,
+        netCapacityCleared::DECIMAL(12,3) AS net_capacity_cleared,
+        clearingPrice::DECIMAL(9,3) AS clearing_price
+    FROM mra.results_interface
+    WHERE month = {yyyymm};
+
+INSERT INTO results_zone
+(SELECT * FROM tmp_zone
+WHERE NOT EXISTS (
+    SELECT * FROM results_zone d
+    WHERE d.auction = tmp_zone.auction
+    AND d.period_start = tmp_zone.period_start
+    AND d.capacity_zone_id = tmp_zone.capacity_zone_id
+    )
+)
+ORDER BY capacity_zone_id;
+
+INSERT INTO results_interface
+(SELECT * FROM tmp_interface
+WHERE NOT EXISTS (
+    SELECT * FROM results_interface d
+    WHERE d.auction = tmp_interface.auction
+    AND d.period_start = tmp_interface.period_start
+    AND d.external_interface_id = tmp_interface.external_interface_id
+    )
+)
+ORDER BY external_interface_id;
+
+DETACH mra;
+"#,
+            mra = self.mra_duckdb_path,
+            cp = CapabilityYear::containing(month),
+            start = start,
+            end = start.saturating_add(1.month()),
+            yyyymm = month.strftime("%Y%m"),
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ZoneRecord {
+    pub auction: String,
+    pub capability_period: String,
+    pub period_start: Date,
+    pub period_end: Date,
+    pub capacity_zone_id: u32,
+    pub capacity_zone_name: String,
+    pub capacity_zone_type: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub supply_offers_submitted: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub demand_bids_submitted: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub supply_offers_cleared: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub demand_bids_cleared: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub net_capacity_cleared: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub clearing_price: Decimal,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InterfaceRecord {
+    pub auction: String,
+    pub capability_period: String,
+    pub period_start: Date,
+    pub period_end: Date,
+    pub external_interface_id: u32,
+    pub external_interface_name: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub supply_offers_submitted: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub demand_bids_submitted: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub supply_offers_cleared: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub demand_bids_cleared: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub net_capacity_cleared: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub clearing_price: Decimal,
+}
+
+fn decimal(v: ValueRef) -> Decimal {
+    match v {
+        ValueRef::Decimal(v) => v,
+        _ => Decimal::MIN,
+    }
+}
+
+fn to_date(days_since_epoch: i32) -> Date {
+    Date::ZERO + (719528 + days_since_epoch).days()
+}
+
+/// The filter as a SQL `AND ...` clause.  The `name_column` is the column
+/// `QueryFilter::name` applies to.
+fn where_clause(query_filter: &QueryFilter, name_column: &str) -> String {
+    let mut out = String::new();
+    if let Some(auction) = &query_filter.auction {
+        out.push_str(&format!(
+            "
+    AND auction = '{}'",
+            auction
+        ));
+    }
+    if let Some(auction_in) = &query_filter.auction_in {
+        out.push_str(&format!(
+            "
+    AND auction IN ('{}')",
+            auction_in.join("','")
+        ));
+    }
+    if let Some(capability_period) = &query_filter.capability_period {
+        out.push_str(&format!(
+            "
+    AND capability_period = '{}'",
+            capability_period
+        ));
+    }
+    if let Some(period_start_gte) = &query_filter.period_start_gte {
+        out.push_str(&format!(
+            "
+    AND period_start >= '{}'",
+            period_start_gte
+        ));
+    }
+    if let Some(period_start_lte) = &query_filter.period_start_lte {
+        out.push_str(&format!(
+            "
+    AND period_start <= '{}'",
+            period_start_lte
+        ));
+    }
+    if let Some(name) = &query_filter.name {
+        out.push_str(&format!(
+            "
+    AND {} = '{}'",
+            name_column, name
+        ));
+    }
+    out
+}
+
+fn push_limit(query: &mut String, limit: Option<usize>) {
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+}
+
+/// Get the clearing results by capacity zone.  `QueryFilter::name` filters
+/// on the capacity zone name.
+pub fn get_zone_results(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<ZoneRecord>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    auction,
+    capability_period,
+    period_start,
+    period_end,
+    capacity_zone_id,
+    capacity_zone_name,
+    capacity_zone_type,
+    supply_offers_submitted,
+    demand_bids_submitted,
+    supply_offers_cleared,
+    demand_bids_cleared,
+    net_capacity_cleared,
+    clearing_price
+FROM results_zone WHERE 1=1"#,
+    );
+    query.push_str(&where_clause(query_filter, "capacity_zone_name"));
+    query.push_str(
+        "
+ORDER BY period_start, auction, capacity_zone_id",
+    );
+    push_limit(&mut query, limit);
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ZoneRecord {
+            auction: row.get::<usize, String>(0)?,
+            capability_period: row.get::<usize, String>(1)?,
+            period_start: to_date(row.get::<usize, i32>(2)?),
+            period_end: to_date(row.get::<usize, i32>(3)?),
+            capacity_zone_id: row.get::<usize, u32>(4)?,
+            capacity_zone_name: row.get::<usize, String>(5)?,
+            capacity_zone_type: row.get::<usize, String>(6)?,
+            supply_offers_submitted: decimal(row.get_ref_unwrap(7)),
+            demand_bids_submitted: decimal(row.get_ref_unwrap(8)),
+            supply_offers_cleared: decimal(row.get_ref_unwrap(9)),
+            demand_bids_cleared: decimal(row.get_ref_unwrap(10)),
+            net_capacity_cleared: decimal(row.get_ref_unwrap(11)),
+            clearing_price: decimal(row.get_ref_unwrap(12)),
+        })
+    })?;
+    let results: Vec<ZoneRecord> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+/// Get the clearing results by external interface.  `QueryFilter::name`
+/// filters on the external interface name.
+pub fn get_interface_results(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<InterfaceRecord>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    auction,
+    capability_period,
+    period_start,
+    period_end,
+    external_interface_id,
+    external_interface_name,
+    supply_offers_submitted,
+    demand_bids_submitted,
+    supply_offers_cleared,
+    demand_bids_cleared,
+    net_capacity_cleared,
+    clearing_price
+FROM results_interface WHERE 1=1"#,
+    );
+    query.push_str(&where_clause(query_filter, "external_interface_name"));
+    query.push_str(
+        "
+ORDER BY period_start, auction, external_interface_id",
+    );
+    push_limit(&mut query, limit);
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(InterfaceRecord {
+            auction: row.get::<usize, String>(0)?,
+            capability_period: row.get::<usize, String>(1)?,
+            period_start: to_date(row.get::<usize, i32>(2)?),
+            period_end: to_date(row.get::<usize, i32>(3)?),
+            external_interface_id: row.get::<usize, u32>(4)?,
+            external_interface_name: row.get::<usize, String>(5)?,
+            supply_offers_submitted: decimal(row.get_ref_unwrap(6)),
+            demand_bids_submitted: decimal(row.get_ref_unwrap(7)),
+            supply_offers_cleared: decimal(row.get_ref_unwrap(8)),
+            demand_bids_cleared: decimal(row.get_ref_unwrap(9)),
+            net_capacity_cleared: decimal(row.get_ref_unwrap(10)),
+            clearing_price: decimal(row.get_ref_unwrap(11)),
+        })
+    })?;
+    let results: Vec<InterfaceRecord> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    /// One of FCA, ARA1, ARA2, ARA3, MRA
+    pub auction: Option<String>,
+    pub auction_in: Option<Vec<String>>,
+    /// A capability period like "2025-26"
+    pub capability_period: Option<String>,
+    pub period_start_gte: Option<Date>,
+    pub period_start_lte: Option<Date>,
+    /// The capacity zone or the external interface name
+    pub name: Option<String>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.auction {
+            params.insert("auction", value.to_string());
+        }
+        if let Some(value) = &self.auction_in {
+            params.insert("auction_in", value.join(","));
+        }
+        if let Some(value) = &self.capability_period {
+            params.insert("capability_period", value.to_string());
+        }
+        if let Some(value) = &self.period_start_gte {
+            params.insert("period_start_gte", value.to_string());
+        }
+        if let Some(value) = &self.period_start_lte {
+            params.insert("period_start_lte", value.to_string());
+        }
+        if let Some(value) = &self.name {
+            params.insert("name", value.to_string());
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn auction<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.auction = Some(value.into());
+        self
+    }
+
+    pub fn auction_in(mut self, values_in: Vec<String>) -> Self {
+        self.inner.auction_in = Some(values_in);
+        self
+    }
+
+    pub fn capability_year(mut self, value: &CapabilityYear) -> Self {
+        self.inner.capability_period = Some(value.to_string());
+        self
+    }
+
+    pub fn period_start_gte(mut self, value: Date) -> Self {
+        self.inner.period_start_gte = Some(value);
+        self
+    }
+
+    pub fn period_start_lte(mut self, value: Date) -> Self {
+        self.inner.period_start_lte = Some(value);
+        self
+    }
+
+    pub fn name<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.name = Some(value.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::prod_db::ProdDb, interval::month::month};
+    use duckdb::Connection;
+    use jiff::civil::date;
+    use rust_decimal_macros::dec;
+    use std::error::Error;
+    use std::path::Path;
+
+    #[test]
+    fn test_update_sql() -> Result<(), Box<dyn Error>> {
+        // the MRA results tables, as read by api::isone::capacity::monthly_capacity_results
+        let dir =
+            std::env::temp_dir().join(format!("bust_capacity_results_{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let mra_path = dir.join("mra.duckdb");
+        let _ = std::fs::remove_file(&mra_path);
+        Connection::open(&mra_path)?.execute_batch(
+            r"
+CREATE TABLE results_zone (
+    month UINTEGER,
+    capacityZoneId UINTEGER,
+    capacityZoneType ENUM('ROP', 'Export', 'Import'),
+    capacityZoneName VARCHAR,
+    supplyOffersSubmitted FLOAT,
+    demandBidsSubmitted FLOAT,
+    supplyOffersCleared FLOAT,
+    demandBidsCleared FLOAT,
+    netCapacityCleared FLOAT,
+    clearingPrice FLOAT,
+);
+INSERT INTO results_zone VALUES
+    (202502, 8500, 'ROP', 'Rest-of-Pool', 1100, 900, 400, 350, 50, 1.1),
+    (202503, 8500, 'ROP', 'Rest-of-Pool', 1200.5, 950.25, 410, 380.5, 29.5, 1.25),
+    (202503, 8505, 'Export', 'Northern New England', 300, 100, 120, 80, 40, 1.25);
+CREATE TABLE results_interface (
+    month UINTEGER,
+    externalInterfaceId UINTEGER,
+    externalInterfaceName VARCHAR,
+    supplyOffersSubmitted FLOAT,
+    demandBidsSubmitted FLOAT,
+    supplyOffersCleared FLOAT,
+    demandBidsCleared FLOAT,
+    netCapacityCleared FLOAT,
+    clearingPrice FLOAT,
+);
+INSERT INTO results_interface VALUES
+    (202503, 8503, 'New York AC Ties', 150, 0, 100, 0, 100, 1.25);
+",
+        )?;
+        let archive = IsoneCapacityAuctionResultsArchive {
+            mra_duckdb_path: mra_path.to_str().unwrap().to_string(),
+            duckdb_path: dir.join("results.duckdb").to_str().unwrap().to_string(),
+        };
+        // inserting twice doesn't duplicate the rows
+        for _ in 0..2 {
+            Connection::open(&archive.duckdb_path)?
+                .execute_batch(&archive.update_sql(&month(2025, 3)))?;
+        }
+        let conn = Connection::open(&archive.duckdb_path)?;
+
+        let filter = QueryFilterBuilder::new().auction("MRA").build();
+        let zones = get_zone_results(&conn, &filter, None)?;
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones[0].capability_period, "2024-25");
+        assert_eq!(zones[0].period_start, date(2025, 3, 1));
+        assert_eq!(zones[0].period_end, date(2025, 4, 1));
+        assert_eq!(zones[0].capacity_zone_type, "ROP");
+        assert_eq!(zones[0].net_capacity_cleared, dec!(29.5));
+        assert_eq!(zones[1].capacity_zone_type, "Export");
+        let interfaces = get_interface_results(&conn, &filter, None)?;
+        assert_eq!(interfaces.len(), 1);
+        assert_eq!(interfaces[0].external_interface_name, "New York AC Ties");
+        assert_eq!(interfaces[0].clearing_price, dec!(1.25));
+
+        drop(conn);
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE results_zone (
+    auction VARCHAR NOT NULL,
+    capability_period VARCHAR NOT NULL,
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    capacity_zone_id UINTEGER NOT NULL,
+    capacity_zone_name VARCHAR NOT NULL,
+    capacity_zone_type VARCHAR NOT NULL,
+    supply_offers_submitted DECIMAL(12,3) NOT NULL,
+    demand_bids_submitted DECIMAL(12,3) NOT NULL,
+    supply_offers_cleared DECIMAL(12,3) NOT NULL,
+    demand_bids_cleared DECIMAL(12,3) NOT NULL,
+    net_capacity_cleared DECIMAL(12,3) NOT NULL,
+    clearing_price DECIMAL(9,3) NOT NULL,
+);
+INSERT INTO results_zone VALUES
+    ('MRA', '2024-25', '2025-03-01', '2025-04-01', 8500, 'Rest-of-Pool', 'ROP', 1200.5, 950.25, 410, 380.5, 29.5, 1.250),
+    ('MRA', '2024-25', '2025-04-01', '2025-05-01', 8500, 'Rest-of-Pool', 'ROP', 1100, 900, 400, 350, 50, 1.100),
+    ('MRA', '2025-26', '2025-06-01', '2025-07-01', 8500, 'Rest-of-Pool', 'ROP', 1000, 800, 300, 250, 50, 0.900);
+",
+        )?;
+        let filter = QueryFilterBuilder::new()
+            .auction("MRA")
+            .capability_year(&CapabilityYear::containing(&month(2025, 3)))
+            .period_start_lte(date(2025, 3, 1))
+            .build();
+        let xs = get_zone_results(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].period_start, date(2025, 3, 1));
+        assert_eq!(xs[0].net_capacity_cleared, dec!(29.5));
+        assert_eq!(xs[0].clearing_price, dec!(1.25));
+
+        let filter = QueryFilterBuilder::new().name("Rest-of-Pool").build();
+        let xs = get_zone_results(&conn, &filter, Some(2))?;
+        assert_eq!(xs.len(), 2);
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn update_db() -> Result<(), Box<dyn Error>> {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+        dotenvy::from_path(Path::new(".env/test.env")).unwrap();
+        let archive = ProdDb::isone_capacity_auction_results();
+        for month in month(2025, 6).up_to(month(2025, 9))? {
+            archive.update_duckdb(&month)?;
+        }
+        Ok(())
+    }
+}
@@ -8,11 +8,13 @@ use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::path::Path;
 use std::process::Command;
+use std::sync::Arc;
 
+use crate::db::data_quality::{FileGrid, Frequency, QualitySpec, TableGrid, ValueRange};
+use crate::db::revisions::RevisionSpec;
 use crate::interval::month::Month;
 use crate::utils::serde_helpers::*;
 
-
 #[derive(Clone)]
 pub struct IsoneDaLmpArchive {
     pub base_dir: String,
@@ -40,30 +42,7 @@ impl IsoneDaLmpArchive {
         );
 
         let sql = format!(
-            r#"
-CREATE TABLE IF NOT EXISTS da_lmp (
-    hour_beginning TIMESTAMPTZ NOT NULL,
-    ptid UINTEGER NOT NULL,
-    lmp DECIMAL(9,4) NOT NULL,
-    mcc DECIMAL(9,4) NOT NULL,
-    mcl DECIMAL(9,4) NOT NULL,
-);
-
-CREATE TEMPORARY TABLE tmp
-AS
-    SELECT DISTINCT
-        json_extract(aux, '$.BeginDate')::TIMESTAMPTZ AS hour_beginning,
-        json_extract(aux, '$.Location.@LocId')::UINTEGER AS ptid,
-        json_extract(aux, '$.LmpTotal')::DECIMAL(9,4) AS lmp,
-        json_extract(aux, '$.CongestionComponent')::DECIMAL(9,4) AS mcc,
-        json_extract(aux, '$.LossComponent')::DECIMAL(9,4) AS mcl
-    FROM (
-        SELECT unnest(HourlyLmps.HourlyLmp)::JSON as aux
-        FROM read_json('{}/Raw/{}/WW_DALMP_ISO_{}*.json.gz')
-    )
-    ORDER BY hour_beginning, ptid
-;
-
+            r#"{}
 INSERT INTO da_lmp
 (SELECT * FROM tmp 
 WHERE NOT EXISTS (
@@ -74,9 +53,7 @@ WHERE NOT EXISTS (
 )
 ORDER BY hour_beginning, ptid;
 "#,
-            self.base_dir,
-            month.start_date().year(),
-            month.start_date().strftime("%Y%m"),
+            self.tmp_table_sql(month),
         );
         // println!("{}", sql);
 
@@ -99,6 +76,83 @@ ORDER BY hour_beginning, ptid;
         Ok(())
     }
 
+    /// Upload one month to DuckDB, keeping track of revisions.  Rows that changed
+    /// since the last update are replaced in the `da_lmp` table and the new version
+    /// is also added to the `da_lmp_revisions` table, stamped with `inserted_at`.
+    pub fn update_duckdb_with_revisions(
+        &self,
+        month: &Month,
+        inserted_at: &Zoned,
+    ) -> Result<(), Box<dyn Error>> {
+        info!(
+            "inserting daily DALMP hourly price files for month {} with revisions ...",
+            month
+        );
+
+        let sql = format!(
+            "{}{}",
+            self.tmp_table_sql(month),
+            self.revision_spec().merge_sql("tmp", inserted_at)
+        );
+
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(&sql)
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for month {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+
+    /// Keys and values of the `da_lmp` table used for revision tracking.
+    pub fn revision_spec(&self) -> RevisionSpec {
+        revision_spec()
+    }
+
+    /// Create the table if needed and load the month's files into a temporary table `tmp`.
+    fn tmp_table_sql(&self, month: &Month) -> String {
+        format!(
+            r#"
+CREATE TABLE IF NOT EXISTS da_lmp (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    ptid UINTEGER NOT NULL,
+    lmp DECIMAL(9,4) NOT NULL,
+    mcc DECIMAL(9,4) NOT NULL,
+    mcl DECIMAL(9,4) NOT NULL,
+);
+
+CREATE TEMPORARY TABLE tmp
+AS
+    SELECT DISTINCT
+        json_extract(aux, '$.BeginDate')::TIMESTAMPTZ AS hour_beginning,
+        json_extract(aux, '$.Location.@LocId')::UINTEGER AS ptid,
+        json_extract(aux, '$.LmpTotal')::DECIMAL(9,4) AS lmp,
+        json_extract(aux, '$.CongestionComponent')::DECIMAL(9,4) AS mcc,
+        json_extract(aux, '$.LossComponent')::DECIMAL(9,4) AS mcl
+    FROM (
+        SELECT unnest(HourlyLmps.HourlyLmp)::JSON as aux
+        FROM read_json('{}/Raw/{}/WW_DALMP_ISO_{}*.json.gz')
+    )
+    ORDER BY hour_beginning, ptid
+;
+
+"#,
+            self.base_dir,
+            month.start_date().year(),
+            month.start_date().strftime("%Y%m"),
+        )
+    }
+
     /// Data is usually published before 13:30 every day
     pub fn download_file(&self, date: Date) -> Result<(), Box<dyn Error>> {
         let yyyymmdd = date.strftime("%Y%m%d");
@@ -133,6 +187,31 @@ ORDER BY hour_beginning, ptid;
         }
         Ok(())
     }
+
+    /// The expected grid: one row per ptid per hour, 23/24/25 hours per day.
+    pub fn quality_spec(&self) -> QualitySpec {
+        let archive = self.clone();
+        QualitySpec {
+            name: "isone_dalmp".to_string(),
+            duckdb_path: self.duckdb_path.clone(),
+            table: Some(TableGrid {
+                table: "da_lmp".to_string(),
+                time_column: "hour_beginning".to_string(),
+                key_columns: vec!["ptid".to_string()],
+                expected_keys: (4000..=4008).map(|e| e.to_string()).collect(),
+                frequency: Frequency::Hourly,
+                tz: "America/New_York".to_string(),
+                value_ranges: vec![
+                    ValueRange::new("lmp", Some(-500.0), Some(3000.0)),
+                    ValueRange::new("mcc", Some(-3000.0), Some(3000.0)),
+                    ValueRange::new("mcl", Some(-500.0), Some(500.0)),
+                ],
+                condition: None,
+            }),
+            files: Some(FileGrid::Daily(Arc::new(move |day| archive.filename(day)))),
+            skip_days: vec![],
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -151,6 +230,28 @@ pub struct Record {
     pub mcl: Decimal,
 }
 
+/// A row version from the `da_lmp_revisions` table.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RevisionRecord {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    pub ptid: u32,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub lmp: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub mcc: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub mcl: Decimal,
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub inserted_at: Zoned,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RecordMcc {
     #[serde(
@@ -163,11 +264,12 @@ pub struct RecordMcc {
     pub mcc: Decimal,
 }
 
+/// If the filter has an `as_of` timestamp, return the data as known at that time.
 pub fn get_data(
     conn: &Connection,
     query_filter: &QueryFilter,
 ) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
-    let mut query = String::from(
+    let mut query = format!(
         r#"
 SELECT
     hour_beginning,
@@ -175,7 +277,8 @@ SELECT
     lmp,
     mcc,
     mcl
-FROM da_lmp WHERE 1=1"#,
+FROM {} WHERE 1=1"#,
+        source(query_filter)
     );
     if let Some(hour_beginning) = &query_filter.hour_beginning {
         query.push_str(&format!(
@@ -361,17 +464,138 @@ FROM da_lmp WHERE 1=1"#,
     Ok(results)
 }
 
+/// Get all the versions of the rows matching the filter's `hour_beginning` and `ptid`
+/// conditions, ordered by key and insertion time.  If the filter has an `as_of`
+/// timestamp, only versions inserted at or before that time are returned.
+pub fn get_revisions(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+) -> Result<Vec<RevisionRecord>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    hour_beginning,
+    ptid,
+    lmp,
+    mcc,
+    mcl,
+    inserted_at
+FROM da_lmp_revisions WHERE 1=1"#,
+    );
+    if let Some(hour_beginning) = &query_filter.hour_beginning {
+        query.push_str(&format!(
+            "
+    AND hour_beginning = '{}'",
+            hour_beginning.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(hour_beginning_gte) = &query_filter.hour_beginning_gte {
+        query.push_str(&format!(
+            "
+    AND hour_beginning >= '{}'",
+            hour_beginning_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(hour_beginning_lt) = &query_filter.hour_beginning_lt {
+        query.push_str(&format!(
+            "
+    AND hour_beginning < '{}'",
+            hour_beginning_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(ptid) = query_filter.ptid {
+        query.push_str(&format!(
+            "
+    AND ptid = {}",
+            ptid
+        ));
+    }
+    if let Some(ptid_in) = &query_filter.ptid_in {
+        query.push_str(&format!(
+            "
+    AND ptid IN ({})",
+            ptid_in
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        ));
+    }
+    if let Some(as_of) = &query_filter.as_of {
+        query.push_str(&format!(
+            "
+    AND inserted_at <= to_timestamp({})",
+            as_of.timestamp().as_second()
+        ));
+    }
+    query.push_str("\nORDER BY hour_beginning, ptid, inserted_at;");
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        let hour_beginning = Zoned::new(
+            Timestamp::from_microsecond(_micros0).unwrap(),
+            TimeZone::get("America/New_York").unwrap(),
+        );
+        let ptid: u32 = row.get::<usize, u32>(1)?;
+        let lmp: Decimal = match row.get_ref_unwrap(2) {
+            duckdb::types::ValueRef::Decimal(v) => v,
+            _ => Decimal::MIN,
+        };
+        let mcc: Decimal = match row.get_ref_unwrap(3) {
+            duckdb::types::ValueRef::Decimal(v) => v,
+            _ => Decimal::MIN,
+        };
+        let mcl: Decimal = match row.get_ref_unwrap(4) {
+            duckdb::types::ValueRef::Decimal(v) => v,
+            _ => Decimal::MIN,
+        };
+        let _micros5: i64 = row.get::<usize, i64>(5)?;
+        let inserted_at = Zoned::new(
+            Timestamp::from_microsecond(_micros5).unwrap(),
+            TimeZone::get("America/New_York").unwrap(),
+        );
+        Ok(RevisionRecord {
+            hour_beginning,
+            ptid,
+            lmp,
+            mcc,
+            mcl,
+            inserted_at,
+        })
+    })?;
+    let results: Vec<RevisionRecord> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+fn revision_spec() -> RevisionSpec {
+    RevisionSpec::new(
+        "da_lmp",
+        &["hour_beginning", "ptid"],
+        &["lmp", "mcc", "mcl"],
+    )
+}
+
+/// The table to query, either `da_lmp` or the revisions as of a given time.
+fn source(query_filter: &QueryFilter) -> String {
+    match &query_filter.as_of {
+        Some(as_of) => format!("{} AS da_lmp", revision_spec().latest_sql(Some(as_of))),
+        None => "da_lmp".to_string(),
+    }
+}
+
 pub fn get_data_mcc(
     conn: &Connection,
     query_filter: &QueryFilter,
 ) -> Result<Vec<RecordMcc>, Box<dyn std::error::Error>> {
-    let mut query = String::from(
+    let mut query = format!(
         r#"
 SELECT
     hour_beginning,
     ptid,
     mcc,
-FROM da_lmp WHERE 1=1"#,
+FROM {} WHERE 1=1"#,
+        source(query_filter)
     );
     if let Some(hour_beginning) = &query_filter.hour_beginning {
         query.push_str(&format!(
@@ -458,6 +682,8 @@ pub struct QueryFilter {
     pub mcl_in: Option<Vec<Decimal>>,
     pub mcl_gte: Option<Decimal>,
     pub mcl_lte: Option<Decimal>,
+    /// Return the data as known at this time, from the revisions table
+    pub as_of: Option<Zoned>,
 }
 
 #[derive(Default)]
@@ -570,6 +796,11 @@ impl QueryFilterBuilder {
         self.inner.mcl_lte = Some(value);
         self
     }
+
+    pub fn as_of(mut self, value: Zoned) -> Self {
+        self.inner.as_of = Some(value);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -1,11 +1,18 @@
+use duckdb::Connection;
 use jiff::civil::Date;
-use jiff::Zoned;
+use jiff::{tz::TimeZone, SignedDuration, Timestamp, Zoned};
 use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::path::Path;
 use std::process::Command;
+use url::form_urlencoded;
 
+use crate::elec::aggregation::{self, hour_beginning, Observation};
 use crate::interval::month::Month;
+use crate::time::bucket::{Bucket, BucketLike};
+use crate::utils::serde_helpers::{deserialize_zoned_assume_ny, serialize_zoned_as_offset};
 
 #[derive(Clone)]
 pub struct IsoneFuelMixArchive {
@@ -128,15 +135,485 @@ ORDER BY timestamp, fuel_category;
     }
 }
 
+/// One 5-minute observation for a fuel category
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub timestamp: Zoned,
+    pub fuel_category: String,
+    pub fuel_category_rollup: String,
+    pub mw: i32,
+    pub marginal_flag: Option<bool>,
+}
+
+pub fn get_data(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    timestamp,
+    fuel_category::VARCHAR,
+    fuel_category_rollup::VARCHAR,
+    mw,
+    marginal_flag
+FROM fuel_mix WHERE 1=1"#,
+    );
+    if let Some(timestamp) = &query_filter.timestamp {
+        query.push_str(&format!(
+            "
+    AND timestamp = '{}'",
+            timestamp.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(timestamp_gte) = &query_filter.timestamp_gte {
+        query.push_str(&format!(
+            "
+    AND timestamp >= '{}'",
+            timestamp_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(timestamp_lt) = &query_filter.timestamp_lt {
+        query.push_str(&format!(
+            "
+    AND timestamp < '{}'",
+            timestamp_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(fuel_category) = &query_filter.fuel_category {
+        query.push_str(&format!(
+            "
+    AND fuel_category = '{}'",
+            fuel_category
+        ));
+    }
+    if let Some(fuel_category_in) = &query_filter.fuel_category_in {
+        query.push_str(&format!(
+            "
+    AND fuel_category IN ('{}')",
+            fuel_category_in.join("','")
+        ));
+    }
+    if let Some(fuel_category_rollup) = &query_filter.fuel_category_rollup {
+        query.push_str(&format!(
+            "
+    AND fuel_category_rollup = '{}'",
+            fuel_category_rollup
+        ));
+    }
+    if let Some(marginal_flag) = &query_filter.marginal_flag {
+        query.push_str(&format!(
+            "
+    AND marginal_flag = {}",
+            marginal_flag
+        ));
+    }
+    query.push_str("\nORDER BY timestamp, fuel_category");
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let tz = TimeZone::get("America/New_York")?;
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        Ok(Record {
+            timestamp: Zoned::new(Timestamp::from_microsecond(_micros0).unwrap(), tz.clone()),
+            fuel_category: row.get::<usize, String>(1)?,
+            fuel_category_rollup: row.get::<usize, String>(2)?,
+            mw: row.get::<usize, i32>(3)?,
+            marginal_flag: row.get::<usize, Option<bool>>(4)?,
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+pub use crate::elec::aggregation::Resolution;
+
+/// The fuel mix is reported every 5 minutes
+const INTERVAL_LENGTH: SignedDuration = SignedDuration::from_mins(5);
+
+/// Generation of a fuel (category or rollup) over an hour or a day.  The
+/// `interval_beginning` is the start of the hour or of the day.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Generation {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub interval_beginning: Zoned,
+    pub fuel: String,
+    pub average_mw: f64,
+    pub mwh: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BucketGeneration {
+    pub bucket: Bucket,
+    pub fuel: String,
+    pub average_mw: f64,
+    pub mwh: f64,
+}
+
+/// Fraction of the 5-minute intervals in which a fuel category was flagged
+/// as marginal.  Several fuels can be marginal at the same time, so the
+/// frequencies of an hour can add up to more than 1.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MarginalFrequency {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    pub fuel_category: String,
+    pub frequency: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BucketMarginalFrequency {
+    pub bucket: Bucket,
+    pub fuel_category: String,
+    pub frequency: f64,
+}
+
+fn fuel(record: &Record, rollup: bool) -> &str {
+    if rollup {
+        &record.fuel_category_rollup
+    } else {
+        &record.fuel_category
+    }
+}
+
+/// Number of distinct 5-minute timestamps in each hour
+fn intervals_per_hour(records: &[Record]) -> BTreeMap<Timestamp, usize> {
+    let mut counts: BTreeMap<Timestamp, Vec<Timestamp>> = BTreeMap::new();
+    for r in records {
+        let e = counts
+            .entry(hour_beginning(&r.timestamp).timestamp())
+            .or_default();
+        if e.last() != Some(&r.timestamp.timestamp()) {
+            e.push(r.timestamp.timestamp());
+        }
+    }
+    counts.into_iter().map(|(k, v)| (k, v.len())).collect()
+}
+
+fn observations(records: &[Record], rollup: bool) -> Vec<Observation> {
+    records
+        .iter()
+        .map(|r| Observation {
+            interval_beginning: r.timestamp.clone(),
+            series: fuel(r, rollup).to_string(),
+            mw: r.mw as f64,
+        })
+        .collect()
+}
+
+/// Aggregate the 5-minute records by hour or day, by fuel category or by
+/// rollup.  The average MW is calculated over the intervals with data.
+pub fn aggregate(records: &[Record], resolution: Resolution, rollup: bool) -> Vec<Generation> {
+    aggregation::aggregate(
+        &observations(records, rollup),
+        resolution,
+        INTERVAL_LENGTH,
+    )
+    .into_iter()
+    .map(|e| Generation {
+        interval_beginning: e.period,
+        fuel: e.series,
+        average_mw: e.average_mw,
+        mwh: e.mwh,
+    })
+    .collect()
+}
+
+/// Aggregate the 5-minute records by bucket, by fuel category or by rollup.
+pub fn aggregate_by_bucket(
+    records: &[Record],
+    buckets: &[Bucket],
+    rollup: bool,
+) -> Vec<BucketGeneration> {
+    aggregation::aggregate_by_bucket(&observations(records, rollup), buckets, INTERVAL_LENGTH)
+        .into_iter()
+        .map(|e| BucketGeneration {
+            bucket: e.period,
+            fuel: e.series,
+            average_mw: e.average_mw,
+            mwh: e.mwh,
+        })
+        .collect()
+}
+
+/// Hourly frequency with which each fuel category was marginal.  Fuel
+/// categories that were not marginal in an hour are omitted.
+pub fn marginal_frequency(records: &[Record]) -> Vec<MarginalFrequency> {
+    let hours = intervals_per_hour(records);
+    let mut counts: BTreeMap<(Timestamp, String), (Zoned, usize)> = BTreeMap::new();
+    for r in records.iter().filter(|r| r.marginal_flag == Some(true)) {
+        let start = hour_beginning(&r.timestamp);
+        counts
+            .entry((start.timestamp(), r.fuel_category.clone()))
+            .or_insert((start, 0))
+            .1 += 1;
+    }
+    counts
+        .into_iter()
+        .map(|((ts, fuel_category), (hour_beginning, n))| MarginalFrequency {
+            hour_beginning,
+            fuel_category,
+            frequency: n as f64 / hours[&ts] as f64,
+        })
+        .collect()
+}
+
+/// Frequency with which each fuel category was marginal in the intervals of
+/// a bucket.
+pub fn marginal_frequency_by_bucket(
+    records: &[Record],
+    buckets: &[Bucket],
+) -> Vec<BucketMarginalFrequency> {
+    let hours = intervals_per_hour(records);
+    let mut out = Vec::new();
+    for bucket in buckets {
+        let intervals: usize = hours
+            .iter()
+            .filter(|(ts, _)| bucket.contains(&ts.to_zoned(bucket.timezone())))
+            .map(|(_, n)| n)
+            .sum();
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for r in records
+            .iter()
+            .filter(|r| r.marginal_flag == Some(true) && bucket.contains(&r.timestamp))
+        {
+            *counts.entry(r.fuel_category.clone()).or_default() += 1;
+        }
+        out.extend(
+            counts
+                .into_iter()
+                .map(|(fuel_category, n)| BucketMarginalFrequency {
+                    bucket: *bucket,
+                    fuel_category,
+                    frequency: n as f64 / intervals as f64,
+                }),
+        );
+    }
+    out
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub timestamp: Option<Zoned>,
+    pub timestamp_gte: Option<Zoned>,
+    pub timestamp_lt: Option<Zoned>,
+    pub fuel_category: Option<String>,
+    pub fuel_category_in: Option<Vec<String>>,
+    pub fuel_category_rollup: Option<String>,
+    pub marginal_flag: Option<bool>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.timestamp {
+            params.insert("timestamp", value.to_string());
+        }
+        if let Some(value) = &self.timestamp_gte {
+            params.insert("timestamp_gte", value.to_string());
+        }
+        if let Some(value) = &self.timestamp_lt {
+            params.insert("timestamp_lt", value.to_string());
+        }
+        if let Some(value) = &self.fuel_category {
+            params.insert("fuel_category", value.to_string());
+        }
+        if let Some(value) = &self.fuel_category_in {
+            params.insert("fuel_category_in", value.join(","));
+        }
+        if let Some(value) = &self.fuel_category_rollup {
+            params.insert("fuel_category_rollup", value.to_string());
+        }
+        if let Some(value) = &self.marginal_flag {
+            params.insert("marginal_flag", value.to_string());
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn timestamp(mut self, value: Zoned) -> Self {
+        self.inner.timestamp = Some(value);
+        self
+    }
+
+    pub fn timestamp_gte(mut self, value: Zoned) -> Self {
+        self.inner.timestamp_gte = Some(value);
+        self
+    }
+
+    pub fn timestamp_lt(mut self, value: Zoned) -> Self {
+        self.inner.timestamp_lt = Some(value);
+        self
+    }
+
+    pub fn fuel_category<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.fuel_category = Some(value.into());
+        self
+    }
+
+    pub fn fuel_category_in<I, S>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.inner.fuel_category_in = Some(values.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn fuel_category_rollup<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.fuel_category_rollup = Some(value.into());
+        self
+    }
+
+    pub fn marginal_flag(mut self, value: bool) -> Self {
+        self.inner.marginal_flag = Some(value);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
+    use duckdb::Connection;
     use jiff::{Zoned, civil::date};
     use log::info;
     use std::{error::Error, path::Path};
 
+    use super::*;
     use crate::{db::prod_db::ProdDb, interval::month::month};
 
+    fn test_conn() -> Result<Connection, Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE fuel_mix (
+    timestamp TIMESTAMPTZ,
+    mw INT32,
+    fuel_category_rollup ENUM('Batteries', 'Coal', 'Hydro', 'Natural Gas', 'Nuclear', 'Oil',  'Other', 'Renewables'),
+    fuel_category ENUM('Batteries', 'Coal', 'Hydro', 'Landfill Gas', 'Natural Gas', 'Nuclear', 'Oil', 'Other', 'Refuse', 'Solar', 'Wind', 'Wood'),
+    marginal_flag BOOL
+);
+INSERT INTO fuel_mix VALUES
+    ('2025-07-01 14:00:00-04:00', 6000, 'Natural Gas', 'Natural Gas', TRUE),
+    ('2025-07-01 14:00:00-04:00', 1200, 'Renewables', 'Solar', FALSE),
+    ('2025-07-01 14:00:00-04:00', 300, 'Renewables', 'Wind', FALSE),
+    ('2025-07-01 14:05:00-04:00', 6300, 'Natural Gas', 'Natural Gas', FALSE),
+    ('2025-07-01 14:05:00-04:00', 1100, 'Renewables', 'Solar', FALSE),
+    ('2025-07-01 14:05:00-04:00', 500, 'Renewables', 'Wind', TRUE),
+    ('2025-07-01 23:00:00-04:00', 5000, 'Natural Gas', 'Natural Gas', TRUE),
+    ('2025-07-01 23:00:00-04:00', 0, 'Renewables', 'Solar', FALSE),
+    ('2025-07-01 23:00:00-04:00', 800, 'Renewables', 'Wind', FALSE);
+",
+        )?;
+        Ok(conn)
+    }
+
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = test_conn()?;
+        let filter = QueryFilterBuilder::new()
+            .timestamp_gte("2025-07-01 14:00:00-04:00[America/New_York]".parse()?)
+            .timestamp_lt("2025-07-01 15:00:00-04:00[America/New_York]".parse()?)
+            .fuel_category_in(vec!["Solar", "Wind"])
+            .build();
+        let xs = get_data(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 4);
+        assert_eq!(xs[0].fuel_category, "Solar");
+        assert_eq!(xs[0].fuel_category_rollup, "Renewables");
+        assert_eq!(xs[0].marginal_flag, Some(false));
+        let filter = QueryFilterBuilder::new().marginal_flag(true).build();
+        assert_eq!(get_data(&conn, &filter, None)?.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregate() -> Result<(), Box<dyn Error>> {
+        let conn = test_conn()?;
+        let xs = get_data(&conn, &QueryFilter::default(), None)?;
+
+        let hourly = aggregate(&xs, Resolution::Hour, true);
+        let x = hourly
+            .iter()
+            .find(|e| e.fuel == "Renewables" && e.interval_beginning.hour() == 14)
+            .unwrap();
+        assert_eq!(x.average_mw, 1550.0);
+        assert_eq!(x.mwh, 3100.0 / 12.0);
+
+        let daily = aggregate(&xs, Resolution::Day, false);
+        assert_eq!(daily.len(), 3);
+        let x = daily.iter().find(|e| e.fuel == "Natural Gas").unwrap();
+        assert_eq!(x.interval_beginning.date(), date(2025, 7, 1));
+        assert_eq!(x.average_mw, 17300.0 / 3.0);
+
+        let buckets = aggregate_by_bucket(&xs, &[Bucket::B5x16, Bucket::B7x8], false);
+        let x = buckets
+            .iter()
+            .find(|e| e.bucket == Bucket::B7x8 && e.fuel == "Wind")
+            .unwrap();
+        assert_eq!(x.average_mw, 800.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_marginal_frequency() -> Result<(), Box<dyn Error>> {
+        let conn = test_conn()?;
+        let xs = get_data(&conn, &QueryFilter::default(), None)?;
+        let hourly = marginal_frequency(&xs);
+        assert_eq!(hourly.len(), 3);
+        assert_eq!(hourly[0].fuel_category, "Natural Gas");
+        assert_eq!(hourly[0].frequency, 0.5);
+        assert_eq!(hourly[2].frequency, 1.0);
+
+        let buckets = marginal_frequency_by_bucket(&xs, &[Bucket::Atc]);
+        let x = buckets
+            .iter()
+            .find(|e| e.fuel_category == "Natural Gas")
+            .unwrap();
+        assert_eq!(x.frequency, 2.0 / 3.0);
+        Ok(())
+    }
+
     #[ignore]
     #[test]
     fn update_db() -> Result<(), Box<dyn Error>> {
@@ -2,6 +2,8 @@ use jiff::{civil::Date, Zoned};
 use std::cmp::Ordering::*;
 use std::error::Error;
 
+use crate::utils::downloader::{downloader, AuthProfile};
+
 /// Check if the Day-Ahead LMP file (DALMP) for the given date has been published 
 /// on the public isone website.
 pub fn is_dalmp_published(date: Date) -> Result<bool, Box<dyn Error>> {
@@ -14,7 +16,7 @@ pub fn is_dalmp_published(date: Date) -> Result<bool, Box<dyn Error>> {
                 return Ok(false);
             }
             let url = "https://www.iso-ne.com/isoexpress/web/reports/pricing/-/tree/lmps-da-hourly";
-            let content = reqwest::blocking::get(url)?.text()?;
+            let content = downloader().get(url, AuthProfile::Anonymous, &[])?.text()?;
             let tag = format!("WW_DALMP_ISO_{}.csv", date.strftime("%Y%m%d"));
             if content.contains(&tag) {
                 return Ok(true);
@@ -34,7 +36,7 @@ pub fn is_rtlmp_published(date: Date) -> Result<bool, Box<dyn Error>> {
         Less => {
             let url =
                 "https://www.iso-ne.com/isoexpress/web/reports/pricing/-/tree/lmps-rt-hourly-final";
-            let content = reqwest::blocking::get(url)?.text()?;
+            let content = downloader().get(url, AuthProfile::Anonymous, &[])?.text()?;
             let tag = format!("lmp_rt_final_{}.csv", date.strftime("%Y%m%d"));
             if content.contains(&tag) {
                 return Ok(true);
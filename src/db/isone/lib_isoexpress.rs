@@ -1,19 +1,6 @@
-use std::{
-    env,
-    error::Error,
-    fmt::Display,
-    fs::{self, File},
-    io,
-    path::Path,
-    process::Command,
-    time::Duration,
-};
+use std::{error::Error, fmt::Display, path::Path};
 
-use reqwest::{
-    blocking::Client,
-    header::{ACCEPT, UPGRADE_INSECURE_REQUESTS, USER_AGENT},
-    StatusCode,
-};
+use crate::utils::downloader::{downloader, AuthProfile};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReportError {
@@ -31,6 +18,9 @@ impl Display for ReportError {
     }
 }
 
+/// Download a file using the shared downloader.  If `require_auth` is true, the
+/// ISO-NE webservices credentials are used, otherwise the source is inferred
+/// from the url.
 pub fn download_file(
     url: String,
     require_auth: bool,
@@ -38,44 +28,12 @@ pub fn download_file(
     file_path: &Path,
     gzip: bool,
 ) -> Result<(), Box<dyn Error>> {
-    let client = Client::new();
-    let mut builder = client
-        .get(url)
-        .timeout(Duration::from_secs(60)) // set to 60 seconds, adjust as needed
-        .header(USER_AGENT, "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36")
-        .header(UPGRADE_INSECURE_REQUESTS, "1");
-    if let Some(accept_header) = accept_header {
-        builder = builder.header(ACCEPT, accept_header);
-    }
-    if require_auth {
-        let user_name = env::var("ISONE_WS_USER").unwrap();
-        let password = env::var("ISONE_WS_PASSWORD").unwrap();
-        builder = builder.basic_auth(user_name, Some(password));
-    }
-    let response = builder.send();
-    // println!("{:?}", response);
-    if response.as_ref().unwrap().status() != StatusCode::OK {
-        return Err(Box::from(format!("Download failed! {:?}", response)));
-    }
-    let body = response.unwrap().text().expect("invalid body");
-    // println!("{}", body);
-
-    let dir = file_path.parent().unwrap();
-    let _ = fs::create_dir_all(dir);
-    let mut out = File::create(file_path).expect("failed to create file");
-    io::copy(&mut body.as_bytes(), &mut out).expect("failed to copy content");
-
-    // gzip it
-    if gzip {
-        Command::new("gzip")
-            .args(["-f", file_path.to_str().unwrap()])
-            .current_dir(dir)
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
-    }
-
+    let profile = if require_auth {
+        AuthProfile::IsoneWebservices
+    } else {
+        AuthProfile::from_url(&url)
+    };
+    downloader().download_file(&url, profile, accept_header.as_deref(), file_path, gzip)?;
     Ok(())
 }
 
@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+
+use duckdb::Connection;
+use jiff::civil::Date;
+use jiff::{tz::TimeZone, Timestamp, Zoned};
+use log::{error, info};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::interval::month::Month;
+use crate::utils::serde_helpers::*;
+
+/// ISONE three-day hourly load forecast by reliability region (load zone).
+/// The ISO publishes several forecasts a day, each identified by its
+/// `creation_date`.
+#[derive(Clone)]
+pub struct IsoneLoadForecastArchive {
+    pub base_dir: String,
+    pub duckdb_path: String,
+}
+
+impl IsoneLoadForecastArchive {
+    /// Return the json filename for the day.  Does not check if the file exists.
+    pub fn filename(&self, date: &Date) -> String {
+        self.base_dir.to_owned()
+            + "/Raw/"
+            + &date.year().to_string()
+            + "/rr_load_forecast_"
+            + &date.strftime("%Y%m%d").to_string()
+            + ".json"
+    }
+
+    /// The file for a day has all the forecasts issued on that day.
+    /// See https://webservices.iso-ne.com/api/v1.1/reliabilityregionloadforecast/day/20250501
+    pub fn download_file(&self, date: Date) -> Result<(), Box<dyn Error>> {
+        super::lib_isoexpress::download_file(
+            format!(
+                "https://webservices.iso-ne.com/api/v1.1/reliabilityregionloadforecast/day/{}",
+                date.strftime("%Y%m%d")
+            ),
+            true,
+            Some("application/json".to_string()),
+            Path::new(&self.filename(&date)),
+            true,
+        )
+    }
+
+    /// Look for missing days.  Does not download current day.
+    pub fn download_missing_days(&self, month: Month) -> Result<(), Box<dyn Error>> {
+        let last = Zoned::now().date();
+        for day in month.days() {
+            if day >= last {
+                continue;
+            }
+            let fname = format!("{}.gz", self.filename(&day));
+            if !Path::new(&fname).exists() {
+                info!("Working on {}", day);
+                self.download_file(day)?;
+                info!("  downloaded file for {}", day);
+            }
+        }
+        Ok(())
+    }
+
+    /// Upload one month to DuckDB.  Forecasts already in the table are skipped.
+    pub fn update_duckdb(&self, month: &Month) -> Result<(), Box<dyn Error>> {
+        info!(
+            "inserting ISONE load forecast files for month {} ...",
+            month
+        );
+        let sql = format!(
+            r#"
+CREATE TABLE IF NOT EXISTS load_forecast (
+    creation_date TIMESTAMPTZ NOT NULL,
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    zone VARCHAR NOT NULL,
+    load DECIMAL(9,2) NOT NULL,
+);
+
+CREATE TEMPORARY TABLE tmp AS
+    SELECT
+        make_timestamptz(epoch_us(CreationDate)) AS creation_date,
+        make_timestamptz(epoch_us(BeginDate)) AS hour_beginning,
+        ReliabilityRegion::VARCHAR AS zone,
+        LoadMw::DECIMAL(9,2) AS load
+    FROM (
+        SELECT unnest(ReliabilityRegionLoadForecasts.ReliabilityRegionLoadForecast, recursive := true)
+        FROM read_json('{}/Raw/{}/rr_load_forecast_{}*.json.gz')
+    )
+ORDER BY creation_date, hour_beginning, zone;
+
+INSERT INTO load_forecast
+(SELECT * FROM tmp
+WHERE NOT EXISTS (
+    SELECT * FROM load_forecast d
+    WHERE d.creation_date = tmp.creation_date
+    AND d.hour_beginning = tmp.hour_beginning
+    AND d.zone = tmp.zone
+    )
+)
+ORDER BY creation_date, hour_beginning, zone;
+"#,
+            self.base_dir,
+            month.year(),
+            month.strftime("%Y%m"),
+        );
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(&sql)
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for month {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub creation_date: Zoned,
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    pub zone: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub load: Decimal,
+}
+
+pub fn get_data(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    creation_date,
+    hour_beginning,
+    zone,
+    load
+FROM load_forecast WHERE 1=1"#,
+    );
+    if let Some(creation_date_gte) = &query_filter.creation_date_gte {
+        query.push_str(&format!(
+            "
+    AND creation_date >= '{}'",
+            creation_date_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(creation_date_lt) = &query_filter.creation_date_lt {
+        query.push_str(&format!(
+            "
+    AND creation_date < '{}'",
+            creation_date_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(hour_beginning_gte) = &query_filter.hour_beginning_gte {
+        query.push_str(&format!(
+            "
+    AND hour_beginning >= '{}'",
+            hour_beginning_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(hour_beginning_lt) = &query_filter.hour_beginning_lt {
+        query.push_str(&format!(
+            "
+    AND hour_beginning < '{}'",
+            hour_beginning_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(zone) = &query_filter.zone {
+        query.push_str(&format!(
+            "
+    AND zone = '{}'",
+            zone
+        ));
+    }
+    if let Some(zone_in) = &query_filter.zone_in {
+        query.push_str(&format!(
+            "
+    AND zone IN ('{}')",
+            zone_in.join("','")
+        ));
+    }
+    query.push_str(
+        "
+ORDER BY creation_date, hour_beginning, zone",
+    );
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        let _micros1: i64 = row.get::<usize, i64>(1)?;
+        let load: Decimal = match row.get_ref_unwrap(3) {
+            duckdb::types::ValueRef::Decimal(v) => v,
+            _ => Decimal::MIN,
+        };
+        Ok(Record {
+            creation_date: Zoned::new(Timestamp::from_microsecond(_micros0).unwrap(), tz.clone()),
+            hour_beginning: Zoned::new(Timestamp::from_microsecond(_micros1).unwrap(), tz.clone()),
+            zone: row.get::<usize, String>(2)?,
+            load,
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub creation_date_gte: Option<Zoned>,
+    pub creation_date_lt: Option<Zoned>,
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    pub zone: Option<String>,
+    pub zone_in: Option<Vec<String>>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.creation_date_gte {
+            params.insert("creation_date_gte", value.to_string());
+        }
+        if let Some(value) = &self.creation_date_lt {
+            params.insert("creation_date_lt", value.to_string());
+        }
+        if let Some(value) = &self.hour_beginning_gte {
+            params.insert("hour_beginning_gte", value.to_string());
+        }
+        if let Some(value) = &self.hour_beginning_lt {
+            params.insert("hour_beginning_lt", value.to_string());
+        }
+        if let Some(value) = &self.zone {
+            params.insert("zone", value.to_string());
+        }
+        if let Some(value) = &self.zone_in {
+            params.insert("zone_in", value.join(","));
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn creation_date_gte(mut self, value: Zoned) -> Self {
+        self.inner.creation_date_gte = Some(value);
+        self
+    }
+
+    pub fn creation_date_lt(mut self, value: Zoned) -> Self {
+        self.inner.creation_date_lt = Some(value);
+        self
+    }
+
+    pub fn hour_beginning_gte(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_gte = Some(value);
+        self
+    }
+
+    pub fn hour_beginning_lt(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_lt = Some(value);
+        self
+    }
+
+    pub fn zone<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.zone = Some(value.into());
+        self
+    }
+
+    pub fn zone_in(mut self, values_in: Vec<String>) -> Self {
+        self.inner.zone_in = Some(values_in);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::prod_db::ProdDb, interval::month::month};
+    use duckdb::Connection;
+    use rust_decimal_macros::dec;
+    use std::error::Error;
+
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE load_forecast (
+    creation_date TIMESTAMPTZ NOT NULL,
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    zone VARCHAR NOT NULL,
+    load DECIMAL(9,2) NOT NULL,
+);
+INSERT INTO load_forecast VALUES
+    ('2025-05-01 09:30:00-04:00', '2025-05-02 17:00:00-04:00', '.Z.MAINE', 1254),
+    ('2025-05-01 09:30:00-04:00', '2025-05-02 17:00:00-04:00', '.Z.NEMASSBOST', 2875),
+    ('2025-05-01 15:30:00-04:00', '2025-05-02 17:00:00-04:00', '.Z.NEMASSBOST', 2901);
+",
+        )?;
+        let filter = QueryFilterBuilder::new()
+            .zone(".Z.NEMASSBOST")
+            .creation_date_gte("2025-05-01 12:00[America/New_York]".parse()?)
+            .build();
+        let xs = get_data(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].load, dec!(2901));
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn update_db() -> Result<(), Box<dyn Error>> {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+        dotenvy::from_path(Path::new(".env/test.env")).unwrap();
+        let archive = ProdDb::isone_load_forecast();
+        let months = month(2025, 1).up_to(month(2025, 5))?;
+        for month in months {
+            archive.download_missing_days(month)?;
+            archive.update_duckdb(&month)?;
+        }
+        Ok(())
+    }
+}
@@ -20,7 +20,10 @@ use std::path::Path;
 use std::process::Command;
 
 use crate::db::isone::lib_isoexpress;
+use crate::interval::month::Month;
 
+/// The ISONE capability (commitment) period, from Jun 1 to May 31.
+#[derive(Clone, Debug, PartialEq)]
 pub struct CapabilityYear(Zoned);
 
 impl CapabilityYear {
@@ -29,6 +32,15 @@ impl CapabilityYear {
         Self(zoned.unwrap())
     }
 
+    /// The capability year the month belongs to.
+    pub fn containing(month: &Month) -> Self {
+        if month.month() >= 6 {
+            Self::with_start_year(month.year())
+        } else {
+            Self::with_start_year(month.year() - 1)
+        }
+    }
+
     pub fn start(&self) -> &Zoned {
         &self.0
     }
@@ -42,6 +54,26 @@ impl CapabilityYear {
     }
 }
 
+impl std::str::FromStr for CapabilityYear {
+    type Err = String;
+    /// Parse a string like "2025-26".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || {
+            format!(
+                "Invalid capability year: {}, expecting a value like 2025-26",
+                s
+            )
+        };
+        let (start, end) = s.split_once('-').ok_or_else(err)?;
+        let year = start.parse::<i16>().map_err(|_| err())?;
+        let end = end.parse::<i16>().map_err(|_| err())?;
+        if (year + 1) % 100 != end {
+            return Err(err());
+        }
+        Ok(Self::with_start_year(year))
+    }
+}
+
 impl fmt::Display for CapabilityYear {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}-{}", self.0.year(), self.end().strftime("%y"))
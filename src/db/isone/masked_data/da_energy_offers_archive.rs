@@ -1,9 +1,16 @@
 use jiff::civil::*;
+use log::{error, info};
 use std::error::Error;
 use std::path::Path;
+use std::process::Command;
 
+use crate::api::isone::_api_isone_core::Market;
 use crate::db::isone::lib_isoexpress;
+use crate::interval::month::Month;
 
+/// Masked DA and RT energy offers.  Both markets share one schema, in tables
+/// `da_offers` and `rt_offers` of the same DuckDB file, with one row for each
+/// offer segment.
 #[derive(Clone)]
 pub struct IsoneDaEnergyOffersArchive {
     pub base_dir: String,
@@ -12,123 +19,223 @@ pub struct IsoneDaEnergyOffersArchive {
 
 impl IsoneDaEnergyOffersArchive {
     /// Return the json filename for the day.  Does not check if the file exists.  
-    pub fn filename(&self, date: &Date) -> String {
+    pub fn filename(&self, date: &Date, market: &Market) -> String {
         self.base_dir.to_owned()
             + "/Raw/"
             + &date.year().to_string()
-            + "/hbdayaheadenergyoffer_"
+            + "/"
+            + report_name(market)
+            + "_"
             + &date.strftime("%Y%m%d").to_string()
             + ".json"
     }
 
-    /// https://webservices.iso-ne.com/api/v1.1/hbdaasenergyoffer/day/20250301
-    pub fn download_file(&self, date: &Date) -> Result<(), Box<dyn Error>> {
+    /// https://webservices.iso-ne.com/api/v1.1/hbdayaheadenergyoffer/day/20250301
+    /// https://webservices.iso-ne.com/api/v1.1/hbrealtimeenergyoffer/day/20250301
+    pub fn download_file(&self, date: &Date, market: &Market) -> Result<(), Box<dyn Error>> {
         let yyyymmdd = date.strftime("%Y%m%d");
         lib_isoexpress::download_file(
             format!(
-                "https://webservices.iso-ne.com/api/v1.1/hbdayaheadenergyoffer/day/{}",
+                "https://webservices.iso-ne.com/api/v1.1/{}/day/{}",
+                report_name(market),
                 yyyymmdd
             ),
             true,
             Some("application/json".to_string()),
-            Path::new(&self.filename(date)),
+            Path::new(&self.filename(date, market)),
             true,
         )
     }
 
-    // Upload one month to DuckDB.
-    // Assumes all json.gz file exists for DA and RT.  Skips the day if it doesn't exist.
-    //     pub fn update_duckdb(&self, month: &Month) -> Result<(), Box<dyn Error>> {
-    //         info!(
-    //             "inserting daily DAAS energy offers files for month {} ...",
-    //             month
-    //         );
-
-    //         let sql = format!(
-    //             r#"
-    // CREATE TABLE IF NOT EXISTS offers (
-    //     hour_beginning TIMESTAMPTZ NOT NULL,
-    //     masked_lead_participant_id INTEGER NOT NULL,
-    //     masked_asset_id INTEGER NOT NULL,
-    //     offer_mw DECIMAL(9,2) NOT NULL,
-    //     tmsr_offer_price DECIMAL(9,2) NOT NULL,
-    //     tmnsr_offer_price DECIMAL(9,2) NOT NULL,
-    //     tmor_offer_price DECIMAL(9,2) NOT NULL,
-    //     eir_offer_price DECIMAL(9,2) NOT NULL,
-    // );
-
-    // CREATE TEMPORARY TABLE tmp
-    // AS
-    //     SELECT *
-    //     FROM (
-    //         SELECT unnest(isone_web_services.offer_publishing.day_ahead_ancillary_services.daas_gen_offer_data, recursive := true)
-    //         FROM read_json('{}/Raw/{}/hbdaasenergyoffer_{}-*.json.gz')
-    //     )
-    //     ORDER BY local_day
-    // ;
-
-    // INSERT INTO offers
-    // (
-    //     SELECT
-    //         local_day::TIMESTAMPTZ as hour_beginning,
-    //         masked_lead_participant_id::INTEGER as masked_lead_participant_id,
-    //         masked_asset_id::INTEGER as masked_asset_id,
-    //         offer_mw::DECIMAL(9,2) as offer_mw,
-    //         tmsr_offer_price::DECIMAL(9,2) as tmsr_offer_price,
-    //         tmnsr_offer_price::DECIMAL(9,2) as tmnsr_offer_price,
-    //         tmor_offer_price::DECIMAL(9,2) as tmor_offer_price,
-    //         eir_offer_price::DECIMAL(9,2) as eir_offer_price
-    //     FROM tmp t
-    // WHERE NOT EXISTS (
-    //         SELECT * FROM offers o
-    //         WHERE
-    //             o.hour_beginning = t.local_day AND
-    //             o.masked_lead_participant_id = t.masked_lead_participant_id AND
-    //             o.masked_asset_id = t.masked_asset_id AND
-    //             o.tmsr_offer_price = t.tmsr_offer_price AND
-    //             o.tmnsr_offer_price = t.tmnsr_offer_price AND
-    //             o.tmor_offer_price = t.tmor_offer_price AND
-    //             o.eir_offer_price = t.eir_offer_price
-    //     )
-    // ) ORDER BY hour_beginning, masked_lead_participant_id, masked_asset_id;
-    // "#,
-    //             self.base_dir,
-    //             month.start_date().year(),
-    //             month
-    //         );
-    //         // println!("{}", sql);
-
-    //         let output = Command::new("duckdb")
-    //             .arg("-c")
-    //             .arg(&sql)
-    //             .arg(&self.duckdb_path)
-    //             .output()
-    //             .expect("Failed to invoke duckdb command");
-
-    //         let stdout = String::from_utf8_lossy(&output.stdout);
-    //         let stderr = String::from_utf8_lossy(&output.stderr);
-    //         if output.status.success() {
-    //             info!("{}", stdout);
-    //             info!("done");
-    //         } else {
-    //             error!("Failed to update duckdb for month {}: {}", month, stderr);
-    //         }
-
-    //         Ok(())
-    //     }
+    /// Upload one month of DA or RT offers to DuckDB.
+    /// Skips the days that don't have a json.gz file.
+    ///
+    pub fn update_duckdb(&self, month: &Month, market: &Market) -> Result<(), Box<dyn Error>> {
+        info!(
+            "inserting daily {} energy offers files for month {} ...",
+            market, month
+        );
+        let table = table_name(market);
+        let root = match market {
+            Market::DA => "HbDayAheadEnergyOffers.HbDayAheadEnergyOffer",
+            Market::RT => "HbRealTimeEnergyOffers.HbRealTimeEnergyOffer",
+        };
+
+        let sql = format!(
+            r#"
+{}
+
+LOAD icu;SET TimeZone = 'America/New_York';
+CREATE TEMPORARY TABLE tmp AS
+    SELECT 
+        json_extract_string(aux, '$.BeginDate')::TIMESTAMPTZ AS HourBeginning,
+        json_extract(aux, '$.MaskedParticipantId')::UINTEGER AS MaskedParticipantId,
+        json_extract(aux, '$.MaskedAssetId')::UINTEGER AS MaskedAssetId,
+        json_extract_string(aux, '$.UnitStatus')::ENUM('ECONOMIC', 'UNAVAILABLE', 'MUST_RUN') AS UnitStatus,
+        json_extract(aux, '$.MustTakeEnergy')::DECIMAL(9,2) AS MustTakeEnergy,
+        json_extract(aux, '$.MaxDailyEnergy')::DECIMAL(9,2) AS MaxDailyEnergy,
+        json_extract(aux, '$.EcoMax')::DECIMAL(9,2) AS EcoMax,
+        json_extract(aux, '$.EcoMin')::DECIMAL(9,2) AS EcoMin,
+        json_extract(aux, '$.ColdStartupPrice')::DECIMAL(12,2) AS ColdStartupPrice,
+        json_extract(aux, '$.IntermediateStartupPrice')::DECIMAL(12,2) AS IntermediateStartupPrice,
+        json_extract(aux, '$.HotStartupPrice')::DECIMAL(12,2) AS HotStartupPrice,
+        json_extract(aux, '$.NoLoadPrice')::DECIMAL(12,2) AS NoLoadPrice,
+        (idx - 1)::UTINYINT AS Segment,
+        json_extract(seg, '$.Mw')::DECIMAL(9,2) AS Quantity,
+        json_extract(seg, '$.Price')::DECIMAL(9,2) AS Price
+    FROM (
+        SELECT aux, unnest(list_transform(segments, (s, i) -> {{'idx': i, 'seg': s}}), recursive := true)
+        FROM (
+            SELECT 
+                aux,
+                -- a single segment is not wrapped in an array
+                CASE json_type(aux, '$.Segments.Segment')
+                    WHEN 'ARRAY' THEN json_extract(aux, '$.Segments.Segment')::JSON[]
+                    WHEN 'OBJECT' THEN [json_extract(aux, '$.Segments.Segment')]
+                    ELSE []::JSON[]
+                END AS segments
+            FROM (
+                SELECT unnest({})::JSON AS aux
+                FROM read_json('{}/Raw/{}/{}_{}*.json.gz')
+            )
+        )
+    )
+;
+
+INSERT INTO {} BY NAME
+(SELECT * FROM tmp t
+WHERE NOT EXISTS (
+    SELECT * FROM {} o
+    WHERE
+        o.HourBeginning = t.HourBeginning AND
+        o.MaskedAssetId = t.MaskedAssetId AND
+        o.Segment = t.Segment
+    )
+)
+ORDER BY HourBeginning, MaskedAssetId, Segment;
+"#,
+            create_table_sql(market),
+            root,
+            self.base_dir,
+            month.start_date().year(),
+            report_name(market),
+            month.strftime("%Y%m"),
+            table,
+            table,
+        );
+        // println!("{}", sql);
+
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(&sql)
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!(
+                "Failed to update duckdb for {} month {}: {}",
+                market, month, stderr
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn report_name(market: &Market) -> &'static str {
+    match market {
+        Market::DA => "hbdayaheadenergyoffer",
+        Market::RT => "hbrealtimeenergyoffer",
+    }
+}
+
+/// Either `da_offers` or `rt_offers`
+pub fn table_name(market: &Market) -> String {
+    format!("{}_offers", market.to_string().to_lowercase())
+}
+
+/// The schema shared by the DA and RT offers.  Segments are numbered from 0
+/// in the order they are published.
+pub fn create_table_sql(market: &Market) -> String {
+    format!(
+        r#"
+CREATE TABLE IF NOT EXISTS {} (
+    HourBeginning TIMESTAMPTZ NOT NULL,
+    MaskedParticipantId UINTEGER NOT NULL,
+    MaskedAssetId UINTEGER NOT NULL,
+    UnitStatus ENUM('ECONOMIC', 'UNAVAILABLE', 'MUST_RUN') NOT NULL,
+    MustTakeEnergy DECIMAL(9,2),
+    MaxDailyEnergy DECIMAL(9,2),
+    EcoMax DECIMAL(9,2),
+    EcoMin DECIMAL(9,2),
+    ColdStartupPrice DECIMAL(12,2),
+    IntermediateStartupPrice DECIMAL(12,2),
+    HotStartupPrice DECIMAL(12,2),
+    NoLoadPrice DECIMAL(12,2),
+    Segment UTINYINT NOT NULL,
+    Quantity DECIMAL(9,2) NOT NULL,
+    Price DECIMAL(9,2) NOT NULL,
+);"#,
+        table_name(market)
+    )
 }
 
 #[cfg(test)]
 mod tests {
 
+    use duckdb::Connection;
     use jiff::civil::date;
     use std::{error::Error, path::Path};
 
+    use super::*;
     use crate::{
+        api::isone::{_api_isone_core::UnitStatus, masked::masked_energy_offers::get_stack},
         db::prod_db::ProdDb,
         interval::{interval_base::DateExt, term::Term},
     };
 
+    #[test]
+    fn test_shared_schema() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        for market in [Market::DA, Market::RT] {
+            conn.execute_batch(&create_table_sql(&market))?;
+        }
+        conn.execute_batch(
+            r#"
+INSERT INTO rt_offers (HourBeginning, MaskedParticipantId, MaskedAssetId, UnitStatus, Segment, Quantity, Price) VALUES
+    ('2025-07-01 15:00:00-04:00', 1, 100, 'ECONOMIC', 0, 50.0, 25.5),
+    ('2025-07-01 15:00:00-04:00', 1, 100, 'ECONOMIC', 1, 20.0, 40.0),
+    ('2025-07-01 15:00:00-04:00', 2, 200, 'MUST_RUN', 0, 80.0, -10.0),
+    ('2025-07-01 15:00:00-04:00', 3, 300, 'UNAVAILABLE', 0, 90.0, 30.0);
+"#,
+        )?;
+        let xs = get_stack(
+            &conn,
+            Market::RT,
+            vec!["2025-07-01 15:00:00-04".parse()?],
+        )?;
+        assert_eq!(xs.len(), 3);
+        assert_eq!(xs[0].masked_asset_id, 200);
+        assert_eq!(xs[0].unit_status, UnitStatus::MustRun);
+        assert_eq!(xs[1].price, 25.5);
+        assert!(get_stack(&conn, Market::DA, vec!["2025-07-01 15:00:00-04".parse()?])?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_filename() {
+        let archive = ProdDb::isone_masked_da_energy_offers();
+        assert!(archive
+            .filename(&date(2025, 3, 1), &Market::RT)
+            .ends_with("/Raw/2025/hbrealtimeenergyoffer_20250301.json"));
+    }
+
     #[ignore]
     #[test]
     fn update_db() -> Result<(), Box<dyn Error>> {
@@ -140,14 +247,16 @@ mod tests {
 
         let archive = ProdDb::isone_masked_da_energy_offers();
         let term = "Apr25-May25".parse::<Term>()?;
-        for day in &term.days() {
-            println!("Processing {}", day);
-            archive.download_file(day)?;
-        }
-        let months = term.months();
-        for month in &months {
-            println!("Updating DuckDB for month {}", month);
-            // archive.update_duckdb(month)?;
+        for market in [Market::DA, Market::RT] {
+            for day in &term.days() {
+                println!("Processing {} {}", market, day);
+                archive.download_file(day, &market)?;
+            }
+            let months = term.months();
+            for month in &months {
+                println!("Updating DuckDB for {} month {}", market, month);
+                archive.update_duckdb(month, &market)?;
+            }
         }
 
         Ok(())
@@ -160,7 +269,8 @@ mod tests {
         let archive = ProdDb::isone_masked_da_energy_offers();
         let days = date(2025, 11, 30).up_to(date(2025, 11, 30));
         for day in days {
-            archive.download_file(&day)?;
+            archive.download_file(&day, &Market::DA)?;
+            archive.download_file(&day, &Market::RT)?;
         }
         Ok(())
     }
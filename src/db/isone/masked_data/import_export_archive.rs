@@ -1,12 +1,19 @@
+use duckdb::{types::ValueRef, Connection};
 use jiff::civil::*;
+use jiff::{tz::TimeZone, Timestamp, Zoned};
 use log::{error, info};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
 use std::path::Path;
 use std::process::Command;
+use url::form_urlencoded;
 
 use crate::api::isone::_api_isone_core::Market;
 use crate::db::isone::lib_isoexpress;
 use crate::interval::month::Month;
+use crate::utils::serde_helpers::{deserialize_zoned_assume_ny, serialize_zoned_as_offset};
 
 #[derive(Clone)]
 pub struct ImportExportArchive {
@@ -130,6 +137,616 @@ ORDER BY hour_beginning, market_type, masked_customer_id;
     }
 }
 
+/// The masked id of the external node of a transaction.  Imports and wheels
+/// enter New England at their source, exports leave at their sink.
+const INTERFACE_ID: &str =
+    "CASE WHEN direction = 'EXPORT' THEN masked_sink_id ELSE masked_source_id END";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    pub market_type: Market,
+    pub masked_customer_id: u32,
+    pub masked_source_id: u32,
+    pub masked_sink_id: u32,
+    pub emergency_flag: bool,
+    /// One of IMPORT, EXPORT, THROUGH
+    pub direction: String,
+    /// One of FIXED, DISPATCHABLE, UP-TO CONGESTION
+    pub transaction_type: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub mw: Decimal,
+    /// Fixed transactions are price takers and don't have a price
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub price: Option<Decimal>,
+}
+
+fn decimal(v: ValueRef) -> Option<Decimal> {
+    match v {
+        ValueRef::Decimal(v) => Some(v),
+        _ => None,
+    }
+}
+
+fn where_clause(query_filter: &QueryFilter) -> String {
+    let mut out = String::new();
+    if let Some(hour_beginning_gte) = &query_filter.hour_beginning_gte {
+        out.push_str(&format!(
+            "
+    AND hour_beginning >= '{}'",
+            hour_beginning_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(hour_beginning_lt) = &query_filter.hour_beginning_lt {
+        out.push_str(&format!(
+            "
+    AND hour_beginning < '{}'",
+            hour_beginning_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(market_type) = &query_filter.market_type {
+        out.push_str(&format!(
+            "
+    AND market_type = '{}'",
+            market_type
+        ));
+    }
+    if let Some(masked_customer_id) = &query_filter.masked_customer_id {
+        out.push_str(&format!(
+            "
+    AND masked_customer_id = {}",
+            masked_customer_id
+        ));
+    }
+    if let Some(masked_interface_id) = &query_filter.masked_interface_id {
+        out.push_str(&format!(
+            "
+    AND {} = {}",
+            INTERFACE_ID, masked_interface_id
+        ));
+    }
+    if let Some(masked_interface_id_in) = &query_filter.masked_interface_id_in {
+        out.push_str(&format!(
+            "
+    AND {} IN ({})",
+            INTERFACE_ID,
+            masked_interface_id_in.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(",")
+        ));
+    }
+    if let Some(direction) = &query_filter.direction {
+        out.push_str(&format!(
+            "
+    AND direction = '{}'",
+            direction
+        ));
+    }
+    if let Some(transaction_type) = &query_filter.transaction_type {
+        out.push_str(&format!(
+            "
+    AND transaction_type = '{}'",
+            transaction_type
+        ));
+    }
+    if let Some(emergency_flag) = &query_filter.emergency_flag {
+        out.push_str(&format!(
+            "
+    AND emergency_flag = {}",
+            emergency_flag
+        ));
+    }
+    out
+}
+
+fn push_limit(query: &mut String, limit: Option<usize>) {
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+}
+
+pub fn get_data(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    hour_beginning,
+    market_type::VARCHAR,
+    masked_customer_id,
+    masked_source_id,
+    masked_sink_id,
+    emergency_flag,
+    direction::VARCHAR,
+    transaction_type::VARCHAR,
+    mw,
+    price
+FROM bidsoffers WHERE 1=1"#,
+    );
+    query.push_str(&where_clause(query_filter));
+    query.push_str(
+        "
+ORDER BY hour_beginning, market_type, masked_customer_id, masked_source_id, masked_sink_id",
+    );
+    push_limit(&mut query, limit);
+
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        Ok(Record {
+            hour_beginning: Zoned::new(Timestamp::from_microsecond(_micros0).unwrap(), tz.clone()),
+            market_type: row
+                .get::<usize, String>(1)?
+                .parse::<Market>()
+                .unwrap_or(Market::DA),
+            masked_customer_id: row.get::<usize, u32>(2)?,
+            masked_source_id: row.get::<usize, u32>(3)?,
+            masked_sink_id: row.get::<usize, u32>(4)?,
+            emergency_flag: row.get::<usize, bool>(5)?,
+            direction: row.get::<usize, String>(6)?,
+            transaction_type: row.get::<usize, String>(7)?,
+            mw: decimal(row.get_ref_unwrap(8)).unwrap_or(Decimal::MIN),
+            price: decimal(row.get_ref_unwrap(9)),
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+/// Hourly MW submitted at an interface, by direction and transaction type.
+///
+/// The masked report lists the transactions as submitted, it doesn't say
+/// which ones cleared.  See [`compare_with_flows`] for an estimate of the
+/// cleared import MW based on the actual flows at the interface.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HourlySummary {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    pub market_type: Market,
+    pub masked_interface_id: u32,
+    pub direction: String,
+    pub transaction_type: String,
+    pub transactions: u32,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub offered_mw: Decimal,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub min_price: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub max_price: Option<Decimal>,
+}
+
+/// Aggregate the transactions by hour, market, interface, direction and
+/// transaction type.
+pub fn get_hourly_summary(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<HourlySummary>, Box<dyn Error>> {
+    let mut query = format!(
+        r#"
+SELECT
+    hour_beginning,
+    market_type::VARCHAR AS market_type,
+    {} AS masked_interface_id,
+    direction::VARCHAR AS direction,
+    transaction_type::VARCHAR AS transaction_type,
+    COUNT(*)::UINTEGER AS transactions,
+    SUM(mw) AS offered_mw,
+    MIN(price) AS min_price,
+    MAX(price) AS max_price
+FROM bidsoffers WHERE 1=1"#,
+        INTERFACE_ID
+    );
+    query.push_str(&where_clause(query_filter));
+    query.push_str(
+        "
+GROUP BY ALL
+ORDER BY hour_beginning, market_type, masked_interface_id, direction, transaction_type",
+    );
+    push_limit(&mut query, limit);
+
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        Ok(HourlySummary {
+            hour_beginning: Zoned::new(Timestamp::from_microsecond(_micros0).unwrap(), tz.clone()),
+            market_type: row
+                .get::<usize, String>(1)?
+                .parse::<Market>()
+                .unwrap_or(Market::DA),
+            masked_interface_id: row.get::<usize, u32>(2)?,
+            direction: row.get::<usize, String>(3)?,
+            transaction_type: row.get::<usize, String>(4)?,
+            transactions: row.get::<usize, u32>(5)?,
+            offered_mw: decimal(row.get_ref_unwrap(6)).unwrap_or(Decimal::MIN),
+            min_price: decimal(row.get_ref_unwrap(7)),
+            max_price: decimal(row.get_ref_unwrap(8)),
+        })
+    })?;
+    let results: Vec<HourlySummary> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CurvePoint {
+    /// `None` for fixed (price taking) imports
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub price: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub mw: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub cumulative_mw: Decimal,
+}
+
+/// The import offers at one interface for one hour, in merit order.  Fixed
+/// imports come first, followed by the dispatchable imports sorted by price.
+/// Up-to congestion transactions are not included since their price is a
+/// spread and not an energy price.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SupplyCurve {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    pub market_type: Market,
+    pub masked_interface_id: u32,
+    pub points: Vec<CurvePoint>,
+}
+
+impl SupplyCurve {
+    pub fn total_mw(&self) -> Decimal {
+        self.points.last().map_or(Decimal::ZERO, |p| p.cumulative_mw)
+    }
+
+    /// The MW of fixed imports
+    pub fn fixed_mw(&self) -> Decimal {
+        self.points
+            .iter()
+            .filter(|p| p.price.is_none())
+            .map(|p| p.mw)
+            .sum()
+    }
+
+    /// The MW offered at or below a given price, including the fixed imports.
+    pub fn mw_at(&self, price: Decimal) -> Decimal {
+        self.points
+            .iter()
+            .filter(|p| p.price.is_none_or(|x| x <= price))
+            .map(|p| p.mw)
+            .sum()
+    }
+
+    /// The offer that needs to be dispatched to import `mw`.  Return `None`
+    /// if `mw` is not positive or exceeds the MW offered.
+    pub fn marginal_point(&self, mw: Decimal) -> Option<&CurvePoint> {
+        if mw <= Decimal::ZERO {
+            return None;
+        }
+        self.points.iter().find(|p| p.cumulative_mw >= mw)
+    }
+}
+
+/// Build the import supply curves for each hour, market and interface.  The
+/// `direction` and `transaction_type` fields of the filter are ignored.
+pub fn get_import_supply_curves(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+) -> Result<Vec<SupplyCurve>, Box<dyn Error>> {
+    let filter = QueryFilter {
+        direction: Some("IMPORT".to_string()),
+        transaction_type: None,
+        ..query_filter.clone()
+    };
+    let mut query = format!(
+        r#"
+SELECT
+    hour_beginning,
+    market_type::VARCHAR AS market_type,
+    {} AS masked_interface_id,
+    price,
+    mw
+FROM bidsoffers
+WHERE transaction_type IN ('FIXED', 'DISPATCHABLE')"#,
+        INTERFACE_ID
+    );
+    query.push_str(&where_clause(&filter));
+    query.push_str(
+        "
+ORDER BY hour_beginning, market_type, masked_interface_id, price NULLS FIRST, mw DESC;",
+    );
+
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        Ok((
+            _micros0,
+            row.get::<usize, String>(1)?,
+            row.get::<usize, u32>(2)?,
+            decimal(row.get_ref_unwrap(3)),
+            decimal(row.get_ref_unwrap(4)).unwrap_or(Decimal::ZERO),
+        ))
+    })?;
+
+    let mut curves: Vec<SupplyCurve> = Vec::new();
+    let mut key: Option<(i64, String, u32)> = None;
+    for row in rows {
+        let (micros, market, interface_id, price, mw) = row?;
+        let k = (micros, market, interface_id);
+        if key.as_ref() != Some(&k) {
+            curves.push(SupplyCurve {
+                hour_beginning: Zoned::new(Timestamp::from_microsecond(micros).unwrap(), tz.clone()),
+                market_type: k.1.parse::<Market>().unwrap_or(Market::DA),
+                masked_interface_id: interface_id,
+                points: Vec::new(),
+            });
+            key = Some(k);
+        }
+        let curve = curves.last_mut().unwrap();
+        let cumulative_mw = curve.total_mw() + mw;
+        curve.points.push(CurvePoint {
+            price,
+            mw,
+            cumulative_mw,
+        });
+    }
+    Ok(curves)
+}
+
+/// Attach the actual interchange database (see `IsoneActualInterchangeArchive`)
+/// as `flows_db` so [`compare_with_flows`] can read its `flows` table.
+pub fn attach_actual_interchange(conn: &Connection, duckdb_path: &str) -> Result<(), Box<dyn Error>> {
+    conn.execute_batch(&format!("ATTACH '{}' AS flows_db (READ_ONLY);", duckdb_path))?;
+    Ok(())
+}
+
+/// The import supply curve at an interface compared with the actual flow.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FlowComparison {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    pub market_type: Market,
+    pub masked_interface_id: u32,
+    pub ptid: u32,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub offered_mw: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub offered_fixed_mw: Decimal,
+    /// Net interchange at the interface, positive for imports
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub actual_flow_mw: Option<Decimal>,
+    /// The part of the curve needed to supply the actual flow, capped at the
+    /// MW offered.  The fixed imports are dispatched first.
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub cleared_fixed_mw: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub cleared_dispatchable_mw: Option<Decimal>,
+    /// The price of the dispatchable offer that supplies the last MW of the
+    /// actual flow.  `None` if the flow is covered by the fixed imports or
+    /// exceeds the MW offered.
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub marginal_price: Option<Decimal>,
+}
+
+/// Compare the import supply curves at a masked interface with the hourly
+/// actual flows at the interface node `ptid`.  The masked ids are not
+/// published, so the caller needs to know which interface node the masked id
+/// corresponds to.  Requires the actual interchange database to be attached
+/// as `flows_db`, see [`attach_actual_interchange`].
+pub fn compare_with_flows(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    masked_interface_id: u32,
+    ptid: u32,
+) -> Result<Vec<FlowComparison>, Box<dyn Error>> {
+    let filter = QueryFilter {
+        masked_interface_id: Some(masked_interface_id),
+        masked_interface_id_in: None,
+        ..query_filter.clone()
+    };
+    let curves = get_import_supply_curves(conn, &filter)?;
+
+    let mut query = format!(
+        r#"
+SELECT hour_beginning, Net
+FROM flows_db.flows
+WHERE ptid = {}"#,
+        ptid
+    );
+    if let Some(gte) = &filter.hour_beginning_gte {
+        query.push_str(&format!(
+            "
+    AND hour_beginning >= '{}'",
+            gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(lt) = &filter.hour_beginning_lt {
+        query.push_str(&format!(
+            "
+    AND hour_beginning < '{}'",
+            lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    query.push(';');
+    let mut stmt = conn.prepare(&query)?;
+    let flows: HashMap<i64, Decimal> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<usize, i64>(0)?,
+                decimal(row.get_ref_unwrap(1)).unwrap_or(Decimal::ZERO),
+            ))
+        })?
+        .collect::<Result<_, _>>()?;
+
+    let out = curves
+        .iter()
+        .map(|curve| {
+            let offered_fixed_mw = curve.fixed_mw();
+            let actual_flow_mw = flows
+                .get(&curve.hour_beginning.timestamp().as_microsecond())
+                .cloned();
+            let cleared = actual_flow_mw.map(|flow| {
+                let cleared = flow.max(Decimal::ZERO).min(curve.total_mw());
+                let fixed = cleared.min(offered_fixed_mw);
+                (fixed, cleared - fixed)
+            });
+            let marginal_price = actual_flow_mw
+                .and_then(|flow| curve.marginal_point(flow))
+                .and_then(|p| p.price);
+            FlowComparison {
+                hour_beginning: curve.hour_beginning.clone(),
+                market_type: curve.market_type,
+                masked_interface_id: curve.masked_interface_id,
+                ptid,
+                offered_mw: curve.total_mw(),
+                offered_fixed_mw,
+                actual_flow_mw,
+                cleared_fixed_mw: cleared.map(|e| e.0),
+                cleared_dispatchable_mw: cleared.map(|e| e.1),
+                marginal_price,
+            }
+        })
+        .collect();
+    Ok(out)
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    pub market_type: Option<Market>,
+    pub masked_customer_id: Option<u32>,
+    /// The masked source id for imports and wheels, the masked sink id for exports
+    pub masked_interface_id: Option<u32>,
+    pub masked_interface_id_in: Option<Vec<u32>>,
+    pub direction: Option<String>,
+    pub transaction_type: Option<String>,
+    pub emergency_flag: Option<bool>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.hour_beginning_gte {
+            params.insert("hour_beginning_gte", value.to_string());
+        }
+        if let Some(value) = &self.hour_beginning_lt {
+            params.insert("hour_beginning_lt", value.to_string());
+        }
+        if let Some(value) = &self.market_type {
+            params.insert("market_type", value.to_string());
+        }
+        if let Some(value) = &self.masked_customer_id {
+            params.insert("masked_customer_id", value.to_string());
+        }
+        if let Some(value) = &self.masked_interface_id {
+            params.insert("masked_interface_id", value.to_string());
+        }
+        if let Some(value) = &self.masked_interface_id_in {
+            params.insert(
+                "masked_interface_id_in",
+                value.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(","),
+            );
+        }
+        if let Some(value) = &self.direction {
+            params.insert("direction", value.to_string());
+        }
+        if let Some(value) = &self.transaction_type {
+            params.insert("transaction_type", value.to_string());
+        }
+        if let Some(value) = &self.emergency_flag {
+            params.insert("emergency_flag", value.to_string());
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn hour_beginning_gte(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_gte = Some(value);
+        self
+    }
+
+    pub fn hour_beginning_lt(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_lt = Some(value);
+        self
+    }
+
+    pub fn market_type(mut self, value: Market) -> Self {
+        self.inner.market_type = Some(value);
+        self
+    }
+
+    pub fn masked_customer_id(mut self, value: u32) -> Self {
+        self.inner.masked_customer_id = Some(value);
+        self
+    }
+
+    pub fn masked_interface_id(mut self, value: u32) -> Self {
+        self.inner.masked_interface_id = Some(value);
+        self
+    }
+
+    pub fn masked_interface_id_in(mut self, values_in: Vec<u32>) -> Self {
+        self.inner.masked_interface_id_in = Some(values_in);
+        self
+    }
+
+    pub fn direction<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.direction = Some(value.into());
+        self
+    }
+
+    pub fn transaction_type<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.transaction_type = Some(value.into());
+        self
+    }
+
+    pub fn emergency_flag(mut self, value: bool) -> Self {
+        self.inner.emergency_flag = Some(value);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -141,9 +758,114 @@ mod tests {
         interval::{interval_base::DateExt, month::month},
     };
     // use crate::interval::interval::DateExt;
+    use rust_decimal_macros::dec;
 
     use super::*;
 
+    fn setup(conn: &Connection) -> Result<(), Box<dyn Error>> {
+        conn.execute_batch(
+            r"
+CREATE TABLE bidsoffers (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    market_type ENUM('DA', 'RT') NOT NULL,
+    masked_customer_id UINTEGER NOT NULL,
+    masked_source_id UINTEGER NOT NULL,
+    masked_sink_id UINTEGER NOT NULL,
+    emergency_flag BOOLEAN NOT NULL,
+    direction ENUM('IMPORT', 'EXPORT', 'THROUGH') NOT NULL,
+    transaction_type ENUM('FIXED', 'DISPATCHABLE', 'UP-TO CONGESTION') NOT NULL,
+    mw DECIMAL(9,2) NOT NULL,
+    price DECIMAL(9,2),
+);
+INSERT INTO bidsoffers VALUES
+    ('2025-07-01 17:00:00-04:00', 'DA', 1, 100, 200, false, 'IMPORT', 'FIXED', 300, NULL),
+    ('2025-07-01 17:00:00-04:00', 'DA', 2, 100, 200, false, 'IMPORT', 'DISPATCHABLE', 150, 45.00),
+    ('2025-07-01 17:00:00-04:00', 'DA', 3, 100, 201, false, 'IMPORT', 'DISPATCHABLE', 100, 20.00),
+    ('2025-07-01 17:00:00-04:00', 'DA', 3, 100, 201, false, 'IMPORT', 'UP-TO CONGESTION', 50, 5.00),
+    ('2025-07-01 17:00:00-04:00', 'DA', 4, 200, 100, false, 'EXPORT', 'DISPATCHABLE', 75, 60.00),
+    ('2025-07-01 18:00:00-04:00', 'DA', 1, 100, 200, false, 'IMPORT', 'FIXED', 300, NULL);
+ATTACH ':memory:' AS flows_db;
+CREATE TABLE flows_db.flows (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    ptid UINTEGER NOT NULL,
+    Net DECIMAL(9,2) NOT NULL,
+    Purchase DECIMAL(9,2) NOT NULL,
+    Sale DECIMAL(9,2) NOT NULL,
+);
+INSERT INTO flows_db.flows VALUES
+    ('2025-07-01 17:00:00-04:00', 4012, 350, 350, 0),
+    ('2025-07-01 18:00:00-04:00', 4012, 250, 250, 0);
+",
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        setup(&conn)?;
+        let filter = QueryFilterBuilder::new()
+            .market_type(Market::DA)
+            .direction("EXPORT")
+            .build();
+        let xs = get_data(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].masked_sink_id, 100);
+        assert_eq!(xs[0].price, Some(dec!(60.00)));
+
+        // the interface of an export is the sink
+        let filter = QueryFilterBuilder::new().masked_interface_id(100).build();
+        let xs = get_data(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hourly_summary() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        setup(&conn)?;
+        let filter = QueryFilterBuilder::new()
+            .hour_beginning_lt("2025-07-01 18:00[America/New_York]".parse()?)
+            .build();
+        let xs = get_hourly_summary(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 4);
+        let dispatchable = xs
+            .iter()
+            .find(|e| e.direction == "IMPORT" && e.transaction_type == "DISPATCHABLE")
+            .unwrap();
+        assert_eq!(dispatchable.transactions, 2);
+        assert_eq!(dispatchable.offered_mw, dec!(250));
+        assert_eq!(dispatchable.min_price, Some(dec!(20.00)));
+        assert_eq!(dispatchable.max_price, Some(dec!(45.00)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_supply_curve() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        setup(&conn)?;
+        let curves = get_import_supply_curves(&conn, &QueryFilter::default())?;
+        assert_eq!(curves.len(), 2);
+        let curve = &curves[0];
+        assert_eq!(curve.masked_interface_id, 100);
+        assert_eq!(curve.points.len(), 3);
+        assert_eq!(curve.fixed_mw(), dec!(300));
+        assert_eq!(curve.total_mw(), dec!(550));
+        assert_eq!(curve.mw_at(dec!(30)), dec!(400));
+        assert_eq!(curve.marginal_point(dec!(350)).unwrap().price, Some(dec!(20.00)));
+        assert_eq!(curve.marginal_point(dec!(600)), None);
+
+        let xs = compare_with_flows(&conn, &QueryFilter::default(), 100, 4012)?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].actual_flow_mw, Some(dec!(350)));
+        assert_eq!(xs[0].cleared_fixed_mw, Some(dec!(300)));
+        assert_eq!(xs[0].cleared_dispatchable_mw, Some(dec!(50)));
+        assert_eq!(xs[0].marginal_price, Some(dec!(20.00)));
+        assert_eq!(xs[1].cleared_fixed_mw, Some(dec!(250)));
+        assert_eq!(xs[1].marginal_price, None);
+        Ok(())
+    }
+
     #[ignore]
     #[test]
     fn update_db() -> Result<(), Box<dyn Error>> {
@@ -1,6 +1,8 @@
 pub mod actual_interchange_archive;
 pub mod binding_constraints_da;
+pub mod binding_constraints_rt;
 pub mod calendar_events;
+pub mod capacity_auction_results_archive;
 pub mod daas_reserve_data_archive;
 pub mod daas_strike_prices_archive;
 pub mod dalmp_archive;
@@ -8,12 +10,16 @@ pub mod ftr_prices_archive;
 pub mod fuelmix_archive;
 pub mod lib_dam;
 pub mod lib_isoexpress;
+pub mod load_forecast_archive;
 pub mod masked_data;
 pub mod mis;
 pub mod monthly_capacity_auction_archive;
 pub mod participants_archive;
+pub mod rt_reserve_prices_archive;
+pub mod rtlmp5_archive;
 pub mod rtlmp_archive;
 pub mod sevenday_capacity_forecast_archive;
 pub mod sevenday_solar_forecast_archive;
 pub mod single_source_contingency_archive;
+pub mod system_load_archive;
 pub mod total_transfer_capability_archive;
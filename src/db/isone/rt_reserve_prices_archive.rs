@@ -0,0 +1,615 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+
+use duckdb::{types::ValueRef, Connection};
+use jiff::civil::Date;
+use jiff::{tz::TimeZone, Timestamp, Zoned};
+use log::{error, info};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::db::data_quality::{FileGrid, Frequency, QualitySpec, TableGrid, ValueRange};
+use crate::interval::month::Month;
+use crate::utils::serde_helpers::*;
+
+/// The two ISONE real-time reserve price reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtReserveReport {
+    FiveMinute,
+    Hourly,
+}
+
+impl RtReserveReport {
+    /// Name of the webservices endpoint
+    fn endpoint(&self) -> &'static str {
+        match self {
+            RtReserveReport::FiveMinute => "fiveminutereserveprice",
+            RtReserveReport::Hourly => "hourlyrtreserveprice",
+        }
+    }
+
+    /// Name of the table in the database
+    pub fn table(&self) -> &'static str {
+        match self {
+            RtReserveReport::FiveMinute => "rt_reserve_prices_5min",
+            RtReserveReport::Hourly => "rt_reserve_prices_hourly",
+        }
+    }
+
+    /// Path to the array of observations in the json file
+    fn json_path(&self) -> &'static str {
+        match self {
+            RtReserveReport::FiveMinute => "FiveMinReservePrices.FiveMinReservePrice",
+            RtReserveReport::Hourly => "HourlyRtReservePrices.HourlyRtReservePrice",
+        }
+    }
+}
+
+impl std::str::FromStr for RtReserveReport {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "5min" | "fiveminute" => Ok(RtReserveReport::FiveMinute),
+            "hourly" => Ok(RtReserveReport::Hourly),
+            _ => Err(format!("Invalid RT reserve price report: {}", s)),
+        }
+    }
+}
+
+/// ISONE real-time reserve clearing prices for the ten-minute spinning (TMSR),
+/// ten-minute non-spinning (TMNSR) and thirty-minute operating (TMOR) reserves
+/// by reserve zone (ROS, SWCT, CT, NEMA/BSTN).
+#[derive(Clone)]
+pub struct IsoneRtReservePricesArchive {
+    pub base_dir: String,
+    pub duckdb_path: String,
+}
+
+impl IsoneRtReservePricesArchive {
+    /// Return the json filename for the day.  Does not check if the file exists.
+    pub fn filename(&self, date: &Date, report: RtReserveReport) -> String {
+        self.base_dir.to_owned()
+            + "/Raw/"
+            + &date.year().to_string()
+            + "/"
+            + report.endpoint()
+            + "_"
+            + &date.strftime("%Y%m%d").to_string()
+            + ".json"
+    }
+
+    /// See https://webservices.iso-ne.com/api/v1.1/fiveminutereserveprice/day/20250501
+    /// and https://webservices.iso-ne.com/api/v1.1/hourlyrtreserveprice/day/20250501
+    pub fn download_file(&self, date: Date, report: RtReserveReport) -> Result<(), Box<dyn Error>> {
+        super::lib_isoexpress::download_file(
+            format!(
+                "https://webservices.iso-ne.com/api/v1.1/{}/day/{}",
+                report.endpoint(),
+                date.strftime("%Y%m%d")
+            ),
+            true,
+            Some("application/json".to_string()),
+            Path::new(&self.filename(&date, report)),
+            true,
+        )
+    }
+
+    /// Look for missing days.  Does not download current day.
+    pub fn download_missing_days(
+        &self,
+        month: Month,
+        report: RtReserveReport,
+    ) -> Result<(), Box<dyn Error>> {
+        let last = Zoned::now().date();
+        for day in month.days() {
+            if day >= last {
+                continue;
+            }
+            let fname = format!("{}.gz", self.filename(&day, report));
+            if !Path::new(&fname).exists() {
+                info!("Working on {}", day);
+                self.download_file(day, report)?;
+                info!("  downloaded file for {}", day);
+            }
+        }
+        Ok(())
+    }
+
+    /// Upload one month to DuckDB.  Rows already in the table are skipped.
+    pub fn update_duckdb(
+        &self,
+        month: &Month,
+        report: RtReserveReport,
+    ) -> Result<(), Box<dyn Error>> {
+        info!(
+            "inserting ISONE {} files for month {} ...",
+            report.table(),
+            month
+        );
+        let sql = format!(
+            r#"
+CREATE TABLE IF NOT EXISTS {table} (
+    interval_beginning TIMESTAMPTZ NOT NULL,
+    reserve_zone VARCHAR NOT NULL,
+    tmsr_clearing_price DECIMAL(9,2) NOT NULL,
+    tmnsr_clearing_price DECIMAL(9,2) NOT NULL,
+    tmor_clearing_price DECIMAL(9,2) NOT NULL,
+    tmsr_designated_mw DECIMAL(9,2),
+    tmnsr_designated_mw DECIMAL(9,2),
+    tmor_designated_mw DECIMAL(9,2),
+);
+
+CREATE TEMPORARY TABLE tmp AS
+    SELECT
+        make_timestamptz(epoch_us(BeginDate)) AS interval_beginning,
+        ReserveZoneName::VARCHAR AS reserve_zone,
+        TmsrClearingPrice::DECIMAL(9,2) AS tmsr_clearing_price,
+        TmnsrClearingPrice::DECIMAL(9,2) AS tmnsr_clearing_price,
+        TmorClearingPrice::DECIMAL(9,2) AS tmor_clearing_price,
+        TmsrDesignatedMw::DECIMAL(9,2) AS tmsr_designated_mw,
+        TmnsrDesignatedMw::DECIMAL(9,2) AS tmnsr_designated_mw,
+        TmorDesignatedMw::DECIMAL(9,2) AS tmor_designated_mw
+    FROM (
+        SELECT unnest({path}, recursive := true)
+        FROM read_json('{dir}/Raw/{year}/{endpoint}_{yyyymm}*.json.gz')
+    )
+ORDER BY interval_beginning, reserve_zone;
+
+INSERT INTO {table}
+(SELECT * FROM tmp
+WHERE NOT EXISTS (
+    SELECT * FROM {table} d
+    WHERE d.interval_beginning = tmp.interval_beginning
+    AND d.reserve_zone = tmp.reserve_zone
+    )
+)
+ORDER BY interval_beginning, reserve_zone;
+"#,
+            table = report.table(),
+            path = report.json_path(),
+            dir = self.base_dir,
+            year = month.year(),
+            endpoint = report.endpoint(),
+            yyyymm = month.strftime("%Y%m"),
+        );
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(&sql)
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for month {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+
+    pub fn quality_spec(&self) -> QualitySpec {
+        let archive = self.clone();
+        QualitySpec {
+            name: "isone_rt_reserve_prices".to_string(),
+            duckdb_path: self.duckdb_path.clone(),
+            table: Some(TableGrid {
+                table: RtReserveReport::FiveMinute.table().to_string(),
+                time_column: "interval_beginning".to_string(),
+                key_columns: vec!["reserve_zone".to_string()],
+                expected_keys: ["ROS", "SWCT", "CT", "NEMA/BSTN"]
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect(),
+                frequency: Frequency::FiveMinute,
+                tz: "America/New_York".to_string(),
+                value_ranges: vec![
+                    ValueRange::new("tmsr_clearing_price", Some(0.0), Some(10000.0)),
+                    ValueRange::new("tmnsr_clearing_price", Some(0.0), Some(10000.0)),
+                    ValueRange::new("tmor_clearing_price", Some(0.0), Some(10000.0)),
+                ],
+                condition: None,
+            }),
+            files: Some(FileGrid::Daily(Arc::new(move |day| {
+                archive.filename(day, RtReserveReport::FiveMinute)
+            }))),
+            skip_days: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    /// Hour beginning for the hourly report
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub interval_beginning: Zoned,
+    pub reserve_zone: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tmsr_clearing_price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tmnsr_clearing_price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub tmor_clearing_price: Decimal,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub tmsr_designated_mw: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub tmnsr_designated_mw: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub tmor_designated_mw: Option<Decimal>,
+}
+
+fn decimal(v: ValueRef) -> Option<Decimal> {
+    match v {
+        ValueRef::Decimal(v) => Some(v),
+        _ => None,
+    }
+}
+
+/// The filter as a SQL `AND ...` clause on `{prefix}interval_beginning` and
+/// `{prefix}reserve_zone`.
+fn where_clause(query_filter: &QueryFilter, prefix: &str) -> String {
+    let mut out = String::new();
+    if let Some(interval_beginning_gte) = &query_filter.interval_beginning_gte {
+        out.push_str(&format!(
+            "
+    AND {}interval_beginning >= '{}'",
+            prefix,
+            interval_beginning_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(interval_beginning_lt) = &query_filter.interval_beginning_lt {
+        out.push_str(&format!(
+            "
+    AND {}interval_beginning < '{}'",
+            prefix,
+            interval_beginning_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(reserve_zone) = &query_filter.reserve_zone {
+        out.push_str(&format!(
+            "
+    AND {}reserve_zone = '{}'",
+            prefix, reserve_zone
+        ));
+    }
+    if let Some(reserve_zone_in) = &query_filter.reserve_zone_in {
+        out.push_str(&format!(
+            "
+    AND {}reserve_zone IN ('{}')",
+            prefix,
+            reserve_zone_in.join("','")
+        ));
+    }
+    out
+}
+
+fn push_limit(query: &mut String, limit: Option<usize>) {
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+}
+
+pub fn get_data(
+    conn: &Connection,
+    report: RtReserveReport,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = format!(
+        r#"
+SELECT
+    interval_beginning,
+    reserve_zone,
+    tmsr_clearing_price,
+    tmnsr_clearing_price,
+    tmor_clearing_price,
+    tmsr_designated_mw,
+    tmnsr_designated_mw,
+    tmor_designated_mw
+FROM {} WHERE 1=1"#,
+        report.table()
+    );
+    query.push_str(&where_clause(query_filter, ""));
+    query.push_str(
+        "
+ORDER BY interval_beginning, reserve_zone",
+    );
+    push_limit(&mut query, limit);
+
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        Ok(Record {
+            interval_beginning: Zoned::new(
+                Timestamp::from_microsecond(_micros0).unwrap(),
+                tz.clone(),
+            ),
+            reserve_zone: row.get::<usize, String>(1)?,
+            tmsr_clearing_price: decimal(row.get_ref_unwrap(2)).unwrap_or(Decimal::MIN),
+            tmnsr_clearing_price: decimal(row.get_ref_unwrap(3)).unwrap_or(Decimal::MIN),
+            tmor_clearing_price: decimal(row.get_ref_unwrap(4)).unwrap_or(Decimal::MIN),
+            tmsr_designated_mw: decimal(row.get_ref_unwrap(5)),
+            tmnsr_designated_mw: decimal(row.get_ref_unwrap(6)),
+            tmor_designated_mw: decimal(row.get_ref_unwrap(7)),
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+/// Hourly DA and RT clearing prices side by side, for one reserve zone.
+/// The DA reserves are cleared system-wide, so the same DA price applies
+/// to all reserve zones.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpreadRecord {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    pub reserve_zone: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub da_tmsr_clearing_price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub rt_tmsr_clearing_price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub da_tmnsr_clearing_price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub rt_tmnsr_clearing_price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub da_tmor_clearing_price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub rt_tmor_clearing_price: Decimal,
+}
+
+impl SpreadRecord {
+    /// DA minus RT TMSR price
+    pub fn tmsr_spread(&self) -> Decimal {
+        self.da_tmsr_clearing_price - self.rt_tmsr_clearing_price
+    }
+
+    /// DA minus RT TMNSR price
+    pub fn tmnsr_spread(&self) -> Decimal {
+        self.da_tmnsr_clearing_price - self.rt_tmnsr_clearing_price
+    }
+
+    /// DA minus RT TMOR price
+    pub fn tmor_spread(&self) -> Decimal {
+        self.da_tmor_clearing_price - self.rt_tmor_clearing_price
+    }
+}
+
+/// Attach the DA reserve data database (see `DaasReserveDataArchive`) as `da`
+/// so [`get_da_rt_prices`] can join against its `reserve_data` table.
+pub fn attach_da_reserve_data(
+    conn: &Connection,
+    da_duckdb_path: &str,
+) -> Result<(), Box<dyn Error>> {
+    conn.execute_batch(&format!("ATTACH '{}' AS da (READ_ONLY);", da_duckdb_path))?;
+    Ok(())
+}
+
+/// Join the hourly RT reserve prices with the DA `reserve_data` prices.
+/// Requires the DA database to be attached as `da`, see [`attach_da_reserve_data`].
+/// Only hours present in both markets are returned.
+pub fn get_da_rt_prices(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<SpreadRecord>, Box<dyn std::error::Error>> {
+    let mut query = format!(
+        r#"
+SELECT
+    rt.interval_beginning AS hour_beginning,
+    rt.reserve_zone,
+    da.tmsr_clearing_price,
+    rt.tmsr_clearing_price,
+    da.tmnsr_clearing_price,
+    rt.tmnsr_clearing_price,
+    da.tmor_clearing_price,
+    rt.tmor_clearing_price
+FROM {} rt
+JOIN da.reserve_data da
+    ON da.hour_beginning = rt.interval_beginning
+WHERE 1=1"#,
+        RtReserveReport::Hourly.table()
+    );
+    query.push_str(&where_clause(query_filter, "rt."));
+    query.push_str(
+        "
+ORDER BY hour_beginning, reserve_zone",
+    );
+    push_limit(&mut query, limit);
+
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        Ok(SpreadRecord {
+            hour_beginning: Zoned::new(Timestamp::from_microsecond(_micros0).unwrap(), tz.clone()),
+            reserve_zone: row.get::<usize, String>(1)?,
+            da_tmsr_clearing_price: decimal(row.get_ref_unwrap(2)).unwrap_or(Decimal::MIN),
+            rt_tmsr_clearing_price: decimal(row.get_ref_unwrap(3)).unwrap_or(Decimal::MIN),
+            da_tmnsr_clearing_price: decimal(row.get_ref_unwrap(4)).unwrap_or(Decimal::MIN),
+            rt_tmnsr_clearing_price: decimal(row.get_ref_unwrap(5)).unwrap_or(Decimal::MIN),
+            da_tmor_clearing_price: decimal(row.get_ref_unwrap(6)).unwrap_or(Decimal::MIN),
+            rt_tmor_clearing_price: decimal(row.get_ref_unwrap(7)).unwrap_or(Decimal::MIN),
+        })
+    })?;
+    let results: Vec<SpreadRecord> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub interval_beginning_gte: Option<Zoned>,
+    pub interval_beginning_lt: Option<Zoned>,
+    pub reserve_zone: Option<String>,
+    pub reserve_zone_in: Option<Vec<String>>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.interval_beginning_gte {
+            params.insert("interval_beginning_gte", value.to_string());
+        }
+        if let Some(value) = &self.interval_beginning_lt {
+            params.insert("interval_beginning_lt", value.to_string());
+        }
+        if let Some(value) = &self.reserve_zone {
+            params.insert("reserve_zone", value.to_string());
+        }
+        if let Some(value) = &self.reserve_zone_in {
+            params.insert("reserve_zone_in", value.join(","));
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn interval_beginning_gte(mut self, value: Zoned) -> Self {
+        self.inner.interval_beginning_gte = Some(value);
+        self
+    }
+
+    pub fn interval_beginning_lt(mut self, value: Zoned) -> Self {
+        self.inner.interval_beginning_lt = Some(value);
+        self
+    }
+
+    pub fn reserve_zone<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.reserve_zone = Some(value.into());
+        self
+    }
+
+    pub fn reserve_zone_in(mut self, values_in: Vec<String>) -> Self {
+        self.inner.reserve_zone_in = Some(values_in);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::prod_db::ProdDb, interval::month::month};
+    use duckdb::Connection;
+    use rust_decimal_macros::dec;
+    use std::error::Error;
+
+    fn setup(conn: &Connection) -> Result<(), Box<dyn Error>> {
+        conn.execute_batch(
+            r"
+CREATE TABLE rt_reserve_prices_hourly (
+    interval_beginning TIMESTAMPTZ NOT NULL,
+    reserve_zone VARCHAR NOT NULL,
+    tmsr_clearing_price DECIMAL(9,2) NOT NULL,
+    tmnsr_clearing_price DECIMAL(9,2) NOT NULL,
+    tmor_clearing_price DECIMAL(9,2) NOT NULL,
+    tmsr_designated_mw DECIMAL(9,2),
+    tmnsr_designated_mw DECIMAL(9,2),
+    tmor_designated_mw DECIMAL(9,2),
+);
+INSERT INTO rt_reserve_prices_hourly VALUES
+    ('2025-07-01 17:00:00-04:00', 'ROS', 25.50, 10.25, 0.00, 180, 1200, 700),
+    ('2025-07-01 17:00:00-04:00', 'SWCT', 25.50, 10.25, 5.00, NULL, NULL, NULL),
+    ('2025-07-01 18:00:00-04:00', 'ROS', 0.00, 0.00, 0.00, 185, 1210, 705);
+ATTACH ':memory:' AS da;
+CREATE TABLE da.reserve_data (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    tmsr_clearing_price DECIMAL(9,2) NOT NULL,
+    tmnsr_clearing_price DECIMAL(9,2) NOT NULL,
+    tmor_clearing_price DECIMAL(9,2) NOT NULL,
+);
+INSERT INTO da.reserve_data VALUES
+    ('2025-07-01 17:00:00-04:00', 12.00, 8.50, 3.25),
+    ('2025-07-01 18:00:00-04:00', 11.00, 7.50, 3.00);
+",
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        setup(&conn)?;
+        let filter = QueryFilterBuilder::new().reserve_zone("ROS").build();
+        let xs = get_data(&conn, RtReserveReport::Hourly, &filter, None)?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].tmsr_clearing_price, dec!(25.50));
+        assert_eq!(xs[0].tmnsr_designated_mw, Some(dec!(1200)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_da_rt_prices() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        setup(&conn)?;
+        let filter = QueryFilterBuilder::new()
+            .interval_beginning_lt("2025-07-01 18:00[America/New_York]".parse()?)
+            .build();
+        let xs = get_da_rt_prices(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[1].reserve_zone, "SWCT");
+        assert_eq!(xs[1].da_tmor_clearing_price, dec!(3.25));
+        assert_eq!(xs[1].tmor_spread(), dec!(-1.75));
+        assert_eq!(xs[0].tmsr_spread(), dec!(-13.50));
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn update_db() -> Result<(), Box<dyn Error>> {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+        dotenvy::from_path(Path::new(".env/test.env")).unwrap();
+        let archive = ProdDb::isone_rt_reserve_prices();
+        for report in [RtReserveReport::Hourly, RtReserveReport::FiveMinute] {
+            let months = month(2025, 1).up_to(month(2025, 7))?;
+            for month in months {
+                archive.download_missing_days(month, report)?;
+                archive.update_duckdb(&month, report)?;
+            }
+        }
+        Ok(())
+    }
+}
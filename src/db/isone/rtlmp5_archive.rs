@@ -0,0 +1,552 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+
+use duckdb::{types::ValueRef, Connection};
+use itertools::Itertools;
+use jiff::civil::Date;
+use jiff::{tz::TimeZone, Timestamp, ToSpan, Zoned};
+use log::{error, info};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::api::isone::lmp::Row as HourlyRow;
+use crate::db::data_quality::{FileGrid, Frequency, QualitySpec, TableGrid, ValueRange};
+use crate::db::nyiso::dalmp::LmpComponent;
+use crate::interval::month::Month;
+use crate::utils::serde_helpers::*;
+
+/// The 5-minute RT prices are first published as preliminary, and replaced
+/// by the final prices a few days later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Rt5Version {
+    Prelim,
+    Final,
+}
+
+impl Rt5Version {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Rt5Version::Prelim => "prelim",
+            Rt5Version::Final => "final",
+        }
+    }
+
+    /// Name of the table in the database
+    pub fn table(&self) -> String {
+        format!("rt_lmp5_{}", self.as_str())
+    }
+}
+
+impl std::fmt::Display for Rt5Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for Rt5Version {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "prelim" => Ok(Rt5Version::Prelim),
+            "final" => Ok(Rt5Version::Final),
+            _ => Err(format!("Can't parse 5-min RT version: {}", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Rt5Version {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// ISONE 5-minute RT LMPs, preliminary and final.
+#[derive(Clone)]
+pub struct IsoneRtLmp5Archive {
+    pub base_dir: String,
+    pub duckdb_path: String,
+}
+
+impl IsoneRtLmp5Archive {
+    /// Return the json filename for the day.  Does not check if the file exists.
+    pub fn filename(&self, date: &Date, version: Rt5Version) -> String {
+        self.base_dir.to_owned()
+            + "/Raw/"
+            + &date.year().to_string()
+            + "/"
+            + version.as_str()
+            + "/rt_lmp5_"
+            + &date.strftime("%Y%m%d").to_string()
+            + ".json"
+    }
+
+    /// Preliminary prices are available shortly after the interval, the final
+    /// prices a few business days later.
+    /// See https://webservices.iso-ne.com/api/v1.1/fiveminutelmp/final/day/20250501
+    pub fn download_file(&self, date: Date, version: Rt5Version) -> Result<(), Box<dyn Error>> {
+        super::lib_isoexpress::download_file(
+            format!(
+                "https://webservices.iso-ne.com/api/v1.1/fiveminutelmp/{}/day/{}",
+                version.as_str(),
+                date.strftime("%Y%m%d")
+            ),
+            true,
+            Some("application/json".to_string()),
+            Path::new(&self.filename(&date, version)),
+            true,
+        )
+    }
+
+    /// Look for missing days.  Does not download current day.
+    pub fn download_missing_days(
+        &self,
+        month: Month,
+        version: Rt5Version,
+    ) -> Result<(), Box<dyn Error>> {
+        let last = Zoned::now().date();
+        for day in month.days() {
+            if day >= last {
+                continue;
+            }
+            let fname = format!("{}.gz", self.filename(&day, version));
+            if !Path::new(&fname).exists() {
+                info!("Working on {}", day);
+                self.download_file(day, version)?;
+                info!("  downloaded file for {}", day);
+            }
+        }
+        Ok(())
+    }
+
+    /// Upload one month to DuckDB.  Rows already in the table are skipped.
+    /// When the final prices are inserted, the preliminary prices for the
+    /// same intervals are deleted.
+    pub fn update_duckdb(&self, month: &Month, version: Rt5Version) -> Result<(), Box<dyn Error>> {
+        info!(
+            "inserting 5-min {} RTLMP files for month {} ...",
+            version, month
+        );
+        let mut sql = format!(
+            r#"
+CREATE TABLE IF NOT EXISTS {table} (
+    interval_beginning TIMESTAMPTZ NOT NULL,
+    ptid UINTEGER NOT NULL,
+    lmp DECIMAL(9,4) NOT NULL,
+    mcc DECIMAL(9,4) NOT NULL,
+    mcl DECIMAL(9,4) NOT NULL,
+);
+
+CREATE TEMPORARY TABLE tmp
+AS
+    SELECT DISTINCT
+        json_extract(aux, '$.BeginDate')::TIMESTAMPTZ AS interval_beginning,
+        json_extract(aux, '$.Location.@LocId')::UINTEGER AS ptid,
+        json_extract(aux, '$.LmpTotal')::DECIMAL(9,4) AS lmp,
+        json_extract(aux, '$.CongestionComponent')::DECIMAL(9,4) AS mcc,
+        json_extract(aux, '$.LossComponent')::DECIMAL(9,4) AS mcl
+    FROM (
+        SELECT unnest(FiveMinLmps.FiveMinLmp)::JSON as aux
+        FROM read_json('{dir}/Raw/{year}/{version}/rt_lmp5_{yyyymm}*.json.gz')
+    )
+    ORDER BY interval_beginning, ptid
+;
+
+INSERT INTO {table}
+(SELECT * FROM tmp
+WHERE NOT EXISTS (
+    SELECT * FROM {table} d
+    WHERE d.interval_beginning = tmp.interval_beginning
+    AND d.ptid = tmp.ptid
+    )
+)
+ORDER BY interval_beginning, ptid;
+"#,
+            table = version.table(),
+            dir = self.base_dir,
+            year = month.year(),
+            version = version.as_str(),
+            yyyymm = month.strftime("%Y%m"),
+        );
+        if version == Rt5Version::Final {
+            sql.push_str(&format!(
+                r#"
+CREATE TABLE IF NOT EXISTS {prelim} AS SELECT * FROM tmp LIMIT 0;
+DELETE FROM {prelim}
+WHERE interval_beginning IN (SELECT DISTINCT interval_beginning FROM tmp);
+"#,
+                prelim = Rt5Version::Prelim.table()
+            ));
+        }
+
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(&sql)
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for month {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+
+    /// The expected grid for the final prices: one row per ptid per 5 minutes.
+    pub fn quality_spec(&self) -> QualitySpec {
+        let archive = self.clone();
+        QualitySpec {
+            name: "isone_rtlmp5".to_string(),
+            duckdb_path: self.duckdb_path.clone(),
+            table: Some(TableGrid {
+                table: Rt5Version::Final.table(),
+                time_column: "interval_beginning".to_string(),
+                key_columns: vec!["ptid".to_string()],
+                expected_keys: (4000..=4008).map(|e| e.to_string()).collect(),
+                frequency: Frequency::FiveMinute,
+                tz: "America/New_York".to_string(),
+                value_ranges: vec![
+                    ValueRange::new("lmp", Some(-1000.0), Some(10000.0)),
+                    ValueRange::new("mcc", Some(-5000.0), Some(5000.0)),
+                    ValueRange::new("mcl", Some(-500.0), Some(500.0)),
+                ],
+                condition: None,
+            }),
+            files: Some(FileGrid::Daily(Arc::new(move |day| {
+                archive.filename(day, Rt5Version::Final)
+            }))),
+            skip_days: vec![],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Row {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub interval_beginning: Zoned,
+    pub ptid: u32,
+    pub component: LmpComponent,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub price: Decimal,
+}
+
+/// The `[start, end]` dates as a SQL filter on the `column` timestamp.
+fn time_filter(column: &str, start: Date, end: Date) -> String {
+    format!(
+        "{column} >= '{}' AND {column} < '{}'",
+        start
+            .in_tz("America/New_York")
+            .unwrap()
+            .strftime("%Y-%m-%d %H:%M:%S.000%:z"),
+        end.in_tz("America/New_York")
+            .unwrap()
+            .checked_add(1.day())
+            .unwrap()
+            .strftime("%Y-%m-%d %H:%M:%S.000%:z"),
+    )
+}
+
+fn ptid_filter(ptids: &Option<Vec<u32>>) -> String {
+    match ptids {
+        Some(ids) => format!("\nAND ptid in ({}) ", ids.iter().join(", ")),
+        None => "".to_string(),
+    }
+}
+
+fn component_list(components: &Option<Vec<LmpComponent>>) -> String {
+    match components {
+        Some(cs) => cs.iter().join(", "),
+        None => "lmp, mcc, mcl".to_string(),
+    }
+}
+
+/// Get the 5-minute prices between a [start, end] date for a list of ptids.
+pub fn get_prices(
+    conn: &Connection,
+    version: Rt5Version,
+    start: Date,
+    end: Date,
+    ptids: Option<Vec<u32>>,
+    components: Option<Vec<LmpComponent>>,
+) -> Result<Vec<Row>, Box<dyn Error>> {
+    let query = format!(
+        r#"
+UNPIVOT (
+    SELECT interval_beginning, ptid, {components} FROM {}
+    WHERE {}{}
+)
+ON {components}
+INTO
+    NAME component
+    VALUE price
+ORDER BY component, ptid, interval_beginning;
+    "#,
+        version.table(),
+        time_filter("interval_beginning", start, end),
+        ptid_filter(&ptids),
+        components = component_list(&components),
+    );
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let micro: i64 = row.get(0)?;
+        Ok(Row {
+            interval_beginning: Zoned::new(
+                Timestamp::from_microsecond(micro).unwrap(),
+                TimeZone::get("America/New_York").unwrap(),
+            ),
+            ptid: row.get(1)?,
+            component: row.get::<usize, String>(2)?.parse().unwrap(),
+            price: match row.get_ref_unwrap(3) {
+                ValueRef::Decimal(v) => v,
+                _ => Decimal::MIN,
+            },
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Aggregate the 5-minute prices to hourly by averaging the intervals in each
+/// hour.  This is how ISONE calculates the published hourly RT prices, so the
+/// result should match the `rt_lmp` table of the hourly archive.
+pub fn get_hourly_prices(
+    conn: &Connection,
+    version: Rt5Version,
+    start: Date,
+    end: Date,
+    ptids: Option<Vec<u32>>,
+    components: Option<Vec<LmpComponent>>,
+) -> Result<Vec<HourlyRow>, Box<dyn Error>> {
+    let query = format!(
+        r#"
+WITH hourly AS (
+    SELECT
+        to_timestamp(epoch_us(interval_beginning) // 3600000000 * 3600) AS hour_beginning,
+        ptid,
+        avg(lmp)::DECIMAL(9,4) AS lmp,
+        avg(mcc)::DECIMAL(9,4) AS mcc,
+        avg(mcl)::DECIMAL(9,4) AS mcl
+    FROM {}
+    WHERE {}{}
+    GROUP BY hour_beginning, ptid
+)
+UNPIVOT (SELECT hour_beginning, ptid, {components} FROM hourly)
+ON {components}
+INTO
+    NAME component
+    VALUE price
+ORDER BY component, ptid, hour_beginning;
+    "#,
+        version.table(),
+        time_filter("interval_beginning", start, end),
+        ptid_filter(&ptids),
+        components = component_list(&components),
+    );
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let micro: i64 = row.get(0)?;
+        Ok(HourlyRow {
+            hour_beginning: Zoned::new(
+                Timestamp::from_microsecond(micro).unwrap(),
+                TimeZone::get("America/New_York").unwrap(),
+            ),
+            ptid: row.get(1)?,
+            component: row.get::<usize, String>(2)?.parse().unwrap(),
+            price: match row.get_ref_unwrap(3) {
+                ValueRef::Decimal(v) => v,
+                _ => Decimal::MIN,
+            },
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// An hour where the aggregated 5-minute price doesn't match the published
+/// hourly price.  A missing value means the hour is only in one of the sources.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct HourlyMismatch {
+    #[serde(serialize_with = "serialize_zoned_as_offset")]
+    pub hour_beginning: Zoned,
+    pub ptid: u32,
+    pub component: LmpComponent,
+    pub aggregated: Option<Decimal>,
+    pub published: Option<Decimal>,
+}
+
+/// Compare the hourly prices aggregated from the 5-minute prices with the
+/// published hourly prices.  Return the hours where the absolute difference
+/// is larger than `tolerance`.
+pub fn validate_hourly(
+    aggregated: &[HourlyRow],
+    published: &[HourlyRow],
+    tolerance: Decimal,
+) -> Vec<HourlyMismatch> {
+    let key = |r: &HourlyRow| {
+        (
+            r.hour_beginning.timestamp(),
+            r.ptid,
+            r.component.to_string(),
+        )
+    };
+    let published_map: HashMap<_, &HourlyRow> = published.iter().map(|r| (key(r), r)).collect();
+    let mut seen = std::collections::HashSet::new();
+    let mut out: Vec<HourlyMismatch> = Vec::new();
+    for row in aggregated {
+        let k = key(row);
+        match published_map.get(&k) {
+            Some(p) if (p.price - row.price).abs() <= tolerance => {}
+            p => out.push(HourlyMismatch {
+                hour_beginning: row.hour_beginning.clone(),
+                ptid: row.ptid,
+                component: row.component,
+                aggregated: Some(row.price),
+                published: p.map(|p| p.price),
+            }),
+        }
+        seen.insert(k);
+    }
+    for row in published {
+        if !seen.contains(&key(row)) {
+            out.push(HourlyMismatch {
+                hour_beginning: row.hour_beginning.clone(),
+                ptid: row.ptid,
+                component: row.component,
+                aggregated: None,
+                published: Some(row.price),
+            });
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::prod_db::ProdDb, interval::month::month};
+    use jiff::civil::date;
+    use rust_decimal_macros::dec;
+    use std::error::Error;
+
+    fn setup_conn() -> Result<Connection, Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        let mut values: Vec<String> = Vec::new();
+        // 12 intervals in HE1, lmp goes from 20 to 31, mcc is 1 in the first half
+        for i in 0..12 {
+            values.push(format!(
+                "('2025-05-01 00:{:02}:00-04:00', 4000, {}, {}, 0.5)",
+                5 * i,
+                20 + i,
+                if i < 6 { 1 } else { 0 }
+            ));
+        }
+        conn.execute_batch(&format!(
+            r"
+CREATE TABLE rt_lmp5_final (
+    interval_beginning TIMESTAMPTZ NOT NULL,
+    ptid UINTEGER NOT NULL,
+    lmp DECIMAL(9,4) NOT NULL,
+    mcc DECIMAL(9,4) NOT NULL,
+    mcl DECIMAL(9,4) NOT NULL,
+);
+INSERT INTO rt_lmp5_final VALUES {};
+",
+            values.join(",\n")
+        ))?;
+        Ok(conn)
+    }
+
+    #[test]
+    fn test_get_prices() -> Result<(), Box<dyn Error>> {
+        let conn = setup_conn()?;
+        let xs = get_prices(
+            &conn,
+            Rt5Version::Final,
+            date(2025, 5, 1),
+            date(2025, 5, 1),
+            Some(vec![4000]),
+            Some(vec![LmpComponent::Lmp]),
+        )?;
+        assert_eq!(xs.len(), 12);
+        assert_eq!(xs[11].price, dec!(31));
+        Ok(())
+    }
+
+    #[test]
+    fn test_hourly_aggregation() -> Result<(), Box<dyn Error>> {
+        let conn = setup_conn()?;
+        let xs = get_hourly_prices(
+            &conn,
+            Rt5Version::Final,
+            date(2025, 5, 1),
+            date(2025, 5, 1),
+            None,
+            None,
+        )?;
+        assert_eq!(xs.len(), 3);
+        let hb: Zoned = "2025-05-01 00:00[America/New_York]".parse()?;
+        let lmp = xs
+            .iter()
+            .find(|r| r.component == LmpComponent::Lmp)
+            .unwrap();
+        assert_eq!(lmp.hour_beginning, hb);
+        assert_eq!(lmp.price, dec!(25.5));
+        let mcc = xs
+            .iter()
+            .find(|r| r.component == LmpComponent::Mcc)
+            .unwrap();
+        assert_eq!(mcc.price, dec!(0.5));
+
+        // validate against the published hourly prices
+        let published = vec![
+            HourlyRow {
+                hour_beginning: hb.clone(),
+                ptid: 4000,
+                component: LmpComponent::Lmp,
+                price: dec!(25.50),
+            },
+            HourlyRow {
+                hour_beginning: hb.clone(),
+                ptid: 4000,
+                component: LmpComponent::Mcc,
+                price: dec!(0.75),
+            },
+        ];
+        let mismatches = validate_hourly(&xs, &published, dec!(0.01));
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].component, LmpComponent::Mcc);
+        assert_eq!(mismatches[1].component, LmpComponent::Mcl);
+        assert_eq!(mismatches[1].published, None);
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn update_db() -> Result<(), Box<dyn Error>> {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+        dotenvy::from_path(Path::new(".env/test.env")).unwrap();
+        let archive = ProdDb::isone_rtlmp5();
+        let months = month(2025, 1).up_to(month(2025, 5))?;
+        for month in months {
+            archive.download_missing_days(month, Rt5Version::Final)?;
+            archive.update_duckdb(&month, Rt5Version::Final)?;
+        }
+        Ok(())
+    }
+}
@@ -6,7 +6,10 @@ use std::error::Error;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
+use std::sync::Arc;
 
+use crate::db::data_quality::{FileGrid, Frequency, QualitySpec, TableGrid, ValueRange};
+use crate::db::revisions::RevisionSpec;
 use crate::interval::month::Month;
 
 #[derive(Debug, PartialEq)]
@@ -45,30 +48,7 @@ impl IsoneRtLmpArchive {
         );
 
         let sql = format!(
-            r#"
-CREATE TABLE IF NOT EXISTS rt_lmp (
-    hour_beginning TIMESTAMPTZ NOT NULL,
-    ptid UINTEGER NOT NULL,
-    lmp DECIMAL(9,4) NOT NULL,
-    mcc DECIMAL(9,4) NOT NULL,
-    mcl DECIMAL(9,4) NOT NULL,
-);
-
-CREATE TEMPORARY TABLE tmp
-AS
-    SELECT DISTINCT
-        json_extract(aux, '$.BeginDate')::TIMESTAMPTZ AS hour_beginning,
-        json_extract(aux, '$.Location.@LocId')::UINTEGER AS ptid,
-        json_extract(aux, '$.LmpTotal')::DECIMAL(9,4) AS lmp,
-        json_extract(aux, '$.CongestionComponent')::DECIMAL(9,4) AS mcc,
-        json_extract(aux, '$.LossComponent')::DECIMAL(9,4) AS mcl
-    FROM (
-        SELECT unnest(HourlyLmps.HourlyLmp)::JSON as aux
-        FROM read_json('{}/Raw/{}/WW_RTLMP_ISO_{}*.json.gz')
-    )
-    ORDER BY hour_beginning, ptid
-;
-
+            r#"{}
 INSERT INTO rt_lmp
 (SELECT * FROM tmp 
 WHERE NOT EXISTS (
@@ -79,9 +59,7 @@ WHERE NOT EXISTS (
 )
 ORDER BY hour_beginning, ptid;
 "#,
-            self.base_dir,
-            month.start_date().year(),
-            month.start_date().strftime("%Y%m"),
+            self.tmp_table_sql(month),
         );
         // println!("{}", sql);
 
@@ -104,6 +82,87 @@ ORDER BY hour_beginning, ptid;
         Ok(())
     }
 
+    /// Upload one month to DuckDB, keeping track of revisions.  Rows that changed
+    /// since the last update are replaced in the `rt_lmp` table and the new version
+    /// is also added to the `rt_lmp_revisions` table, stamped with `inserted_at`.
+    pub fn update_duckdb_with_revisions(
+        &self,
+        month: &Month,
+        inserted_at: &Zoned,
+    ) -> Result<(), Box<dyn Error>> {
+        info!(
+            "inserting daily RTLMP hourly price files for month {} with revisions ...",
+            month
+        );
+
+        let sql = format!(
+            "{}{}",
+            self.tmp_table_sql(month),
+            self.revision_spec().merge_sql("tmp", inserted_at)
+        );
+
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(&sql)
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for month {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+
+    /// Keys and values of the `rt_lmp` table used for revision tracking.
+    pub fn revision_spec(&self) -> RevisionSpec {
+        RevisionSpec::new(
+            "rt_lmp",
+            &["hour_beginning", "ptid"],
+            &["lmp", "mcc", "mcl"],
+        )
+    }
+
+    /// Create the table if needed and load the month's files into a temporary table `tmp`.
+    fn tmp_table_sql(&self, month: &Month) -> String {
+        format!(
+            r#"
+CREATE TABLE IF NOT EXISTS rt_lmp (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    ptid UINTEGER NOT NULL,
+    lmp DECIMAL(9,4) NOT NULL,
+    mcc DECIMAL(9,4) NOT NULL,
+    mcl DECIMAL(9,4) NOT NULL,
+);
+
+CREATE TEMPORARY TABLE tmp
+AS
+    SELECT DISTINCT
+        json_extract(aux, '$.BeginDate')::TIMESTAMPTZ AS hour_beginning,
+        json_extract(aux, '$.Location.@LocId')::UINTEGER AS ptid,
+        json_extract(aux, '$.LmpTotal')::DECIMAL(9,4) AS lmp,
+        json_extract(aux, '$.CongestionComponent')::DECIMAL(9,4) AS mcc,
+        json_extract(aux, '$.LossComponent')::DECIMAL(9,4) AS mcl
+    FROM (
+        SELECT unnest(HourlyLmps.HourlyLmp)::JSON as aux
+        FROM read_json('{}/Raw/{}/WW_RTLMP_ISO_{}*.json.gz')
+    )
+    ORDER BY hour_beginning, ptid
+;
+
+"#,
+            self.base_dir,
+            month.start_date().year(),
+            month.start_date().strftime("%Y%m"),
+        )
+    }
+
     /// Data is usually published before 13:30 every day
     pub fn download_file(&self, date: Date) -> Result<(), Box<dyn Error>> {
         let yyyymmdd = date.strftime("%Y%m%d");
@@ -142,6 +201,31 @@ ORDER BY hour_beginning, ptid;
         }
         Ok(())
     }
+
+    /// The expected grid: one row per ptid per hour, 23/24/25 hours per day.
+    pub fn quality_spec(&self) -> QualitySpec {
+        let archive = self.clone();
+        QualitySpec {
+            name: "isone_rtlmp".to_string(),
+            duckdb_path: self.duckdb_path.clone(),
+            table: Some(TableGrid {
+                table: "rt_lmp".to_string(),
+                time_column: "hour_beginning".to_string(),
+                key_columns: vec!["ptid".to_string()],
+                expected_keys: (4000..=4008).map(|e| e.to_string()).collect(),
+                frequency: Frequency::Hourly,
+                tz: "America/New_York".to_string(),
+                value_ranges: vec![
+                    ValueRange::new("lmp", Some(-500.0), Some(3000.0)),
+                    ValueRange::new("mcc", Some(-3000.0), Some(3000.0)),
+                    ValueRange::new("mcl", Some(-500.0), Some(500.0)),
+                ],
+                condition: None,
+            }),
+            files: Some(FileGrid::Daily(Arc::new(move |day| archive.filename(day)))),
+            skip_days: vec![],
+        }
+    }
 }
 
 #[cfg(test)]
@@ -2,7 +2,7 @@
 // Created on 2025-10-31 with elec_server/utils/lib_duckdb_builder.dart
 
 use log::{error, info};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::path::Path;
 use std::process::Command;
@@ -16,6 +16,7 @@ use jiff::{civil::Date, ToSpan};
 use jiff::{tz::TimeZone, Zoned};
 
 use crate::interval::month::Month;
+use crate::utils::serde_helpers::{serialize_option_zoned_as_offset, serialize_zoned_as_offset};
 
 pub struct SevendayCapacityForecastArchive {
     pub base_dir: String,
@@ -1131,6 +1132,112 @@ LIMIT {};",
     Ok(results)
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PeakLoadVintage {
+    #[serde(serialize_with = "serialize_zoned_as_offset")]
+    pub creation_time: Zoned,
+    pub for_day: Date,
+    /// The day index in the report, 1 for the first day
+    pub day_index: u8,
+    /// Number of days between the creation date and the target day
+    pub days_ahead: i32,
+    pub peak_load_mw: Option<i32>,
+    /// Change from the previous vintage for the same target day
+    pub peak_load_change_mw: Option<i32>,
+    pub surplus_deficiency_mw: Option<i32>,
+    /// Change from the previous vintage for the same target day
+    pub surplus_deficiency_change_mw: Option<i32>,
+    pub is_power_watch: Option<bool>,
+    pub is_power_warn: Option<bool>,
+}
+
+/// Get all the vintages of the forecast for the target days between `start`
+/// and `end` (inclusive), sorted by target day and creation time.
+pub fn get_vintages(
+    conn: &Connection,
+    start: Date,
+    end: Date,
+) -> Result<Vec<PeakLoadVintage>, Box<dyn std::error::Error>> {
+    let filter = QueryFilterBuilder::new()
+        .for_day_gte(start)
+        .for_day_lte(end)
+        .build();
+    let records = get_data(conn, &filter, None)?;
+    Ok(vintages(records))
+}
+
+/// Calculate the changes between consecutive vintages for each target day.
+pub fn vintages(mut records: Vec<Record>) -> Vec<PeakLoadVintage> {
+    records.sort_by(|a, b| {
+        (a.for_day, a.creation_time.timestamp()).cmp(&(b.for_day, b.creation_time.timestamp()))
+    });
+    let mut out: Vec<PeakLoadVintage> = Vec::with_capacity(records.len());
+    for (i, r) in records.iter().enumerate() {
+        let previous = match i {
+            0 => None,
+            _ if records[i - 1].for_day != r.for_day => None,
+            _ => Some(&records[i - 1]),
+        };
+        let change = |f: fn(&Record) -> Option<i32>| match (previous.and_then(f), f(r)) {
+            (Some(p), Some(c)) => Some(c - p),
+            _ => None,
+        };
+        out.push(PeakLoadVintage {
+            creation_time: r.creation_time.clone(),
+            for_day: r.for_day,
+            day_index: r.day_index,
+            days_ahead: (r.for_day - r.creation_time.date()).get_days(),
+            peak_load_mw: r.peak_load_mw,
+            peak_load_change_mw: change(|e| e.peak_load_mw),
+            surplus_deficiency_mw: r.surplus_deficiency_mw,
+            surplus_deficiency_change_mw: change(|e| e.surplus_deficiency_mw),
+            is_power_watch: r.is_power_watch,
+            is_power_warn: r.is_power_warn,
+        });
+    }
+    out
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SurplusTrajectory {
+    pub for_day: Date,
+    /// The surplus/deficiency from the last vintage created each number of
+    /// days ahead of the target day
+    pub surplus_deficiency_mw: BTreeMap<i32, i32>,
+    pub min_surplus_deficiency_mw: Option<i32>,
+    /// Creation time of the first vintage with a power watch, if any
+    #[serde(serialize_with = "serialize_option_zoned_as_offset")]
+    pub first_power_watch: Option<Zoned>,
+    /// Creation time of the first vintage with a power warning, if any
+    #[serde(serialize_with = "serialize_option_zoned_as_offset")]
+    pub first_power_warn: Option<Zoned>,
+}
+
+/// Summarize the vintages into one surplus/deficiency trajectory for each
+/// target day.  The vintages need to be sorted as returned by [`vintages`].
+pub fn surplus_trajectories(vintages: &[PeakLoadVintage]) -> Vec<SurplusTrajectory> {
+    vintages
+        .chunk_by(|a, b| a.for_day == b.for_day)
+        .map(|xs| {
+            let first_with = |f: fn(&PeakLoadVintage) -> Option<bool>| {
+                xs.iter()
+                    .find(|e| f(e) == Some(true))
+                    .map(|e| e.creation_time.clone())
+            };
+            SurplusTrajectory {
+                for_day: xs[0].for_day,
+                surplus_deficiency_mw: xs
+                    .iter()
+                    .filter_map(|e| e.surplus_deficiency_mw.map(|v| (e.days_ahead, v)))
+                    .collect(),
+                min_surplus_deficiency_mw: xs.iter().filter_map(|e| e.surplus_deficiency_mw).min(),
+                first_power_watch: first_with(|e| e.is_power_watch),
+                first_power_warn: first_with(|e| e.is_power_warn),
+            }
+        })
+        .collect()
+}
+
 #[derive(Debug, Default, Deserialize)]
 pub struct QueryFilter {
     pub creation_time: Option<Zoned>,
@@ -2082,6 +2189,76 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_vintages() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE capacity_forecast (
+    creation_time TIMESTAMPTZ NOT NULL,
+    for_day DATE NOT NULL,
+    day_index UINT8 NOT NULL,
+    cso_mw INT,
+    cold_weather_outages_mw INT,
+    other_gen_outages_mw INT,
+    delist_mw INT,
+    total_available_gen_mw INT,
+    peak_import_mw INT,
+    total_available_gen_import_mw INT,
+    peak_load_mw INT,
+    replacement_reserve_req_mw INT,
+    required_reserve_mw INT,
+    required_reserve_incl_replacement_mw INT,
+    total_load_plus_required_reserve_mw INT,
+    drr_mw INT,
+    surplus_deficiency_mw INT,
+    is_power_watch BOOLEAN,
+    is_power_warn BOOLEAN,
+    is_cold_weather_watch BOOLEAN,
+    is_cold_weather_warn BOOLEAN,
+    is_cold_weather_event BOOLEAN,
+    boston_high_temp_f INT1,
+    boston_dew_point_f INT1,
+    hartford_high_temp_f INT1,
+    hartford_dew_point_f INT1,
+);
+INSERT INTO capacity_forecast (creation_time, for_day, day_index, peak_load_mw, surplus_deficiency_mw, is_power_watch, is_power_warn) VALUES
+    ('2025-12-07 09:55:00-05:00', '2025-12-08', 2, 18920, 1500, false, false),
+    ('2025-12-05 09:30:00-05:00', '2025-12-08', 4, 18500, 2100, false, false),
+    ('2025-12-07 09:07:00-05:00', '2025-12-08', 2, 18875, 1600, false, false),
+    ('2025-12-06 09:30:00-05:00', '2025-12-08', 3, 18700, 900, true, false),
+    ('2025-12-06 09:30:00-05:00', '2025-12-09', 4, 19100, 700, false, false);
+",
+        )?;
+        let xs = get_vintages(&conn, date(2025, 12, 8), date(2025, 12, 9))?;
+        assert_eq!(xs.len(), 5);
+        assert_eq!(xs[0].days_ahead, 3);
+        assert_eq!(xs[0].peak_load_change_mw, None);
+        assert_eq!(xs[1].peak_load_change_mw, Some(200));
+        assert_eq!(xs[1].surplus_deficiency_change_mw, Some(-1200));
+        assert_eq!(xs[3].peak_load_change_mw, Some(45));
+        assert_eq!(xs[4].for_day, date(2025, 12, 9));
+        assert_eq!(xs[4].peak_load_change_mw, None);
+        let json = serde_json::to_value(&xs[0])?;
+        assert_eq!(json["creation_time"], "2025-12-05 09:30:00-05:00");
+
+        let ts = surplus_trajectories(&xs);
+        assert_eq!(ts.len(), 2);
+        // the last vintage one day ahead wins
+        assert_eq!(ts[0].surplus_deficiency_mw.get(&1), Some(&1500));
+        assert_eq!(ts[0].surplus_deficiency_mw.len(), 3);
+        assert_eq!(ts[0].min_surplus_deficiency_mw, Some(900));
+        assert_eq!(
+            ts[0].first_power_watch.as_ref().map(|e| e.date()),
+            Some(date(2025, 12, 6))
+        );
+        assert_eq!(ts[0].first_power_warn, None);
+        let json = serde_json::to_value(&ts[0])?;
+        assert_eq!(json["first_power_watch"], "2025-12-06 09:30:00-05:00");
+        assert_eq!(json["first_power_warn"], serde_json::Value::Null);
+        Ok(())
+    }
+
     #[ignore]
     #[test]
     fn update_db() -> Result<(), Box<dyn Error>> {
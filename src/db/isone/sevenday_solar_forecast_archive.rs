@@ -1,15 +1,20 @@
 use csv::StringRecord;
+use duckdb::Connection;
 use itertools::Itertools;
 use jiff::civil::*;
 use jiff::Zoned;
+use log::info;
 use regex::Regex;
 use std::error::Error;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 use std::process::Command;
+use std::sync::Arc;
 
+use crate::db::data_quality::{FileGrid, QualitySpec};
 use crate::interval::month::Month;
+use crate::utils::compression::create_gz;
 
 use super::mis::lib_mis::parse_hour_ending;
 
@@ -20,6 +25,7 @@ pub struct Row {
     pub forecast_generation: usize,
 }
 
+#[derive(Clone)]
 pub struct SevendaySolarForecastArchive {
     pub base_dir: String,
     pub duckdb_path: String,
@@ -95,35 +101,77 @@ impl SevendaySolarForecastArchive {
     ///
     pub fn make_gzfile_for_month(&self, month: &Month) -> Result<(), Box<dyn Error>> {
         let file_out = format!(
-            "{}/month/solar_forecast_{}.csv",
+            "{}/month/solar_forecast_{}.csv.gz",
             self.base_dir.to_owned(),
             month
         );
-        let mut wtr = csv::Writer::from_path(&file_out)?;
+        let mut wtr = csv::Writer::from_writer(create_gz(Path::new(&file_out))?);
 
         for date in month.days() {
             let path = self.filename(date);
             let rows = self.read_file(path)?;
             for row in rows {
-                let _ = wtr.write_record(&[
+                wtr.write_record(&[
                     row.report_date.to_string(),
                     row.forecast_hour_beginning
                         .strftime("%Y-%m-%dT%H:%M:%S.000%:z")
                         .to_string(),
                     row.forecast_generation.to_string(),
-                ]);
+                ])?;
             }
         }
         wtr.flush()?;
+        wtr.into_inner().map_err(|e| e.into_error())?.finish()?;
+        Ok(())
+    }
 
-        // gzip it
-        Command::new("gzip")
-            .args(["-f", &file_out])
-            .current_dir(format!("{}/month", self.base_dir))
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("gzip failed");
+    /// Create the `forecast` table if it doesn't exist.
+    pub fn setup(&self) -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open(&self.duckdb_path)?;
+        conn.execute_batch(
+            r"
+CREATE TABLE IF NOT EXISTS forecast (
+    report_date DATE,
+    forecast_hour_beginning TIMESTAMPTZ,
+    forecast_generation USMALLINT,
+);",
+        )?;
+        Ok(())
+    }
+
+    /// Replace the forecasts issued in the month with the content of the
+    /// monthly gz file, see [`Self::make_gzfile_for_month`].
+    pub fn update_duckdb(&self, month: &Month) -> Result<(), Box<dyn Error>> {
+        info!("inserting 7 day solar forecasts for month {} ...", month);
+        self.setup()?;
+        let conn = Connection::open(&self.duckdb_path)?;
+        let n = conn.execute(
+            &format!(
+                r"
+DELETE FROM forecast
+WHERE report_date >= '{}'
+AND report_date <= '{}';",
+                month.start_date(),
+                month.end_date()
+            ),
+            [],
+        )?;
+        info!("  deleted {} rows", n);
+        let n = conn.execute(
+            &format!(
+                r"
+INSERT INTO forecast
+SELECT report_date, forecast_hour_beginning::TIMESTAMPTZ, forecast_generation
+FROM read_csv(
+    '{}/month/solar_forecast_{}.csv.gz',
+    header = false,
+    columns = {{'report_date': 'DATE', 'forecast_hour_beginning': 'VARCHAR', 'forecast_generation': 'USMALLINT'}})
+ORDER BY report_date, forecast_hour_beginning;",
+                self.base_dir, month
+            ),
+            [],
+        )?;
+        info!("  inserted {} rows", n);
         Ok(())
     }
 
@@ -153,6 +201,18 @@ impl SevendaySolarForecastArchive {
         }
         Ok(())
     }
+
+    /// One raw CSV report per day.
+    pub fn quality_spec(&self) -> QualitySpec {
+        let archive = self.clone();
+        QualitySpec {
+            name: "isone_sevenday_solar_forecast".to_string(),
+            duckdb_path: self.duckdb_path.clone(),
+            table: None,
+            files: Some(FileGrid::Daily(Arc::new(move |day| archive.filename(*day)))),
+            skip_days: vec![],
+        }
+    }
 }
 
 #[cfg(test)]
@@ -165,6 +225,39 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_update_duckdb() -> Result<(), Box<dyn Error>> {
+        let dir = std::env::temp_dir().join(format!("bust_solar_forecast_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("month"))?;
+        let archive = SevendaySolarForecastArchive {
+            base_dir: dir.to_str().unwrap().to_string(),
+            duckdb_path: dir.join("forecast.duckdb").to_str().unwrap().to_string(),
+        };
+        let mut gz = create_gz(&dir.join("month/solar_forecast_2024-08.csv.gz"))?;
+        std::io::Write::write_all(
+            &mut gz,
+            b"2024-08-01,2024-08-01T11:00:00.000-04:00,789\n2024-08-01,2024-08-02T11:00:00.000-04:00,801\n",
+        )?;
+        gz.finish()?;
+
+        // inserting the same month twice doesn't duplicate rows
+        let month = "2024-08".parse::<Month>()?;
+        archive.update_duckdb(&month)?;
+        archive.update_duckdb(&month)?;
+        let conn = Connection::open(&archive.duckdb_path)?;
+        let (n, mw): (i64, u16) = conn.query_row(
+            "SELECT count(*), max(forecast_generation) FROM forecast WHERE forecast_hour_beginning = '2024-08-01 15:00:00Z'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        assert_eq!((n, mw), (1, 789));
+        let n: i64 = conn.query_row("SELECT count(*) FROM forecast", [], |row| row.get(0))?;
+        assert_eq!(n, 2);
+        drop(conn);
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
     #[test]
     fn download_days() -> Result<(), Box<dyn Error>> {
         let archive = ProdDb::isone_sevenday_solar_forecast();
@@ -1,9 +1,14 @@
 use duckdb::Connection;
 use jiff::civil::*;
+use jiff::{tz::TimeZone, Timestamp, Zoned};
 use log::{error, info};
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::path::Path;
+use url::form_urlencoded;
+
+use crate::utils::serde_helpers::*;
 
 #[derive(Clone)]
 pub struct SingleSourceContingencyArchive {
@@ -109,6 +114,173 @@ INSERT INTO ssc BY NAME
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub begin_date: Zoned,
+    pub interface_name: String,
+    pub rt_flow_mw: f64,
+    pub lowest_limit_mw: f64,
+    pub distribution_factor: f64,
+    pub actual_margin_mw: f64,
+    pub authorized_margin_mw: f64,
+    pub base_limit_mw: f64,
+    pub single_source_contingency_limit_mw: f64,
+}
+
+pub fn get_data(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    begin_date,
+    interface_name,
+    rt_flow_mw,
+    lowest_limit_mw,
+    distribution_factor,
+    actual_margin_mw,
+    authorized_margin_mw,
+    base_limit_mw,
+    single_source_contingency_limit_mw
+FROM ssc WHERE 1=1"#,
+    );
+    if let Some(begin_date_gte) = &query_filter.begin_date_gte {
+        query.push_str(&format!(
+            "
+    AND begin_date >= '{}'",
+            begin_date_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(begin_date_lt) = &query_filter.begin_date_lt {
+        query.push_str(&format!(
+            "
+    AND begin_date < '{}'",
+            begin_date_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(interface_name) = &query_filter.interface_name {
+        query.push_str(&format!(
+            "
+    AND interface_name = '{}'",
+            interface_name
+        ));
+    }
+    if let Some(interface_name_in) = &query_filter.interface_name_in {
+        query.push_str(&format!(
+            "
+    AND interface_name IN ('{}')",
+            interface_name_in.join("','")
+        ));
+    }
+    query.push_str(
+        "
+ORDER BY begin_date, interface_name",
+    );
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        Ok(Record {
+            begin_date: Zoned::new(Timestamp::from_microsecond(_micros0).unwrap(), tz.clone()),
+            interface_name: row.get::<usize, String>(1)?,
+            rt_flow_mw: row.get::<usize, f64>(2)?,
+            lowest_limit_mw: row.get::<usize, f64>(3)?,
+            distribution_factor: row.get::<usize, f64>(4)?,
+            actual_margin_mw: row.get::<usize, f64>(5)?,
+            authorized_margin_mw: row.get::<usize, f64>(6)?,
+            base_limit_mw: row.get::<usize, f64>(7)?,
+            single_source_contingency_limit_mw: row.get::<usize, f64>(8)?,
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub begin_date_gte: Option<Zoned>,
+    pub begin_date_lt: Option<Zoned>,
+    pub interface_name: Option<String>,
+    pub interface_name_in: Option<Vec<String>>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.begin_date_gte {
+            params.insert("begin_date_gte", value.to_string());
+        }
+        if let Some(value) = &self.begin_date_lt {
+            params.insert("begin_date_lt", value.to_string());
+        }
+        if let Some(value) = &self.interface_name {
+            params.insert("interface_name", value.to_string());
+        }
+        if let Some(value) = &self.interface_name_in {
+            params.insert("interface_name_in", value.join(","));
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn begin_date_gte(mut self, value: Zoned) -> Self {
+        self.inner.begin_date_gte = Some(value);
+        self
+    }
+
+    pub fn begin_date_lt(mut self, value: Zoned) -> Self {
+        self.inner.begin_date_lt = Some(value);
+        self
+    }
+
+    pub fn interface_name<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.interface_name = Some(value.into());
+        self
+    }
+
+    pub fn interface_name_in(mut self, values_in: Vec<String>) -> Self {
+        self.inner.interface_name_in = Some(values_in);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -119,6 +291,38 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE ssc (
+    begin_date TIMESTAMPTZ NOT NULL,
+    rt_flow_mw DOUBLE NOT NULL,
+    lowest_limit_mw DOUBLE NOT NULL,
+    distribution_factor DOUBLE NOT NULL,
+    interface_name VARCHAR NOT NULL,
+    actual_margin_mw DOUBLE NOT NULL,
+    authorized_margin_mw DOUBLE NOT NULL,
+    base_limit_mw DOUBLE NOT NULL,
+    single_source_contingency_limit_mw DOUBLE NOT NULL
+);
+INSERT INTO ssc VALUES
+    ('2025-05-04 10:00:00-04:00', 1450, 1400, 1.0, 'Phase II', 250, 200, 2000, 1650),
+    ('2025-05-04 10:05:00-04:00', 1460, 1400, 1.0, 'Phase II', 240, 200, 2000, 1640),
+    ('2025-05-04 10:05:00-04:00', 520, 550, 0.9, 'New Brunswick', 100, 80, 1000, 700);
+",
+        )?;
+        let filter = QueryFilterBuilder::new()
+            .interface_name("Phase II")
+            .begin_date_gte("2025-05-04 10:05[America/New_York]".parse()?)
+            .build();
+        let xs = get_data(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].single_source_contingency_limit_mw, 1640.0);
+        Ok(())
+    }
+
     #[ignore]
     #[test]
     fn update_db() -> Result<(), Box<dyn Error>> {
@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+
+use duckdb::Connection;
+use jiff::civil::Date;
+use jiff::{tz::TimeZone, Timestamp, Zoned};
+use log::{error, info};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::interval::month::Month;
+use crate::utils::serde_helpers::*;
+
+/// The two ISONE system load reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemLoadReport {
+    FiveMinute,
+    Hourly,
+}
+
+impl SystemLoadReport {
+    /// Name of the webservices endpoint
+    fn endpoint(&self) -> &'static str {
+        match self {
+            SystemLoadReport::FiveMinute => "fiveminutesystemload",
+            SystemLoadReport::Hourly => "hourlysysload",
+        }
+    }
+
+    /// Name of the table in the database
+    pub fn table(&self) -> &'static str {
+        match self {
+            SystemLoadReport::FiveMinute => "system_load_5min",
+            SystemLoadReport::Hourly => "system_load_hourly",
+        }
+    }
+
+    /// Path to the array of observations in the json file
+    fn json_path(&self) -> &'static str {
+        match self {
+            SystemLoadReport::FiveMinute => "FiveMinSystemLoads.FiveMinSystemLoad",
+            SystemLoadReport::Hourly => "HourlySystemLoads.HourlySystemLoad",
+        }
+    }
+
+    /// Name of the load column in the json file
+    fn load_field(&self) -> &'static str {
+        match self {
+            SystemLoadReport::FiveMinute => "LoadMw",
+            SystemLoadReport::Hourly => "Load",
+        }
+    }
+}
+
+impl std::str::FromStr for SystemLoadReport {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "5min" | "fiveminute" => Ok(SystemLoadReport::FiveMinute),
+            "hourly" => Ok(SystemLoadReport::Hourly),
+            _ => Err(format!("Invalid system load report: {}", s)),
+        }
+    }
+}
+
+/// ISONE system load, at 5-minute and hourly resolution.
+#[derive(Clone)]
+pub struct IsoneSystemLoadArchive {
+    pub base_dir: String,
+    pub duckdb_path: String,
+}
+
+impl IsoneSystemLoadArchive {
+    /// Return the json filename for the day.  Does not check if the file exists.
+    pub fn filename(&self, date: &Date, report: SystemLoadReport) -> String {
+        self.base_dir.to_owned()
+            + "/Raw/"
+            + &date.year().to_string()
+            + "/"
+            + report.endpoint()
+            + "_"
+            + &date.strftime("%Y%m%d").to_string()
+            + ".json"
+    }
+
+    /// Data for the previous day is complete in the morning.
+    /// See https://webservices.iso-ne.com/api/v1.1/fiveminutesystemload/day/20250501
+    /// and https://webservices.iso-ne.com/api/v1.1/hourlysysload/day/20250501
+    pub fn download_file(
+        &self,
+        date: Date,
+        report: SystemLoadReport,
+    ) -> Result<(), Box<dyn Error>> {
+        super::lib_isoexpress::download_file(
+            format!(
+                "https://webservices.iso-ne.com/api/v1.1/{}/day/{}",
+                report.endpoint(),
+                date.strftime("%Y%m%d")
+            ),
+            true,
+            Some("application/json".to_string()),
+            Path::new(&self.filename(&date, report)),
+            true,
+        )
+    }
+
+    /// Look for missing days.  Does not download current day.
+    pub fn download_missing_days(
+        &self,
+        month: Month,
+        report: SystemLoadReport,
+    ) -> Result<(), Box<dyn Error>> {
+        let last = Zoned::now().date();
+        for day in month.days() {
+            if day >= last {
+                continue;
+            }
+            let fname = format!("{}.gz", self.filename(&day, report));
+            if !Path::new(&fname).exists() {
+                info!("Working on {}", day);
+                self.download_file(day, report)?;
+                info!("  downloaded file for {}", day);
+            }
+        }
+        Ok(())
+    }
+
+    /// Upload one month to DuckDB.  Rows already in the table are skipped.
+    pub fn update_duckdb(
+        &self,
+        month: &Month,
+        report: SystemLoadReport,
+    ) -> Result<(), Box<dyn Error>> {
+        info!(
+            "inserting ISONE {} files for month {} ...",
+            report.table(),
+            month
+        );
+        let sql = format!(
+            r#"
+CREATE TABLE IF NOT EXISTS {table} (
+    interval_beginning TIMESTAMPTZ NOT NULL,
+    load DECIMAL(9,2) NOT NULL,
+    native_load DECIMAL(9,2),
+    ard_demand DECIMAL(9,2),
+);
+
+CREATE TEMPORARY TABLE tmp AS
+    SELECT
+        make_timestamptz(epoch_us(BeginDate)) AS interval_beginning,
+        {load}::DECIMAL(9,2) AS load,
+        NativeLoad::DECIMAL(9,2) AS native_load,
+        ArdDemand::DECIMAL(9,2) AS ard_demand
+    FROM (
+        SELECT unnest({path}, recursive := true)
+        FROM read_json('{dir}/Raw/{year}/{endpoint}_{yyyymm}*.json.gz')
+    )
+ORDER BY interval_beginning;
+
+INSERT INTO {table}
+(SELECT * FROM tmp
+WHERE NOT EXISTS (
+    SELECT * FROM {table} d
+    WHERE d.interval_beginning = tmp.interval_beginning
+    )
+)
+ORDER BY interval_beginning;
+"#,
+            table = report.table(),
+            load = report.load_field(),
+            path = report.json_path(),
+            dir = self.base_dir,
+            year = month.year(),
+            endpoint = report.endpoint(),
+            yyyymm = month.strftime("%Y%m"),
+        );
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(&sql)
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for month {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    /// Hour beginning for the hourly report
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub interval_beginning: Zoned,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub load: Decimal,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub native_load: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub ard_demand: Option<Decimal>,
+}
+
+pub fn get_data(
+    conn: &Connection,
+    report: SystemLoadReport,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = format!(
+        r#"
+SELECT
+    interval_beginning,
+    load,
+    native_load,
+    ard_demand
+FROM {} WHERE 1=1"#,
+        report.table()
+    );
+    if let Some(interval_beginning_gte) = &query_filter.interval_beginning_gte {
+        query.push_str(&format!(
+            "
+    AND interval_beginning >= '{}'",
+            interval_beginning_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(interval_beginning_lt) = &query_filter.interval_beginning_lt {
+        query.push_str(&format!(
+            "
+    AND interval_beginning < '{}'",
+            interval_beginning_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    query.push_str(
+        "
+ORDER BY interval_beginning",
+    );
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let decimal = |v: duckdb::types::ValueRef| match v {
+        duckdb::types::ValueRef::Decimal(v) => Some(v),
+        _ => None,
+    };
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        let interval_beginning = Zoned::new(
+            Timestamp::from_microsecond(_micros0).unwrap(),
+            TimeZone::get("America/New_York").unwrap(),
+        );
+        Ok(Record {
+            interval_beginning,
+            load: decimal(row.get_ref_unwrap(1)).unwrap_or(Decimal::MIN),
+            native_load: decimal(row.get_ref_unwrap(2)),
+            ard_demand: decimal(row.get_ref_unwrap(3)),
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub interval_beginning_gte: Option<Zoned>,
+    pub interval_beginning_lt: Option<Zoned>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.interval_beginning_gte {
+            params.insert("interval_beginning_gte", value.to_string());
+        }
+        if let Some(value) = &self.interval_beginning_lt {
+            params.insert("interval_beginning_lt", value.to_string());
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn interval_beginning_gte(mut self, value: Zoned) -> Self {
+        self.inner.interval_beginning_gte = Some(value);
+        self
+    }
+
+    pub fn interval_beginning_lt(mut self, value: Zoned) -> Self {
+        self.inner.interval_beginning_lt = Some(value);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::prod_db::ProdDb, interval::month::month};
+    use duckdb::Connection;
+    use rust_decimal_macros::dec;
+    use std::error::Error;
+
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE system_load_hourly (
+    interval_beginning TIMESTAMPTZ NOT NULL,
+    load DECIMAL(9,2) NOT NULL,
+    native_load DECIMAL(9,2),
+    ard_demand DECIMAL(9,2),
+);
+INSERT INTO system_load_hourly VALUES
+    ('2025-05-01 00:00:00-04:00', 10512.25, 10840.1, NULL),
+    ('2025-05-01 01:00:00-04:00', 10107.50, 10401.7, 12.5);
+",
+        )?;
+        let filter = QueryFilterBuilder::new()
+            .interval_beginning_gte("2025-05-01 01:00[America/New_York]".parse()?)
+            .build();
+        let xs = get_data(&conn, SystemLoadReport::Hourly, &filter, None)?;
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].load, dec!(10107.50));
+        assert_eq!(xs[0].ard_demand, Some(dec!(12.5)));
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn update_db() -> Result<(), Box<dyn Error>> {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+        dotenvy::from_path(Path::new(".env/test.env")).unwrap();
+        let archive = ProdDb::isone_system_load();
+        for report in [SystemLoadReport::Hourly, SystemLoadReport::FiveMinute] {
+            let months = month(2025, 1).up_to(month(2025, 5))?;
+            for month in months {
+                archive.download_missing_days(month, report)?;
+                archive.update_duckdb(&month, report)?;
+            }
+        }
+        Ok(())
+    }
+}
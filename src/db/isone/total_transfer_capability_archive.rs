@@ -1,13 +1,19 @@
+use duckdb::Connection;
 use itertools::Itertools;
 use jiff::civil::*;
+use jiff::{tz::TimeZone, Timestamp, Zoned};
 use log::error;
 use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
+use url::form_urlencoded;
 
 use crate::interval::month::Month;
+use crate::utils::serde_helpers::*;
 
 //
 pub struct TotalTransferCapabilityArchive {
@@ -34,6 +40,15 @@ impl TotalTransferCapabilityArchive {
         ]
     }
 
+    /// The interface names, e.g. `hq_phase2`.  Each interface has an import
+    /// and an export column in the `ttc_limits` table.
+    pub fn interfaces() -> Vec<String> {
+        Self::all_columns()
+            .iter()
+            .filter_map(|e| e.strip_suffix("_import").map(|s| s.to_string()))
+            .collect()
+    }
+
     /// Path to the CSV file with the ISO report for a given day.
     /// ISO doesn't publish this data as part of their webservices API.
     /// https://webservices.iso-ne.com/api/v1.1/totaltransfercapability/day/20250101
@@ -176,6 +191,165 @@ ORDER BY hour_beginning;
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    /// The interface name, e.g. `hq_phase2`
+    pub interface: String,
+    pub import_mw: i64,
+    pub export_mw: i64,
+}
+
+/// Get the TTC limits in long format, one row for each hour and interface.
+pub fn get_data(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let interfaces = match &query_filter.interface_in {
+        Some(xs) => xs.clone(),
+        None => TotalTransferCapabilityArchive::interfaces(),
+    };
+    let interfaces: Vec<String> = match &query_filter.interface {
+        Some(x) => interfaces.into_iter().filter(|e| e == x).collect(),
+        None => interfaces,
+    };
+    if interfaces.is_empty() {
+        return Ok(vec![]);
+    }
+    for interface in &interfaces {
+        if !TotalTransferCapabilityArchive::interfaces().contains(interface) {
+            return Err(format!("Unknown TTC interface {}", interface).into());
+        }
+    }
+
+    let mut filter = String::new();
+    if let Some(hour_beginning_gte) = &query_filter.hour_beginning_gte {
+        filter.push_str(&format!(
+            "
+    AND hour_beginning >= '{}'",
+            hour_beginning_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(hour_beginning_lt) = &query_filter.hour_beginning_lt {
+        filter.push_str(&format!(
+            "
+    AND hour_beginning < '{}'",
+            hour_beginning_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    let mut query = interfaces
+        .iter()
+        .map(|e| {
+            format!(
+                r#"
+SELECT hour_beginning, '{e}' AS interface, {e}_import AS import_mw, {e}_export AS export_mw
+FROM ttc_limits WHERE 1=1{filter}"#
+            )
+        })
+        .join("\nUNION ALL");
+    query.push_str(
+        "
+ORDER BY hour_beginning, interface",
+    );
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let tz = TimeZone::get("America/New_York").unwrap();
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        Ok(Record {
+            hour_beginning: Zoned::new(Timestamp::from_microsecond(_micros0).unwrap(), tz.clone()),
+            interface: row.get::<usize, String>(1)?,
+            import_mw: row.get::<usize, i64>(2)?,
+            export_mw: row.get::<usize, i64>(3)?,
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    pub interface: Option<String>,
+    pub interface_in: Option<Vec<String>>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.hour_beginning_gte {
+            params.insert("hour_beginning_gte", value.to_string());
+        }
+        if let Some(value) = &self.hour_beginning_lt {
+            params.insert("hour_beginning_lt", value.to_string());
+        }
+        if let Some(value) = &self.interface {
+            params.insert("interface", value.to_string());
+        }
+        if let Some(value) = &self.interface_in {
+            params.insert("interface_in", value.join(","));
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn hour_beginning_gte(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_gte = Some(value);
+        self
+    }
+
+    pub fn hour_beginning_lt(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_lt = Some(value);
+        self
+    }
+
+    pub fn interface<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.interface = Some(value.into());
+        self
+    }
+
+    pub fn interface_in(mut self, values_in: Vec<String>) -> Self {
+        self.inner.interface_in = Some(values_in);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use jiff::civil::date;
@@ -188,6 +362,37 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        let columns = TotalTransferCapabilityArchive::all_columns()
+            .iter()
+            .map(|e| format!("{} int64 NOT NULL", e))
+            .join(", ");
+        conn.execute_batch(&format!(
+            r"
+CREATE TABLE ttc_limits (hour_beginning TIMESTAMPTZ NOT NULL, {});
+INSERT INTO ttc_limits VALUES
+    ('2025-05-04 00:00:00-04:00', 1400, 1200, 200, 200, 346, 330, 1000, 550, 217, 100, 2000, 1200),
+    ('2025-05-04 01:00:00-04:00', 1400, 1200, 200, 200, 346, 330, 1000, 550, 217, 100, 1800, 1200);
+",
+            columns
+        ))?;
+        let filter = QueryFilterBuilder::new()
+            .interface_in(vec!["hq_phase2".to_string(), "nb".to_string()])
+            .hour_beginning_gte("2025-05-04 01:00[America/New_York]".parse()?)
+            .build();
+        let xs = get_data(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].interface, "hq_phase2");
+        assert_eq!(xs[0].import_mw, 1800);
+        assert_eq!(xs[1].export_mw, 550);
+
+        let filter = QueryFilterBuilder::new().interface("foo").build();
+        assert!(get_data(&conn, &filter, None).is_ok_and(|xs| xs.is_empty()));
+        Ok(())
+    }
+
     #[ignore]
     #[test]
     fn update_db() -> Result<(), Box<dyn Error>> {
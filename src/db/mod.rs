@@ -1,6 +1,7 @@
 pub mod bls;
 pub mod caiso;
 pub mod calendar;
+pub mod data_quality;
 pub mod epa;
 pub mod hq;
 pub mod ieso;
@@ -8,6 +9,8 @@ pub mod isone;
 pub mod nodal;
 pub mod nrc;
 pub mod nyiso;
+pub mod parquet_archive;
 pub mod prod_db;
+pub mod revisions;
 pub mod statistics_canada;
 pub mod ui;
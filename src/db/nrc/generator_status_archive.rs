@@ -7,8 +7,11 @@ use std::env;
 use std::error::Error;
 use std::fs::File;
 use std::io::Read;
+use std::path::Path;
 use std::process::Command;
 
+use crate::utils::compression::gzip_file;
+
 #[derive(Debug, PartialEq)]
 pub struct Row {
     pub report_date: Date,
@@ -149,14 +152,8 @@ ON c.Unit = g.unit_name;
 
         // gzip the file for storage
         for year in years {
-            let path = format!("{}powerstatus.txt", year);
-            Command::new("gzip")
-                .args(["-f", &path])
-                .current_dir(&dir)
-                .spawn()
-                .unwrap()
-                .wait()
-                .unwrap_or_else(|_| panic!("gzip year {} failed", year));
+            let path = format!("{}/{}powerstatus.txt", dir, year);
+            gzip_file(Path::new(&path))?;
         }
 
         Ok(())
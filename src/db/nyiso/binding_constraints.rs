@@ -15,12 +15,12 @@ use jiff::Timestamp;
 use jiff::{tz::TimeZone, Zoned};
 use log::{error, info};
 use rust_decimal::Decimal;
-use std::fs::{self, File};
-use std::io::Read;
 use std::process::Command;
 use std::str::FromStr;
 
 use crate::interval::month::Month;
+use crate::utils::compression::unzip_to_gz;
+use crate::utils::downloader::{downloader, AuthProfile};
 use crate::utils::serde_helpers::*;
 
 #[derive(Clone)]
@@ -59,60 +59,24 @@ impl NyisoBindingConstraintsDaArchive {
             "https://mis.nyiso.com/public/csv/DAMLimitingConstraints/{}",
             zip_path.file_name().unwrap().to_str().unwrap()
         );
-        let mut resp = reqwest::blocking::get(url)?;
-        let mut out = File::create(&binding)?;
-        std::io::copy(&mut resp, &mut out)?;
+        downloader().download_file(&url, AuthProfile::Nyiso, None, zip_path, false)?;
         info!("downloaded file: {}", binding);
 
-        // Unzip the file
+        // Unzip the file and gzip each daily csv file
         info!("Unzipping file {:?}", zip_path);
-        let mut zip_file = File::open(zip_path)?;
-        let mut zip_data = Vec::new();
-        zip_file.read_to_end(&mut zip_data)?;
-        let reader = std::io::Cursor::new(zip_data);
-        let mut zip = zip::ZipArchive::new(reader)?;
-        use std::fs::File as StdFile;
-        use std::io::copy as std_copy;
-
-        for i in 0..zip.len() {
-            let mut file = zip.by_index(i)?;
-            let out_path = match file.enclosed_name() {
-                Some(path) => path.to_owned(),
-                None => continue,
-            };
-            let day: Date = out_path.file_name().unwrap().to_str().unwrap()[0..8]
+        unzip_to_gz(zip_path, |name| {
+            let day: Date = name
+                .get(0..8)
+                .unwrap_or_default()
                 .parse()
-                .map_err(|_| format!("Invalid date in filename: {:?}", out_path))?;
-            let out_path = self.base_dir.to_owned()
-                + "/Raw/"
-                + &day.year().to_string()
-                + "/"
-                + out_path.file_name().unwrap().to_str().unwrap();
-            let dir = Path::new(&out_path).parent().unwrap();
-            fs::create_dir_all(dir)?;
-
-            // Use blocking std::fs::File and std::io::copy for extraction
-            let mut outfile = StdFile::create(&out_path)?;
-            std_copy(&mut file, &mut outfile)?;
-            info!(" -- extracted file to {}", out_path);
-
-            // Gzip the csv file
-            let mut csv_file = File::open(&out_path)?;
-            let mut csv_data = Vec::new();
-            csv_file.read_to_end(&mut csv_data)?;
-            let gz_path = format!("{}.gz", out_path);
-            let mut gz_file = File::create(&gz_path)?;
-            let mut encoder =
-                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
-            use std::io::Write;
-            encoder.write_all(&csv_data)?;
-            let compressed_data = encoder.finish()?;
-            gz_file.write_all(&compressed_data)?;
-            info!(" -- gzipped file to {}", gz_path);
-
-            // Remove the original csv file
-            std::fs::remove_file(&out_path)?;
-        }
+                .map_err(|_| format!("Invalid date in filename: {}", name))?;
+            Ok(Some(format!(
+                "{}/Raw/{}/{}",
+                self.base_dir,
+                day.year(),
+                name
+            )))
+        })?;
 
         // Remove the zip file
         std::fs::remove_file(zip_path)?;
@@ -1,24 +1,20 @@
-// use std::collections::HashMap;
+use std::collections::HashMap;
 use std::error::Error;
+use std::fmt;
+use std::fs;
 use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
 
-// use duckdb::Connection;
-// use jiff::civil::Date;
-// use serde::{Deserialize, Serialize};
-// use url::form_urlencoded;
-
-// use convert_case::{Case, Casing};
-// use jiff::Timestamp;
-// use jiff::{tz::TimeZone, Zoned};
+use duckdb::Connection;
 use log::{error, info};
-// use rust_decimal::Decimal;
-use std::fs::{self, File};
-use std::io::Read;
-use std::process::Command;
-// use std::str::FromStr;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize};
+use url::form_urlencoded;
 
 use crate::interval::month::Month;
-// use crate::utils::serde_helpers::*;
+use crate::utils::compression::unzip_to_gz;
+use crate::utils::downloader::{downloader, AuthProfile};
 
 #[derive(Clone)]
 pub struct NyisoCapacityOffersArchive {
@@ -37,14 +33,14 @@ impl NyisoCapacityOffersArchive {
             + "01biddata_icapbids_csv.zip"
     }
 
-    /// Return the file path of the csv file with data for one day
-    pub fn filename_bids(&self, month: &Month) -> String {
+    /// Return the file path of the csv file with the offers for the month
+    pub fn filename(&self, month: &Month) -> String {
         self.base_dir.to_owned()
             + "/Raw/"
             + month.year().to_string().as_str()
             + "/"
             + &month.strftime("%Y%m").to_string()
-            + "01biddata_icapbids_csv.zip"
+            + "01biddata_icapbids.csv"
     }
 
     /// Data is published around 10:30 every day
@@ -63,60 +59,24 @@ impl NyisoCapacityOffersArchive {
             zip_path.file_name().unwrap().to_str().unwrap()
         );
         println!("Downloading file from URL: {}", url);
-        let mut resp = reqwest::blocking::get(url)?;
-        let mut out = File::create(&binding)?;
-        std::io::copy(&mut resp, &mut out)?;
+        downloader().download_file(&url, AuthProfile::Nyiso, None, zip_path, false)?;
         info!("downloaded file: {}", binding);
 
-        // Unzip the file
+        // Unzip the file and gzip each monthly csv file
         info!("Unzipping file {:?}", zip_path);
-        let mut zip_file = File::open(zip_path)?;
-        let mut zip_data = Vec::new();
-        zip_file.read_to_end(&mut zip_data)?;
-        let reader = std::io::Cursor::new(zip_data);
-        let mut zip = zip::ZipArchive::new(reader)?;
-        use std::fs::File as StdFile;
-        use std::io::copy as std_copy;
-
-        for i in 0..zip.len() {
-            let mut file = zip.by_index(i)?;
-            let out_path = match file.enclosed_name() {
-                Some(path) => path.to_owned(),
-                None => continue,
-            };
-            let month: Month = out_path.file_name().unwrap().to_str().unwrap()[0..6]
+        unzip_to_gz(zip_path, |name| {
+            let month: Month = name
+                .get(0..6)
+                .unwrap_or_default()
                 .parse()
-                .map_err(|_| format!("Invalid month in filename: {:?}", out_path))?;
-            let out_path = self.base_dir.to_owned()
-                + "/Raw/"
-                + &month.year().to_string()
-                + "/"
-                + out_path.file_name().unwrap().to_str().unwrap();
-            let dir = Path::new(&out_path).parent().unwrap();
-            fs::create_dir_all(dir)?;
-
-            // Use blocking std::fs::File and std::io::copy for extraction
-            let mut outfile = StdFile::create(&out_path)?;
-            std_copy(&mut file, &mut outfile)?;
-            info!(" -- extracted file to {}", out_path);
-
-            // Gzip the csv file
-            let mut csv_file = File::open(&out_path)?;
-            let mut csv_data = Vec::new();
-            csv_file.read_to_end(&mut csv_data)?;
-            let gz_path = format!("{}.gz", out_path);
-            let mut gz_file = File::create(&gz_path)?;
-            let mut encoder =
-                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
-            use std::io::Write;
-            encoder.write_all(&csv_data)?;
-            let compressed_data = encoder.finish()?;
-            gz_file.write_all(&compressed_data)?;
-            info!(" -- gzipped file to {}", gz_path);
-
-            // Remove the original csv file
-            std::fs::remove_file(&out_path)?;
-        }
+                .map_err(|_| format!("Invalid month in filename: {}", name))?;
+            Ok(Some(format!(
+                "{}/Raw/{}/{}",
+                self.base_dir,
+                month.year(),
+                name
+            )))
+        })?;
 
         // Remove the zip file
         std::fs::remove_file(zip_path)?;
@@ -125,64 +85,61 @@ impl NyisoCapacityOffersArchive {
         Ok(())
     }
 
-    /// Update duckdb with published data for the month.  No checks are made to see
-    /// if there are missing files.  Does not delete any existing data.  So if data
-    /// is wrong for some reason, it needs to be manually deleted first!
+    /// Update duckdb with the offers for the auctions held in the month.  Offers
+    /// already in the table are skipped.
     ///
+    /// The NYISO file has one row per offer segment, with columns `Auction Type`
+    /// (Strip, Monthly, Spot), `Capability Period`, `Auction Month`,
+    /// `Forward Month`, `Location` (NYCA, G-J, NYC, LI), `Masked Participant ID`,
+    /// `Segment`, `MW` and `Price ($/kW-Month)`.
     pub fn update_duckdb(&self, month: &Month) -> Result<(), Box<dyn Error>> {
-        info!(
-            "inserting da binding constraint files for the month {} ...",
-            month
-        );
+        info!("inserting ICAP auction offers for the month {} ...", month);
         let sql = format!(
             r#"
-CREATE TABLE IF NOT EXISTS binding_constraints (
-    market ENUM('DA', 'RT') NOT NULL,
-    hour_beginning TIMESTAMPTZ NOT NULL,
-    limiting_facility VARCHAR NOT NULL,
-    facility_ptid INT64 NOT NULL,
-    contingency VARCHAR NOT NULL,
-    constraint_cost DECIMAL(9,4) NOT NULL,
+CREATE TABLE IF NOT EXISTS capacity_offers (
+    auction_type ENUM('Strip', 'Monthly', 'Spot') NOT NULL,
+    capability_period VARCHAR NOT NULL,
+    auction_month VARCHAR NOT NULL,
+    forward_month VARCHAR NOT NULL,
+    location VARCHAR NOT NULL,
+    masked_participant_id INTEGER NOT NULL,
+    segment INTEGER NOT NULL,
+    mw DECIMAL(9,1) NOT NULL,
+    price DECIMAL(9,4) NOT NULL,
 );
 
 CREATE TEMPORARY TABLE tmp
 AS (
-    SELECT 
-        'DA' AS market,
-        case "TIME ZONE" 
-            when 'EST' then strptime("Time Stamp" || ' -0500', '%m/%d/%Y %H:%M %z')::TIMESTAMPTZ
-            when 'EDT' then strptime("Time Stamp" || ' -0400', '%m/%d/%Y %H:%M %z')::TIMESTAMPTZ
-            else NULL
-        end AS hour_beginning, 
-        "Limiting Facility"::VARCHAR AS limiting_facility,
-        "Facility PTID"::INT64 AS facility_ptid,
-        "Contingency"::VARCHAR AS contingency,
-        "Constraint Cost($)"::DECIMAL(9,4) AS constraint_cost
-    FROM read_csv('{}/Raw/{}/{}*DAMLimitingConstraints.csv.gz', 
-        header = true,
-        types = {{'Facility PTID': 'INT64', 'Constraint Cost($)': 'DECIMAL(9,4)'}}
-));
-
-
-INSERT INTO binding_constraints
+    SELECT
+        "Auction Type"::VARCHAR AS auction_type,
+        "Capability Period"::VARCHAR AS capability_period,
+        strftime(strptime("Auction Month", '%m/%Y'), '%Y-%m') AS auction_month,
+        strftime(strptime("Forward Month", '%m/%Y'), '%Y-%m') AS forward_month,
+        "Location"::VARCHAR AS location,
+        "Masked Participant ID"::INTEGER AS masked_participant_id,
+        "Segment"::INTEGER AS segment,
+        "MW"::DECIMAL(9,1) AS mw,
+        "Price ($/kW-Month)"::DECIMAL(9,4) AS price
+    FROM read_csv('{}.gz', header = true, all_varchar = true)
+);
+
+INSERT INTO capacity_offers
 (
     SELECT * FROM tmp t
     WHERE NOT EXISTS (
-        SELECT * FROM binding_constraints d
+        SELECT * FROM capacity_offers d
         WHERE
-            d.market = t.market AND
-            d.hour_beginning = t.hour_beginning AND
-            d.limiting_facility = t.limiting_facility AND
-            d.facility_ptid = t.facility_ptid AND
-            d.contingency = t.contingency AND
-            d.constraint_cost = t.constraint_cost
+            d.auction_type = t.auction_type AND
+            d.auction_month = t.auction_month AND
+            d.forward_month = t.forward_month AND
+            d.location = t.location AND
+            d.masked_participant_id = t.masked_participant_id AND
+            d.segment = t.segment
     )
-);
-
+)
+ORDER BY auction_type, forward_month, location, price;
         "#,
-            self.base_dir,
-            month.start_date().year(),
-            month.start_date().strftime("%Y%m"),
+            self.filename(month),
         );
         let output = Command::new("duckdb")
             .arg("-c")
@@ -204,43 +161,449 @@ INSERT INTO binding_constraints
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum AuctionType {
+    Strip,
+    Monthly,
+    Spot,
+}
+
+impl fmt::Display for AuctionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for AuctionType {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "strip" => Ok(AuctionType::Strip),
+            "monthly" => Ok(AuctionType::Monthly),
+            "spot" => Ok(AuctionType::Spot),
+            _ => Err(format!("Failed parsing {} as an auction type", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AuctionType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        AuctionType::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The ICAP localities.  Offers in a nested locality also count as supply for
+/// the localities that contain it: NYC is inside G-J, and all of them are
+/// inside NYCA.
+pub fn nested_locations(location: &str) -> Vec<&'static str> {
+    match location {
+        "NYCA" => vec!["NYCA", "G-J", "NYC", "LI"],
+        "G-J" => vec!["G-J", "NYC"],
+        "NYC" => vec!["NYC"],
+        "LI" => vec!["LI"],
+        _ => vec![],
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub auction_type: AuctionType,
+    pub capability_period: String,
+    pub auction_month: String,
+    pub forward_month: String,
+    pub location: String,
+    pub masked_participant_id: i32,
+    pub segment: i32,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub mw: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub price: Decimal,
+}
+
+pub fn get_data(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    auction_type,
+    capability_period,
+    auction_month,
+    forward_month,
+    location,
+    masked_participant_id,
+    segment,
+    mw,
+    price
+FROM capacity_offers WHERE 1=1"#,
+    );
+    if let Some(auction_type) = &query_filter.auction_type {
+        query.push_str(&format!(
+            "
+    AND auction_type = '{}'",
+            auction_type
+        ));
+    }
+    if let Some(auction_month) = &query_filter.auction_month {
+        query.push_str(&format!(
+            "
+    AND auction_month = '{}'",
+            auction_month
+        ));
+    }
+    if let Some(forward_month) = &query_filter.forward_month {
+        query.push_str(&format!(
+            "
+    AND forward_month = '{}'",
+            forward_month
+        ));
+    }
+    if let Some(location) = &query_filter.location {
+        query.push_str(&format!(
+            "
+    AND location = '{}'",
+            location
+        ));
+    }
+    if let Some(location_in) = &query_filter.location_in {
+        query.push_str(&format!(
+            "
+    AND location IN ('{}')",
+            location_in.join("','")
+        ));
+    }
+    query.push_str(
+        "
+ORDER BY auction_type, forward_month, location, price, masked_participant_id, segment",
+    );
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let auction_type: AuctionType = row
+            .get::<usize, String>(0)?
+            .parse()
+            .map_err(|e: String| {
+                duckdb::Error::FromSqlConversionFailure(0, duckdb::types::Type::Text, e.into())
+            })?;
+        let mw: Decimal = match row.get_ref_unwrap(7) {
+            duckdb::types::ValueRef::Decimal(v) => v,
+            _ => Decimal::MIN,
+        };
+        let price: Decimal = match row.get_ref_unwrap(8) {
+            duckdb::types::ValueRef::Decimal(v) => v,
+            _ => Decimal::MIN,
+        };
+        Ok(Record {
+            auction_type,
+            capability_period: row.get::<usize, String>(1)?,
+            auction_month: row.get::<usize, String>(2)?,
+            forward_month: row.get::<usize, String>(3)?,
+            location: row.get::<usize, String>(4)?,
+            masked_participant_id: row.get::<usize, i32>(5)?,
+            segment: row.get::<usize, i32>(6)?,
+            mw,
+            price,
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+/// One step of an aggregate supply curve.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SupplyPoint {
+    #[serde(with = "rust_decimal::serde::float")]
+    pub price: Decimal,
+    /// MW offered at this price
+    #[serde(with = "rust_decimal::serde::float")]
+    pub mw: Decimal,
+    /// MW offered at this price or lower
+    #[serde(with = "rust_decimal::serde::float")]
+    pub cumulative_mw: Decimal,
+}
+
+/// Aggregate the offers into a supply curve: the MW are summed by price and
+/// sorted by increasing price.
+pub fn supply_curve(offers: &[Record]) -> Vec<SupplyPoint> {
+    let mut by_price: Vec<(Decimal, Decimal)> = Vec::new();
+    let mut sorted: Vec<&Record> = offers.iter().collect();
+    sorted.sort_by_key(|r| r.price);
+    for offer in sorted {
+        match by_price.last_mut() {
+            Some((price, mw)) if *price == offer.price => *mw += offer.mw,
+            _ => by_price.push((offer.price, offer.mw)),
+        }
+    }
+    let mut cumulative_mw = Decimal::ZERO;
+    by_price
+        .into_iter()
+        .map(|(price, mw)| {
+            cumulative_mw += mw;
+            SupplyPoint {
+                price,
+                mw,
+                cumulative_mw,
+            }
+        })
+        .collect()
+}
+
+/// Get the supply curve of one auction for a locality, including the offers
+/// from the nested localities.  An auction is identified by its type, the
+/// month it is held in and the forward month it clears.
+pub fn get_supply_curve(
+    conn: &Connection,
+    auction_type: AuctionType,
+    auction_month: &Month,
+    forward_month: &Month,
+    location: &str,
+) -> Result<Vec<SupplyPoint>, Box<dyn Error>> {
+    let locations = nested_locations(location);
+    if locations.is_empty() {
+        return Err(format!("Unknown ICAP location {}", location).into());
+    }
+    let filter = QueryFilterBuilder::new()
+        .auction_type(auction_type)
+        .auction_month(auction_month.to_string())
+        .forward_month(forward_month.to_string())
+        .location_in(locations.iter().map(|e| e.to_string()).collect())
+        .build();
+    let offers = get_data(conn, &filter, None)?;
+    Ok(supply_curve(&offers))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub auction_type: Option<AuctionType>,
+    pub auction_month: Option<String>,
+    pub forward_month: Option<String>,
+    pub location: Option<String>,
+    pub location_in: Option<Vec<String>>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.auction_type {
+            params.insert("auction_type", value.to_string());
+        }
+        if let Some(value) = &self.auction_month {
+            params.insert("auction_month", value.to_string());
+        }
+        if let Some(value) = &self.forward_month {
+            params.insert("forward_month", value.to_string());
+        }
+        if let Some(value) = &self.location {
+            params.insert("location", value.to_string());
+        }
+        if let Some(value) = &self.location_in {
+            params.insert("location_in", value.join(","));
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn auction_type(mut self, value: AuctionType) -> Self {
+        self.inner.auction_type = Some(value);
+        self
+    }
+
+    pub fn auction_month<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.auction_month = Some(value.into());
+        self
+    }
+
+    pub fn forward_month<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.forward_month = Some(value.into());
+        self
+    }
+
+    pub fn location<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.location = Some(value.into());
+        self
+    }
+
+    pub fn location_in(mut self, values_in: Vec<String>) -> Self {
+        self.inner.location_in = Some(values_in);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{db::prod_db::ProdDb, interval::month::month};
-    // use duckdb::{AccessMode, Config, Connection};
+    use duckdb::Connection;
+    use rust_decimal_macros::dec;
     use std::error::Error;
 
-    // #[test]
-    // fn test_get_data() -> Result<(), Box<dyn Error>> {
-    //     let config = Config::default().access_mode(AccessMode::ReadOnly)?;
-    //     let conn =
-    //         Connection::open_with_flags(ProdDb::nyiso_binding_constraints_da().duckdb_path, config)
-    //             .unwrap();
-    //     let filter = QueryFilterBuilder::new().build();
-    //     let xs: Vec<Record> = get_data(&conn, &filter, Some(5)).unwrap();
-    //     conn.close().unwrap();
-    //     assert_eq!(xs.len(), 5);
-    //     Ok(())
-    // }
-
-    // #[ignore]
-    // #[test]
-    // fn update_db() -> Result<(), Box<dyn Error>> {
-    //     let _ = env_logger::builder()
-    //         .filter_level(log::LevelFilter::Info)
-    //         .is_test(true)
-    //         .try_init();
-    //     dotenvy::from_path(Path::new(".env/test.env")).unwrap();
-    //     let archive = ProdDb::nyiso_binding_constraints_da();
-
-    //     let months = month(2020, 2).up_to(month(2026, 1))?;
-    //     for month in months {
-    //         println!("Processing month {}", month);
-    //         archive.update_duckdb(month)?;
-    //     }
-    //     Ok(())
-    // }
+    #[test]
+    fn test_supply_curve() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE capacity_offers (
+    auction_type ENUM('Strip', 'Monthly', 'Spot') NOT NULL,
+    capability_period VARCHAR NOT NULL,
+    auction_month VARCHAR NOT NULL,
+    forward_month VARCHAR NOT NULL,
+    location VARCHAR NOT NULL,
+    masked_participant_id INTEGER NOT NULL,
+    segment INTEGER NOT NULL,
+    mw DECIMAL(9,1) NOT NULL,
+    price DECIMAL(9,4) NOT NULL,
+);
+INSERT INTO capacity_offers VALUES
+    ('Spot', 'Summer 2025', '2025-06', '2025-07', 'NYCA', 1, 1, 100.0, 0.0),
+    ('Spot', 'Summer 2025', '2025-06', '2025-07', 'NYC', 2, 1, 50.0, 0.0),
+    ('Spot', 'Summer 2025', '2025-06', '2025-07', 'NYC', 2, 2, 20.0, 5.5),
+    ('Spot', 'Summer 2025', '2025-06', '2025-07', 'LI', 3, 1, 30.0, 2.0),
+    ('Monthly', 'Summer 2025', '2025-05', '2025-07', 'NYCA', 1, 1, 10.0, 1.0),
+    ('Monthly', 'Summer 2025', '2025-06', '2025-07', 'NYCA', 1, 1, 25.0, 1.5);
+",
+        )?;
+        let curve = get_supply_curve(
+            &conn,
+            AuctionType::Spot,
+            &month(2025, 6),
+            &month(2025, 7),
+            "NYCA",
+        )?;
+        assert_eq!(
+            curve
+                .iter()
+                .map(|p| (p.price, p.cumulative_mw))
+                .collect::<Vec<_>>(),
+            vec![
+                (dec!(0), dec!(150)),
+                (dec!(2), dec!(180)),
+                (dec!(5.5), dec!(200))
+            ]
+        );
+        let curve = get_supply_curve(
+            &conn,
+            AuctionType::Spot,
+            &month(2025, 6),
+            &month(2025, 7),
+            "G-J",
+        )?;
+        assert_eq!(curve.last().unwrap().cumulative_mw, dec!(70));
+        assert!(get_supply_curve(
+            &conn,
+            AuctionType::Spot,
+            &month(2025, 6),
+            &month(2025, 7),
+            "ROS"
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_supply_curve_auction_month() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE capacity_offers (
+    auction_type ENUM('Strip', 'Monthly', 'Spot') NOT NULL,
+    capability_period VARCHAR NOT NULL,
+    auction_month VARCHAR NOT NULL,
+    forward_month VARCHAR NOT NULL,
+    location VARCHAR NOT NULL,
+    masked_participant_id INTEGER NOT NULL,
+    segment INTEGER NOT NULL,
+    mw DECIMAL(9,1) NOT NULL,
+    price DECIMAL(9,4) NOT NULL,
+);
+INSERT INTO capacity_offers VALUES
+    ('Monthly', 'Summer 2025', '2025-05', '2025-07', 'NYCA', 1, 1, 10.0, 1.0),
+    ('Monthly', 'Summer 2025', '2025-05', '2025-07', 'NYCA', 2, 1, 15.0, 3.0),
+    ('Monthly', 'Summer 2025', '2025-06', '2025-07', 'NYCA', 1, 1, 25.0, 1.5);
+",
+        )?;
+        // the offers of the May auction for July are not stacked with the June ones
+        let curve = get_supply_curve(
+            &conn,
+            AuctionType::Monthly,
+            &month(2025, 5),
+            &month(2025, 7),
+            "NYCA",
+        )?;
+        assert_eq!(
+            curve
+                .iter()
+                .map(|p| (p.price, p.cumulative_mw))
+                .collect::<Vec<_>>(),
+            vec![(dec!(1), dec!(10)), (dec!(3), dec!(25))]
+        );
+        let curve = get_supply_curve(
+            &conn,
+            AuctionType::Monthly,
+            &month(2025, 6),
+            &month(2025, 7),
+            "NYCA",
+        )?;
+        assert_eq!(
+            curve
+                .iter()
+                .map(|p| (p.price, p.cumulative_mw))
+                .collect::<Vec<_>>(),
+            vec![(dec!(1.5), dec!(25))]
+        );
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn update_db() -> Result<(), Box<dyn Error>> {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+        dotenvy::from_path(Path::new(".env/test.env")).unwrap();
+        let archive = ProdDb::nyiso_capacity_offers();
+        let months = month(2024, 1).up_to(month(2024, 12))?;
+        for month in months {
+            archive.update_duckdb(&month)?;
+        }
+        Ok(())
+    }
 
     #[ignore]
     #[test]
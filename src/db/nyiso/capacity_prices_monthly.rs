@@ -2,6 +2,7 @@
 // Created on 2026-07-06 with Dart package reduct
 
 use std::error::Error;
+use std::sync::Arc;
 use std::{collections::HashMap, process::Command};
 
 use duckdb::Connection;
@@ -11,6 +12,7 @@ use url::form_urlencoded;
 
 use rust_decimal::Decimal;
 
+use crate::db::data_quality::{FileGrid, QualitySpec};
 use crate::interval::month::Month;
 
 #[derive(Clone)]
@@ -88,6 +90,18 @@ INSERT INTO capacity_prices_monthly
 
         Ok(())
     }
+
+    /// One processed report per month.
+    pub fn quality_spec(&self) -> QualitySpec {
+        let archive = self.clone();
+        QualitySpec {
+            name: "nyiso_capacity_prices_monthly".to_string(),
+            duckdb_path: self.duckdb_path.clone(),
+            table: None,
+            files: Some(FileGrid::Monthly(Arc::new(move |month| archive.filename(month)))),
+            skip_days: vec![],
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -2,18 +2,20 @@ use duckdb::Connection;
 use itertools::Itertools;
 use jiff::{civil::*, tz, Timestamp, Zoned};
 use log::{error, info};
-use reqwest::blocking::get;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::error::Error;
 use std::fmt;
-use std::fs::{self, File};
-use std::io::Read;
+use std::fs;
 use std::path::Path;
 use std::process::Command;
 use std::str::FromStr;
+use std::sync::Arc;
 
+use crate::db::data_quality::{FileGrid, Frequency, QualitySpec, TableGrid, ValueRange};
 use crate::interval::month::Month;
+use crate::utils::compression::unzip_to_gz;
+use crate::utils::downloader::{downloader, AuthProfile};
 
 #[derive(Debug, Serialize, Clone, PartialEq, Copy)]
 pub enum LmpComponent {
@@ -67,6 +69,7 @@ impl<'de> Deserialize<'de> for LmpComponent {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum NodeType {
     Gen,
     Zone,
@@ -206,60 +209,24 @@ impl NyisoDalmpArchive {
             "https://mis.nyiso.com/public/csv/damlbmp/{}",
             zip_path.file_name().unwrap().to_str().unwrap()
         );
-        let mut resp = get(url)?;
-        let mut out = File::create(&binding)?;
-        std::io::copy(&mut resp, &mut out)?;
+        downloader().download_file(&url, AuthProfile::Nyiso, None, zip_path, false)?;
         info!("downloaded file: {}", binding);
 
-        // Unzip the file
+        // Unzip the file and gzip each daily csv file
         info!("Unzipping file {:?}", zip_path);
-        let mut zip_file = File::open(zip_path)?;
-        let mut zip_data = Vec::new();
-        zip_file.read_to_end(&mut zip_data)?;
-        let reader = std::io::Cursor::new(zip_data);
-        let mut zip = zip::ZipArchive::new(reader)?;
-        use std::fs::File as StdFile;
-        use std::io::copy as std_copy;
-
-        for i in 0..zip.len() {
-            let mut file = zip.by_index(i)?;
-            let out_path = match file.enclosed_name() {
-                Some(path) => path.to_owned(),
-                None => continue,
-            };
-            let day: Date = out_path.file_name().unwrap().to_str().unwrap()[0..8]
+        unzip_to_gz(zip_path, |name| {
+            let day: Date = name
+                .get(0..8)
+                .unwrap_or_default()
                 .parse()
-                .map_err(|_| format!("Invalid date in filename: {:?}", out_path))?;
-            let out_path = self.base_dir.to_owned()
-                + "/Raw/"
-                + &day.year().to_string()
-                + "/"
-                + out_path.file_name().unwrap().to_str().unwrap();
-            let dir = Path::new(&out_path).parent().unwrap();
-            fs::create_dir_all(dir)?;
-
-            // Use blocking std::fs::File and std::io::copy for extraction
-            let mut outfile = StdFile::create(&out_path)?;
-            std_copy(&mut file, &mut outfile)?;
-            info!(" -- extracted file to {}", out_path);
-
-            // Gzip the csv file
-            let mut csv_file = File::open(&out_path)?;
-            let mut csv_data = Vec::new();
-            csv_file.read_to_end(&mut csv_data)?;
-            let gz_path = format!("{}.gz", out_path);
-            let mut gz_file = File::create(&gz_path)?;
-            let mut encoder =
-                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
-            use std::io::Write;
-            encoder.write_all(&csv_data)?;
-            let compressed_data = encoder.finish()?;
-            gz_file.write_all(&compressed_data)?;
-            info!(" -- gzipped file to {}", gz_path);
-
-            // Remove the original csv file
-            std::fs::remove_file(&out_path)?;
-        }
+                .map_err(|_| format!("Invalid date in filename: {}", name))?;
+            Ok(Some(format!(
+                "{}/Raw/{}/{}",
+                self.base_dir,
+                day.year(),
+                name
+            )))
+        })?;
 
         // Remove the zip file
         std::fs::remove_file(zip_path)?;
@@ -388,6 +355,34 @@ WHERE first_day = '{}';
 
         Ok(())
     }
+
+    /// The expected grid: one row per ptid (zones and generators) per hour.
+    /// Raw files are checked for the zones only.
+    pub fn quality_spec(&self) -> QualitySpec {
+        let archive = self.clone();
+        QualitySpec {
+            name: "nyiso_dalmp".to_string(),
+            duckdb_path: self.duckdb_path.clone(),
+            table: Some(TableGrid {
+                table: "dalmp".to_string(),
+                time_column: "hour_beginning".to_string(),
+                key_columns: vec!["ptid".to_string()],
+                expected_keys: (61752..=61762).map(|e| e.to_string()).collect(),
+                frequency: Frequency::Hourly,
+                tz: "America/New_York".to_string(),
+                value_ranges: vec![
+                    ValueRange::new("lmp", Some(-1000.0), Some(3000.0)),
+                    ValueRange::new("mcc", Some(-3000.0), Some(3000.0)),
+                    ValueRange::new("mlc", Some(-500.0), Some(500.0)),
+                ],
+                condition: None,
+            }),
+            files: Some(FileGrid::Daily(Arc::new(move |day| {
+                archive.filename(day, NodeType::Zone)
+            }))),
+            skip_days: vec![],
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,361 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use duckdb::Connection;
+use log::{error, info};
+
+use crate::db::nyiso::rtlmp::dst_bounds;
+use crate::interval::month::Month;
+use crate::utils::compression::unzip_to_gz;
+use crate::utils::downloader::{downloader, AuthProfile};
+
+/// Number of price/quantity segments in a NYISO generator bid
+pub const SEGMENTS: usize = 12;
+
 #[derive(Clone)]
 pub struct NyisoEnergyOffersArchive {
     pub base_dir: String,
     pub duckdb_path: String,
 }
+
+impl NyisoEnergyOffersArchive {
+    /// Return the full file path of the zip file with data for the entire month
+    pub fn filename_zip(&self, month: &Month) -> String {
+        self.base_dir.to_owned()
+            + "/Raw/"
+            + month.year().to_string().as_str()
+            + "/"
+            + &month.strftime("%Y%m").to_string()
+            + "01biddata_genbids_csv.zip"
+    }
+
+    /// Return the file path of the csv file with data for the month
+    pub fn filename(&self, month: &Month) -> String {
+        self.base_dir.to_owned()
+            + "/Raw/"
+            + month.year().to_string().as_str()
+            + "/"
+            + &month.strftime("%Y%m").to_string()
+            + "01biddata_genbids.csv"
+    }
+
+    /// Masked generator bids are published with a 3 month lag, for both the
+    /// DAM and the HAM, in one monthly file.
+    /// See https://mis.nyiso.com/public/csv/biddata/20240101biddata_genbids_csv.zip
+    /// Take the monthly zip file, extract it and compress the csv file as a gz file.
+    pub fn download_file(&self, month: &Month) -> Result<(), Box<dyn Error>> {
+        let binding = self.filename_zip(month);
+        let zip_path = Path::new(&binding);
+
+        let url = format!(
+            "https://mis.nyiso.com/public/csv/biddata/{}",
+            zip_path.file_name().unwrap().to_str().unwrap()
+        );
+        downloader().download_file(&url, AuthProfile::Nyiso, None, zip_path, false)?;
+        info!("downloaded file: {}", binding);
+
+        info!("Unzipping file {:?}", zip_path);
+        let csv_path = self.filename(month);
+        unzip_to_gz(zip_path, |name| {
+            if name.ends_with("genbids.csv") {
+                Ok(Some(csv_path.clone()))
+            } else {
+                Ok(None)
+            }
+        })?;
+
+        // Remove the zip file
+        fs::remove_file(zip_path)?;
+        info!("removed zip file {:?}", zip_path);
+
+        Ok(())
+    }
+
+    /// Create the `offers` table and the `offers_long` view.
+    ///
+    /// The `offers` table keeps the columns of the NYISO file, one row per
+    /// unit, hour and market, with the cumulative MW of each segment.  The
+    /// `offers_long` view has one row per segment with the incremental MW, in
+    /// the same shape as the ISONE masked offers.
+    pub fn setup(&self) -> Result<(), Box<dyn Error>> {
+        info!("initializing NYISO energy offers archive ...");
+        let dir = Path::new(&self.duckdb_path).parent().unwrap();
+        fs::create_dir_all(dir)
+            .unwrap_or_else(|_| panic!("Failed to create directory: {}", dir.display()));
+
+        if fs::exists(&self.duckdb_path)? {
+            fs::remove_file(&self.duckdb_path)?;
+        }
+        let conn = Connection::open(self.duckdb_path.clone())?;
+        conn.execute_batch(&setup_sql())?;
+        Ok(())
+    }
+
+    /// SQL to insert the bids of the month.  The "Date Time" column is New
+    /// York local time, the repeated hour at the end of DST is in EDT the
+    /// first time it shows up for a unit and market, and in EST the second time.
+    fn update_sql(&self, month: &Month) -> String {
+        let dispatch = (1..=SEGMENTS)
+            .map(|i| {
+                format!(
+                    r#"        "Dispatch $/MW{i}"::DECIMAL(9,2) AS "Dispatch $/MW{i}",
+        "Dispatch MW{i}"::DECIMAL(9,1) AS "Dispatch MW{i}","#
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (edt_start, edt_end, repeat_end) = dst_bounds(month.year());
+        format!(
+            r#"
+CREATE TEMPORARY TABLE raw AS
+    SELECT *, row_number() OVER () AS rn
+    FROM read_csv('{file}.gz', header = true, all_varchar = true)
+    WHERE "Market" IN ('DAM', 'HAM');
+
+CREATE TEMPORARY TABLE tmp AS
+    SELECT
+        "Masked Gen ID"::INTEGER AS "Masked Gen ID",
+        (strftime(local, '%Y-%m-%d %H:%M:%S') || if(
+            local >= '{edt_start}' AND (local < '{edt_end}' OR (local < '{repeat_end}' AND occurrence = 1)),
+            '-04:00', '-05:00'))::TIMESTAMPTZ AS "Date Time",
+        "Market"::VARCHAR AS "Market",
+        "Upper Oper Limit"::DECIMAL(9,1) AS "Upper Oper Limit",
+        "Emer Upper Oper Limit"::DECIMAL(9,1) AS "Emer Upper Oper Limit",
+{dispatch}
+        "Self Committed MW"::DECIMAL(9,1) AS "Self Committed MW",
+        "Bid Status"::VARCHAR AS "Bid Status",
+    FROM (
+        SELECT *,
+            strptime("Date Time", ['%m/%d/%Y %H:%M:%S', '%m/%d/%Y %H:%M']) AS local,
+            row_number() OVER (PARTITION BY "Masked Gen ID", "Market", "Date Time" ORDER BY rn) AS occurrence
+        FROM raw
+    );
+
+INSERT INTO offers
+    SELECT * FROM tmp t
+    WHERE NOT EXISTS (
+        SELECT * FROM offers o
+        WHERE o."Masked Gen ID" = t."Masked Gen ID"
+        AND o."Date Time" = t."Date Time"
+        AND o."Market" = t."Market"
+    )
+    ORDER BY "Date Time", "Masked Gen ID";
+"#,
+            file = self.filename(month),
+            edt_start = edt_start.strftime("%Y-%m-%d %H:%M:%S"),
+            edt_end = edt_end.strftime("%Y-%m-%d %H:%M:%S"),
+            repeat_end = repeat_end.strftime("%Y-%m-%d %H:%M:%S"),
+        )
+    }
+
+    /// Update duckdb with published data for the month.  Rows that are already
+    /// in the table are skipped, so it is safe to backfill a range of months.
+    ///
+    pub fn update_duckdb(&self, month: &Month) -> Result<(), Box<dyn Error>> {
+        info!("inserting NYISO generator bids for the month {} ...", month);
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(self.update_sql(month))
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for month {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn setup_sql() -> String {
+    let columns = (1..=SEGMENTS)
+        .map(|i| {
+            format!(
+                r#"    "Dispatch $/MW{i}" DECIMAL(9,2),
+    "Dispatch MW{i}" DECIMAL(9,1),"#
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let segments = (1..=SEGMENTS)
+        .map(|i| {
+            let quantity = if i == 1 {
+                "\"Dispatch MW1\"".to_string()
+            } else {
+                format!("\"Dispatch MW{}\" - \"Dispatch MW{}\"", i, i - 1)
+            };
+            format!(
+                r#"    SELECT "Masked Gen ID" AS MaskedAssetId, "Market" AS Market, "Date Time" AS HourBeginning,
+        {} AS Segment, {} AS Quantity, "Dispatch $/MW{i}" AS Price,
+        "Upper Oper Limit" AS UpperOperLimit, "Self Committed MW" AS SelfCommittedMw, "Bid Status" AS BidStatus
+    FROM offers WHERE "Dispatch $/MW{i}" IS NOT NULL"#,
+                i - 1,
+                quantity,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n    UNION ALL\n");
+    format!(
+        r#"
+BEGIN;
+CREATE TABLE IF NOT EXISTS offers (
+    "Masked Gen ID" INTEGER NOT NULL,
+    "Date Time" TIMESTAMPTZ NOT NULL,
+    "Market" ENUM('DAM', 'HAM') NOT NULL,
+    "Upper Oper Limit" DECIMAL(9,1),
+    "Emer Upper Oper Limit" DECIMAL(9,1),
+{columns}
+    "Self Committed MW" DECIMAL(9,1),
+    "Bid Status" VARCHAR,
+);
+CREATE INDEX IF NOT EXISTS idx_offers ON offers ("Date Time");
+COMMENT ON TABLE offers IS 'NYISO masked generator bids, DAM and HAM';
+CREATE OR REPLACE VIEW offers_long AS
+{segments};
+COMMIT;
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::{error::Error, fs, io::Write, path::Path};
+
+    use duckdb::Connection;
+    use itertools::Itertools;
+    use jiff::Timestamp;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        db::{nyiso::energy_offers::*, prod_db::ProdDb},
+        interval::month::month,
+        utils::compression::create_gz,
+    };
+
+    #[test]
+    fn test_offers_long() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(&setup_sql())?;
+        conn.execute_batch(
+            r#"
+INSERT INTO offers ("Masked Gen ID", "Date Time", "Market", "Upper Oper Limit",
+    "Dispatch $/MW1", "Dispatch MW1", "Dispatch $/MW2", "Dispatch MW2", "Bid Status")
+VALUES (35537750, '2024-03-01 00:00:00-05:00', 'DAM', 200, 15.6, 150, 25.0, 180, 'ACCEPTED');
+"#,
+        )?;
+        let mut stmt = conn.prepare(
+            "SELECT Segment, Quantity, Price FROM offers_long ORDER BY Segment;",
+        )?;
+        let rows: Vec<(i32, Decimal, Decimal)> = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get(0)?,
+                    match row.get_ref_unwrap(1) {
+                        duckdb::types::ValueRef::Decimal(v) => v,
+                        _ => Decimal::MIN,
+                    },
+                    match row.get_ref_unwrap(2) {
+                        duckdb::types::ValueRef::Decimal(v) => v,
+                        _ => Decimal::MIN,
+                    },
+                ))
+            })?
+            .collect::<Result<_, _>>()?;
+        assert_eq!(
+            rows,
+            vec![(0, dec!(150), dec!(15.6)), (1, dec!(30), dec!(25.0))]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_fall_dst() -> Result<(), Box<dyn Error>> {
+        let dir = std::env::temp_dir().join(format!("bust_nyiso_offers_{}", std::process::id()));
+        let archive = NyisoEnergyOffersArchive {
+            base_dir: dir.to_str().unwrap().to_string(),
+            duckdb_path: dir.join("offers.duckdb").to_str().unwrap().to_string(),
+        };
+        let dispatch = (1..=SEGMENTS)
+            .map(|i| format!("\"Dispatch $/MW{i}\",\"Dispatch MW{i}\""))
+            .join(",");
+        let header = format!("\"Masked Gen ID\",\"Date Time\",\"Market\",\"Upper Oper Limit\",\"Emer Upper Oper Limit\",{dispatch},\"Self Committed MW\",\"Bid Status\"\n");
+        let empty = ",".repeat(2 * SEGMENTS - 2);
+        let rows = ["00:00", "01:00", "01:00", "02:00"]
+            .iter()
+            .enumerate()
+            .map(|(i, hour)| {
+                format!(
+                    "35537750,\"11/03/2024 {hour}\",\"DAM\",200,210,{}.5,150{empty},0,\"ACCEPTED\"",
+                    20 + i
+                )
+            })
+            .join("\n");
+        let mut gz = create_gz(Path::new(&format!(
+            "{}.gz",
+            archive.filename(&month(2024, 11))
+        )))?;
+        gz.write_all(header.as_bytes())?;
+        gz.write_all(rows.as_bytes())?;
+        gz.finish()?;
+
+        // inserting twice doesn't duplicate the rows
+        Connection::open(&archive.duckdb_path)?.execute_batch(&setup_sql())?;
+        for _ in 0..2 {
+            Connection::open(&archive.duckdb_path)?
+                .execute_batch(&archive.update_sql(&month(2024, 11)))?;
+        }
+        let conn = Connection::open(&archive.duckdb_path)?;
+        let mut stmt = conn.prepare(
+            r#"SELECT "Date Time", "Dispatch $/MW1"::DOUBLE FROM offers ORDER BY "Date Time""#,
+        )?;
+        let rows: Vec<(String, f64)> = stmt
+            .query_map([], |row| {
+                let micro: i64 = row.get(0)?;
+                Ok((
+                    Timestamp::from_microsecond(micro).unwrap().to_string(),
+                    row.get(1)?,
+                ))
+            })?
+            .collect::<Result<_, _>>()?;
+        drop(stmt);
+        drop(conn);
+        fs::remove_dir_all(&dir)?;
+        assert_eq!(
+            rows,
+            vec![
+                ("2024-11-03T04:00:00Z".to_string(), 20.5),
+                ("2024-11-03T05:00:00Z".to_string(), 21.5),
+                ("2024-11-03T06:00:00Z".to_string(), 22.5),
+                ("2024-11-03T07:00:00Z".to_string(), 23.5),
+            ]
+        );
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn update_db() -> Result<(), Box<dyn Error>> {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+        dotenvy::from_path(Path::new(".env/test.env")).unwrap();
+        let archive = ProdDb::nyiso_energy_offers();
+        let months = month(2024, 1).up_to(month(2024, 3))?;
+        for month in months {
+            archive.download_file(&month)?;
+            archive.update_duckdb(&month)?;
+        }
+        Ok(())
+    }
+}
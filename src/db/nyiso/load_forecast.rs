@@ -0,0 +1,476 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use duckdb::Connection;
+use jiff::civil::Date;
+use jiff::{tz::TimeZone, Timestamp, ToSpan, Zoned};
+use log::{error, info};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::db::nyiso::rtlmp::dst_bounds;
+use crate::interval::month::Month;
+use crate::utils::compression::unzip_to_gz;
+use crate::utils::downloader::{downloader, AuthProfile};
+use crate::utils::serde_helpers::*;
+
+/// NYISO ISO load forecast, hourly by zone for the next 7 days.  Every daily
+/// file is a forecast vintage, identified by its `as_of_date`.
+#[derive(Clone)]
+pub struct NyisoLoadForecastArchive {
+    pub base_dir: String,
+    pub duckdb_path: String,
+}
+
+impl NyisoLoadForecastArchive {
+    /// Return the full file path of the zip file with data for the entire month
+    pub fn filename_zip(&self, month: &Month) -> String {
+        self.base_dir.to_owned()
+            + "/Raw/"
+            + month.year().to_string().as_str()
+            + "/"
+            + &month.strftime("%Y%m").to_string()
+            + "01isolf_csv.zip"
+    }
+
+    /// Return the file path of the csv file with the forecast made on one day
+    pub fn filename(&self, as_of_date: &Date) -> String {
+        self.base_dir.to_owned()
+            + "/Raw/"
+            + as_of_date.year().to_string().as_str()
+            + "/"
+            + &as_of_date.strftime("%Y%m%d").to_string()
+            + "isolf.csv"
+    }
+
+    /// The forecast is published every morning.
+    /// See https://mis.nyiso.com/public/csv/isolf/20250501isolf_csv.zip
+    /// Take the monthly zip file, extract it and compress each individual day as a gz file.
+    pub fn download_file(&self, month: &Month) -> Result<(), Box<dyn Error>> {
+        let binding = self.filename_zip(month);
+        let zip_path = Path::new(&binding);
+        fs::create_dir_all(zip_path.parent().unwrap())?;
+
+        let url = format!(
+            "https://mis.nyiso.com/public/csv/isolf/{}",
+            zip_path.file_name().unwrap().to_str().unwrap()
+        );
+        downloader().download_file(&url, AuthProfile::Nyiso, None, zip_path, false)?;
+        info!("downloaded file: {}", binding);
+
+        info!("Unzipping file {:?}", zip_path);
+        unzip_to_gz(zip_path, |name| {
+            let day: Date = Date::strptime("%Y%m%d", name.get(0..8).unwrap_or_default())
+                .map_err(|_| format!("Invalid date in filename: {}", name))?;
+            Ok(Some(self.filename(&day)))
+        })?;
+
+        fs::remove_file(zip_path)?;
+        info!("removed zip file {:?}", zip_path);
+
+        Ok(())
+    }
+
+    /// SQL to insert the forecast vintages published in the month.  The
+    /// `Time Stamp` column is New York local time, the repeated hour at the
+    /// end of DST is in EDT the first time it shows up in a file, and in EST
+    /// the second time.
+    fn update_sql(&self, month: &Month) -> String {
+        let (edt_start, edt_end, repeat_end) = dst_bounds(month.year());
+        format!(
+            r#"
+CREATE TABLE IF NOT EXISTS load_forecast (
+    as_of_date DATE NOT NULL,
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    zone VARCHAR NOT NULL,
+    load DECIMAL(9,1) NOT NULL,
+);
+
+CREATE TEMPORARY TABLE raw AS
+    SELECT *, row_number() OVER () AS rn
+    FROM read_csv('{dir}/Raw/{year}/{yyyymm}*isolf.csv.gz', header = true, all_varchar = true, filename = true);
+
+CREATE TEMPORARY TABLE tmp
+AS (
+    SELECT
+        strptime(regexp_extract(filename, '(\d{{8}})isolf', 1), '%Y%m%d')::DATE AS as_of_date,
+        (strftime(local, '%Y-%m-%d %H:%M:%S') || if(
+            local >= '{edt_start}' AND (local < '{edt_end}' OR (local < '{repeat_end}' AND occurrence = 1)),
+            '-04:00', '-05:00'))::TIMESTAMPTZ AS hour_beginning,
+        zone,
+        load::DECIMAL(9,1) AS load
+    FROM (
+        SELECT *,
+            strptime("Time Stamp", ['%m/%d/%Y %H:%M:%S', '%m/%d/%Y %H:%M']) AS local,
+            row_number() OVER (PARTITION BY filename, zone, "Time Stamp" ORDER BY rn) AS occurrence
+        FROM (
+            UNPIVOT raw
+            ON COLUMNS(* EXCLUDE ("Time Stamp", filename, rn))
+            INTO NAME zone VALUE load
+        )
+    )
+);
+
+INSERT INTO load_forecast
+(
+    SELECT * FROM tmp t
+    WHERE NOT EXISTS (
+        SELECT * FROM load_forecast d
+        WHERE
+            d.as_of_date = t.as_of_date AND
+            d.hour_beginning = t.hour_beginning AND
+            d.zone = t.zone
+    )
+)
+ORDER BY as_of_date, hour_beginning, zone;
+        "#,
+            dir = self.base_dir,
+            year = month.year(),
+            yyyymm = month.strftime("%Y%m"),
+            edt_start = edt_start.strftime("%Y-%m-%d %H:%M:%S"),
+            edt_end = edt_end.strftime("%Y-%m-%d %H:%M:%S"),
+            repeat_end = repeat_end.strftime("%Y-%m-%d %H:%M:%S"),
+        )
+    }
+
+    /// Update duckdb with the forecast vintages published in the month.
+    /// Vintages already in the table are skipped.
+    ///
+    /// The NYISO files are wide, with a `Time Stamp` column (hour beginning)
+    /// and one column for each of the 11 zones plus `NYISO` for the total.
+    pub fn update_duckdb(&self, month: &Month) -> Result<(), Box<dyn Error>> {
+        info!("inserting NYISO load forecast for month {} ...", month);
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(self.update_sql(month))
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for month {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub as_of_date: Date,
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    pub zone: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub load: Decimal,
+}
+
+pub fn get_data(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    as_of_date,
+    hour_beginning,
+    zone,
+    load
+FROM load_forecast WHERE 1=1"#,
+    );
+    if let Some(as_of_date) = &query_filter.as_of_date {
+        query.push_str(&format!(
+            "
+    AND as_of_date = '{}'",
+            as_of_date
+        ));
+    }
+    if let Some(as_of_date_gte) = &query_filter.as_of_date_gte {
+        query.push_str(&format!(
+            "
+    AND as_of_date >= '{}'",
+            as_of_date_gte
+        ));
+    }
+    if let Some(as_of_date_lte) = &query_filter.as_of_date_lte {
+        query.push_str(&format!(
+            "
+    AND as_of_date <= '{}'",
+            as_of_date_lte
+        ));
+    }
+    if let Some(hour_beginning_gte) = &query_filter.hour_beginning_gte {
+        query.push_str(&format!(
+            "
+    AND hour_beginning >= '{}'",
+            hour_beginning_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(hour_beginning_lt) = &query_filter.hour_beginning_lt {
+        query.push_str(&format!(
+            "
+    AND hour_beginning < '{}'",
+            hour_beginning_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(zone) = &query_filter.zone {
+        query.push_str(&format!(
+            "
+    AND zone = '{}'",
+            zone
+        ));
+    }
+    if let Some(zone_in) = &query_filter.zone_in {
+        query.push_str(&format!(
+            "
+    AND zone IN ('{}')",
+            zone_in.join("','")
+        ));
+    }
+    query.push_str(
+        "
+ORDER BY as_of_date, hour_beginning, zone",
+    );
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _n0 = 719528 + row.get::<usize, i32>(0)?;
+        let as_of_date = Date::ZERO + _n0.days();
+        let _micros1: i64 = row.get::<usize, i64>(1)?;
+        let hour_beginning = Zoned::new(
+            Timestamp::from_microsecond(_micros1).unwrap(),
+            TimeZone::get("America/New_York").unwrap(),
+        );
+        let load: Decimal = match row.get_ref_unwrap(3) {
+            duckdb::types::ValueRef::Decimal(v) => v,
+            _ => Decimal::MIN,
+        };
+        Ok(Record {
+            as_of_date,
+            hour_beginning,
+            zone: row.get::<usize, String>(2)?,
+            load,
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub as_of_date: Option<Date>,
+    pub as_of_date_gte: Option<Date>,
+    pub as_of_date_lte: Option<Date>,
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    pub zone: Option<String>,
+    pub zone_in: Option<Vec<String>>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.as_of_date {
+            params.insert("as_of_date", value.to_string());
+        }
+        if let Some(value) = &self.as_of_date_gte {
+            params.insert("as_of_date_gte", value.to_string());
+        }
+        if let Some(value) = &self.as_of_date_lte {
+            params.insert("as_of_date_lte", value.to_string());
+        }
+        if let Some(value) = &self.hour_beginning_gte {
+            params.insert("hour_beginning_gte", value.to_string());
+        }
+        if let Some(value) = &self.hour_beginning_lt {
+            params.insert("hour_beginning_lt", value.to_string());
+        }
+        if let Some(value) = &self.zone {
+            params.insert("zone", value.to_string());
+        }
+        if let Some(value) = &self.zone_in {
+            params.insert("zone_in", value.join(","));
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn as_of_date(mut self, value: Date) -> Self {
+        self.inner.as_of_date = Some(value);
+        self
+    }
+
+    pub fn as_of_date_gte(mut self, value: Date) -> Self {
+        self.inner.as_of_date_gte = Some(value);
+        self
+    }
+
+    pub fn as_of_date_lte(mut self, value: Date) -> Self {
+        self.inner.as_of_date_lte = Some(value);
+        self
+    }
+
+    pub fn hour_beginning_gte(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_gte = Some(value);
+        self
+    }
+
+    pub fn hour_beginning_lt(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_lt = Some(value);
+        self
+    }
+
+    pub fn zone<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.zone = Some(value.into());
+        self
+    }
+
+    pub fn zone_in(mut self, values_in: Vec<String>) -> Self {
+        self.inner.zone_in = Some(values_in);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::prod_db::ProdDb, interval::month::month, utils::compression::create_gz};
+    use duckdb::Connection;
+    use jiff::civil::date;
+    use rust_decimal_macros::dec;
+    use std::error::Error;
+    use std::io::Write;
+
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE load_forecast (
+    as_of_date DATE NOT NULL,
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    zone VARCHAR NOT NULL,
+    load DECIMAL(9,1) NOT NULL,
+);
+INSERT INTO load_forecast VALUES
+    ('2025-05-01', '2025-05-02 16:00:00-04:00', 'NYISO', 17105),
+    ('2025-05-02', '2025-05-02 16:00:00-04:00', 'NYISO', 17342),
+    ('2025-05-02', '2025-05-02 16:00:00-04:00', 'N.Y.C.', 6012);
+",
+        )?;
+        // all the vintages for one hour
+        let filter = QueryFilterBuilder::new().zone("NYISO").build();
+        let xs = get_data(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].as_of_date, date(2025, 5, 1));
+        assert_eq!(xs[1].load, dec!(17342));
+
+        let filter = QueryFilterBuilder::new()
+            .as_of_date(date(2025, 5, 2))
+            .build();
+        assert_eq!(get_data(&conn, &filter, None)?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_fall_dst() -> Result<(), Box<dyn Error>> {
+        let dir = std::env::temp_dir().join(format!("bust_nyiso_isolf_{}", std::process::id()));
+        let archive = NyisoLoadForecastArchive {
+            base_dir: dir.to_str().unwrap().to_string(),
+            duckdb_path: dir.join("isolf.duckdb").to_str().unwrap().to_string(),
+        };
+        let path = format!("{}.gz", archive.filename(&date(2024, 11, 2)));
+        let mut gz = create_gz(Path::new(&path))?;
+        gz.write_all(
+            b"\"Time Stamp\",\"N.Y.C.\",\"NYISO\"
+\"11/03/2024 00:00\",5001,15001
+\"11/03/2024 01:00\",5002,15002
+\"11/03/2024 01:00\",5003,15003
+\"11/03/2024 02:00\",5004,15004",
+        )?;
+        gz.finish()?;
+
+        // inserting twice doesn't duplicate the rows
+        for _ in 0..2 {
+            Connection::open(&archive.duckdb_path)?
+                .execute_batch(&archive.update_sql(&month(2024, 11)))?;
+        }
+        let conn = Connection::open(&archive.duckdb_path)?;
+        let filter = QueryFilterBuilder::new().zone("NYISO").build();
+        let xs = get_data(&conn, &filter, None)?;
+        drop(conn);
+        fs::remove_dir_all(&dir)?;
+        assert_eq!(
+            xs.iter()
+                .map(|e| (e.hour_beginning.timestamp().to_string(), e.load))
+                .collect::<Vec<_>>(),
+            vec![
+                ("2024-11-03T04:00:00Z".to_string(), dec!(15001)),
+                ("2024-11-03T05:00:00Z".to_string(), dec!(15002)),
+                ("2024-11-03T06:00:00Z".to_string(), dec!(15003)),
+                ("2024-11-03T07:00:00Z".to_string(), dec!(15004)),
+            ]
+        );
+        assert_eq!(xs[2].as_of_date, date(2024, 11, 2));
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn update_db() -> Result<(), Box<dyn Error>> {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+        let archive = ProdDb::nyiso_load_forecast();
+        let months = month(2025, 1).up_to(month(2025, 5))?;
+        for month in months {
+            archive.download_file(&month)?;
+            archive.update_duckdb(&month)?;
+        }
+        Ok(())
+    }
+}
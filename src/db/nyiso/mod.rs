@@ -4,7 +4,9 @@ pub mod capacity_prices_monthly;
 pub mod capacity_seasons;
 pub mod dalmp;
 pub mod energy_offers;
+pub mod load_forecast;
 pub mod ptid_table;
+pub mod rt_load;
 pub mod rtlmp;
 pub mod scheduled_outages;
 pub mod transmission_outages_da;
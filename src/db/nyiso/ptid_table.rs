@@ -12,11 +12,12 @@ use url::form_urlencoded;
 
 use convert_case::{Case, Casing};
 use log::{error, info};
-use std::fs::File;
-use std::io::Read;
+use std::path::Path;
 use std::process::Command;
 use std::str::FromStr;
 
+use crate::utils::downloader::{downloader, AuthProfile};
+
 #[derive(Clone)]
 pub struct NyisoPtidTableArchive {
     pub base_dir: String,
@@ -39,26 +40,8 @@ impl NyisoPtidTableArchive {
         let binding = self.filename(&Zoned::now().date());
 
         let url = "http://mis.nyiso.com/public/csv/generator/generator.csv";
-        let mut resp = reqwest::blocking::get(url)?;
-        let mut out = File::create(&binding)?;
-        std::io::copy(&mut resp, &mut out)?;
-        info!("downloaded file: {}", binding);
-
-        // Gzip the csv file
-        let mut csv_file = File::open(&binding)?;
-        let mut csv_data = Vec::new();
-        csv_file.read_to_end(&mut csv_data)?;
-        let gz_path = format!("{}.gz", binding);
-        let mut gz_file = File::create(&gz_path)?;
-        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
-        use std::io::Write;
-        encoder.write_all(&csv_data)?;
-        let compressed_data = encoder.finish()?;
-        gz_file.write_all(&compressed_data)?;
-        info!(" -- gzipped file to {}", gz_path);
-
-        // Remove the original csv file
-        std::fs::remove_file(&binding)?;
+        downloader().download_file(url, AuthProfile::Nyiso, None, Path::new(&binding), true)?;
+        info!("downloaded file: {}.gz", binding);
 
         Ok(())
     }
@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use duckdb::Connection;
+use jiff::civil::Date;
+use jiff::{tz::TimeZone, Timestamp, Zoned};
+use log::{error, info};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use crate::interval::month::Month;
+use crate::utils::compression::unzip_to_gz;
+use crate::utils::downloader::{downloader, AuthProfile};
+use crate::utils::serde_helpers::*;
+
+/// NYISO integrated real-time actual load, hourly, by zone.
+#[derive(Clone)]
+pub struct NyisoRtLoadArchive {
+    pub base_dir: String,
+    pub duckdb_path: String,
+}
+
+impl NyisoRtLoadArchive {
+    /// Return the full file path of the zip file with data for the entire month
+    pub fn filename_zip(&self, month: &Month) -> String {
+        self.base_dir.to_owned()
+            + "/Raw/"
+            + month.year().to_string().as_str()
+            + "/"
+            + &month.strftime("%Y%m").to_string()
+            + "01palIntegrated_csv.zip"
+    }
+
+    /// Return the file path of the csv file with data for one day
+    pub fn filename(&self, day: &Date) -> String {
+        self.base_dir.to_owned()
+            + "/Raw/"
+            + day.year().to_string().as_str()
+            + "/"
+            + &day.strftime("%Y%m%d").to_string()
+            + "palIntegrated.csv"
+    }
+
+    /// Data for the previous day is published in the morning.
+    /// See https://mis.nyiso.com/public/csv/palIntegrated/20250501palIntegrated_csv.zip
+    /// Take the monthly zip file, extract it and compress each individual day as a gz file.
+    pub fn download_file(&self, month: &Month) -> Result<(), Box<dyn Error>> {
+        let binding = self.filename_zip(month);
+        let zip_path = Path::new(&binding);
+        fs::create_dir_all(zip_path.parent().unwrap())?;
+
+        let url = format!(
+            "https://mis.nyiso.com/public/csv/palIntegrated/{}",
+            zip_path.file_name().unwrap().to_str().unwrap()
+        );
+        downloader().download_file(&url, AuthProfile::Nyiso, None, zip_path, false)?;
+        info!("downloaded file: {}", binding);
+
+        info!("Unzipping file {:?}", zip_path);
+        unzip_to_gz(zip_path, |name| {
+            let day: Date = Date::strptime("%Y%m%d", name.get(0..8).unwrap_or_default())
+                .map_err(|_| format!("Invalid date in filename: {}", name))?;
+            Ok(Some(self.filename(&day)))
+        })?;
+
+        fs::remove_file(zip_path)?;
+        info!("removed zip file {:?}", zip_path);
+
+        Ok(())
+    }
+
+    /// Update duckdb with published data for the month.  Rows already in the
+    /// table are skipped.
+    ///
+    /// The NYISO files have columns `Time Stamp` (hour beginning), `Time Zone`
+    /// (EST or EDT), `Name`, `PTID` and `Integrated Load`.
+    pub fn update_duckdb(&self, month: &Month) -> Result<(), Box<dyn Error>> {
+        info!("inserting NYISO integrated RT load for month {} ...", month);
+        let sql = format!(
+            r#"
+CREATE TABLE IF NOT EXISTS rt_load (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    ptid INTEGER NOT NULL,
+    name VARCHAR NOT NULL,
+    load DECIMAL(9,1) NOT NULL,
+);
+
+CREATE TEMPORARY TABLE tmp
+AS (
+    SELECT
+        strptime("Time Stamp" || ' ' || if("Time Zone" = 'EDT', '-04:00', '-05:00'),
+            ['%m/%d/%Y %H:%M:%S %z', '%m/%d/%Y %H:%M %z'])::TIMESTAMPTZ AS hour_beginning,
+        "PTID"::INTEGER AS ptid,
+        "Name"::VARCHAR AS name,
+        "Integrated Load"::DECIMAL(9,1) AS load
+    FROM read_csv('{}/Raw/{}/{}*palIntegrated.csv.gz', header = true, all_varchar = true)
+    WHERE "Integrated Load" IS NOT NULL
+);
+
+INSERT INTO rt_load
+(
+    SELECT * FROM tmp t
+    WHERE NOT EXISTS (
+        SELECT * FROM rt_load d
+        WHERE
+            d.hour_beginning = t.hour_beginning AND
+            d.ptid = t.ptid
+    )
+)
+ORDER BY hour_beginning, ptid;
+        "#,
+            self.base_dir,
+            month.year(),
+            month.strftime("%Y%m"),
+        );
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(&sql)
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for month {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(
+        serialize_with = "serialize_zoned_as_offset",
+        deserialize_with = "deserialize_zoned_assume_ny"
+    )]
+    pub hour_beginning: Zoned,
+    pub ptid: i32,
+    pub name: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub load: Decimal,
+}
+
+pub fn get_data(
+    conn: &Connection,
+    query_filter: &QueryFilter,
+    limit: Option<usize>,
+) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
+    let mut query = String::from(
+        r#"
+SELECT
+    hour_beginning,
+    ptid,
+    name,
+    load
+FROM rt_load WHERE 1=1"#,
+    );
+    if let Some(hour_beginning_gte) = &query_filter.hour_beginning_gte {
+        query.push_str(&format!(
+            "
+    AND hour_beginning >= '{}'",
+            hour_beginning_gte.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(hour_beginning_lt) = &query_filter.hour_beginning_lt {
+        query.push_str(&format!(
+            "
+    AND hour_beginning < '{}'",
+            hour_beginning_lt.strftime("%Y-%m-%d %H:%M:%S.000%:z")
+        ));
+    }
+    if let Some(ptid) = &query_filter.ptid {
+        query.push_str(&format!(
+            "
+    AND ptid = {}",
+            ptid
+        ));
+    }
+    if let Some(ptid_in) = &query_filter.ptid_in {
+        query.push_str(&format!(
+            "
+    AND ptid IN ({})",
+            ptid_in
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        ));
+    }
+    if let Some(name) = &query_filter.name {
+        query.push_str(&format!(
+            "
+    AND name = '{}'",
+            name
+        ));
+    }
+    if let Some(name_in) = &query_filter.name_in {
+        query.push_str(&format!(
+            "
+    AND name IN ('{}')",
+            name_in.join("','")
+        ));
+    }
+    query.push_str(
+        "
+ORDER BY hour_beginning, ptid",
+    );
+    match limit {
+        Some(l) => {
+            query.push_str(&format!(
+                "
+LIMIT {};",
+                l
+            ));
+        }
+        None => {
+            query.push(';');
+        }
+    }
+
+    let mut stmt = conn.prepare(&query)?;
+    let rows = stmt.query_map([], |row| {
+        let _micros0: i64 = row.get::<usize, i64>(0)?;
+        let hour_beginning = Zoned::new(
+            Timestamp::from_microsecond(_micros0).unwrap(),
+            TimeZone::get("America/New_York").unwrap(),
+        );
+        let load: Decimal = match row.get_ref_unwrap(3) {
+            duckdb::types::ValueRef::Decimal(v) => v,
+            _ => Decimal::MIN,
+        };
+        Ok(Record {
+            hour_beginning,
+            ptid: row.get::<usize, i32>(1)?,
+            name: row.get::<usize, String>(2)?,
+            load,
+        })
+    })?;
+    let results: Vec<Record> = rows.collect::<Result<_, _>>()?;
+    Ok(results)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct QueryFilter {
+    pub hour_beginning_gte: Option<Zoned>,
+    pub hour_beginning_lt: Option<Zoned>,
+    pub ptid: Option<i32>,
+    pub ptid_in: Option<Vec<i32>>,
+    pub name: Option<String>,
+    pub name_in: Option<Vec<String>>,
+}
+
+impl QueryFilter {
+    pub fn to_query_url(&self) -> String {
+        let mut params = HashMap::new();
+        if let Some(value) = &self.hour_beginning_gte {
+            params.insert("hour_beginning_gte", value.to_string());
+        }
+        if let Some(value) = &self.hour_beginning_lt {
+            params.insert("hour_beginning_lt", value.to_string());
+        }
+        if let Some(value) = &self.ptid {
+            params.insert("ptid", value.to_string());
+        }
+        if let Some(value) = &self.ptid_in {
+            let joined = value
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            params.insert("ptid_in", joined);
+        }
+        if let Some(value) = &self.name {
+            params.insert("name", value.to_string());
+        }
+        if let Some(value) = &self.name_in {
+            params.insert("name_in", value.join(","));
+        }
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&params)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct QueryFilterBuilder {
+    inner: QueryFilter,
+}
+
+impl QueryFilterBuilder {
+    pub fn new() -> Self {
+        Self {
+            inner: QueryFilter::default(),
+        }
+    }
+
+    pub fn build(self) -> QueryFilter {
+        self.inner
+    }
+
+    pub fn hour_beginning_gte(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_gte = Some(value);
+        self
+    }
+
+    pub fn hour_beginning_lt(mut self, value: Zoned) -> Self {
+        self.inner.hour_beginning_lt = Some(value);
+        self
+    }
+
+    pub fn ptid(mut self, value: i32) -> Self {
+        self.inner.ptid = Some(value);
+        self
+    }
+
+    pub fn ptid_in(mut self, values_in: Vec<i32>) -> Self {
+        self.inner.ptid_in = Some(values_in);
+        self
+    }
+
+    pub fn name<S: Into<String>>(mut self, value: S) -> Self {
+        self.inner.name = Some(value.into());
+        self
+    }
+
+    pub fn name_in(mut self, values_in: Vec<String>) -> Self {
+        self.inner.name_in = Some(values_in);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::prod_db::ProdDb, interval::month::month};
+    use duckdb::Connection;
+    use rust_decimal_macros::dec;
+    use std::error::Error;
+
+    #[test]
+    fn test_get_data() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+CREATE TABLE rt_load (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    ptid INTEGER NOT NULL,
+    name VARCHAR NOT NULL,
+    load DECIMAL(9,1) NOT NULL,
+);
+INSERT INTO rt_load VALUES
+    ('2025-05-01 00:00:00-04:00', 61757, 'CAPITL', 1012.3),
+    ('2025-05-01 00:00:00-04:00', 61761, 'N.Y.C.', 4650.9),
+    ('2025-05-01 01:00:00-04:00', 61761, 'N.Y.C.', 4423.1);
+",
+        )?;
+        let filter = QueryFilterBuilder::new()
+            .name("N.Y.C.")
+            .hour_beginning_gte("2025-05-01 00:00[America/New_York]".parse()?)
+            .build();
+        let xs = get_data(&conn, &filter, None)?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].load, dec!(4650.9));
+        assert_eq!(
+            xs[1].hour_beginning,
+            "2025-05-01 01:00[America/New_York]".parse::<Zoned>()?
+        );
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn update_db() -> Result<(), Box<dyn Error>> {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+        let archive = ProdDb::nyiso_rt_load();
+        let months = month(2025, 1).up_to(month(2025, 5))?;
+        for month in months {
+            archive.download_file(&month)?;
+            archive.update_duckdb(&month)?;
+        }
+        Ok(())
+    }
+}
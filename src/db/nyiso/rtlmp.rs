@@ -1,18 +1,77 @@
-// use duckdb::Connection;
-// use itertools::Itertools;
-// use jiff::{civil::*, tz, Timestamp, Zoned};
-// use log::{error, info};
-// use reqwest::blocking::get;
-// use rust_decimal::Decimal;
-// use serde::{Deserialize, Deserializer, Serialize};
-// use std::error::Error;
-// use std::fmt;
-// use std::fs::{self, File};
-// use std::path::Path;
-// use std::process::Command;
-// use std::str::FromStr;
-
-// use crate::interval::month::Month;
+use duckdb::Connection;
+use itertools::Itertools;
+use jiff::{civil::*, tz, Timestamp, Zoned};
+use log::{error, info};
+use rust_decimal::Decimal;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::db::data_quality::{FileGrid, Frequency, QualitySpec, TableGrid, ValueRange};
+use crate::db::nyiso::dalmp::{LmpComponent, NodeType, Row};
+use crate::interval::month::Month;
+use crate::utils::compression::unzip_to_gz;
+use crate::utils::downloader::{downloader, AuthProfile};
+
+/// NYISO publishes two real-time price reports:
+/// - `Hourly`: the time-weighted (integrated) hourly RT LBMP, in the `rtlbmp` folder
+/// - `FiveMinute`: the RTD interval prices, in the `realtime` folder
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RtReport {
+    Hourly,
+    FiveMinute,
+}
+
+impl RtReport {
+    /// Name of the NYISO folder, also used as the file name suffix
+    pub fn folder(&self) -> &'static str {
+        match self {
+            RtReport::Hourly => "rtlbmp",
+            RtReport::FiveMinute => "realtime",
+        }
+    }
+
+    /// Name of the DuckDB table
+    pub fn table(&self) -> &'static str {
+        match self {
+            RtReport::Hourly => "rtlmp",
+            RtReport::FiveMinute => "rtlmp5",
+        }
+    }
+
+    /// Name of the timestamp column in the DuckDB table
+    fn time_column(&self) -> &'static str {
+        match self {
+            RtReport::Hourly => "hour_beginning",
+            RtReport::FiveMinute => "interval_beginning",
+        }
+    }
+}
+
+fn node_suffix(node_type: NodeType) -> &'static str {
+    match node_type {
+        NodeType::Gen => "gen",
+        NodeType::Zone => "zone",
+    }
+}
+
+/// The DST period of the year in New York, as local times.  EDT starts at
+/// `edt_start`, EST is back at `edt_end`, and the local times in
+/// `[edt_end, repeat_end)` happen twice, first in EDT and then in EST.
+pub(crate) fn dst_bounds(year: i16) -> (DateTime, DateTime, DateTime) {
+    let tz = tz::TimeZone::get("America/New_York").unwrap();
+    let start = date(year, 1, 1).to_zoned(tz.clone()).unwrap().timestamp();
+    let mut transitions = tz.following(start);
+    let spring = transitions.next().unwrap();
+    let fall = transitions.next().unwrap();
+    (
+        spring.offset().to_datetime(spring.timestamp()),
+        fall.offset().to_datetime(fall.timestamp()),
+        spring.offset().to_datetime(fall.timestamp()),
+    )
+}
 
 #[derive(Clone)]
 pub struct NyisoRtlmpArchive {
@@ -20,283 +79,445 @@ pub struct NyisoRtlmpArchive {
     pub duckdb_path: String,
 }
 
-// impl NyisoRtlmpArchive {
-//     /// Get data from DuckDB.
-//     /// If `ptids` is `None`, return all of them.
-//     /// If `components` is `None`, return all three LMP components (lmp, mcc, mlc).
-//     ///
-//     pub fn get_data(
-//         &self,
-//         conn: &Connection,
-//         start_date: Date,
-//         end_date: Date,
-//         component: LmpComponent,
-//         ptids: Option<Vec<i32>>,
-//     ) -> Result<Vec<Row>, Box<dyn Error>> {
-//         let mut query = "SELECT ptid, hour_beginning, ".to_string();
-//         query.push_str(&format!("{} FROM dalmp ", match component {
-//             LmpComponent::Lmp => "lmp",
-//             LmpComponent::Mcl => "mlc",
-//             LmpComponent::Mcc => "mcc",
-//         }));
-//         query = format!(
-//             r#"
-//             {}
-//             WHERE hour_beginning >= '{}'
-//             AND hour_beginning < '{}'
-//             {}
-//             ORDER BY ptid, hour_beginning;
-//         "#,
-//             query,
-//             start_date
-//                 .to_zoned(tz::TimeZone::get("America/New_York").unwrap())
-//                 .unwrap()
-//                 .strftime("%Y-%m-%d %H:%M:%S%:z"),
-//             end_date
-//                 .tomorrow()
-//                 .unwrap()
-//                 .to_zoned(tz::TimeZone::get("America/New_York").unwrap())
-//                 .unwrap()
-//                 .strftime("%Y-%m-%d %H:%M:%S%:z"),
-//             match ptids {
-//                 Some(ids) => format!("AND ptid in ({})", ids.iter().join(",")),
-//                 None => "".to_string(),
-//             }
-//         );
-//         // println!("{}", query);
-//         let mut stmt = conn.prepare(&query).unwrap();
-//         let res_iter = stmt.query_map([], |row| {
-//             let value = match row.get_ref_unwrap(2) {
-//                 duckdb::types::ValueRef::Decimal(v) => v,
-//                 _ => Decimal::MIN,
-//             };
-//             let micro: i64 = row.get(1).unwrap();
-//             let ts = Timestamp::from_second(micro / 1_000_000).unwrap();
-//             Ok(Row {
-//                 hour_beginning: Zoned::new(
-//                     ts,
-//                     jiff::tz::TimeZone::get("America/New_York").unwrap(),
-//                 ),
-//                 ptid: row.get(0).unwrap(),
-//                 component,
-//                 value,
-//             })
-//         })?;
-//         let res: Vec<Row> = res_iter.map(|e| e.unwrap()).collect();
-//         Ok(res)
-//     }
-
-//     /// Return the full file path of the zip file with data for the entire month
-//     pub fn filename(&self, month: &Month, node_type: NodeType) -> String {
-//         match node_type {
-//             NodeType::Gen => {
-//                 self.base_dir.to_owned()
-//                     + "/Raw/"
-//                     + &month.start_date().strftime("%Y%m%d").to_string()
-//                     + "damlbmp_gen_csv.zip"
-//             }
-//             NodeType::Zone => {
-//                 self.base_dir.to_owned()
-//                     + "/Raw/"
-//                     + &month.start_date().strftime("%Y%m%d").to_string()
-//                     + "damlbmp_zone_csv.zip"
-//             }
-//         }
-//     }
-
-//     /// Data is published around 10:30 every day
-//     /// https://mis.nyiso.com/public/csv/damlbmp/20250501damlbmp_gen_csv.zip
-//     pub fn download_file(&self, month: Month, node_type: NodeType) -> Result<(), Box<dyn Error>> {
-//         let binding = self.filename(&month, node_type);
-//         let path = Path::new(&binding);
-
-//         let url = format!(
-//             "https://mis.nyiso.com/public/csv/damlbmp/{}",
-//             path.file_name().unwrap().to_str().unwrap()
-//         );
-//         let mut resp = get(url)?;
-//         let mut out = File::create(&binding)?;
-//         std::io::copy(&mut resp, &mut out)?;
-//         info!("downloaded file: {}", binding);
-//         Ok(())
-//     }
-
-//     pub fn setup(&self) -> Result<(), Box<dyn Error>> {
-//         info!("initializing NYISO DALMP archive ...");
-//         let dir = Path::new(&self.duckdb_path).parent().unwrap();
-//         fs::create_dir_all(dir)
-//             .unwrap_or_else(|_| panic!("Failed to create directory: {}", dir.display()));
-
-//         if fs::exists(&self.duckdb_path)? {
-//             fs::remove_file(&self.duckdb_path)?;
-//         }
-//         let conn = Connection::open(self.duckdb_path.clone())?;
-//         conn.execute_batch(
-//             r"
-//     BEGIN;
-//     CREATE TABLE IF NOT EXISTS dalmp (
-//         hour_beginning TIMESTAMPTZ NOT NULL,
-//         ptid INTEGER NOT NULL,
-//         lmp DECIMAL(9,2) NOT NULL,
-//         mlc DECIMAL(9,2) NOT NULL,
-//         mcc DECIMAL(9,2) NOT NULL,
-//     );
-//     CREATE INDEX idx ON dalmp (ptid);
-//     COMMENT ON TABLE dalmp IS 'Hourly DAM prices for all NYISO zones + generators';
-//     COMMIT;
-//         ",
-//         )?;
-//         Ok(())
-//     }
-
-//     /// Update duckdb with published data for the month.  No checks are made to see
-//     /// if there are missing files.  Does not delete any existing data.  So if data
-//     /// is wrong for some reason, it needs to be manually deleted first!
-//     ///
-//     pub fn update_duckdb(&self, month: Month) -> Result<(), Box<dyn Error>> {
-//         info!(
-//             "inserting zone + gen files from the monthly zip for {} ...",
-//             month
-//         );
-//         let sql = format!(
-//             r#"
-//         LOAD zipfs;
-//         CREATE TEMPORARY TABLE tmp1 AS SELECT * FROM 'zip://{}/*.csv';
-//         CREATE TEMPORARY TABLE tmp2 AS SELECT * FROM 'zip://{}/*.csv';
-
-//         CREATE TEMPORARY TABLE tmp AS
-//         (SELECT
-//             strptime("Time Stamp" || ' America/New_York' , '%m/%d/%Y %H:%M %Z')::TIMESTAMPTZ AS "hour_beginning",
-//             ptid::INTEGER AS ptid,
-//             "LBMP ($/MWHr)"::DECIMAL(9,2) AS "lmp",
-//             "Marginal Cost Losses ($/MWHr)"::DECIMAL(9,2) AS "mlc",
-//             "Marginal Cost Congestion ($/MWHr)"::DECIMAL(9,2) AS "mcc"
-//         FROM tmp1
-//         )
-//         UNION
-//         (SELECT
-//             strptime("Time Stamp" || ' America/New_York' , '%m/%d/%Y %H:%M %Z')::TIMESTAMPTZ AS "hour_beginning",
-//             ptid::INTEGER AS ptid,
-//             "LBMP ($/MWHr)"::DECIMAL(9,2) AS "lmp",
-//             "Marginal Cost Losses ($/MWHr)"::DECIMAL(9,2) AS "mlc",
-//             "Marginal Cost Congestion ($/MWHr)"::DECIMAL(9,2) AS "mcc"
-//         FROM tmp2
-//         )
-//         ORDER BY hour_beginning, ptid;
-
-//         INSERT INTO dalmp
-//         (SELECT hour_beginning, ptid, lmp, mlc, mcc FROM tmp
-//         WHERE NOT EXISTS (
-//             SELECT * FROM dalmp d
-//             WHERE d.hour_beginning = tmp.hour_beginning
-//             AND d.ptid = tmp.ptid
-//         ))
-//         ORDER BY hour_beginning, ptid;
-//         "#,
-//             self.filename(&month, NodeType::Zone),
-//             self.filename(&month, NodeType::Gen),
-//         );
-//         let output = Command::new("duckdb")
-//             .arg("-c")
-//             .arg(&sql)
-//             .arg(&self.duckdb_path)
-//             .output()
-//             .expect("Failed to invoke duckdb command");
-
-//         let stdout = String::from_utf8_lossy(&output.stdout);
-//         let stderr = String::from_utf8_lossy(&output.stderr);
-//         if output.status.success() {
-//             info!("{}", stdout);
-//             info!("done");
-//         } else {
-//             error!("Failed to update duckdb for month {}: {}", month, stderr);
-//         }
-
-//         Ok(())
-//     }
-// }
-
-// #[cfg(test)]
-// mod tests {
-
-//     use std::{error::Error, path::Path, vec};
-
-//     use rust_decimal_macros::dec;
-
-//     use crate::{
-//         db::{nyiso::dalmp::*, prod_db::ProdDb},
-//         interval::month::month,
-//     };
-
-//     #[ignore]
-//     #[test]
-//     fn update_db() -> Result<(), Box<dyn Error>> {
-//         let _ = env_logger::builder()
-//             .filter_level(log::LevelFilter::Info)
-//             .is_test(true)
-//             .try_init();
-//         dotenvy::from_path(Path::new(".env/test.env")).unwrap();
-//         let archive = ProdDb::nyiso_dalmp();
-//         archive.setup()?;
-
-//         let months = month(2020, 1).up_to(month(2025, 10))?;
-//         for month in months {
-//             println!("Processing month {}", month);
-//             archive.update_duckdb(month)?;
-//         }
-//         Ok(())
-//     }
-
-//     #[test]
-//     fn get_data_test() -> Result<(), Box<dyn Error>> {
-//         dotenvy::from_path(Path::new(".env/test.env")).unwrap();
-//         let archive = ProdDb::nyiso_dalmp();
-//         let conn = duckdb::Connection::open(archive.duckdb_path.clone())?;
-//         // test a zone location at DST
-//         let rows = archive.get_data(
-//             &conn,
-//             date(2024, 11, 3),
-//             date(2024, 11, 3),
-//             LmpComponent::Lmp,
-//             Some(vec![61752]),
-//         )?;
-//         assert_eq!(rows.len(), 25);
-//         let values = rows[0..=2].iter().map(|r| r.value).collect::<Vec<_>>();
-//         assert_eq!(values, vec![dec!(29.27), dec!(27.32), dec!(27.14)]);
-//         assert_eq!(
-//             rows[2].hour_beginning,
-//             "2024-11-03T01:00:00-05:00[America/New_York]".parse()?
-//         );
-//         assert_eq!(rows[2].value, dec!(27.14));
-
-//         // test a gen location
-//         let rows = archive.get_data(
-//             &conn,
-//             date(2025, 6, 27),
-//             date(2025, 6, 27),
-//             LmpComponent::Lmp,
-//             Some(vec![23575]),
-//         )?;
-//         assert_eq!(rows.len(), 24);
-//         assert_eq!(
-//             rows[0].hour_beginning,
-//             "2025-06-27T00:00:00-04:00[America/New_York]".parse()?
-//         );
-//         assert_eq!(rows[0].value, dec!(37.59));
-
-//         Ok(())
-//     }
-
-//     #[ignore]
-//     #[test]
-//     fn download_file() -> Result<(), Box<dyn Error>> {
-//         dotenvy::from_path(Path::new(".env/test.env")).unwrap();
-//         let archive = ProdDb::nyiso_dalmp();
-//         let months = month(2025, 6).up_to(month(2025, 6))?;
-//         for month in months {
-//             archive.download_file(month, NodeType::Gen)?;
-//             archive.download_file(month, NodeType::Zone)?;
-//         }
-//         Ok(())
-//     }
-// }
+impl NyisoRtlmpArchive {
+    /// Get hourly RT data from DuckDB, same shape as the DA data.
+    /// If `ptids` is `None`, return all of them.
+    ///
+    pub fn get_data(
+        &self,
+        conn: &Connection,
+        start_date: Date,
+        end_date: Date,
+        component: LmpComponent,
+        ptids: Option<Vec<i32>>,
+    ) -> Result<Vec<Row>, Box<dyn Error>> {
+        let query = format!(
+            r#"
+SELECT ptid, hour_beginning, {}
+FROM rtlmp
+WHERE hour_beginning >= '{}'
+AND hour_beginning < '{}'
+{}
+ORDER BY ptid, hour_beginning;
+        "#,
+            match component {
+                LmpComponent::Lmp => "lmp",
+                LmpComponent::Mcl => "mlc",
+                LmpComponent::Mcc => "mcc",
+            },
+            start_date
+                .to_zoned(tz::TimeZone::get("America/New_York").unwrap())
+                .unwrap()
+                .strftime("%Y-%m-%d %H:%M:%S%:z"),
+            end_date
+                .tomorrow()
+                .unwrap()
+                .to_zoned(tz::TimeZone::get("America/New_York").unwrap())
+                .unwrap()
+                .strftime("%Y-%m-%d %H:%M:%S%:z"),
+            match ptids {
+                Some(ids) => format!("AND ptid in ({})", ids.iter().join(",")),
+                None => "".to_string(),
+            }
+        );
+        let mut stmt = conn.prepare(&query)?;
+        let res_iter = stmt.query_map([], |row| {
+            let value = match row.get_ref_unwrap(2) {
+                duckdb::types::ValueRef::Decimal(v) => v,
+                _ => Decimal::MIN,
+            };
+            let micro: i64 = row.get(1)?;
+            Ok(Row {
+                hour_beginning: Zoned::new(
+                    Timestamp::from_microsecond(micro).unwrap(),
+                    tz::TimeZone::get("America/New_York").unwrap(),
+                ),
+                ptid: row.get(0)?,
+                component,
+                value,
+            })
+        })?;
+        let res: Vec<Row> = res_iter.collect::<Result<_, _>>()?;
+        Ok(res)
+    }
+
+    /// Return the full file path of the zip file with data for the entire month,
+    /// e.g. `20250501rtlbmp_zone_csv.zip`
+    pub fn filename_zip(&self, month: &Month, report: RtReport, node_type: NodeType) -> String {
+        format!(
+            "{}/Raw/{}{}_{}_csv.zip",
+            self.base_dir,
+            month.start_date().strftime("%Y%m%d"),
+            report.folder(),
+            node_suffix(node_type)
+        )
+    }
+
+    /// Return the file path of the csv file with data for one day.  The five
+    /// minute files are kept in a separate `5min` directory.
+    pub fn filename(&self, day: &Date, report: RtReport, node_type: NodeType) -> String {
+        let dir = match report {
+            RtReport::Hourly => format!("{}/Raw/{}", self.base_dir, day.year()),
+            RtReport::FiveMinute => format!("{}/Raw/5min/{}", self.base_dir, day.year()),
+        };
+        format!(
+            "{}/{}{}_{}.csv",
+            dir,
+            day.strftime("%Y%m%d"),
+            report.folder(),
+            node_suffix(node_type)
+        )
+    }
+
+    /// Hourly data for the previous day is published in the early morning.
+    /// See https://mis.nyiso.com/public/csv/rtlbmp/20250501rtlbmp_zone_csv.zip
+    /// and https://mis.nyiso.com/public/csv/realtime/20250501realtime_zone_csv.zip
+    /// Take the monthly zip file, extract it and compress each individual day as a gz file.
+    pub fn download_file(
+        &self,
+        month: Month,
+        report: RtReport,
+        node_type: NodeType,
+    ) -> Result<(), Box<dyn Error>> {
+        let binding = self.filename_zip(&month, report, node_type);
+        let zip_path = Path::new(&binding);
+
+        let url = format!(
+            "https://mis.nyiso.com/public/csv/{}/{}",
+            report.folder(),
+            zip_path.file_name().unwrap().to_str().unwrap()
+        );
+        downloader().download_file(&url, AuthProfile::Nyiso, None, zip_path, false)?;
+        info!("downloaded file: {}", binding);
+
+        // Unzip the file and gzip each daily csv file
+        info!("Unzipping file {:?}", zip_path);
+        unzip_to_gz(zip_path, |name| {
+            let day: Date = name
+                .get(0..8)
+                .unwrap_or_default()
+                .parse()
+                .map_err(|_| format!("Invalid date in filename: {}", name))?;
+            Ok(Some(self.filename(&day, report, node_type)))
+        })?;
+
+        // Remove the zip file
+        std::fs::remove_file(zip_path)?;
+        info!("removed zip file {:?}", zip_path);
+
+        Ok(())
+    }
+
+    pub fn setup(&self) -> Result<(), Box<dyn Error>> {
+        info!("initializing NYISO RTLMP archive ...");
+        let dir = Path::new(&self.duckdb_path).parent().unwrap();
+        fs::create_dir_all(dir)
+            .unwrap_or_else(|_| panic!("Failed to create directory: {}", dir.display()));
+
+        if fs::exists(&self.duckdb_path)? {
+            fs::remove_file(&self.duckdb_path)?;
+        }
+        let conn = Connection::open(self.duckdb_path.clone())?;
+        conn.execute_batch(
+            r"
+    BEGIN;
+    CREATE TABLE IF NOT EXISTS rtlmp (
+        hour_beginning TIMESTAMPTZ NOT NULL,
+        ptid INTEGER NOT NULL,
+        lmp DECIMAL(9,2) NOT NULL,
+        mlc DECIMAL(9,2) NOT NULL,
+        mcc DECIMAL(9,2) NOT NULL,
+    );
+    CREATE INDEX idx ON rtlmp (ptid);
+    COMMENT ON TABLE rtlmp IS 'Hourly integrated RT prices for all NYISO zones + generators';
+    CREATE TABLE IF NOT EXISTS rtlmp5 (
+        interval_beginning TIMESTAMPTZ NOT NULL,
+        ptid INTEGER NOT NULL,
+        lmp DECIMAL(9,2) NOT NULL,
+        mlc DECIMAL(9,2) NOT NULL,
+        mcc DECIMAL(9,2) NOT NULL,
+    );
+    CREATE INDEX idx5 ON rtlmp5 (ptid);
+    COMMENT ON TABLE rtlmp5 IS '5-min RTD prices for all NYISO zones + generators';
+    COMMIT;
+        ",
+        )?;
+        Ok(())
+    }
+
+    /// SQL to insert the zone + gen files of the month into the table of the report.
+    /// The hourly files have timestamps like `05/01/2025 00:00`, the five minute
+    /// files like `05/01/2025 00:05:00`.  The five minute timestamps are interval
+    /// ending, so they are shifted back 5 minutes to get the interval beginning.
+    ///
+    /// The files have no time zone column.  The timestamps are in local time, and
+    /// the local times repeated at the end of DST show up in file order, first EDT
+    /// then EST.  Each timestamp gets an explicit `-04:00` or `-05:00` offset.
+    fn update_sql(&self, month: Month, report: RtReport) -> String {
+        // glob over all the days of the month
+        let first = month.start_date();
+        let pattern = |node_type| {
+            self.filename(&first, report, node_type).replace(
+                &first.strftime("%Y%m%d").to_string(),
+                &format!("{}*", first.strftime("%Y%m")),
+            )
+        };
+        let (edt_start, edt_end, repeat_end) = dst_bounds(first.year());
+        let shift = match report {
+            RtReport::Hourly => 0,
+            RtReport::FiveMinute => 5,
+        };
+        let select = |tmp: &str| {
+            format!(
+                r#"SELECT
+            (strftime(local - INTERVAL {shift} MINUTE, '%Y-%m-%d %H:%M:%S') || if(
+                local >= '{edt_start}' AND (local < '{edt_end}' OR (local < '{repeat_end}' AND occurrence = 1)),
+                '-04:00', '-05:00'))::TIMESTAMPTZ AS "{time}",
+            ptid::INTEGER AS ptid,
+            "LBMP ($/MWHr)"::DECIMAL(9,2) AS "lmp",
+            "Marginal Cost Losses ($/MWHr)"::DECIMAL(9,2) AS "mlc",
+            "Marginal Cost Congestion ($/MWHr)"::DECIMAL(9,2) AS "mcc"
+        FROM (
+            SELECT *,
+                strptime("Time Stamp", ['%m/%d/%Y %H:%M:%S', '%m/%d/%Y %H:%M']) AS local,
+                row_number() OVER (PARTITION BY ptid, "Time Stamp" ORDER BY rn) AS occurrence
+            FROM {tmp}
+        )"#,
+                edt_start = edt_start.strftime("%Y-%m-%d %H:%M:%S"),
+                edt_end = edt_end.strftime("%Y-%m-%d %H:%M:%S"),
+                repeat_end = repeat_end.strftime("%Y-%m-%d %H:%M:%S"),
+                time = report.time_column(),
+            )
+        };
+        format!(
+            r#"
+        CREATE TEMPORARY TABLE tmp1 AS SELECT *, row_number() OVER () AS rn FROM read_csv('{zone}.gz', all_varchar = true);
+        CREATE TEMPORARY TABLE tmp2 AS SELECT *, row_number() OVER () AS rn FROM read_csv('{gen}.gz', all_varchar = true);
+
+        CREATE TEMPORARY TABLE tmp AS
+        ({select1})
+        UNION
+        ({select2})
+        ORDER BY {time}, ptid;
+
+        INSERT INTO {table}
+        (SELECT {time}, ptid, lmp, mlc, mcc FROM tmp
+        WHERE NOT EXISTS (
+            SELECT * FROM {table} d
+            WHERE d.{time} = tmp.{time}
+            AND d.ptid = tmp.ptid
+        ))
+        ORDER BY {time}, ptid;
+        "#,
+            zone = pattern(NodeType::Zone),
+            gen = pattern(NodeType::Gen),
+            select1 = select("tmp1"),
+            select2 = select("tmp2"),
+            time = report.time_column(),
+            table = report.table(),
+        )
+    }
+
+    /// Update duckdb with published data for the month.  No checks are made to see
+    /// if there are missing files.  Does not delete any existing data.  So if data
+    /// is wrong for some reason, it needs to be manually deleted first!
+    ///
+    pub fn update_duckdb(&self, month: Month, report: RtReport) -> Result<(), Box<dyn Error>> {
+        info!(
+            "inserting zone + gen {} files for the month {} ...",
+            report.folder(),
+            month
+        );
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(self.update_sql(month, report))
+            .arg(&self.duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() {
+            info!("{}", stdout);
+            info!("done");
+        } else {
+            error!("Failed to update duckdb for month {}: {}", month, stderr);
+        }
+
+        Ok(())
+    }
+
+    /// The expected grid: one row per ptid (zones and generators) per hour.
+    /// Raw files are checked for the zones only.
+    pub fn quality_spec(&self) -> QualitySpec {
+        let archive = self.clone();
+        QualitySpec {
+            name: "nyiso_rtlmp".to_string(),
+            duckdb_path: self.duckdb_path.clone(),
+            table: Some(TableGrid {
+                table: "rtlmp".to_string(),
+                time_column: "hour_beginning".to_string(),
+                key_columns: vec!["ptid".to_string()],
+                expected_keys: (61752..=61762).map(|e| e.to_string()).collect(),
+                frequency: Frequency::Hourly,
+                tz: "America/New_York".to_string(),
+                value_ranges: vec![
+                    ValueRange::new("lmp", Some(-1000.0), Some(5000.0)),
+                    ValueRange::new("mcc", Some(-5000.0), Some(5000.0)),
+                    ValueRange::new("mlc", Some(-500.0), Some(500.0)),
+                ],
+                condition: None,
+            }),
+            files: Some(FileGrid::Daily(Arc::new(move |day| {
+                archive.filename(day, RtReport::Hourly, NodeType::Zone)
+            }))),
+            skip_days: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::{error::Error, io::Write, path::Path};
+
+    use crate::{
+        db::{nyiso::rtlmp::*, prod_db::ProdDb},
+        interval::month::month,
+        utils::compression::create_gz,
+    };
+
+    #[test]
+    fn test_filenames() {
+        let archive = NyisoRtlmpArchive {
+            base_dir: "/tmp/RtLmp".to_string(),
+            duckdb_path: "/tmp/rtlmp.duckdb".to_string(),
+        };
+        assert_eq!(
+            archive.filename_zip(&month(2025, 5), RtReport::Hourly, NodeType::Zone),
+            "/tmp/RtLmp/Raw/20250501rtlbmp_zone_csv.zip"
+        );
+        assert_eq!(
+            archive.filename(&date(2025, 5, 3), RtReport::FiveMinute, NodeType::Gen),
+            "/tmp/RtLmp/Raw/5min/2025/20250503realtime_gen.csv"
+        );
+        let sql = archive.update_sql(month(2025, 5), RtReport::FiveMinute);
+        assert!(sql.contains("'/tmp/RtLmp/Raw/5min/2025/202505*realtime_zone.csv.gz'"));
+        assert!(sql.contains("INSERT INTO rtlmp5"));
+        assert!(sql.contains("AS \"interval_beginning\""));
+    }
+
+    #[test]
+    fn test_update_fall_dst() -> Result<(), Box<dyn Error>> {
+        let dir = std::env::temp_dir().join(format!("bust_nyiso_rtlmp_{}", std::process::id()));
+        let archive = NyisoRtlmpArchive {
+            base_dir: dir.to_str().unwrap().to_string(),
+            duckdb_path: dir.join("rtlmp.duckdb").to_str().unwrap().to_string(),
+        };
+        let header = "\"Time Stamp\",\"Name\",\"PTID\",\"LBMP ($/MWHr)\",\"Marginal Cost Losses ($/MWHr)\",\"Marginal Cost Congestion ($/MWHr)\"\n";
+        let day = date(2024, 11, 3);
+        let files = [
+            (
+                NodeType::Zone,
+                vec![
+                    "\"11/03/2024 01:00:00\",\"CAPITL\",61757,20.01,1.00,-2.00",
+                    "\"11/03/2024 01:55:00\",\"CAPITL\",61757,20.02,1.00,-2.00",
+                    "\"11/03/2024 01:00:00\",\"CAPITL\",61757,20.03,1.00,-2.00",
+                    "\"11/03/2024 01:55:00\",\"CAPITL\",61757,20.04,1.00,-2.00",
+                    "\"11/03/2024 02:00:00\",\"CAPITL\",61757,20.05,1.00,-2.00",
+                ],
+            ),
+            (
+                NodeType::Gen,
+                vec!["\"11/03/2024 02:00:00\",\"ASTORIA\",23512,19.50,1.00,-2.00"],
+            ),
+        ];
+        for (node_type, rows) in files {
+            let path = format!(
+                "{}.gz",
+                archive.filename(&day, RtReport::FiveMinute, node_type)
+            );
+            fs::create_dir_all(Path::new(&path).parent().unwrap())?;
+            let mut gz = create_gz(Path::new(&path))?;
+            gz.write_all(header.as_bytes())?;
+            gz.write_all(rows.join("\n").as_bytes())?;
+            gz.finish()?;
+        }
+
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+    CREATE TABLE rtlmp5 (
+        interval_beginning TIMESTAMPTZ NOT NULL,
+        ptid INTEGER NOT NULL,
+        lmp DECIMAL(9,2) NOT NULL,
+        mlc DECIMAL(9,2) NOT NULL,
+        mcc DECIMAL(9,2) NOT NULL,
+    );",
+        )?;
+        conn.execute_batch(&archive.update_sql(month(2024, 11), RtReport::FiveMinute))?;
+        let mut stmt = conn.prepare(
+            "SELECT interval_beginning, lmp::DOUBLE FROM rtlmp5 WHERE ptid = 61757 ORDER BY interval_beginning",
+        )?;
+        let rows: Vec<(String, f64)> = stmt
+            .query_map([], |row| {
+                let micro: i64 = row.get(0)?;
+                Ok((
+                    Timestamp::from_microsecond(micro).unwrap().to_string(),
+                    row.get(1)?,
+                ))
+            })?
+            .collect::<Result<_, _>>()?;
+        fs::remove_dir_all(&dir)?;
+        assert_eq!(
+            rows,
+            vec![
+                ("2024-11-03T04:55:00Z".to_string(), 20.01), // 00:55 EDT
+                ("2024-11-03T05:50:00Z".to_string(), 20.02), // 01:50 EDT
+                ("2024-11-03T05:55:00Z".to_string(), 20.03), // 00:55 EST
+                ("2024-11-03T06:50:00Z".to_string(), 20.04), // 01:50 EST
+                ("2024-11-03T06:55:00Z".to_string(), 20.05), // 01:55 EST
+            ]
+        );
+        let n: i64 = conn.query_row("SELECT count(*) FROM rtlmp5", [], |row| row.get(0))?;
+        assert_eq!(n, 6);
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn update_db() -> Result<(), Box<dyn Error>> {
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+        dotenvy::from_path(Path::new(".env/test.env")).unwrap();
+        let archive = ProdDb::nyiso_rtlmp();
+        archive.setup()?;
+
+        let months = month(2024, 1).up_to(month(2025, 12))?;
+        for month in months {
+            println!("Processing month {}", month);
+            archive.update_duckdb(month, RtReport::Hourly)?;
+        }
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn download_file() -> Result<(), Box<dyn Error>> {
+        dotenvy::from_path(Path::new(".env/test.env")).unwrap();
+        let _ = env_logger::builder()
+            .filter_level(log::LevelFilter::Info)
+            .is_test(true)
+            .try_init();
+
+        let archive = ProdDb::nyiso_rtlmp();
+        let months = month(2026, 1).up_to(month(2026, 1))?;
+        for month in months {
+            archive.download_file(month, RtReport::Hourly, NodeType::Gen)?;
+            archive.download_file(month, RtReport::Hourly, NodeType::Zone)?;
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,4 @@
 use duckdb::Connection;
-use glob::glob;
 use jiff::civil::*;
 use jiff::Timestamp;
 use jiff::ToSpan;
@@ -10,14 +9,14 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::error::Error;
 use std::fs;
-use std::fs::File;
-use std::io;
 use std::path::Path;
 use std::process::Command;
 
 use crate::db::nyiso::scheduled_outages::QueryOutages;
 use crate::elec::iso::ISONE;
 use crate::interval::month::Month;
+use crate::utils::compression::unzip_to_gz;
+use crate::utils::downloader::{downloader, AuthProfile};
 use crate::utils::serde_helpers::*;
 
 
@@ -213,46 +212,21 @@ INSERT INTO nyiso_da_outages
             "http://mis.nyiso.com/public/csv/outSched/{}01outSched_csv.zip",
             month.strftime("%Y%m")
         );
-        let resp = reqwest::blocking::get(url).expect("request failed");
         let zip_path = self.filename(&month.start_date()) + ".zip";
-        let dir = Path::new(&zip_path).parent().unwrap();
-        let _ = fs::create_dir_all(dir);
-        let mut out = File::create(&zip_path).expect("failed to create file");
-        io::copy(&mut resp.bytes()?.as_ref(), &mut out).expect("failed to copy content");
-
-        Command::new("unzip")
-            .args(["-o", &zip_path])
-            .current_dir(dir)
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("unzip failed");
+        downloader().download_file(&url, AuthProfile::Nyiso, None, Path::new(&zip_path), false)?;
 
-        // gzip all csv files for the month.  Need to expand the glob pattern in Rust.
-        let pattern = dir.join(format!("{}*outSched.csv", month.strftime("%Y%m")));
-        for entry in glob(pattern.to_str().unwrap()).unwrap() {
-            match entry {
-                Ok(path) => {
-                    Command::new("gzip")
-                        .arg("-f")
-                        .arg(path.file_name().unwrap()) // just the filename, since current_dir is set
-                        .current_dir(dir)
-                        .spawn()
-                        .unwrap()
-                        .wait()
-                        .expect("gzip failed");
-                }
-                Err(e) => println!("{:?}", e),
+        // gzip all csv files for the month
+        let dir = Path::new(&zip_path).parent().unwrap();
+        let prefix = month.strftime("%Y%m").to_string();
+        unzip_to_gz(Path::new(&zip_path), |name| {
+            if name.starts_with(&prefix) && name.ends_with("outSched.csv") {
+                Ok(Some(dir.join(name).to_string_lossy().to_string()))
+            } else {
+                Ok(None)
             }
-        }
+        })?;
 
-        Command::new("rm")
-            .args(["-f", &zip_path])
-            .current_dir(dir)
-            .spawn()
-            .unwrap()
-            .wait()
-            .expect("removed zip file");
+        fs::remove_file(&zip_path)?;
 
         Ok(())
     }
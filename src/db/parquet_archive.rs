@@ -0,0 +1,381 @@
+//! Export DuckDB tables to hive-partitioned Parquet files and import them back.
+//!
+//! A table `da_lmp` exported with time column `hour_beginning` ends up as
+//! ```text
+//! {base_dir}/da_lmp/manifest.json
+//! {base_dir}/da_lmp/year=2025/month=7/data_0.parquet
+//! ...
+//! ```
+//! The manifest keeps the DuckDB column types so the table can be recreated
+//! exactly, and the row count is checked after an import.  From Python, read it with
+//! `pl.scan_parquet("{base_dir}/da_lmp/**/*.parquet", hive_partitioning=True)`.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use duckdb::{AccessMode, Connection};
+use jiff::Zoned;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::lib_duckdb::open_with_retry;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    /// DuckDB type, e.g. `TIMESTAMPTZ`, `DECIMAL(9,4)`, `ENUM('DA', 'RT')`
+    pub data_type: String,
+    pub nullable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub table: String,
+    pub columns: Vec<Column>,
+    /// Column used for the year/month partitions.  If `None`, the table
+    /// is exported to one file.
+    pub time_column: Option<String>,
+    /// Timezone used to calculate the year/month partitions, e.g. the
+    /// timezone of the archive, `Etc/GMT+5` for IESO
+    pub tz: String,
+    pub row_count: u64,
+    pub source: String,
+    pub exported_at: Zoned,
+}
+
+impl Manifest {
+    /// SQL to copy the table into the Parquet directory `dir`.
+    pub fn export_sql(&self, dir: &str) -> String {
+        match &self.time_column {
+            Some(column) => format!(
+                r#"
+SET TimeZone = '{}';
+COPY (
+    SELECT *, year({column}) AS year, month({column}) AS month
+    FROM {}
+    ORDER BY {column}
+) TO '{dir}' (FORMAT parquet, PARTITION_BY (year, month), OVERWRITE_OR_IGNORE true);
+"#,
+                self.tz, self.table,
+            ),
+            None => format!(
+                r#"
+COPY {} TO '{dir}/data_0.parquet' (FORMAT parquet);
+"#,
+                self.table,
+            ),
+        }
+    }
+
+    /// SQL to recreate the table from the Parquet directory `dir`.
+    pub fn import_sql(&self, dir: &str) -> String {
+        let columns = self
+            .columns
+            .iter()
+            .map(|c| {
+                format!(
+                    "    {} {}{}",
+                    c.name,
+                    c.data_type,
+                    if c.nullable { "" } else { " NOT NULL" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+        let names = self
+            .columns
+            .iter()
+            .map(|c| c.name.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (files, options, order_by) = match &self.time_column {
+            Some(column) => (
+                format!("{dir}/*/*/*.parquet"),
+                ", hive_partitioning = true",
+                format!("\nORDER BY {column}"),
+            ),
+            None => (format!("{dir}/*.parquet"), "", "".to_string()),
+        };
+        format!(
+            r#"
+DROP TABLE IF EXISTS {table};
+CREATE TABLE {table} (
+{columns}
+);
+INSERT INTO {table}
+SELECT {names}
+FROM read_parquet('{files}'{options}){order_by};
+"#,
+            table = self.table,
+        )
+    }
+}
+
+pub struct ParquetArchive {
+    pub base_dir: String,
+}
+
+impl ParquetArchive {
+    /// Directory with the Parquet files for this table.
+    pub fn table_dir(&self, table: &str) -> String {
+        format!("{}/{}", self.base_dir, table)
+    }
+
+    pub fn manifest_path(&self, table: &str) -> String {
+        format!("{}/manifest.json", self.table_dir(table))
+    }
+
+    /// Read the schema of a table from a DuckDB file.
+    pub fn get_manifest(
+        &self,
+        duckdb_path: &str,
+        table: &str,
+        time_column: Option<&str>,
+        tz: &str,
+    ) -> Result<Manifest, Box<dyn Error>> {
+        let conn = open_with_retry(
+            duckdb_path,
+            8,
+            Duration::from_millis(25),
+            AccessMode::ReadOnly,
+        )?;
+        let mut stmt = conn.prepare(
+            r#"
+SELECT column_name, data_type, is_nullable = 'YES'
+FROM information_schema.columns
+WHERE table_name = ?
+ORDER BY ordinal_position;"#,
+        )?;
+        let columns: Vec<Column> = stmt
+            .query_map([table], |row| {
+                Ok(Column {
+                    name: row.get(0)?,
+                    data_type: row.get(1)?,
+                    nullable: row.get(2)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        if columns.is_empty() {
+            return Err(format!("Table {} not found in {}", table, duckdb_path).into());
+        }
+        if let Some(column) = time_column {
+            if !columns.iter().any(|c| c.name == column) {
+                return Err(format!("Column {} not found in table {}", column, table).into());
+            }
+        }
+        let row_count: i64 =
+            conn.query_row(&format!("SELECT count(*) FROM {};", table), [], |row| {
+                row.get(0)
+            })?;
+
+        Ok(Manifest {
+            table: table.to_string(),
+            columns,
+            time_column: time_column.map(|e| e.to_string()),
+            tz: tz.to_string(),
+            row_count: row_count as u64,
+            source: duckdb_path.to_string(),
+            exported_at: Zoned::now(),
+        })
+    }
+
+    /// Export a table to Parquet, partitioned by year and month of the `time_column`
+    /// in the timezone `tz`.  A previous export of the table is removed first, so
+    /// no stale files are left behind.
+    pub fn export_table(
+        &self,
+        duckdb_path: &str,
+        table: &str,
+        time_column: Option<&str>,
+        tz: &str,
+    ) -> Result<Manifest, Box<dyn Error>> {
+        info!("exporting table {} from {} ...", table, duckdb_path);
+        let manifest = self.get_manifest(duckdb_path, table, time_column, tz)?;
+        let dir = self.table_dir(table);
+        if Path::new(&dir).exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+
+        let output = Command::new("duckdb")
+            .arg("-readonly")
+            .arg("-c")
+            .arg(manifest.export_sql(&dir))
+            .arg(duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() {
+            error!("Failed to export table {}: {}", table, stderr);
+            return Err(format!("Failed to export table {}: {}", table, stderr).into());
+        }
+        fs::write(
+            self.manifest_path(table),
+            serde_json::to_string_pretty(&manifest)?,
+        )?;
+        info!("done, exported {} rows", manifest.row_count);
+        Ok(manifest)
+    }
+
+    /// Read the manifest of an exported table.
+    pub fn read_manifest(&self, table: &str) -> Result<Manifest, Box<dyn Error>> {
+        let path = self.manifest_path(table);
+        if !Path::new(&path).exists() {
+            return Err(format!("No manifest found at {}", path).into());
+        }
+        let manifest: Manifest = serde_json::from_str(&fs::read_to_string(path)?)?;
+        Ok(manifest)
+    }
+
+    /// Recreate a table in `duckdb_path` from its Parquet export.  If the table
+    /// exists, it is replaced.  Fails if the number of rows imported doesn't
+    /// match the manifest.
+    pub fn import_table(&self, duckdb_path: &str, table: &str) -> Result<(), Box<dyn Error>> {
+        info!("importing table {} into {} ...", table, duckdb_path);
+        let manifest = self.read_manifest(table)?;
+
+        let output = Command::new("duckdb")
+            .arg("-c")
+            .arg(manifest.import_sql(&self.table_dir(table)))
+            .arg(duckdb_path)
+            .output()
+            .expect("Failed to invoke duckdb command");
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() {
+            error!("Failed to import table {}: {}", table, stderr);
+            return Err(format!("Failed to import table {}: {}", table, stderr).into());
+        }
+        info!("{}", stdout);
+
+        let conn = Connection::open(duckdb_path)?;
+        let row_count: i64 =
+            conn.query_row(&format!("SELECT count(*) FROM {};", table), [], |row| {
+                row.get(0)
+            })?;
+        if row_count as u64 != manifest.row_count {
+            error!(
+                "Imported {} rows for table {}, the manifest has {}",
+                row_count, table, manifest.row_count
+            );
+            return Err(format!(
+                "Imported {} rows for table {}, the manifest has {}",
+                row_count, table, manifest.row_count
+            )
+            .into());
+        }
+        info!("done, imported {} rows", row_count);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use duckdb::Connection;
+
+    use super::*;
+
+    #[test]
+    fn test_import_sql() -> Result<(), Box<dyn Error>> {
+        let manifest = Manifest {
+            table: "da_lmp".to_string(),
+            columns: vec![
+                Column {
+                    name: "hour_beginning".to_string(),
+                    data_type: "TIMESTAMP WITH TIME ZONE".to_string(),
+                    nullable: false,
+                },
+                Column {
+                    name: "lmp".to_string(),
+                    data_type: "DECIMAL(9,4)".to_string(),
+                    nullable: true,
+                },
+            ],
+            time_column: Some("hour_beginning".to_string()),
+            tz: "America/New_York".to_string(),
+            row_count: 0,
+            source: "dalmp.duckdb".to_string(),
+            exported_at: "2025-07-01 10:00[America/New_York]".parse()?,
+        };
+        let sql = manifest.import_sql("/tmp/da_lmp");
+        assert!(sql.contains(
+            "    hour_beginning TIMESTAMP WITH TIME ZONE NOT NULL,\n    lmp DECIMAL(9,4)\n"
+        ));
+        assert!(sql.contains("read_parquet('/tmp/da_lmp/*/*/*.parquet', hive_partitioning = true)"));
+
+        // the CREATE TABLE statement is valid
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(sql.split("INSERT INTO").next().unwrap())?;
+
+        // manifest round trip
+        let json = serde_json::to_string(&manifest)?;
+        let other: Manifest = serde_json::from_str(&json)?;
+        assert_eq!(manifest, other);
+        Ok(())
+    }
+
+    #[ignore]
+    #[test]
+    fn test_export_import() -> Result<(), Box<dyn Error>> {
+        let dir = std::env::temp_dir().join(format!("bust_parquet_{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let source = dir.join("source.duckdb").to_str().unwrap().to_string();
+        let target = dir.join("target.duckdb").to_str().unwrap().to_string();
+        let archive = ParquetArchive {
+            base_dir: dir.join("parquet").to_str().unwrap().to_string(),
+        };
+
+        // the last hour of Jan-25 in EST is in February in UTC
+        let conn = Connection::open(&source)?;
+        conn.execute_batch(
+            r#"
+CREATE TABLE da_lmp (hour_beginning TIMESTAMPTZ NOT NULL, ptid UINTEGER NOT NULL, lmp DECIMAL(9,4));
+INSERT INTO da_lmp VALUES
+    ('2025-01-31 23:00:00-05:00', 4000, 45.1234),
+    ('2025-02-01 00:00:00-05:00', 4000, 40.5),
+    ('2025-02-01 01:00:00-05:00', 4000, NULL);
+"#,
+        )?;
+        drop(conn);
+        let manifest = archive.export_table(&source, "da_lmp", Some("hour_beginning"), "Etc/GMT+5")?;
+        assert_eq!(manifest.row_count, 3);
+        assert!(Path::new(&format!("{}/year=2025/month=1", archive.table_dir("da_lmp"))).exists());
+
+        // the February partition shrinks, the old file is not left behind
+        let conn = Connection::open(&source)?;
+        conn.execute_batch("DELETE FROM da_lmp WHERE ptid = 4000 AND lmp IS NULL;")?;
+        drop(conn);
+        archive.export_table(&source, "da_lmp", Some("hour_beginning"), "Etc/GMT+5")?;
+        archive.import_table(&target, "da_lmp")?;
+
+        let conn = Connection::open(&target)?;
+        let mut stmt = conn.prepare(
+            "SELECT epoch(hour_beginning)::BIGINT, ptid, lmp::DOUBLE FROM da_lmp ORDER BY hour_beginning",
+        )?;
+        let rows: Vec<(i64, u32, Option<f64>)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<_, _>>()?;
+        assert_eq!(
+            rows,
+            vec![(1738382400, 4000, Some(45.1234)), (1738386000, 4000, Some(40.5))]
+        );
+        drop(stmt);
+        drop(conn);
+
+        // a manifest that doesn't match the files fails the import
+        let mut manifest = archive.read_manifest("da_lmp")?;
+        manifest.row_count = 3;
+        fs::write(archive.manifest_path("da_lmp"), serde_json::to_string(&manifest)?)?;
+        assert!(archive.import_table(&target, "da_lmp").is_err());
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
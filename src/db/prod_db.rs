@@ -13,18 +13,19 @@ use crate::db::{
         da_lmp_area::IesoDaLmpAreaArchive, generation_output_by_fuel::IesoGenOutputByFuelArchive,
         vgforecast_summary::IesoVGForecastSummaryArchive,
     }, isone::{
-        actual_interchange_archive::IsoneActualInterchangeArchive, binding_constraints_da::IsoneDaBindingConstraintsArchive, calendar_events::IsoneEventsCalendarArchive, dalmp_archive::IsoneDaLmpArchive, ftr_prices_archive::IsoneFtrPricesArchive, fuelmix_archive::IsoneFuelMixArchive, masked_data::{
+        actual_interchange_archive::IsoneActualInterchangeArchive, binding_constraints_da::IsoneDaBindingConstraintsArchive, binding_constraints_rt::IsoneRtBindingConstraintsArchive, calendar_events::IsoneEventsCalendarArchive, capacity_auction_results_archive::IsoneCapacityAuctionResultsArchive, dalmp_archive::IsoneDaLmpArchive, ftr_prices_archive::IsoneFtrPricesArchive, fuelmix_archive::IsoneFuelMixArchive, masked_data::{
             ara_archive::IsoneAraBidsOffersArchive,
             da_energy_offers_archive::IsoneDaEnergyOffersArchive,
             daas_offers_archive::DaasOffersArchive, demand_bids_archive::DemandBidsArchive,
             import_export_archive::ImportExportArchive, mra_archive::IsoneMraBidsOffersArchive,
-        }, participants_archive::IsoneParticipantsArchive, rtlmp_archive::IsoneRtLmpArchive, sevenday_capacity_forecast_archive::SevendayCapacityForecastArchive, total_transfer_capability_archive::TotalTransferCapabilityArchive,
+        }, load_forecast_archive::IsoneLoadForecastArchive, participants_archive::IsoneParticipantsArchive, rt_reserve_prices_archive::IsoneRtReservePricesArchive, rtlmp5_archive::IsoneRtLmp5Archive, rtlmp_archive::IsoneRtLmpArchive, sevenday_capacity_forecast_archive::SevendayCapacityForecastArchive, system_load_archive::IsoneSystemLoadArchive, total_transfer_capability_archive::TotalTransferCapabilityArchive,
     }, nodal::nodal_contracts::NodalContractsArchive, nyiso::{
         binding_constraints::NyisoBindingConstraintsDaArchive,
         capacity_offers::NyisoCapacityOffersArchive,
         capacity_prices_monthly::NyisoCapacityPricesMonthlyArchive,
         capacity_seasons::NyisoCapacitySeasonsArchive, energy_offers::NyisoEnergyOffersArchive,
-        ptid_table::NyisoPtidTableArchive, rtlmp::NyisoRtlmpArchive,
+        load_forecast::NyisoLoadForecastArchive, ptid_table::NyisoPtidTableArchive,
+        rt_load::NyisoRtLoadArchive, rtlmp::NyisoRtlmpArchive,
         scheduled_outages::NyisoScheduledOutagesArchive,
         transmission_outages_da::NyisoTransmissionOutagesDaArchive,
         zonal_uplift::NyisoZonalUpliftArchive,
@@ -32,6 +33,8 @@ use crate::db::{
 };
 
 use super::{
+    data_quality::QualitySpec,
+    parquet_archive::ParquetArchive,
     hq::hydrometeorological_data_archive::HqHydroDataArchive,
     ieso::{
         da_lmp_nodes::IesoDaLmpNodalArchive, da_lmp_zones::IesoDaLmpZonalArchive,
@@ -170,6 +173,14 @@ impl ProdDb {
         }
     }
 
+    pub fn isone_capacity_auction_results() -> IsoneCapacityAuctionResultsArchive {
+        IsoneCapacityAuctionResultsArchive {
+            mra_duckdb_path: ProdDb::isone_mra_bids_offers().duckdb_path,
+            duckdb_path: "/home/adrian/Downloads/Archive/DuckDB/isone/capacity_auction_results.duckdb"
+                .to_string(),
+        }
+    }
+
     pub fn isone_da_binding_constraints() -> IsoneDaBindingConstraintsArchive {
         IsoneDaBindingConstraintsArchive {
             base_dir: "/home/adrian/Downloads/Archive/IsoExpress/GridReports/DaBindingConstraints".to_string(),
@@ -225,6 +236,15 @@ impl ProdDb {
         }
     }
 
+    pub fn isone_load_forecast() -> IsoneLoadForecastArchive {
+        IsoneLoadForecastArchive {
+            base_dir: "/home/adrian/Downloads/Archive/IsoExpress/LoadForecast/ReliabilityRegion"
+                .to_string(),
+            duckdb_path: "/home/adrian/Downloads/Archive/DuckDB/isone/load_forecast.duckdb"
+                .to_string(),
+        }
+    }
+
     pub fn isone_masked_ara_bids_offers() -> IsoneAraBidsOffersArchive {
         IsoneAraBidsOffersArchive {
             base_dir: "/home/adrian/Downloads/Archive/IsoExpress/Capacity/HistoricalBidsOffers/AnnualReconfigurationAuction"
@@ -321,6 +341,40 @@ impl ProdDb {
         }
     }
 
+    pub fn isone_system_load() -> IsoneSystemLoadArchive {
+        IsoneSystemLoadArchive {
+            base_dir: "/home/adrian/Downloads/Archive/IsoExpress/SystemLoad".to_string(),
+            duckdb_path: "/home/adrian/Downloads/Archive/DuckDB/isone/system_load.duckdb"
+                .to_string(),
+        }
+    }
+
+    pub fn isone_rt_binding_constraints() -> IsoneRtBindingConstraintsArchive {
+        IsoneRtBindingConstraintsArchive {
+            base_dir: "/home/adrian/Downloads/Archive/IsoExpress/GridReports/RtBindingConstraints"
+                .to_string(),
+            duckdb_path: "/home/adrian/Downloads/Archive/DuckDB/isone/binding_constraints_rt.duckdb"
+                .to_string(),
+        }
+    }
+
+    pub fn isone_rt_reserve_prices() -> IsoneRtReservePricesArchive {
+        IsoneRtReservePricesArchive {
+            base_dir: "/home/adrian/Downloads/Archive/IsoExpress/PricingReports/RtReservePrice"
+                .to_string(),
+            duckdb_path: "/home/adrian/Downloads/Archive/DuckDB/isone/rt_reserve_prices.duckdb"
+                .to_string(),
+        }
+    }
+
+    pub fn isone_rtlmp5() -> IsoneRtLmp5Archive {
+        IsoneRtLmp5Archive {
+            base_dir: "/home/adrian/Downloads/Archive/IsoExpress/PricingReports/RtLmp5Min"
+                .to_string(),
+            duckdb_path: "/home/adrian/Downloads/Archive/DuckDB/isone/rtlmp5.duckdb".to_string(),
+        }
+    }
+
     pub fn isone_ttc() -> TotalTransferCapabilityArchive {
         TotalTransferCapabilityArchive {
             base_dir: "/home/adrian/Downloads/Archive/IsoExpress/Ttc".to_string(),
@@ -433,6 +487,14 @@ impl ProdDb {
         }
     }
 
+    pub fn nyiso_load_forecast() -> NyisoLoadForecastArchive {
+        NyisoLoadForecastArchive {
+            base_dir: "/home/adrian/Downloads/Archive/Nyiso/LoadForecast".to_string(),
+            duckdb_path: "/home/adrian/Downloads/Archive/DuckDB/nyiso/load_forecast.duckdb"
+                .to_string(),
+        }
+    }
+
     pub fn nyiso_ptid_table() -> NyisoPtidTableArchive {
         NyisoPtidTableArchive {
             base_dir: "/home/adrian/Downloads/Archive/Nyiso/PnodeTable".to_string(),
@@ -441,6 +503,13 @@ impl ProdDb {
         }
     }
 
+    pub fn nyiso_rt_load() -> NyisoRtLoadArchive {
+        NyisoRtLoadArchive {
+            base_dir: "/home/adrian/Downloads/Archive/Nyiso/RtLoad".to_string(),
+            duckdb_path: "/home/adrian/Downloads/Archive/DuckDB/nyiso/rt_load.duckdb".to_string(),
+        }
+    }
+
     pub fn nyiso_rtlmp() -> NyisoRtlmpArchive {
         NyisoRtlmpArchive {
             base_dir: "/home/adrian/Downloads/Archive/Nyiso/RtLmpHourly".to_string(),
@@ -474,6 +543,27 @@ impl ProdDb {
         }
     }
 
+    pub fn parquet_archive() -> ParquetArchive {
+        ParquetArchive {
+            base_dir: "/home/adrian/Downloads/Archive/Parquet".to_string(),
+        }
+    }
+
+    /// All the archives with a declared quality spec.
+    pub fn quality_specs() -> Vec<QualitySpec> {
+        vec![
+            ProdDb::ieso_dalmp_area().quality_spec(),
+            ProdDb::isone_dalmp().quality_spec(),
+            ProdDb::isone_rtlmp().quality_spec(),
+            ProdDb::isone_rt_reserve_prices().quality_spec(),
+            ProdDb::isone_rtlmp5().quality_spec(),
+            ProdDb::isone_sevenday_solar_forecast().quality_spec(),
+            ProdDb::nyiso_capacity_prices_monthly().quality_spec(),
+            ProdDb::nyiso_dalmp().quality_spec(),
+            ProdDb::nyiso_rtlmp().quality_spec(),
+        ]
+    }
+
     pub fn scratch() -> ScratchArchive {
         ScratchArchive {
             duckdb_path: "/home/adrian/Downloads/Archive/DuckDB/scratch.duckdb".to_string(),
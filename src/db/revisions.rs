@@ -0,0 +1,205 @@
+//! Revision tracking for archives where the ISO republishes data.
+//!
+//! The main table (e.g. `da_lmp`) always holds the latest known values.  Every
+//! row version is also kept in a companion `{table}_revisions` table, which has
+//! the same columns plus an `inserted_at TIMESTAMPTZ` column with the time the
+//! version was ingested.  A new version is only stored when the values for a
+//! key change, so re-ingesting the same file is a no-op.
+//!
+//! When the revisions table is created for an existing archive, it is seeded
+//! with the current content of the main table, stamped with the time of that
+//! first merge.  Earlier versions are unknown, so an `as_of` before tracking
+//! started returns no rows rather than values published later.
+//!
+//! MIS reports already keep every version in their tables (see the `version`
+//! column), so they don't need this.
+
+use jiff::Zoned;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevisionSpec {
+    /// Name of the main table, e.g. `da_lmp`
+    pub table: String,
+    /// Columns that uniquely identify a row, e.g. `hour_beginning, ptid`
+    pub key_columns: Vec<String>,
+    /// Columns whose changes create a new revision
+    pub value_columns: Vec<String>,
+}
+
+impl RevisionSpec {
+    pub fn new(table: &str, key_columns: &[&str], value_columns: &[&str]) -> Self {
+        RevisionSpec {
+            table: table.to_string(),
+            key_columns: key_columns.iter().map(|e| e.to_string()).collect(),
+            value_columns: value_columns.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    pub fn revisions_table(&self) -> String {
+        format!("{}_revisions", self.table)
+    }
+
+    fn columns(&self) -> String {
+        self.key_columns
+            .iter()
+            .chain(self.value_columns.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Latest row version for each key.  If `as_of` is set, only versions
+    /// inserted at or before that time are considered.
+    pub fn latest_sql(&self, as_of: Option<&Zoned>) -> String {
+        format!(
+            r#"(
+    SELECT {}
+    FROM {}{}
+    QUALIFY row_number() OVER (PARTITION BY {} ORDER BY inserted_at DESC) = 1
+)"#,
+            self.columns(),
+            self.revisions_table(),
+            match as_of {
+                Some(t) => format!(
+                    "\n    WHERE inserted_at <= to_timestamp({})",
+                    t.timestamp().as_second()
+                ),
+                None => "".to_string(),
+            },
+            self.key_columns.join(", "),
+        )
+    }
+
+    /// SQL to merge the rows of the table `source` (same columns as the main table)
+    /// into the main table, keeping the previous versions in the revisions table.
+    /// The main table should already exist.
+    pub fn merge_sql(&self, source: &str, inserted_at: &Zoned) -> String {
+        let ts = format!("to_timestamp({})", inserted_at.timestamp().as_second());
+        let join = self
+            .key_columns
+            .iter()
+            .map(|k| format!("s.{k} = r.{k}"))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let changed = self
+            .value_columns
+            .iter()
+            .map(|v| format!("s.{v} IS DISTINCT FROM r.{v}"))
+            .collect::<Vec<_>>()
+            .join("\n    OR ");
+        let delete = self
+            .key_columns
+            .iter()
+            .map(|k| format!("{}.{k} = changed.{k}", self.table))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        format!(
+            r#"
+CREATE TABLE IF NOT EXISTS {revisions} AS
+    SELECT *, {ts} AS inserted_at FROM {table} LIMIT 0;
+
+INSERT INTO {revisions}
+    SELECT *, {ts} FROM {table}
+    WHERE NOT EXISTS (SELECT 1 FROM {revisions});
+
+CREATE TEMPORARY TABLE changed AS
+SELECT s.*
+FROM {source} s
+LEFT JOIN {latest} r
+ON {join}
+WHERE r.{first_key} IS NULL
+    OR {changed};
+
+INSERT INTO {revisions}
+    SELECT *, {ts} FROM changed;
+
+DELETE FROM {table} USING changed
+WHERE {delete};
+
+INSERT INTO {table}
+    SELECT * FROM changed
+    ORDER BY {keys};
+
+DROP TABLE changed;
+"#,
+            revisions = self.revisions_table(),
+            table = self.table,
+            latest = self.latest_sql(None),
+            first_key = self.key_columns[0],
+            keys = self.key_columns.join(", "),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use duckdb::Connection;
+    use jiff::civil::date;
+
+    use super::*;
+
+    fn values(conn: &Connection, sql: &str) -> Result<Vec<(i32, f64)>, Box<dyn Error>> {
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    #[test]
+    fn test_merge_and_as_of() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        let spec = RevisionSpec::new("prices", &["id"], &["price"]);
+        conn.execute_batch(
+            r#"
+CREATE TABLE prices (id INTEGER NOT NULL, price DOUBLE);
+INSERT INTO prices VALUES (1, 10.0), (2, 20.0);
+CREATE TABLE tmp AS SELECT * FROM prices;
+"#,
+        )?;
+        let t1 = date(2025, 1, 1).at(10, 0, 0, 0).in_tz("America/New_York")?;
+        let t2 = date(2025, 1, 2).at(10, 0, 0, 0).in_tz("America/New_York")?;
+
+        // first update seeds the revisions, re-inserting the same data is a no-op
+        conn.execute_batch(&spec.merge_sql("tmp", &t1))?;
+        conn.execute_batch(&spec.merge_sql("tmp", &t1))?;
+        let n: i64 = conn.query_row("SELECT count(*) FROM prices_revisions", [], |r| r.get(0))?;
+        assert_eq!(n, 2);
+
+        // a correction for id 2 and a new id 3
+        conn.execute_batch(
+            r#"
+DELETE FROM tmp;
+INSERT INTO tmp VALUES (1, 10.0), (2, 25.0), (3, 30.0);
+"#,
+        )?;
+        conn.execute_batch(&spec.merge_sql("tmp", &t2))?;
+        assert_eq!(
+            values(&conn, "SELECT id, price FROM prices ORDER BY id")?,
+            vec![(1, 10.0), (2, 25.0), (3, 30.0)]
+        );
+        let n: i64 = conn.query_row("SELECT count(*) FROM prices_revisions", [], |r| r.get(0))?;
+        assert_eq!(n, 4);
+
+        // nothing is known before tracking started
+        let t0 = date(2024, 12, 31).at(10, 0, 0, 0).in_tz("America/New_York")?;
+        let sql = format!(
+            "SELECT id, price FROM {} ORDER BY id",
+            spec.latest_sql(Some(&t0))
+        );
+        assert_eq!(values(&conn, &sql)?, vec![]);
+
+        // as known on the first day
+        let sql = format!(
+            "SELECT id, price FROM {} ORDER BY id",
+            spec.latest_sql(Some(&t1))
+        );
+        assert_eq!(values(&conn, &sql)?, vec![(1, 10.0), (2, 20.0)]);
+        let sql = format!(
+            "SELECT id, price FROM {} ORDER BY id",
+            spec.latest_sql(Some(&t2))
+        );
+        assert_eq!(values(&conn, &sql)?, vec![(1, 10.0), (2, 25.0), (3, 30.0)]);
+        Ok(())
+    }
+}
@@ -1,10 +1,10 @@
 use duckdb::Connection;
 use jiff::Timestamp;
-use reqwest::blocking::get;
 use std::error::Error;
-use std::fs::File;
-use std::io::copy;
-use std::process::Command;
+use std::path::Path;
+
+use crate::utils::compression::unzip_file;
+use crate::utils::downloader::{downloader, AuthProfile};
 
 #[derive(Clone)]
 pub struct StatisticsCanadaGenerationArchive {
@@ -41,19 +41,12 @@ impl StatisticsCanadaGenerationArchive {
     pub fn download_file(&self) -> Result<(), Box<dyn Error>> {
         println!("{}", self.filename());
         let url = "https://www150.statcan.gc.ca/n1/tbl/csv/25100015-eng.zip";
-        let mut resp = get(url)?;
-        let mut out = File::create(self.filename())?;
-        copy(&mut resp, &mut out)?;
-
-        Command::new("unzip")
-            .args([
-                "-o",
-                &self.filename(),
-                "-d",
-                &(self.base_dir.clone() + "/Raw/"),
-            ])
-            .spawn()?
-            .wait()?;
+        downloader().download_file(url, AuthProfile::Anonymous, None, Path::new(&self.filename()), false)?;
+
+        unzip_file(
+            Path::new(&self.filename()),
+            Path::new(&(self.base_dir.clone() + "/Raw/")),
+        )?;
         Ok(())
     }
 
@@ -0,0 +1,195 @@
+//! Aggregate power time series, e.g. the generation by fuel, to hourly, daily
+//! or bucket values.  Each value is the MW at the start of an interval, and
+//! the length of the interval is taken from the timestamps of the data, so
+//! 5-minute and hourly series get the right MWh.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use jiff::{SignedDuration, Timestamp, Zoned};
+use serde::Deserialize;
+
+use crate::time::bucket::{Bucket, BucketLike};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Resolution {
+    Hour,
+    Day,
+}
+
+/// The MW of one series (e.g. a fuel type) at the start of an interval
+#[derive(Clone, Debug, PartialEq)]
+pub struct Observation {
+    pub interval_beginning: Zoned,
+    pub series: String,
+    pub mw: f64,
+}
+
+/// Average MW and energy of one series over a period.  The period is the
+/// start of the hour or of the day, or a bucket.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Aggregate<P> {
+    pub period: P,
+    pub series: String,
+    pub average_mw: f64,
+    pub mwh: f64,
+}
+
+pub fn hour_beginning(zoned: &Zoned) -> Zoned {
+    let seconds = zoned.timestamp().as_second();
+    Timestamp::from_second(seconds - seconds.rem_euclid(3600))
+        .unwrap()
+        .to_zoned(zoned.time_zone().clone())
+}
+
+/// Length in seconds of the interval starting at each distinct timestamp: the
+/// time to the next timestamp, but no longer than `max_length` so gaps in the
+/// data don't get filled.  The last interval is `max_length` long.
+pub fn interval_lengths(
+    observations: &[Observation],
+    max_length: SignedDuration,
+) -> BTreeMap<Timestamp, f64> {
+    let timestamps: Vec<Timestamp> = observations
+        .iter()
+        .map(|o| o.interval_beginning.timestamp())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    timestamps
+        .iter()
+        .enumerate()
+        .map(|(i, ts)| {
+            let length = match timestamps.get(i + 1) {
+                Some(next) => next.duration_since(*ts).min(max_length),
+                None => max_length,
+            };
+            (*ts, length.as_secs() as f64)
+        })
+        .collect()
+}
+
+/// Sum the energy and the length of the intervals with data of each series.
+/// Several observations of the same series at the same time are added up.
+fn totals<'a>(
+    observations: impl Iterator<Item = &'a Observation>,
+    lengths: &BTreeMap<Timestamp, f64>,
+) -> BTreeMap<String, (f64, BTreeSet<Timestamp>)> {
+    let mut groups: BTreeMap<String, (f64, BTreeSet<Timestamp>)> = BTreeMap::new();
+    for o in observations {
+        let ts = o.interval_beginning.timestamp();
+        let e = groups.entry(o.series.clone()).or_default();
+        e.0 += o.mw * lengths[&ts];
+        e.1.insert(ts);
+    }
+    groups
+}
+
+fn to_aggregates<P: Clone>(
+    period: P,
+    groups: BTreeMap<String, (f64, BTreeSet<Timestamp>)>,
+    lengths: &BTreeMap<Timestamp, f64>,
+) -> Vec<Aggregate<P>> {
+    groups
+        .into_iter()
+        .map(move |(series, (energy, timestamps))| {
+            let seconds: f64 = timestamps.iter().map(|ts| lengths[ts]).sum();
+            Aggregate {
+                period: period.clone(),
+                series,
+                average_mw: energy / seconds,
+                mwh: energy / 3600.0,
+            }
+        })
+        .collect()
+}
+
+/// Aggregate the observations of each series by hour or by day.  The average
+/// MW is calculated over the intervals with data for the series.
+pub fn aggregate(
+    observations: &[Observation],
+    resolution: Resolution,
+    max_length: SignedDuration,
+) -> Vec<Aggregate<Zoned>> {
+    let lengths = interval_lengths(observations, max_length);
+    let mut periods: BTreeMap<Timestamp, (Zoned, Vec<&Observation>)> = BTreeMap::new();
+    for o in observations {
+        let start = match resolution {
+            Resolution::Hour => hour_beginning(&o.interval_beginning),
+            Resolution::Day => o.interval_beginning.start_of_day().unwrap(),
+        };
+        periods
+            .entry(start.timestamp())
+            .or_insert((start, Vec::new()))
+            .1
+            .push(o);
+    }
+    periods
+        .into_values()
+        .flat_map(|(start, xs)| to_aggregates(start, totals(xs.into_iter(), &lengths), &lengths))
+        .collect()
+}
+
+/// Aggregate the observations of each series by bucket.  The buckets are
+/// evaluated in their own timezone.
+pub fn aggregate_by_bucket(
+    observations: &[Observation],
+    buckets: &[Bucket],
+    max_length: SignedDuration,
+) -> Vec<Aggregate<Bucket>> {
+    let lengths = interval_lengths(observations, max_length);
+    let mut out = Vec::new();
+    for bucket in buckets {
+        let tz = bucket.timezone();
+        let xs = observations
+            .iter()
+            .filter(|o| bucket.contains(&o.interval_beginning.with_time_zone(tz.clone())));
+        out.extend(to_aggregates(*bucket, totals(xs, &lengths), &lengths));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obs(ts: &str, series: &str, mw: f64) -> Observation {
+        Observation {
+            interval_beginning: ts.parse().unwrap(),
+            series: series.to_string(),
+            mw,
+        }
+    }
+
+    #[test]
+    fn test_interval_lengths() {
+        let xs = vec![
+            obs("2025-07-01 14:00-04:00[America/New_York]", "Wind", 100.0),
+            obs("2025-07-01 14:05-04:00[America/New_York]", "Wind", 100.0),
+            obs("2025-07-01 14:07-04:00[America/New_York]", "Wind", 100.0),
+            obs("2025-07-01 15:00-04:00[America/New_York]", "Wind", 100.0),
+        ];
+        let lengths = interval_lengths(&xs, SignedDuration::from_mins(5));
+        assert_eq!(
+            lengths.values().cloned().collect::<Vec<_>>(),
+            vec![300.0, 120.0, 300.0, 300.0]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_irregular() {
+        // one value for 5 minutes, then one for 55 minutes
+        let xs = vec![
+            obs("2025-07-01 14:00-04:00[America/New_York]", "Wind", 120.0),
+            obs("2025-07-01 14:05-04:00[America/New_York]", "Wind", 60.0),
+            obs("2025-07-01 15:00-04:00[America/New_York]", "Wind", 0.0),
+        ];
+        let hourly = aggregate(&xs, Resolution::Hour, SignedDuration::from_hours(1));
+        assert_eq!(hourly.len(), 2);
+        assert_eq!(hourly[0].mwh, 65.0);
+        assert_eq!(hourly[0].average_mw, 65.0);
+
+        let daily = aggregate_by_bucket(&xs, &[Bucket::Atc], SignedDuration::from_hours(1));
+        assert_eq!(daily[0].mwh, 65.0);
+        assert_eq!(daily[0].average_mw, 32.5);
+    }
+}
@@ -0,0 +1,439 @@
+//! Evaluate forecast vintages against realized values.
+//!
+//! A forecast issued on day `D` for an hour of day `D + k - 1` has horizon
+//! `k`, so the forecast for the rest of the issue day has horizon 1.  Errors
+//! are forecast minus actual.  The MAPE skips the hours with an actual below
+//! a floor, otherwise the night hours of a solar forecast dominate it.
+
+use std::collections::{BTreeMap, HashMap};
+
+use duckdb::Connection;
+use jiff::{
+    civil::Date,
+    tz::{self, TimeZone},
+    Timestamp, ToSpan, Zoned,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::ieso::vgforecast_summary::not_total_sql;
+use crate::interval::month::{month, Month};
+use crate::utils::serde_helpers::serialize_zoned_as_offset;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastPoint {
+    /// The day the forecast was issued
+    pub issue_date: Date,
+    pub hour_beginning: Zoned,
+    pub forecast_mw: f64,
+}
+
+/// The forecasts and the actual values by hour beginning
+pub type ForecastsAndActuals = (Vec<ForecastPoint>, HashMap<Timestamp, f64>);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MatchedForecast {
+    pub horizon: u8,
+    #[serde(serialize_with = "serialize_zoned_as_offset")]
+    pub hour_beginning: Zoned,
+    pub forecast_mw: f64,
+    pub actual_mw: f64,
+}
+
+/// Match each forecast with the actual value for the same hour.  Forecasts
+/// without an actual or with a horizon outside `1..=max_horizon` are
+/// dropped.
+pub fn match_actuals(
+    forecasts: &[ForecastPoint],
+    actuals: &HashMap<Timestamp, f64>,
+    max_horizon: u8,
+) -> Vec<MatchedForecast> {
+    forecasts
+        .iter()
+        .filter_map(|f| {
+            let days = (f.hour_beginning.date() - f.issue_date).get_days();
+            let horizon = u8::try_from(days + 1).ok()?;
+            if horizon == 0 || horizon > max_horizon {
+                return None;
+            }
+            let actual_mw = *actuals.get(&f.hour_beginning.timestamp())?;
+            Some(MatchedForecast {
+                horizon,
+                hour_beginning: f.hour_beginning.clone(),
+                forecast_mw: f.forecast_mw,
+                actual_mw,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Grouping {
+    Month,
+    Hour,
+    MonthHour,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccuracyStats {
+    pub horizon: u8,
+    pub month: Option<Month>,
+    /// Hour beginning, 0..23
+    pub hour: Option<i8>,
+    pub observations: usize,
+    /// Mean of forecast minus actual
+    pub bias_mw: f64,
+    pub mae_mw: f64,
+    /// Mean absolute percentage error, as a fraction, over the observations
+    /// with an actual at or above the floor
+    pub mape: Option<f64>,
+}
+
+/// Calculate bias, MAE and MAPE for each horizon and group.
+pub fn accuracy(
+    matched: &[MatchedForecast],
+    grouping: Grouping,
+    min_actual_mw: f64,
+) -> Vec<AccuracyStats> {
+    type Key = (u8, Option<(i16, i8)>, Option<i8>);
+    let mut groups: BTreeMap<Key, Vec<&MatchedForecast>> = BTreeMap::new();
+    for m in matched {
+        let ym = (m.hour_beginning.year(), m.hour_beginning.month());
+        let key = match grouping {
+            Grouping::Month => (m.horizon, Some(ym), None),
+            Grouping::Hour => (m.horizon, None, Some(m.hour_beginning.hour())),
+            Grouping::MonthHour => (m.horizon, Some(ym), Some(m.hour_beginning.hour())),
+        };
+        groups.entry(key).or_default().push(m);
+    }
+    groups
+        .into_iter()
+        .map(|((horizon, ym, hour), xs)| {
+            let n = xs.len() as f64;
+            let pct: Vec<f64> = xs
+                .iter()
+                .filter(|m| m.actual_mw >= min_actual_mw && m.actual_mw > 0.0)
+                .map(|m| ((m.forecast_mw - m.actual_mw) / m.actual_mw).abs())
+                .collect();
+            AccuracyStats {
+                horizon,
+                month: ym.map(|(y, m)| month(y, m)),
+                hour,
+                observations: xs.len(),
+                bias_mw: xs.iter().map(|m| m.forecast_mw - m.actual_mw).sum::<f64>() / n,
+                mae_mw: xs
+                    .iter()
+                    .map(|m| (m.forecast_mw - m.actual_mw).abs())
+                    .sum::<f64>()
+                    / n,
+                mape: (!pct.is_empty()).then(|| pct.iter().sum::<f64>() / pct.len() as f64),
+            }
+        })
+        .collect()
+}
+
+/// Load the ISONE 7-day solar forecasts issued in [start, end] and the
+/// hourly solar generation from the fuel mix.  The connection needs the
+/// forecast database attached as `forecast_db` (see
+/// [`SevendaySolarForecastArchive::update_duckdb`]) and the fuel mix as
+/// `fuel_mix_db`.  The fuel mix only reports the metered solar, so a
+/// forecast that includes behind-the-meter generation shows up as a bias.
+///
+/// [`SevendaySolarForecastArchive::update_duckdb`]: crate::db::isone::sevenday_solar_forecast_archive::SevendaySolarForecastArchive::update_duckdb
+pub fn load_isone_solar(
+    conn: &Connection,
+    start: Date,
+    end: Date,
+) -> Result<ForecastsAndActuals, Box<dyn std::error::Error>> {
+    let tz = TimeZone::get("America/New_York")?;
+    let query = format!(
+        r#"
+SELECT report_date, forecast_hour_beginning, forecast_generation::DOUBLE
+FROM forecast_db.forecast
+WHERE report_date >= '{}'
+AND report_date <= '{}'
+ORDER BY report_date, forecast_hour_beginning;
+"#,
+        start, end
+    );
+    let forecasts = query_forecasts(conn, &query, &tz)?;
+
+    let query = format!(
+        r#"
+SELECT
+    epoch_us(timestamp) // 3600000000 * 3600000000 AS hour_beginning,
+    avg(mw)::DOUBLE
+FROM fuel_mix_db.fuel_mix
+WHERE fuel_category = 'Solar'
+AND timestamp >= '{}'
+AND timestamp < '{}'
+GROUP BY ALL
+ORDER BY hour_beginning;
+"#,
+        start.to_zoned(tz.clone())?.strftime("%Y-%m-%d %H:%M:%S.000%:z"),
+        end.checked_add(8.days())?
+            .to_zoned(tz)?
+            .strftime("%Y-%m-%d %H:%M:%S.000%:z"),
+    );
+    let actuals = query_actuals(conn, &query)?;
+    Ok((forecasts, actuals))
+}
+
+/// Load the IESO market participant wind or solar forecasts issued in
+/// [start, end] and the hourly output of the same fuel.  The connection needs
+/// the VG forecast summary attached as `forecast_db` and the generation
+/// output by fuel as `gen_db`.  If `zone` is `None` the forecast is summed
+/// over the zones, skipping any total rows.  IESO reports in EST all year.
+pub fn load_ieso_vg(
+    conn: &Connection,
+    fuel_type: &str,
+    zone: Option<&str>,
+    start: Date,
+    end: Date,
+) -> Result<ForecastsAndActuals, Box<dyn std::error::Error>> {
+    let tz = TimeZone::fixed(tz::offset(-5));
+    let fuel = match fuel_type.to_lowercase().as_str() {
+        "wind" => "Wind",
+        "solar" => "Solar",
+        _ => return Err(format!("Invalid fuel type {}, use wind or solar", fuel_type).into()),
+    };
+    let zone_filter = match zone {
+        Some(z) => format!("AND zone = '{}'", z),
+        None => format!("AND {}", not_total_sql()),
+    };
+    let query = format!(
+        r#"
+SELECT
+    (epoch_us(forecast_timestamp) - 5 * 3600000000) // 86400000000 AS issue_day,
+    hour_beginning,
+    sum(mw)::DOUBLE
+FROM forecast_db.forecast_summary
+WHERE organization = 'MARKET PARTICIPANT'
+AND fuel_type = '{fuel}'
+{zone_filter}
+AND forecast_timestamp >= '{start}'
+AND forecast_timestamp < '{end}'
+GROUP BY ALL
+ORDER BY issue_day, hour_beginning;
+"#,
+        start = start.to_zoned(tz.clone())?.strftime("%Y-%m-%d %H:%M:%S.000%:z"),
+        end = end
+            .checked_add(1.day())?
+            .to_zoned(tz.clone())?
+            .strftime("%Y-%m-%d %H:%M:%S.000%:z"),
+    );
+    let mut forecasts: Vec<ForecastPoint> = Vec::new();
+    let mut stmt = conn.prepare(&query)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let days: i64 = row.get(0)?;
+        let micros: i64 = row.get(1)?;
+        forecasts.push(ForecastPoint {
+            issue_date: Date::new(1970, 1, 1)?.checked_add(days.days())?,
+            hour_beginning: Timestamp::from_microsecond(micros)?.to_zoned(tz.clone()),
+            forecast_mw: row.get(2)?,
+        });
+    }
+
+    // gen_by_fuel has naive timestamps in EST
+    let query = format!(
+        r#"
+SELECT
+    epoch_us(hour_beginning) + 5 * 3600000000 AS hour_beginning,
+    mw::DOUBLE
+FROM gen_db.gen_by_fuel
+WHERE fuel_type = '{}'
+AND mw IS NOT NULL
+AND hour_beginning >= '{}'
+AND hour_beginning < '{}'
+ORDER BY hour_beginning;
+"#,
+        fuel.to_uppercase(),
+        start,
+        end.checked_add(8.days())?,
+    );
+    let actuals = query_actuals(conn, &query)?;
+    Ok((forecasts, actuals))
+}
+
+fn query_forecasts(
+    conn: &Connection,
+    query: &str,
+    tz: &TimeZone,
+) -> Result<Vec<ForecastPoint>, Box<dyn std::error::Error>> {
+    let mut out: Vec<ForecastPoint> = Vec::new();
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let n = 719528 + row.get::<usize, i32>(0)?;
+        let micros: i64 = row.get(1)?;
+        out.push(ForecastPoint {
+            issue_date: Date::ZERO.checked_add(n.days())?,
+            hour_beginning: Timestamp::from_microsecond(micros)?.to_zoned(tz.clone()),
+            forecast_mw: row.get(2)?,
+        });
+    }
+    Ok(out)
+}
+
+fn query_actuals(
+    conn: &Connection,
+    query: &str,
+) -> Result<HashMap<Timestamp, f64>, Box<dyn std::error::Error>> {
+    let mut out: HashMap<Timestamp, f64> = HashMap::new();
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let micros: i64 = row.get(0)?;
+        out.insert(Timestamp::from_microsecond(micros)?, row.get(1)?);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::civil::date;
+    use std::error::Error;
+
+    #[test]
+    fn test_accuracy() -> Result<(), Box<dyn Error>> {
+        let tz = TimeZone::get("America/New_York")?;
+        let hb = |d: Date, h: i8| d.at(h, 0, 0, 0).to_zoned(tz.clone()).unwrap();
+        let forecasts = vec![
+            ForecastPoint {
+                issue_date: date(2025, 7, 1),
+                hour_beginning: hb(date(2025, 7, 1), 12),
+                forecast_mw: 1100.0,
+            },
+            ForecastPoint {
+                issue_date: date(2025, 7, 1),
+                hour_beginning: hb(date(2025, 7, 2), 12),
+                forecast_mw: 800.0,
+            },
+            ForecastPoint {
+                issue_date: date(2025, 7, 2),
+                hour_beginning: hb(date(2025, 7, 2), 12),
+                forecast_mw: 950.0,
+            },
+            ForecastPoint {
+                issue_date: date(2025, 7, 2),
+                hour_beginning: hb(date(2025, 7, 2), 2),
+                forecast_mw: 5.0,
+            },
+            ForecastPoint {
+                issue_date: date(2025, 7, 1),
+                hour_beginning: hb(date(2025, 7, 9), 12),
+                forecast_mw: 900.0,
+            },
+        ];
+        let actuals: HashMap<Timestamp, f64> = [
+            (hb(date(2025, 7, 1), 12).timestamp(), 1000.0),
+            (hb(date(2025, 7, 2), 12).timestamp(), 1000.0),
+            (hb(date(2025, 7, 2), 2).timestamp(), 0.0),
+        ]
+        .into_iter()
+        .collect();
+
+        let matched = match_actuals(&forecasts, &actuals, 7);
+        assert_eq!(matched.len(), 4);
+        assert_eq!(matched[1].horizon, 2);
+
+        let stats = accuracy(&matched, Grouping::Month, 10.0);
+        assert_eq!(stats.len(), 2);
+        let day1 = &stats[0];
+        assert_eq!(day1.horizon, 1);
+        assert_eq!(day1.month, Some(month(2025, 7)));
+        assert_eq!(day1.observations, 3);
+        assert!((day1.bias_mw - (100.0 - 50.0 + 5.0) / 3.0).abs() < 1e-9);
+        assert!((day1.mae_mw - 155.0 / 3.0).abs() < 1e-9);
+        // the night hour is skipped
+        assert!((day1.mape.unwrap() - 0.075).abs() < 1e-9);
+        assert_eq!(stats[1].bias_mw, -200.0);
+
+        let stats = accuracy(&matched, Grouping::MonthHour, 10.0);
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[0].hour, Some(2));
+        assert_eq!(stats[0].mape, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_isone_solar() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+ATTACH ':memory:' AS forecast_db;
+ATTACH ':memory:' AS fuel_mix_db;
+CREATE TABLE forecast_db.forecast (
+    report_date DATE,
+    forecast_hour_beginning TIMESTAMPTZ,
+    forecast_generation USMALLINT
+);
+INSERT INTO forecast_db.forecast VALUES
+    ('2025-07-01', '2025-07-01 12:00:00-04:00', 1100),
+    ('2025-07-01', '2025-07-02 12:00:00-04:00', 900);
+CREATE TABLE fuel_mix_db.fuel_mix (timestamp TIMESTAMPTZ, mw INT32, fuel_category VARCHAR);
+INSERT INTO fuel_mix_db.fuel_mix VALUES
+    ('2025-07-01 12:00:00-04:00', 990, 'Solar'),
+    ('2025-07-01 12:05:00-04:00', 1010, 'Solar'),
+    ('2025-07-01 12:00:00-04:00', 5000, 'Natural Gas'),
+    ('2025-07-02 12:30:00-04:00', 1000, 'Solar');
+",
+        )?;
+        let (forecasts, actuals) = load_isone_solar(&conn, date(2025, 7, 1), date(2025, 7, 1))?;
+        assert_eq!(forecasts.len(), 2);
+        assert_eq!(forecasts[0].issue_date, date(2025, 7, 1));
+        let matched = match_actuals(&forecasts, &actuals, 7);
+        assert_eq!(matched.len(), 2);
+        assert_eq!(matched[0].actual_mw, 1000.0);
+        assert_eq!(matched[1].horizon, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_ieso_vg() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            r"
+ATTACH ':memory:' AS forecast_db;
+ATTACH ':memory:' AS gen_db;
+CREATE TABLE forecast_db.forecast_summary (
+    forecast_timestamp TIMESTAMPTZ NOT NULL,
+    organization ENUM('MARKET PARTICIPANT', 'EMBEDDED') NOT NULL,
+    fuel_type ENUM('Wind', 'Solar') NOT NULL,
+    zone VARCHAR NOT NULL,
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    mw DECIMAL(9,4) NOT NULL,
+);
+INSERT INTO forecast_db.forecast_summary VALUES
+    ('2025-09-08 05:33:09-05:00', 'MARKET PARTICIPANT', 'Wind', 'NORTHEAST', '2025-09-08 10:00:00-05:00', 300),
+    ('2025-09-08 05:33:09-05:00', 'MARKET PARTICIPANT', 'Wind', 'WEST', '2025-09-08 10:00:00-05:00', 700),
+    ('2025-09-08 05:33:09-05:00', 'MARKET PARTICIPANT', 'Wind', 'OntarioTotal', '2025-09-08 10:00:00-05:00', 1000),
+    ('2025-09-08 05:33:09-05:00', 'EMBEDDED', 'Wind', 'WEST', '2025-09-08 10:00:00-05:00', 50),
+    ('2025-09-08 05:33:09-05:00', 'MARKET PARTICIPANT', 'Wind', 'WEST', '2025-09-09 10:00:00-05:00', 800);
+CREATE TABLE gen_db.gen_by_fuel (
+    hour_beginning TIMESTAMP NOT NULL,
+    fuel_type ENUM('NUCLEAR', 'GAS', 'HYDRO', 'WIND', 'SOLAR', 'BIOFUEL', 'OTHER') NOT NULL,
+    output_quality INT1 NOT NULL,
+    mw UINT16,
+);
+INSERT INTO gen_db.gen_by_fuel VALUES
+    ('2025-09-08 10:00:00', 'WIND', 0, 1200),
+    ('2025-09-08 10:00:00', 'SOLAR', 0, 400),
+    ('2025-09-09 10:00:00', 'WIND', 0, 600);
+",
+        )?;
+        let (forecasts, actuals) =
+            load_ieso_vg(&conn, "wind", None, date(2025, 9, 8), date(2025, 9, 8))?;
+        assert_eq!(forecasts.len(), 2);
+        assert_eq!(forecasts[0].issue_date, date(2025, 9, 8));
+        assert_eq!(forecasts[0].forecast_mw, 1000.0);
+        let matched = match_actuals(&forecasts, &actuals, 7);
+        assert_eq!(matched.len(), 2);
+        assert_eq!(matched[0].actual_mw, 1200.0);
+        assert_eq!(matched[1].horizon, 2);
+        assert_eq!(matched[1].forecast_mw - matched[1].actual_mw, 200.0);
+        Ok(())
+    }
+}
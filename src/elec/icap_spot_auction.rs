@@ -0,0 +1,333 @@
+//! Simulate the clearing of the NYISO ICAP spot auction.
+//!
+//! Each locality has a linear demand curve going through the reference point
+//! (requirement MW, reference price) and reaching zero at the zero-crossing
+//! point (requirement × zero-crossing %), capped at a maximum price.  The
+//! supply is the offers of the locality plus the offers of the localities
+//! nested inside it (see [`nested_locations`]).
+//!
+//! Each locality is cleared on its own, then the price of a nested locality
+//! is floored at the price of the locality that contains it.  This is simpler
+//! than the NYISO optimization, which clears all localities jointly, but it
+//! is close enough to test scenarios before an auction.
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use duckdb::Connection;
+use rust_decimal::prelude::ToPrimitive;
+use serde::Serialize;
+
+use crate::db::nyiso::capacity_offers::{
+    get_data, nested_locations, AuctionType, QueryFilterBuilder,
+};
+use crate::interval::month::Month;
+
+/// The ICAP localities, a locality comes after the one containing it.
+pub const LOCALITIES: [(&str, Option<&str>); 4] = [
+    ("NYCA", None),
+    ("G-J", Some("NYCA")),
+    ("NYC", Some("G-J")),
+    ("LI", Some("NYCA")),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DemandCurve {
+    /// Forecast peak load × (1 + IRM) for NYCA, or × LCR for a locality, in MW
+    pub requirement_mw: f64,
+    /// Price at the requirement, in $/kW-month
+    pub reference_price: f64,
+    /// The curve reaches $0 at this multiple of the requirement, e.g. 1.12
+    pub zero_crossing_pct: f64,
+    /// Price cap, in $/kW-month
+    pub max_price: f64,
+}
+
+impl DemandCurve {
+    /// Build the curve from the load forecast and the IRM (for NYCA) or
+    /// LCR (for a locality).  The price cap is 1.5 times the reference price.
+    pub fn new(
+        peak_load_mw: f64,
+        requirement_pct: f64,
+        reference_price: f64,
+        zero_crossing_pct: f64,
+    ) -> DemandCurve {
+        DemandCurve {
+            requirement_mw: peak_load_mw * requirement_pct,
+            reference_price,
+            zero_crossing_pct,
+            max_price: 1.5 * reference_price,
+        }
+    }
+
+    fn zero_crossing_mw(&self) -> f64 {
+        self.requirement_mw * self.zero_crossing_pct
+    }
+
+    /// Price of the demand curve for a quantity of capacity
+    pub fn price_at(&self, mw: f64) -> f64 {
+        let zcp = self.zero_crossing_mw();
+        let price = self.reference_price * (zcp - mw) / (zcp - self.requirement_mw);
+        price.clamp(0.0, self.max_price)
+    }
+
+    /// Quantity demanded at a price, the inverse of [`DemandCurve::price_at`]
+    /// on the sloped part of the curve.
+    pub fn mw_at(&self, price: f64) -> f64 {
+        let zcp = self.zero_crossing_mw();
+        zcp - price.clamp(0.0, self.max_price) * (zcp - self.requirement_mw) / self.reference_price
+    }
+}
+
+/// A block of capacity offered at a price, in $/kW-month
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Offer {
+    pub price: f64,
+    pub mw: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClearingResult {
+    pub location: String,
+    pub price: f64,
+    pub cleared_mw: f64,
+    pub requirement_mw: f64,
+    /// Total MW offered, including the nested localities
+    pub offered_mw: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpotAuction {
+    pub demand_curves: HashMap<String, DemandCurve>,
+    /// Offers located in each locality, not including the nested localities
+    pub offers: HashMap<String, Vec<Offer>>,
+}
+
+impl SpotAuction {
+    /// Load the spot auction offers for the month from the capacity offers
+    /// archive.
+    pub fn from_archive(
+        conn: &Connection,
+        month: &Month,
+        demand_curves: HashMap<String, DemandCurve>,
+    ) -> Result<SpotAuction, Box<dyn Error>> {
+        let filter = QueryFilterBuilder::new()
+            .auction_type(AuctionType::Spot)
+            .forward_month(month.to_string())
+            .build();
+        let mut offers: HashMap<String, Vec<Offer>> = HashMap::new();
+        for record in get_data(conn, &filter, None)? {
+            offers.entry(record.location).or_default().push(Offer {
+                price: record.price.to_f64().unwrap_or_default(),
+                mw: record.mw.to_f64().unwrap_or_default(),
+            });
+        }
+        Ok(SpotAuction {
+            demand_curves,
+            offers,
+        })
+    }
+
+    pub fn set_demand_curve(&mut self, location: &str, curve: DemandCurve) {
+        self.demand_curves.insert(location.to_string(), curve);
+    }
+
+    /// Add a block of capacity to a locality, e.g. a new resource.
+    pub fn add_offer(&mut self, location: &str, price: f64, mw: f64) {
+        self.offers
+            .entry(location.to_string())
+            .or_default()
+            .push(Offer { price, mw });
+    }
+
+    /// Remove MW from a locality, starting with the cheapest offers, e.g. a
+    /// retirement of a price-taking unit.  Return the MW actually removed.
+    pub fn remove_mw(&mut self, location: &str, mw: f64) -> f64 {
+        let Some(offers) = self.offers.get_mut(location) else {
+            return 0.0;
+        };
+        offers.sort_by(|a, b| a.price.total_cmp(&b.price));
+        let mut left = mw;
+        for offer in offers.iter_mut() {
+            let x = offer.mw.min(left);
+            offer.mw -= x;
+            left -= x;
+            if left <= 0.0 {
+                break;
+            }
+        }
+        offers.retain(|o| o.mw > 0.0);
+        mw - left
+    }
+
+    /// Offers for a locality including the nested localities, sorted by price
+    fn supply(&self, location: &str) -> Vec<Offer> {
+        let mut out: Vec<Offer> = nested_locations(location)
+            .iter()
+            .flat_map(|l| self.offers.get(*l).cloned().unwrap_or_default())
+            .collect();
+        out.sort_by(|a, b| a.price.total_cmp(&b.price));
+        out
+    }
+
+    /// Clear all the localities that have a demand curve.
+    pub fn clear(&self) -> Vec<ClearingResult> {
+        let mut prices: HashMap<&str, f64> = HashMap::new();
+        let mut out: Vec<ClearingResult> = Vec::new();
+        for (location, parent) in LOCALITIES {
+            let Some(curve) = self.demand_curves.get(location) else {
+                continue;
+            };
+            let supply = self.supply(location);
+            let (mut price, cleared_mw) = clear_one(curve, &supply);
+            // floor at the nearest enclosing locality that cleared
+            let mut ancestor = parent;
+            while let Some(p) = ancestor {
+                if let Some(parent_price) = prices.get(p) {
+                    price = price.max(*parent_price);
+                    break;
+                }
+                ancestor = LOCALITIES.iter().find(|(l, _)| *l == p).and_then(|(_, q)| *q);
+            }
+            prices.insert(location, price);
+            out.push(ClearingResult {
+                location: location.to_string(),
+                price,
+                cleared_mw,
+                requirement_mw: curve.requirement_mw,
+                offered_mw: supply.iter().map(|o| o.mw).sum(),
+            });
+        }
+        out
+    }
+}
+
+/// Intersect a demand curve with a supply stack sorted by price.  Return the
+/// clearing price and the cleared MW.
+pub fn clear_one(curve: &DemandCurve, supply: &[Offer]) -> (f64, f64) {
+    let mut q0 = 0.0;
+    for offer in supply {
+        let q1 = q0 + offer.mw;
+        if curve.price_at(q1) >= offer.price {
+            // the entire block clears
+            q0 = q1;
+            continue;
+        }
+        if curve.price_at(q0) >= offer.price {
+            // the block clears partially and sets the price
+            return (offer.price, curve.mw_at(offer.price).max(q0));
+        }
+        // the block doesn't clear, the demand curve sets the price
+        return (curve.price_at(q0), q0);
+    }
+    (curve.price_at(q0), q0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve() -> DemandCurve {
+        DemandCurve::new(1000.0, 1.2, 10.0, 1.1)
+    }
+
+    #[test]
+    fn test_demand_curve() {
+        let c = curve();
+        assert!((c.requirement_mw - 1200.0).abs() < 1e-9);
+        assert!((c.price_at(1200.0) - 10.0).abs() < 1e-9);
+        assert!((c.price_at(1320.0) - 0.0).abs() < 1e-9);
+        assert!((c.price_at(1260.0) - 5.0).abs() < 1e-9);
+        // capped at 1.5 × reference price
+        assert!((c.price_at(0.0) - 15.0).abs() < 1e-9);
+        assert!((c.mw_at(5.0) - 1260.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_clear_one() {
+        let c = curve();
+        // the last block sets the price
+        let supply = vec![
+            Offer {
+                price: 0.0,
+                mw: 1200.0,
+            },
+            Offer {
+                price: 5.0,
+                mw: 100.0,
+            },
+        ];
+        let (price, mw) = clear_one(&c, &supply);
+        assert!((price - 5.0).abs() < 1e-9);
+        assert!((mw - 1260.0).abs() < 1e-9);
+
+        // the demand curve sets the price
+        let supply = vec![
+            Offer {
+                price: 0.0,
+                mw: 1230.0,
+            },
+            Offer {
+                price: 9.0,
+                mw: 100.0,
+            },
+        ];
+        let (price, mw) = clear_one(&c, &supply);
+        assert!((price - 7.5).abs() < 1e-9);
+        assert!((mw - 1230.0).abs() < 1e-9);
+
+        // all the supply clears
+        let (price, _) = clear_one(
+            &c,
+            &[Offer {
+                price: 0.0,
+                mw: 1320.0,
+            }],
+        );
+        assert!(price.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_scenarios() {
+        let mut auction = SpotAuction::default();
+        auction.set_demand_curve("NYCA", curve());
+        auction.set_demand_curve("NYC", DemandCurve::new(100.0, 0.8, 20.0, 1.2));
+        auction.add_offer("NYCA", 0.0, 1150.0);
+        auction.add_offer("NYC", 0.0, 110.0);
+
+        let results = auction.clear();
+        assert_eq!(results.len(), 2);
+        // NYCA has 1260 MW offered, clears at 5.0
+        assert!((results[0].price - 5.0).abs() < 1e-9);
+        assert!((results[0].offered_mw - 1260.0).abs() < 1e-9);
+        // NYC has excess supply but the price is floored at the NYCA price
+        assert_eq!(results[1].location, "NYC");
+        assert!((results[1].price - 5.0).abs() < 1e-9);
+
+        // retire 60 MW in NYC
+        assert!((auction.remove_mw("NYC", 60.0) - 60.0).abs() < 1e-9);
+        let results = auction.clear();
+        assert!((results[0].price - 10.0).abs() < 1e-9);
+        assert!(results[1].price > results[0].price);
+    }
+
+    #[test]
+    fn test_floor_at_nearest_cleared_ancestor() {
+        // G-J has no demand curve, so NYC is floored at the NYCA price
+        let mut auction = SpotAuction::default();
+        auction.set_demand_curve("NYCA", curve());
+        auction.set_demand_curve("NYC", DemandCurve::new(100.0, 0.8, 20.0, 1.2));
+        auction.add_offer("NYCA", 0.0, 1000.0);
+        auction.add_offer("NYC", 0.0, 200.0);
+
+        let results = auction.clear();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.location != "G-J"));
+        // NYC alone clears at $0 with 200 MW against a 96 MW zero-crossing
+        assert!(clear_one(&auction.demand_curves["NYC"], &auction.supply("NYC")).0 < 1e-9);
+        let nyca = results[0].price;
+        assert!(nyca > 0.0);
+        assert_eq!(results[1].location, "NYC");
+        assert!((results[1].price - nyca).abs() < 1e-9);
+    }
+}
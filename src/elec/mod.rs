@@ -1,2 +1,7 @@
+pub mod aggregation;
+pub mod forecast_accuracy;
 pub mod ftr_auction;
+pub mod icap_spot_auction;
 pub mod iso;
+pub mod shift_factors;
+pub mod supply_curve;
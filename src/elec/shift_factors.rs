@@ -0,0 +1,308 @@
+//! Estimate the shift factors of nodes to the binding constraints from
+//! market data, when no PTDFs are published.
+//!
+//! In the DA market the congestion component of a node is the sum over the
+//! binding constraints of the node's shift factor times the constraint
+//! shadow price.  Regressing the hourly MCC of a node on the hourly shadow
+//! prices of the frequently binding constraints over a term recovers the
+//! shift factors, in the sign convention of the market.  A constraint that
+//! binds under several contingencies has the shadow prices summed, so its
+//! estimate is an average of the post-contingency shift factors.
+//!
+//! The loaders expect the DA LMP database attached as `lmp_db` and the DA
+//! binding constraints database attached as `constraints_db`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+
+use duckdb::Connection;
+use jiff::{Timestamp, Zoned};
+use serde::Serialize;
+
+use crate::interval::interval_base::IntervalTzLike;
+use crate::interval::term::Term;
+use crate::math::least_squares::ols_through_origin;
+
+/// Hourly MCC by node and hourly shadow prices by constraint over a term
+#[derive(Debug, Clone, Default)]
+pub struct CongestionData {
+    pub mcc: HashMap<u32, BTreeMap<Timestamp, f64>>,
+    pub shadow_prices: HashMap<String, HashMap<Timestamp, f64>>,
+}
+
+impl CongestionData {
+    /// The constraints that bind in at least `min_binding_hours` hours, most
+    /// frequent first, at most `max_constraints` of them.
+    pub fn frequent_constraints(&self, min_binding_hours: usize, max_constraints: usize) -> Vec<String> {
+        let mut xs: Vec<(&String, usize)> = self
+            .shadow_prices
+            .iter()
+            .map(|(name, prices)| (name, prices.values().filter(|v| **v != 0.0).count()))
+            .filter(|(_, count)| *count >= min_binding_hours)
+            .collect();
+        xs.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        xs.into_iter()
+            .take(max_constraints)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShiftFactor {
+    pub constraint_name: String,
+    /// Change of the node MCC for a $1/MWh change of the constraint shadow price
+    pub shift_factor: f64,
+    pub std_error: f64,
+    pub t_stat: f64,
+    pub binding_hours: usize,
+    /// Average over the term of the shift factor × shadow price, in $/MWh.
+    /// The attributions plus the residual add up to the average MCC.
+    pub attributed_mcc: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeFit {
+    pub ptid: u32,
+    pub hours: usize,
+    pub average_mcc: f64,
+    /// Uncentered R² of the regression
+    pub r_squared: f64,
+    /// Average MCC not explained by the constraints, in $/MWh
+    pub residual_mcc: f64,
+    pub shift_factors: Vec<ShiftFactor>,
+}
+
+/// Regress the MCC of one node on the shadow prices of the `constraints`.
+/// Return `None` if the node has no more hours than constraints or if two
+/// constraints always bind together, so their effects can't be separated.
+pub fn estimate_node(data: &CongestionData, ptid: u32, constraints: &[String]) -> Option<NodeFit> {
+    let mcc = data.mcc.get(&ptid)?;
+    let empty = HashMap::new();
+    let prices: Vec<&HashMap<Timestamp, f64>> = constraints
+        .iter()
+        .map(|c| data.shadow_prices.get(c).unwrap_or(&empty))
+        .collect();
+    let x: Vec<Vec<f64>> = mcc
+        .keys()
+        .map(|hour| {
+            prices
+                .iter()
+                .map(|p| p.get(hour).cloned().unwrap_or(0.0))
+                .collect()
+        })
+        .collect();
+    let y: Vec<f64> = mcc.values().cloned().collect();
+    let fit = ols_through_origin(&x, &y)?;
+
+    let n = y.len() as f64;
+    let shift_factors = constraints
+        .iter()
+        .enumerate()
+        .map(|(k, name)| {
+            let sf = fit.coefficients[k];
+            ShiftFactor {
+                constraint_name: name.clone(),
+                shift_factor: sf,
+                std_error: fit.std_errors[k],
+                t_stat: sf / fit.std_errors[k],
+                binding_hours: x.iter().filter(|row| row[k] != 0.0).count(),
+                attributed_mcc: x.iter().map(|row| sf * row[k]).sum::<f64>() / n,
+            }
+        })
+        .collect();
+    Some(NodeFit {
+        ptid,
+        hours: y.len(),
+        average_mcc: y.iter().sum::<f64>() / n,
+        r_squared: fit.r_squared,
+        residual_mcc: fit.residuals.iter().sum::<f64>() / n,
+        shift_factors,
+    })
+}
+
+/// Estimate the shift factors of the `ptids` to the frequently binding
+/// constraints.  Nodes where the regression can't be solved are skipped.
+pub fn estimate(
+    data: &CongestionData,
+    ptids: &[u32],
+    min_binding_hours: usize,
+    max_constraints: usize,
+) -> Vec<NodeFit> {
+    let constraints = data.frequent_constraints(min_binding_hours, max_constraints);
+    ptids
+        .iter()
+        .filter_map(|ptid| estimate_node(data, *ptid, &constraints))
+        .collect()
+}
+
+fn time_filter(column: &str, start: &Zoned, end: &Zoned) -> String {
+    format!(
+        "{column} >= '{}' AND {column} < '{}'",
+        start.strftime("%Y-%m-%d %H:%M:%S.000%:z"),
+        end.strftime("%Y-%m-%d %H:%M:%S.000%:z"),
+    )
+}
+
+fn load(conn: &Connection, mcc_query: &str, shadow_query: &str) -> Result<CongestionData, Box<dyn Error>> {
+    let mut data = CongestionData::default();
+    let mut stmt = conn.prepare(mcc_query)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let hour = Timestamp::from_microsecond(row.get::<usize, i64>(0)?)?;
+        data.mcc
+            .entry(row.get::<usize, u32>(1)?)
+            .or_default()
+            .insert(hour, row.get::<usize, f64>(2)?);
+    }
+    let mut stmt = conn.prepare(shadow_query)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let hour = Timestamp::from_microsecond(row.get::<usize, i64>(0)?)?;
+        data.shadow_prices
+            .entry(row.get::<usize, String>(1)?)
+            .or_default()
+            .insert(hour, row.get::<usize, f64>(2)?);
+    }
+    Ok(data)
+}
+
+/// Load the ISONE DA MCC from `lmp_db.da_lmp` and the DA shadow prices from
+/// `constraints_db.constraints`.
+pub fn load_isone(conn: &Connection, term: &Term, ptids: &[u32]) -> Result<CongestionData, Box<dyn Error>> {
+    let term = term.with_tz(&crate::elec::iso::ISONE.tz);
+    let (start, end) = (term.start(), term.end());
+    let mcc_query = format!(
+        r#"
+SELECT hour_beginning, ptid, mcc::DOUBLE
+FROM lmp_db.da_lmp
+WHERE {}
+AND ptid IN ({});"#,
+        time_filter("hour_beginning", &start, &end),
+        ptids.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(",")
+    );
+    let shadow_query = format!(
+        r#"
+SELECT hour_beginning, constraint_name, SUM(marginal_value)::DOUBLE
+FROM constraints_db.constraints
+WHERE {}
+GROUP BY hour_beginning, constraint_name;"#,
+        time_filter("hour_beginning", &start, &end),
+    );
+    load(conn, &mcc_query, &shadow_query)
+}
+
+/// Load the NYISO DA MCC from `lmp_db.dalmp` and the DA constraint costs from
+/// `constraints_db.binding_constraints`.
+pub fn load_nyiso(conn: &Connection, term: &Term, ptids: &[u32]) -> Result<CongestionData, Box<dyn Error>> {
+    let term = term.with_tz(&jiff::tz::TimeZone::get("America/New_York")?);
+    let (start, end) = (term.start(), term.end());
+    let mcc_query = format!(
+        r#"
+SELECT hour_beginning, ptid::UINTEGER, mcc::DOUBLE
+FROM lmp_db.dalmp
+WHERE {}
+AND ptid IN ({});"#,
+        time_filter("hour_beginning", &start, &end),
+        ptids.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(",")
+    );
+    let shadow_query = format!(
+        r#"
+SELECT hour_beginning, limiting_facility, SUM(constraint_cost)::DOUBLE
+FROM constraints_db.binding_constraints
+WHERE market = 'DA'
+AND {}
+GROUP BY hour_beginning, limiting_facility;"#,
+        time_filter("hour_beginning", &start, &end),
+    );
+    load(conn, &mcc_query, &shadow_query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::civil::date;
+
+    fn setup(conn: &Connection) -> Result<(), Box<dyn Error>> {
+        // node 4001 has shift factor -0.5 to A and 0.2 to B,
+        // node 4002 has shift factor 1.0 to A and doesn't see B.
+        let mut sql = String::from(
+            r"
+ATTACH ':memory:' AS lmp_db;
+ATTACH ':memory:' AS constraints_db;
+CREATE TABLE lmp_db.da_lmp (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    ptid UINTEGER NOT NULL,
+    lmp DECIMAL(9,4) NOT NULL,
+    mcc DECIMAL(9,4) NOT NULL,
+    mcl DECIMAL(9,4) NOT NULL,
+);
+CREATE TABLE constraints_db.constraints (
+    hour_beginning TIMESTAMPTZ NOT NULL,
+    constraint_name VARCHAR NOT NULL,
+    contingency_name VARCHAR NOT NULL,
+    marginal_value DECIMAL(9,2) NOT NULL
+);
+",
+        );
+        let a = [10.0, 0.0, 4.0, 20.0, 0.0, 6.0, 0.0, 8.0];
+        let b = [0.0, 5.0, 5.0, 0.0, 0.0, 10.0, 15.0, 0.0];
+        for h in 0..8 {
+            let hour = format!("2025-07-01 {:02}:00:00-04:00", h);
+            sql.push_str(&format!(
+                "INSERT INTO lmp_db.da_lmp VALUES ('{hour}', 4001, 0, {}, 0), ('{hour}', 4002, 0, {}, 0);\n",
+                -0.5 * a[h] + 0.2 * b[h],
+                a[h],
+            ));
+            if a[h] != 0.0 {
+                // A binds under two contingencies in hour 3
+                if h == 3 {
+                    sql.push_str(&format!(
+                        "INSERT INTO constraints_db.constraints VALUES ('{hour}', 'A', 'c1', 15), ('{hour}', 'A', 'c2', 5);\n"
+                    ));
+                } else {
+                    sql.push_str(&format!(
+                        "INSERT INTO constraints_db.constraints VALUES ('{hour}', 'A', 'Actl', {});\n",
+                        a[h]
+                    ));
+                }
+            }
+            if b[h] != 0.0 {
+                sql.push_str(&format!(
+                    "INSERT INTO constraints_db.constraints VALUES ('{hour}', 'B', 'Actl', {});\n",
+                    b[h]
+                ));
+            }
+        }
+        sql.push_str("INSERT INTO constraints_db.constraints VALUES ('2025-07-01 05:00:00-04:00', 'C', 'Actl', 1);\n");
+        conn.execute_batch(&sql)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_estimate() -> Result<(), Box<dyn Error>> {
+        let conn = Connection::open_in_memory()?;
+        setup(&conn)?;
+        let term = Term::new(date(2025, 7, 1), date(2025, 7, 1)).unwrap();
+        let data = load_isone(&conn, &term, &[4001, 4002])?;
+        assert_eq!(data.mcc[&4001].len(), 8);
+
+        // C binds only once
+        assert_eq!(data.frequent_constraints(2, 10), vec!["A", "B"]);
+
+        let fits = estimate(&data, &[4001, 4002], 2, 10);
+        assert_eq!(fits.len(), 2);
+        let fit = &fits[0];
+        assert_eq!(fit.ptid, 4001);
+        assert!((fit.shift_factors[0].shift_factor + 0.5).abs() < 1e-9);
+        assert!((fit.shift_factors[1].shift_factor - 0.2).abs() < 1e-9);
+        assert_eq!(fit.shift_factors[0].binding_hours, 5);
+        assert!((fit.r_squared - 1.0).abs() < 1e-9);
+        let attributed: f64 = fit.shift_factors.iter().map(|e| e.attributed_mcc).sum();
+        assert!((attributed + fit.residual_mcc - fit.average_mcc).abs() < 1e-9);
+
+        assert!((fits[1].shift_factors[0].shift_factor - 1.0).abs() < 1e-9);
+        assert!(fits[1].shift_factors[1].shift_factor.abs() < 1e-9);
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+//! Clear a masked energy offer stack against a load level.
+//!
+//! The offer segments returned by the ISONE and NYISO stack queries are
+//! sorted by price and accumulated into a supply curve.  The residual load
+//! (load minus imports and must-run generation not in the stack) is then
+//! served from the bottom of the curve, and the segment serving the last MW
+//! sets the price.  This ignores transmission, ramping and commitment, so
+//! the implied price is a system-wide approximation.  Replaying historical
+//! hours against the DA LMP gives the adjustment that reconciles the model
+//! with the market, see [`replay`] and [`calibrate`].
+
+use std::collections::BTreeMap;
+
+use jiff::Timestamp;
+use serde::Serialize;
+
+use crate::api::isone::_api_isone_core::UnitStatus;
+use crate::api::isone::masked::masked_energy_offers::EnergyOffer as IsoneEnergyOffer;
+use crate::api::nyiso::energy_offers::EnergyOffer as NyisoEnergyOffer;
+
+/// Step size used to calculate the price sensitivity
+const SENSITIVITY_MW: f64 = 100.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Segment {
+    pub masked_asset_id: u32,
+    pub price: f64,
+    pub mw: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SupplyCurve {
+    /// Sorted by price
+    pub segments: Vec<Segment>,
+    /// Cumulative MW at the end of each segment
+    pub cumulative_mw: Vec<f64>,
+}
+
+impl SupplyCurve {
+    pub fn new(mut segments: Vec<Segment>) -> SupplyCurve {
+        segments.retain(|s| s.mw > 0.0);
+        segments.sort_by(|a, b| a.price.total_cmp(&b.price));
+        let cumulative_mw = segments
+            .iter()
+            .scan(0.0, |acc, s| {
+                *acc += s.mw;
+                Some(*acc)
+            })
+            .collect();
+        SupplyCurve {
+            segments,
+            cumulative_mw,
+        }
+    }
+
+    pub fn total_mw(&self) -> f64 {
+        self.cumulative_mw.last().cloned().unwrap_or(0.0)
+    }
+
+    /// Index of the segment that serves the `mw`-th MW
+    fn marginal_index(&self, mw: f64) -> Option<usize> {
+        if self.segments.is_empty() || mw > self.total_mw() {
+            return None;
+        }
+        Some(self.cumulative_mw.partition_point(|c| *c < mw))
+    }
+
+    /// Price of the segment that serves the `mw`-th MW.  Return `None` if
+    /// the curve doesn't have enough supply.
+    pub fn price_at(&self, mw: f64) -> Option<f64> {
+        self.marginal_index(mw).map(|i| self.segments[i].price)
+    }
+
+    /// MW offered at or below a price
+    pub fn mw_at(&self, price: f64) -> f64 {
+        let i = self.segments.partition_point(|s| s.price <= price);
+        if i == 0 {
+            0.0
+        } else {
+            self.cumulative_mw[i - 1]
+        }
+    }
+
+    /// Clear the curve against `load_mw` minus the MW of the `adjustment`.
+    pub fn clear(&self, load_mw: f64, adjustment: &Adjustment) -> ClearingResult {
+        let net_load_mw = load_mw - adjustment.total_mw();
+        let marginal_price = self.price_at(net_load_mw);
+        let marginal_units = match marginal_price {
+            Some(price) => {
+                let mut ids: Vec<u32> = self
+                    .segments
+                    .iter()
+                    .filter(|s| s.price == price)
+                    .map(|s| s.masked_asset_id)
+                    .collect();
+                ids.sort_unstable();
+                ids.dedup();
+                ids
+            }
+            None => vec![],
+        };
+        let half = SENSITIVITY_MW / 2.0;
+        let sensitivity = match (
+            self.price_at(net_load_mw - half),
+            self.price_at(net_load_mw + half),
+        ) {
+            (Some(lo), Some(hi)) => Some(hi - lo),
+            _ => None,
+        };
+        ClearingResult {
+            load_mw,
+            net_load_mw,
+            offered_mw: self.total_mw(),
+            marginal_price,
+            marginal_units,
+            price_sensitivity_per_100mw: sensitivity,
+        }
+    }
+}
+
+/// MW served outside of the offer stack
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Adjustment {
+    pub imports_mw: f64,
+    pub must_run_mw: f64,
+}
+
+impl Adjustment {
+    pub fn total_mw(&self) -> f64 {
+        self.imports_mw + self.must_run_mw
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClearingResult {
+    pub load_mw: f64,
+    /// Load minus the adjustment, the MW served by the stack
+    pub net_load_mw: f64,
+    pub offered_mw: f64,
+    /// `None` if the stack can't serve the net load
+    pub marginal_price: Option<f64>,
+    /// The units with a segment at the marginal price
+    pub marginal_units: Vec<u32>,
+    /// Price change for the net load going from 50 MW below to 50 MW above
+    /// the cleared quantity, in $/MWh per 100 MW
+    pub price_sensitivity_per_100mw: Option<f64>,
+}
+
+/// Group the ISONE offers by hour and build one supply curve per hour.
+/// Unavailable units are skipped.
+pub fn from_isone_offers(offers: &[IsoneEnergyOffer]) -> BTreeMap<Timestamp, SupplyCurve> {
+    let mut groups: BTreeMap<Timestamp, Vec<Segment>> = BTreeMap::new();
+    for offer in offers.iter().filter(|o| o.unit_status != UnitStatus::Unavailable) {
+        groups
+            .entry(offer.hour_beginning.timestamp())
+            .or_default()
+            .push(Segment {
+                masked_asset_id: offer.masked_asset_id,
+                price: offer.price as f64,
+                mw: offer.quantity as f64,
+            });
+    }
+    groups
+        .into_iter()
+        .map(|(k, v)| (k, SupplyCurve::new(v)))
+        .collect()
+}
+
+/// Group the NYISO offers by hour and build one supply curve per hour.
+pub fn from_nyiso_offers(offers: &[NyisoEnergyOffer]) -> BTreeMap<Timestamp, SupplyCurve> {
+    let mut groups: BTreeMap<Timestamp, Vec<Segment>> = BTreeMap::new();
+    for offer in offers {
+        let Ok(ts) = Timestamp::from_second(offer.timestamp_s) else {
+            continue;
+        };
+        groups.entry(ts).or_default().push(Segment {
+            masked_asset_id: offer.masked_asset_id,
+            price: offer.price as f64,
+            mw: offer.quantity as f64,
+        });
+    }
+    groups
+        .into_iter()
+        .map(|(k, v)| (k, SupplyCurve::new(v)))
+        .collect()
+}
+
+/// One historical hour cleared against the actual load and compared with
+/// the DA LMP.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Replay {
+    pub timestamp: Timestamp,
+    pub load_mw: f64,
+    pub da_lmp: f64,
+    pub simulated_price: Option<f64>,
+    /// Simulated price minus the DA LMP
+    pub error: Option<f64>,
+    /// The adjustment that makes the curve clear at the DA LMP, i.e. the load
+    /// minus the MW offered at or below the LMP
+    pub implied_adjustment_mw: f64,
+}
+
+pub fn replay(
+    timestamp: Timestamp,
+    curve: &SupplyCurve,
+    load_mw: f64,
+    da_lmp: f64,
+    adjustment: &Adjustment,
+) -> Replay {
+    let result = curve.clear(load_mw, adjustment);
+    Replay {
+        timestamp,
+        load_mw,
+        da_lmp,
+        simulated_price: result.marginal_price,
+        error: result.marginal_price.map(|p| p - da_lmp),
+        implied_adjustment_mw: load_mw - curve.mw_at(da_lmp),
+    }
+}
+
+/// The median implied adjustment over the replayed hours, a robust default
+/// for the imports + must-run MW when simulating other hours.
+pub fn calibrate(replays: &[Replay]) -> Option<f64> {
+    if replays.is_empty() {
+        return None;
+    }
+    let mut xs: Vec<f64> = replays.iter().map(|r| r.implied_adjustment_mw).collect();
+    xs.sort_by(|a, b| a.total_cmp(b));
+    let n = xs.len();
+    Some(if n % 2 == 1 {
+        xs[n / 2]
+    } else {
+        (xs[n / 2 - 1] + xs[n / 2]) / 2.0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve() -> SupplyCurve {
+        SupplyCurve::new(vec![
+            Segment {
+                masked_asset_id: 3,
+                price: 50.0,
+                mw: 100.0,
+            },
+            Segment {
+                masked_asset_id: 1,
+                price: 10.0,
+                mw: 200.0,
+            },
+            Segment {
+                masked_asset_id: 2,
+                price: 25.0,
+                mw: 100.0,
+            },
+            Segment {
+                masked_asset_id: 4,
+                price: 25.0,
+                mw: 50.0,
+            },
+        ])
+    }
+
+    #[test]
+    fn test_curve() {
+        let c = curve();
+        assert_eq!(c.cumulative_mw, vec![200.0, 300.0, 350.0, 450.0]);
+        assert_eq!(c.price_at(150.0), Some(10.0));
+        assert_eq!(c.price_at(200.0), Some(10.0));
+        assert_eq!(c.price_at(201.0), Some(25.0));
+        assert_eq!(c.price_at(500.0), None);
+        assert_eq!(c.mw_at(25.0), 350.0);
+        assert_eq!(c.mw_at(5.0), 0.0);
+    }
+
+    #[test]
+    fn test_clear() {
+        let c = curve();
+        let adj = Adjustment {
+            imports_mw: 100.0,
+            must_run_mw: 0.0,
+        };
+        let r = c.clear(400.0, &adj);
+        assert_eq!(r.net_load_mw, 300.0);
+        assert_eq!(r.marginal_price, Some(25.0));
+        assert_eq!(r.marginal_units, vec![2, 4]);
+        // 250 MW -> $25, 350 MW -> $25
+        assert_eq!(r.price_sensitivity_per_100mw, Some(0.0));
+
+        let r = c.clear(330.0, &Adjustment::default());
+        assert_eq!(r.price_sensitivity_per_100mw, Some(25.0));
+
+        let r = c.clear(1000.0, &Adjustment::default());
+        assert_eq!(r.marginal_price, None);
+        assert!(r.marginal_units.is_empty());
+    }
+
+    #[test]
+    fn test_replay() {
+        let c = curve();
+        let ts = Timestamp::from_second(1_750_000_000).unwrap();
+        let x = replay(ts, &c, 500.0, 25.0, &Adjustment::default());
+        assert_eq!(x.simulated_price, None);
+        assert_eq!(x.implied_adjustment_mw, 150.0);
+        let y = replay(ts, &c, 400.0, 10.0, &Adjustment::default());
+        assert_eq!(y.simulated_price, Some(50.0));
+        assert_eq!(y.error, Some(40.0));
+        assert_eq!(y.implied_adjustment_mw, 200.0);
+        assert_eq!(calibrate(&[x, y]), Some(175.0));
+    }
+}
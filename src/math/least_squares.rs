@@ -0,0 +1,121 @@
+//! Ordinary least squares through the origin, solved with the normal
+//! equations.  Good enough for a few dozen regressors.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OlsFit {
+    pub coefficients: Vec<f64>,
+    pub std_errors: Vec<f64>,
+    /// Uncentered R², since the model has no intercept
+    pub r_squared: f64,
+    pub residuals: Vec<f64>,
+}
+
+/// Fit `y = X β + ε` without an intercept.  Each element of `x` is one
+/// observation.  Return `None` if there are not more observations than
+/// regressors, or if `X'X` is singular, e.g. two regressors are collinear.
+pub fn ols_through_origin(x: &[Vec<f64>], y: &[f64]) -> Option<OlsFit> {
+    let n = y.len();
+    let k = x.first().map_or(0, |row| row.len());
+    if k == 0 || n != x.len() || n <= k {
+        return None;
+    }
+
+    let mut xtx = vec![vec![0.0; k]; k];
+    let mut xty = vec![0.0; k];
+    for (row, yi) in x.iter().zip(y) {
+        for i in 0..k {
+            xty[i] += row[i] * yi;
+            for j in 0..k {
+                xtx[i][j] += row[i] * row[j];
+            }
+        }
+    }
+    let inv = invert(xtx)?;
+    let coefficients: Vec<f64> = (0..k)
+        .map(|i| (0..k).map(|j| inv[i][j] * xty[j]).sum())
+        .collect();
+
+    let residuals: Vec<f64> = x
+        .iter()
+        .zip(y)
+        .map(|(row, yi)| yi - row.iter().zip(&coefficients).map(|(a, b)| a * b).sum::<f64>())
+        .collect();
+    let rss: f64 = residuals.iter().map(|e| e * e).sum();
+    let tss: f64 = y.iter().map(|e| e * e).sum();
+    let sigma2 = rss / (n - k) as f64;
+    let std_errors = (0..k).map(|i| (sigma2 * inv[i][i]).sqrt()).collect();
+    let r_squared = if tss > 0.0 { 1.0 - rss / tss } else { 0.0 };
+
+    Some(OlsFit {
+        coefficients,
+        std_errors,
+        r_squared,
+        residuals,
+    })
+}
+
+/// Gauss-Jordan elimination with partial pivoting.
+fn invert(mut a: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let k = a.len();
+    let scale = a
+        .iter()
+        .flat_map(|row| row.iter())
+        .fold(0.0_f64, |m, v| m.max(v.abs()));
+    let mut inv: Vec<Vec<f64>> = (0..k)
+        .map(|i| (0..k).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    for col in 0..k {
+        let pivot = (col..k).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() <= 1e-12 * scale.max(1.0) {
+            return None;
+        }
+        a.swap(col, pivot);
+        inv.swap(col, pivot);
+        let p = a[col][col];
+        for j in 0..k {
+            a[col][j] /= p;
+            inv[col][j] /= p;
+        }
+        for i in 0..k {
+            if i == col {
+                continue;
+            }
+            let f = a[i][col];
+            if f == 0.0 {
+                continue;
+            }
+            for j in 0..k {
+                a[i][j] -= f * a[col][j];
+                inv[i][j] -= f * inv[col][j];
+            }
+        }
+    }
+    Some(inv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ols() {
+        // y = 2 x1 - 0.5 x2, plus a small disturbance
+        let x = vec![
+            vec![1.0, 0.0],
+            vec![0.0, 1.0],
+            vec![1.0, 1.0],
+            vec![2.0, 1.0],
+            vec![3.0, 2.0],
+        ];
+        let y: Vec<f64> = x.iter().map(|r| 2.0 * r[0] - 0.5 * r[1]).collect();
+        let fit = ols_through_origin(&x, &y).unwrap();
+        assert!((fit.coefficients[0] - 2.0).abs() < 1e-9);
+        assert!((fit.coefficients[1] + 0.5).abs() < 1e-9);
+        assert!((fit.r_squared - 1.0).abs() < 1e-9);
+        assert!(fit.std_errors[0].abs() < 1e-6);
+
+        // collinear regressors
+        let x = vec![vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]];
+        assert!(ols_through_origin(&x, &[1.0, 2.0, 3.0]).is_none());
+    }
+}
@@ -1 +1,2 @@
+pub mod least_squares;
 mod puzzles;
@@ -0,0 +1,157 @@
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use flate2::{write::GzEncoder, Compression};
+use log::info;
+
+/// Create a gz file for writing, creating the parent directories if needed.
+/// Call `finish()` on the encoder when done to catch write errors.
+pub fn create_gz(path: &Path) -> Result<GzEncoder<BufWriter<File>>, Box<dyn Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = File::create(path)
+        .map_err(|e| format!("Failed to create file {}: {}", path.display(), e))?;
+    Ok(GzEncoder::new(BufWriter::new(file), Compression::default()))
+}
+
+/// Stream a reader into a gz file.  Return the number of uncompressed bytes.
+pub fn write_gz<R: Read + ?Sized>(reader: &mut R, path: &Path) -> Result<u64, Box<dyn Error>> {
+    let mut encoder = create_gz(path)?;
+    let n = io::copy(reader, &mut encoder)?;
+    encoder.finish()?.flush()?;
+    Ok(n)
+}
+
+/// Compress a file, same as `gzip -f`: write `{path}.gz` and remove the original.
+/// Return the path of the gz file.
+pub fn gzip_file(path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let gz_path = PathBuf::from(format!("{}.gz", path.display()));
+    let file =
+        File::open(path).map_err(|e| format!("Failed to open file {}: {}", path.display(), e))?;
+    write_gz(&mut BufReader::new(file), &gz_path)?;
+    fs::remove_file(path)?;
+    Ok(gz_path)
+}
+
+/// Extract all the files of a zip archive into the directory `dir`, overwriting
+/// existing files, same as `unzip -o`.  Return the paths of the extracted files.
+pub fn unzip_file(zip_path: &Path, dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let file = File::open(zip_path)
+        .map_err(|e| format!("Failed to open file {}: {}", zip_path.display(), e))?;
+    let mut zip = zip::ZipArchive::new(BufReader::new(file))?;
+    let mut out: Vec<PathBuf> = Vec::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        let name = match entry.enclosed_name() {
+            Some(path) => path,
+            None => continue,
+        };
+        let out_path = dir.join(name);
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path)?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(&out_path)?);
+        io::copy(&mut entry, &mut writer)?;
+        writer.flush()?;
+        out.push(out_path);
+    }
+    Ok(out)
+}
+
+/// Extract each file of a zip archive directly into a gz file, without writing
+/// the uncompressed file to disk.  The function `out_path` maps the file name of
+/// an entry to the path of the uncompressed output file (the `.gz` extension is
+/// added), or `None` to skip the entry.  Return the paths of the gz files.
+pub fn unzip_to_gz<F>(zip_path: &Path, out_path: F) -> Result<Vec<PathBuf>, Box<dyn Error>>
+where
+    F: Fn(&str) -> Result<Option<String>, Box<dyn Error>>,
+{
+    let file = File::open(zip_path)
+        .map_err(|e| format!("Failed to open file {}: {}", zip_path.display(), e))?;
+    let mut zip = zip::ZipArchive::new(BufReader::new(file))?;
+    let mut out: Vec<PathBuf> = Vec::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = match entry
+            .enclosed_name()
+            .and_then(|p| p.file_name().map(|e| e.to_string_lossy().to_string()))
+        {
+            Some(name) => name,
+            None => continue,
+        };
+        let path = match out_path(&name)? {
+            Some(path) => PathBuf::from(format!("{}.gz", path)),
+            None => continue,
+        };
+        write_gz(&mut entry, &path)?;
+        info!(" -- extracted {} to {}", name, path.display());
+        out.push(path);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, io::Read};
+
+    use flate2::read::GzDecoder;
+    use zip::write::SimpleFileOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_gzip_and_unzip() -> Result<(), Box<dyn Error>> {
+        let dir = std::env::temp_dir().join(format!("bust_compression_{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+
+        // gzip a file in place
+        let path = dir.join("a.csv");
+        fs::write(&path, "x,y\n1,2\n")?;
+        let gz_path = gzip_file(&path)?;
+        assert!(!path.exists());
+        let mut content = String::new();
+        GzDecoder::new(File::open(&gz_path)?).read_to_string(&mut content)?;
+        assert_eq!(content, "x,y\n1,2\n");
+
+        // make a zip file with two entries
+        let zip_path = dir.join("data.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path)?);
+        zip.start_file("20250101data.csv", SimpleFileOptions::default())?;
+        zip.write_all(b"day 1")?;
+        zip.start_file("20250102data.csv", SimpleFileOptions::default())?;
+        zip.write_all(b"day 2")?;
+        zip.finish()?;
+
+        let files = unzip_file(&zip_path, &dir.join("out"))?;
+        assert_eq!(files.len(), 2);
+        assert_eq!(fs::read_to_string(&files[1])?, "day 2");
+
+        let out_dir = dir.join("gz");
+        let files = unzip_to_gz(&zip_path, |name| {
+            if name.starts_with("20250102") {
+                Ok(Some(format!("{}/{}", out_dir.display(), name)))
+            } else {
+                Ok(None)
+            }
+        })?;
+        assert_eq!(files, vec![out_dir.join("20250102data.csv.gz")]);
+        let mut content = String::new();
+        GzDecoder::new(File::open(&files[0])?).read_to_string(&mut content)?;
+        assert_eq!(content, "day 2");
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
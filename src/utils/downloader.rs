@@ -0,0 +1,520 @@
+//! A shared HTTP client for downloading ISO files.
+//!
+//! - Credentials and request pacing are set per source with an [`AuthProfile`].
+//! - Requests are retried with exponential back-off on 429 and 5xx responses
+//!   and on connection errors.  A `Retry-After` header is honored.
+//! - Requests to the same host are spaced out by at least the profile's
+//!   minimum interval.
+//! - [`Downloader::download_file`] does a conditional GET (ETag/Last-Modified)
+//!   if the file already exists, and resumes a partial download left from a
+//!   previous failed attempt.
+//! - For tests, set a base url (e.g. `http://127.0.0.1:8999`) to redirect all
+//!   requests to a local mock server.  The shared downloader reads it from the
+//!   `BUST_HTTP_BASE_URL` environment variable.
+
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use reqwest::{
+    blocking::{Client, RequestBuilder, Response},
+    header::{
+        ACCEPT, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RANGE, RETRY_AFTER,
+        USER_AGENT,
+    },
+    StatusCode,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::compression::write_gz;
+
+const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36";
+
+static SHARED: Lazy<Downloader> = Lazy::new(|| {
+    let mut builder = Downloader::builder();
+    if let Ok(base_url) = env::var("BUST_HTTP_BASE_URL") {
+        builder = builder.base_url(&base_url);
+    }
+    builder.build()
+});
+
+/// The shared downloader, use it for all the file downloads.
+pub fn downloader() -> &'static Downloader {
+    &SHARED
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuthProfile {
+    Anonymous,
+    /// ISO-NE webservices, basic auth with `ISONE_WS_USER` and `ISONE_WS_PASSWORD`
+    IsoneWebservices,
+    Nyiso,
+    /// CAISO OASIS throttles clients making more than one request every 5 seconds.
+    CaisoOasis,
+    Ieso,
+}
+
+impl AuthProfile {
+    /// Guess the profile of a public url from its host.
+    pub fn from_url(url: &str) -> AuthProfile {
+        if url.contains("nyiso.com") {
+            AuthProfile::Nyiso
+        } else if url.contains("caiso.com") {
+            AuthProfile::CaisoOasis
+        } else if url.contains("ieso.ca") {
+            AuthProfile::Ieso
+        } else {
+            AuthProfile::Anonymous
+        }
+    }
+
+    /// Minimum time between two requests to the same host.
+    pub fn min_interval(&self) -> Duration {
+        match self {
+            AuthProfile::Anonymous => Duration::ZERO,
+            AuthProfile::IsoneWebservices => Duration::from_millis(200),
+            AuthProfile::Nyiso => Duration::from_millis(200),
+            AuthProfile::CaisoOasis => Duration::from_secs(5),
+            AuthProfile::Ieso => Duration::from_millis(200),
+        }
+    }
+
+    fn authorize(&self, builder: RequestBuilder) -> Result<RequestBuilder, Box<dyn Error>> {
+        match self {
+            AuthProfile::IsoneWebservices => {
+                let user_name = env::var("ISONE_WS_USER")
+                    .map_err(|_| "Environment variable ISONE_WS_USER is not set")?;
+                let password = env::var("ISONE_WS_PASSWORD")
+                    .map_err(|_| "Environment variable ISONE_WS_PASSWORD is not set")?;
+                Ok(builder.basic_auth(user_name, Some(password)))
+            }
+            _ => Ok(builder),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadStatus {
+    Downloaded,
+    /// The server returned 304, the existing file is current
+    NotModified,
+}
+
+impl Display for DownloadStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadStatus::Downloaded => write!(f, "downloaded"),
+            DownloadStatus::NotModified => write!(f, "not modified"),
+        }
+    }
+}
+
+/// Validators saved next to a downloaded file, used for conditional GETs.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+pub struct Downloader {
+    client: Client,
+    max_attempts: u32,
+    initial_backoff: Duration,
+    base_url: Option<String>,
+    last_request: Mutex<HashMap<String, Instant>>,
+}
+
+pub struct DownloaderBuilder {
+    timeout: Duration,
+    max_attempts: u32,
+    initial_backoff: Duration,
+    base_url: Option<String>,
+}
+
+impl DownloaderBuilder {
+    pub fn timeout(mut self, value: Duration) -> Self {
+        self.timeout = value;
+        self
+    }
+
+    /// Total number of attempts, including the first one
+    pub fn max_attempts(mut self, value: u32) -> Self {
+        self.max_attempts = value;
+        self
+    }
+
+    pub fn initial_backoff(mut self, value: Duration) -> Self {
+        self.initial_backoff = value;
+        self
+    }
+
+    /// Send all requests to this scheme and host instead, e.g. a mock server.
+    pub fn base_url(mut self, value: &str) -> Self {
+        self.base_url = Some(value.trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn build(self) -> Downloader {
+        let client = Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .expect("Failed to build HTTP client");
+        Downloader {
+            client,
+            max_attempts: self.max_attempts,
+            initial_backoff: self.initial_backoff,
+            base_url: self.base_url,
+            last_request: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Downloader {
+    pub fn builder() -> DownloaderBuilder {
+        DownloaderBuilder {
+            timeout: Duration::from_secs(120),
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            base_url: None,
+        }
+    }
+
+    /// Replace the scheme and host of the url if a base url is set.
+    fn resolve(&self, url: &str) -> String {
+        match &self.base_url {
+            Some(base) => match url.find("://") {
+                Some(i) => {
+                    let rest = &url[i + 3..];
+                    let path = rest.find('/').map(|j| &rest[j..]).unwrap_or("");
+                    format!("{}{}", base, path)
+                }
+                None => format!("{}{}", base, url),
+            },
+            None => url.to_string(),
+        }
+    }
+
+    /// Wait if the last request to this host was too recent.
+    fn throttle(&self, url: &str, profile: AuthProfile) {
+        let interval = profile.min_interval();
+        if interval.is_zero() {
+            return;
+        }
+        let host = url
+            .split("://")
+            .nth(1)
+            .and_then(|e| e.split('/').next())
+            .unwrap_or(url)
+            .to_string();
+        let wait = {
+            let mut last = self.last_request.lock().unwrap();
+            let now = Instant::now();
+            let next = match last.get(&host) {
+                Some(t) if *t + interval > now => *t + interval,
+                _ => now,
+            };
+            last.insert(host, next);
+            next - now
+        };
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+
+    /// Send a GET request, retrying on 429, 5xx and connection errors.
+    /// Return the response for any other status code.
+    pub fn get(
+        &self,
+        url: &str,
+        profile: AuthProfile,
+        headers: &[(&str, String)],
+    ) -> Result<Response, Box<dyn Error>> {
+        let url = self.resolve(url);
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            self.throttle(&url, profile);
+            let mut builder = self
+                .client
+                .get(&url)
+                .header(USER_AGENT, DEFAULT_USER_AGENT);
+            for (name, value) in headers {
+                builder = builder.header(*name, value);
+            }
+            builder = profile.authorize(builder)?;
+
+            let retry_after = match builder.send() {
+                Ok(response) => {
+                    let status = response.status();
+                    if status != StatusCode::TOO_MANY_REQUESTS && !status.is_server_error() {
+                        return Ok(response);
+                    }
+                    if attempt >= self.max_attempts {
+                        return Err(format!("Download of {} failed with status {}", url, status).into());
+                    }
+                    warn!("Got status {} for {}, retrying", status, url);
+                    response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .map(Duration::from_secs)
+                }
+                Err(e) => {
+                    if attempt >= self.max_attempts {
+                        return Err(format!("Download of {} failed: {}", url, e).into());
+                    }
+                    warn!("Request for {} failed: {}, retrying", url, e);
+                    None
+                }
+            };
+            thread::sleep(retry_after.unwrap_or(backoff));
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+
+    /// Download a url to a file.  If `gzip` is true, the file is saved as
+    /// `{path}.gz`.
+    ///
+    /// If the file already exists, the server is asked only for a newer version.
+    /// A partial download from a failed attempt is kept as `{path}.part` and
+    /// resumed on the next call.
+    pub fn download_file(
+        &self,
+        url: &str,
+        profile: AuthProfile,
+        accept: Option<&str>,
+        path: &Path,
+        gzip: bool,
+    ) -> Result<DownloadStatus, Box<dyn Error>> {
+        let target = if gzip {
+            PathBuf::from(format!("{}.gz", path.display()))
+        } else {
+            path.to_path_buf()
+        };
+        let part = PathBuf::from(format!("{}.part", path.display()));
+        let meta = PathBuf::from(format!("{}.meta", target.display()));
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut headers: Vec<(&str, String)> = Vec::new();
+        if let Some(accept) = accept {
+            headers.push((ACCEPT.as_str(), accept.to_string()));
+        }
+        let offset = fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+        if offset > 0 {
+            headers.push((RANGE.as_str(), format!("bytes={}-", offset)));
+        } else if target.exists() {
+            let validators: Validators = fs::read_to_string(&meta)
+                .ok()
+                .and_then(|e| serde_json::from_str(&e).ok())
+                .unwrap_or_default();
+            if let Some(etag) = validators.etag {
+                headers.push((IF_NONE_MATCH.as_str(), etag));
+            }
+            if let Some(last_modified) = validators.last_modified {
+                headers.push((IF_MODIFIED_SINCE.as_str(), last_modified));
+            }
+        }
+
+        let mut response = self.get(url, profile, &headers)?;
+        let validators = Validators {
+            etag: header_value(&response, ETAG.as_str()),
+            last_modified: header_value(&response, LAST_MODIFIED.as_str()),
+        };
+        let mut out = match response.status() {
+            StatusCode::NOT_MODIFIED => {
+                info!("File {} is not modified", target.display());
+                return Ok(DownloadStatus::NotModified);
+            }
+            StatusCode::PARTIAL_CONTENT if offset > 0 => {
+                info!("Resuming download of {} at byte {}", url, offset);
+                BufWriter::new(OpenOptions::new().append(true).open(&part)?)
+            }
+            StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => {
+                fs::remove_file(&part)?;
+                return Err(format!(
+                    "Can't resume download of {}, removed the partial file",
+                    url
+                )
+                .into());
+            }
+            status if status.is_success() => BufWriter::new(File::create(&part)?),
+            status => {
+                return Err(format!("Download of {} failed with status {}", url, status).into())
+            }
+        };
+        io::copy(&mut response, &mut out)?;
+        out.flush()?;
+        drop(out);
+
+        if gzip {
+            let mut reader = BufReader::new(File::open(&part)?);
+            write_gz(&mut reader, &target)?;
+            fs::remove_file(&part)?;
+        } else {
+            fs::rename(&part, &target)?;
+        }
+        if validators.etag.is_some() || validators.last_modified.is_some() {
+            fs::write(&meta, serde_json::to_string(&validators)?)?;
+        }
+        Ok(DownloadStatus::Downloaded)
+    }
+}
+
+/// Run [`Downloader::download_file`] with the shared downloader on the blocking
+/// thread pool, for use from async code.
+pub async fn download_file_async(
+    url: String,
+    profile: AuthProfile,
+    path: PathBuf,
+) -> Result<DownloadStatus, Box<dyn Error>> {
+    let status = tokio::task::spawn_blocking(move || {
+        downloader()
+            .download_file(&url, profile, None, &path, false)
+            .map_err(|e| e.to_string())
+    })
+    .await??;
+    Ok(status)
+}
+
+fn header_value(response: &Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        error::Error,
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+
+    /// A mock server that replies to each request in turn with one of the
+    /// canned responses.  Return the base url and the received requests.
+    fn mock_server(responses: Vec<String>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let received = requests.clone();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    request.push_str(&line);
+                }
+                received.lock().unwrap().push(request);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (base_url, requests)
+    }
+
+    fn reply(status: &str, headers: &[&str], body: &str) -> String {
+        let mut out = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n", status, body.len());
+        for h in headers {
+            out.push_str(h);
+            out.push_str("\r\n");
+        }
+        out.push_str("\r\n");
+        out.push_str(body);
+        out
+    }
+
+    #[test]
+    fn test_retry_and_conditional_get() -> Result<(), Box<dyn Error>> {
+        let (base_url, requests) = mock_server(vec![
+            reply("503 Service Unavailable", &[], ""),
+            reply("200 OK", &["ETag: \"v1\""], "hello"),
+            reply("304 Not Modified", &[], ""),
+        ]);
+        let downloader = Downloader::builder()
+            .base_url(&base_url)
+            .initial_backoff(Duration::from_millis(10))
+            .build();
+        let dir = std::env::temp_dir().join(format!("bust_downloader_{}", std::process::id()));
+        let path = dir.join("file.json");
+
+        let status = downloader.download_file(
+            "https://webservices.iso-ne.com/api/v1.1/file",
+            AuthProfile::Anonymous,
+            Some("application/json"),
+            &path,
+            true,
+        )?;
+        assert_eq!(status, DownloadStatus::Downloaded);
+        let mut content = String::new();
+        GzDecoder::new(File::open(dir.join("file.json.gz"))?).read_to_string(&mut content)?;
+        assert_eq!(content, "hello");
+
+        let status = downloader.download_file(
+            "https://webservices.iso-ne.com/api/v1.1/file",
+            AuthProfile::Anonymous,
+            None,
+            &path,
+            true,
+        )?;
+        assert_eq!(status, DownloadStatus::NotModified);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("GET /api/v1.1/file HTTP/1.1"));
+        assert!(requests[2].to_lowercase().contains("if-none-match: \"v1\""));
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_resume() -> Result<(), Box<dyn Error>> {
+        let (base_url, requests) = mock_server(vec![reply(
+            "206 Partial Content",
+            &["Content-Range: bytes 3-5/6"],
+            "def",
+        )]);
+        let downloader = Downloader::builder().base_url(&base_url).build();
+        let dir = std::env::temp_dir().join(format!("bust_downloader_resume_{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("file.csv");
+        fs::write(dir.join("file.csv.part"), "abc")?;
+
+        let status =
+            downloader.download_file("http://mis.nyiso.com/file.csv", AuthProfile::Nyiso, None, &path, false)?;
+        assert_eq!(status, DownloadStatus::Downloaded);
+        assert_eq!(fs::read_to_string(&path)?, "abcdef");
+        assert!(!dir.join("file.csv.part").exists());
+        assert!(requests.lock().unwrap()[0].to_lowercase().contains("range: bytes=3-"));
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
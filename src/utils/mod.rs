@@ -1,3 +1,5 @@
+pub mod compression;
+pub mod downloader;
 pub mod lib_duckdb;
 pub mod scratch;
 pub mod send_email;
@@ -13,6 +13,19 @@ where
     serializer.serialize_str(&z.strftime("%Y-%m-%d %H:%M:%S%:z").to_string())
 }
 
+pub fn serialize_option_zoned_as_offset<S>(
+    z: &Option<Zoned>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match z {
+        Some(z) => serialize_zoned_as_offset(z, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 // Custom deserialization function for the Zoned field
 pub fn deserialize_zoned_assume_la<'de, D>(deserializer: D) -> Result<Zoned, D::Error>
 where